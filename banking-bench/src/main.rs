@@ -485,6 +485,7 @@ fn main() {
         gossip_vote_receiver,
         num_banking_threads,
         None,
+        None,
         replay_vote_sender,
         None,
         Arc::new(connection_cache),
@@ -133,12 +133,35 @@ impl<'a> BundleTransactionsOutput<'a> {
                     .iter(),
             )
             .filter_map(|(tx, exec_result)| {
-                matches!(exec_result, Ok(ProcessedTransaction::Executed(_)))
-                    .then(|| tx.to_versioned_transaction())
+                exec_result.is_ok().then(|| tx.to_versioned_transaction())
             })
             .collect()
     }
 
+    /// Transactions that were processed but failed. These are only present if the bundle allows
+    /// them to fail.
+    pub fn failed_transactions(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            &'a RuntimeTransaction<SanitizedTransaction>,
+            &TransactionProcessingResult,
+        ),
+    > {
+        self.transactions
+            .iter()
+            .zip(
+                self.load_and_execute_transactions_output
+                    .processing_results
+                    .iter(),
+            )
+            .filter(|(_, exec_result)| {
+                exec_result
+                    .as_ref()
+                    .is_ok_and(|processed_tx| processed_tx.status().is_err())
+            })
+    }
+
     pub fn executed_transactions(&self) -> Vec<&'a RuntimeTransaction<SanitizedTransaction>> {
         self.transactions
             .iter()
@@ -183,12 +206,15 @@ impl<'a> BundleTransactionsOutput<'a> {
 pub type LoadAndExecuteBundleResult<T> = result::Result<T, LoadAndExecuteBundleError>;
 
 /// Return an Error if a transaction was executed and reverted
-/// NOTE: `execution_results` are zipped with `sanitized_txs` so it's expected a sanitized tx at
-/// position i has a corresponding execution result at position i within the `execution_results`
-/// slice
+/// NOTE: `execution_results` are zipped with `sanitized_txs` and `allowed_to_fail` so it's expected
+/// a sanitized tx at position i has a corresponding execution result at position i within the
+/// `execution_results` slice
+/// Transactions that are allowed to fail may revert or only pay fees, but they must still be
+/// processed so they can be committed.
 pub fn check_bundle_execution_results<'a>(
     execution_results: &'a [TransactionProcessingResult],
     sanitized_txs: &'a [RuntimeTransaction<SanitizedTransaction>],
+    allowed_to_fail: &[bool],
 ) -> result::Result<
     (),
     (
@@ -196,7 +222,11 @@ pub fn check_bundle_execution_results<'a>(
         &'a TransactionProcessingResult,
     ),
 > {
-    for (exec_results, sanitized_tx) in execution_results.iter().zip(sanitized_txs) {
+    for ((exec_results, sanitized_tx), allowed_to_fail) in execution_results
+        .iter()
+        .zip(sanitized_txs)
+        .zip(allowed_to_fail)
+    {
         match exec_results {
             Err(TransactionError::AccountInUse) => {
                 // AccountInUse is expected and should be retried
@@ -206,10 +236,12 @@ pub fn check_bundle_execution_results<'a>(
                 return Err((sanitized_tx, exec_results));
             }
             Ok(ProcessedTransaction::FeesOnly(_)) => {
-                return Err((sanitized_tx, exec_results));
+                if !allowed_to_fail {
+                    return Err((sanitized_tx, exec_results));
+                }
             }
             Ok(ProcessedTransaction::Executed(executed_transaction)) => {
-                if !executed_transaction.execution_details.was_successful() {
+                if !executed_transaction.execution_details.was_successful() && !allowed_to_fail {
                     return Err((sanitized_tx, exec_results));
                 }
             }
@@ -390,14 +422,18 @@ pub fn load_and_execute_bundle<'a>(
         );
         saturating_add_assign!(metrics.load_execute_us, load_execute_us);
 
-        // All transactions within a bundle are expected to be executable + not fail
-        // If there's any transactions that executed and failed or didn't execute due to
-        // unexpected failures (not locking related), bail out of bundle execution early.
+        // All transactions within a bundle are expected to be executable + not fail, unless they're
+        // allowed to fail. If there's any transactions that executed and failed or didn't execute
+        // due to unexpected failures (not locking related), bail out of bundle execution early.
+        let allowed_to_fail: Vec<bool> = (chunk_start..chunk_end)
+            .map(|index| bundle.allowed_to_fail_tx_indexes.contains(&index))
+            .collect();
         if let Err((failing_tx, exec_result)) = check_bundle_execution_results(
             load_and_execute_transactions_output
                 .processing_results
                 .as_slice(),
             batch.sanitized_transactions(),
+            &allowed_to_fail,
         ) {
             // TODO (LB): we should try to return partial results here for successful bundles in a parallel batch.
             //  given a bundle that write locks the following accounts [[A], [B], [C]]
@@ -561,6 +597,7 @@ mod tests {
         },
         solana_svm::transaction_processing_result::TransactionProcessingResultExtensions,
         std::{
+            collections::HashSet,
             sync::{Arc, Barrier, RwLock},
             thread::{sleep, spawn},
             time::Duration,
@@ -601,6 +638,7 @@ mod tests {
         SanitizedBundle {
             transactions,
             bundle_id,
+            allowed_to_fail_tx_indexes: HashSet::new(),
         }
     }
 
@@ -1091,6 +1129,127 @@ mod tests {
         }
     }
 
+    /// Transactions that are allowed to fail may revert without failing the bundle, but the
+    /// bundle still fails if a required transaction reverts
+    #[test]
+    fn test_bundle_allowed_to_fail_transactions() {
+        let (genesis_config_info, bank, _bank_forks) =
+            create_simple_test_bank(MINT_AMOUNT_LAMPORTS);
+
+        let kp1 = Keypair::new();
+        let kp2 = Keypair::new();
+        let transactions = vec![
+            transfer(
+                &genesis_config_info.mint_keypair,
+                &kp1.pubkey(),
+                100_000,
+                genesis_config_info.genesis_config.hash(),
+            ),
+            // kp1 can pay the fee but not the transfer, so it's executed and reverts
+            transfer(
+                &kp1,
+                &kp2.pubkey(),
+                MINT_AMOUNT_LAMPORTS,
+                genesis_config_info.genesis_config.hash(),
+            ),
+            transfer(
+                &genesis_config_info.mint_keypair,
+                &kp2.pubkey(),
+                100_000,
+                genesis_config_info.genesis_config.hash(),
+            ),
+        ];
+        let mut bundle = make_bundle(&transactions, &bank);
+        let default_accounts = vec![None; bundle.transactions.len()];
+
+        let execution_result = load_and_execute_bundle(
+            &bank,
+            &bundle,
+            MAX_PROCESSING_AGE,
+            &MAX_PROCESSING_TIME,
+            true,
+            &LOG_MESSAGE_BYTES_LIMITS,
+            false,
+            None,
+            &default_accounts,
+            &default_accounts,
+        );
+        assert_matches!(
+            execution_result.result(),
+            Err(LoadAndExecuteBundleError::TransactionError { signature, .. })
+                if signature == bundle.transactions[1].signature()
+        );
+
+        bundle.allowed_to_fail_tx_indexes = HashSet::from([1]);
+        let execution_result = load_and_execute_bundle(
+            &bank,
+            &bundle,
+            MAX_PROCESSING_AGE,
+            &MAX_PROCESSING_TIME,
+            true,
+            &LOG_MESSAGE_BYTES_LIMITS,
+            false,
+            None,
+            &default_accounts,
+            &default_accounts,
+        );
+        assert!(execution_result.result().is_ok());
+
+        // the reverted transaction is still recorded so its fee can be committed
+        assert_eq!(
+            execution_result.executed_transaction_batches().concat(),
+            transactions
+                .iter()
+                .cloned()
+                .map(VersionedTransaction::from)
+                .collect::<Vec<_>>()
+        );
+        let failed_signatures: Vec<_> = execution_result
+            .bundle_transaction_results()
+            .iter()
+            .flat_map(|r| r.failed_transactions())
+            .map(|(tx, _)| *tx.signature())
+            .collect();
+        assert_eq!(failed_signatures, vec![*bundle.transactions[1].signature()]);
+    }
+
+    /// Transactions that are allowed to fail must still be processed
+    #[test]
+    fn test_bundle_allowed_to_fail_transaction_not_processed() {
+        let (genesis_config_info, bank, _bank_forks) =
+            create_simple_test_bank(MINT_AMOUNT_LAMPORTS);
+
+        // kp has no funds, so the transaction can't pay fees and isn't processed
+        let kp = Keypair::new();
+        let transactions = vec![transfer(
+            &kp,
+            &kp.pubkey(),
+            1_000,
+            genesis_config_info.genesis_config.hash(),
+        )];
+        let mut bundle = make_bundle(&transactions, &bank);
+        bundle.allowed_to_fail_tx_indexes = HashSet::from([0]);
+
+        let default_accounts = vec![None; bundle.transactions.len()];
+        let execution_result = load_and_execute_bundle(
+            &bank,
+            &bundle,
+            MAX_PROCESSING_AGE,
+            &MAX_PROCESSING_TIME,
+            true,
+            &LOG_MESSAGE_BYTES_LIMITS,
+            false,
+            None,
+            &default_accounts,
+            &default_accounts,
+        );
+        assert_matches!(
+            execution_result.result(),
+            Err(LoadAndExecuteBundleError::TransactionError { execution_result, .. })
+                if execution_result.flattened_result() == Err(TransactionError::AccountNotFound)
+        );
+    }
+
     /// Tests that when the max processing time is exceeded, the bundle is an error
     #[test]
     fn test_bundle_max_processing_time_exceeded() {
@@ -0,0 +1,504 @@
+//! A cache of bundles that are known to fail execution against a given bank.
+//!
+//! Both RPC `simulateBundle` and BundleStage execute bundles with [load_and_execute_bundle], and
+//! the block engine tends to resend the same bundles several times within a slot. When a bundle
+//! fails, the failure is recorded along with the version of every account the bundle references,
+//! including the programdata accounts of the upgradeable programs it invokes. A later lookup
+//! against the same bank only returns the failure if none of those accounts have been written
+//! since, which lets BundleStage reject the bundle without executing it again.
+//!
+//! A bundle that fails while some transactions are allowed to fail also fails when fewer of them
+//! are allowed to, so a failure is only returned if the lookup allows a subset of the transactions
+//! the cached failure allowed.
+//!
+//! [load_and_execute_bundle]: crate::bundle_execution::load_and_execute_bundle
+use {
+    crate::{bundle_execution::LoadAndExecuteBundleError, SanitizedBundle},
+    solana_accounts_db::accounts_cache::CachedAccount,
+    solana_runtime::bank::Bank,
+    solana_sdk::{
+        account::ReadableAccount,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::Slot,
+        hash::Hash,
+        pubkey::Pubkey,
+    },
+    solana_svm::transaction_processing_result::TransactionProcessingResultExtensions,
+    std::{
+        collections::{BTreeMap, HashMap, HashSet},
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Number of banks to keep failures for. Older banks are evicted first.
+pub const MAX_CACHED_BANKS: usize = 4;
+
+/// Number of failures to keep per bank. New failures are dropped once a bank is full.
+pub const MAX_CACHED_BUNDLES_PER_BANK: usize = 10_000;
+
+/// Banks are identified by slot and parent hash, which tells apart banks for the same slot on
+/// different forks. The bank hash can't be used because it isn't known until the bank is frozen.
+type BankKey = (Slot, Hash);
+
+/// The version of every account referenced by a bundle at a point in time.
+///
+/// Every write to an account in the bank's slot replaces its entry in the accounts write cache, so
+/// the entry is used as the account's version. Accounts that haven't been written in the slot have
+/// no version and can only change by being written in it. Checking the versions doesn't load or
+/// hash any account, so it's much cheaper than executing the bundle again.
+#[derive(Clone, Debug)]
+pub struct BundleAccountsState(Vec<(Pubkey, Option<CachedAccount>)>);
+
+impl BundleAccountsState {
+    /// Loads the versions of the accounts referenced by the bundle, including ones loaded from
+    /// address lookup tables and the programdata accounts of the upgradeable programs it invokes.
+    pub fn load(bank: &Bank, bundle: &SanitizedBundle) -> Self {
+        let mut seen = HashSet::new();
+        let mut accounts = Vec::new();
+        for tx in &bundle.transactions {
+            let programdata_addresses = tx
+                .message()
+                .program_instructions_iter()
+                .filter_map(|(program_id, _)| Self::programdata_address(bank, program_id));
+            for pubkey in tx
+                .message()
+                .account_keys()
+                .iter()
+                .copied()
+                .chain(programdata_addresses)
+            {
+                if seen.insert(pubkey) {
+                    accounts.push((pubkey, Self::account_version(bank, &pubkey)));
+                }
+            }
+        }
+        Self(accounts)
+    }
+
+    /// Returns true if none of the accounts have been written since the state was loaded
+    pub fn is_current(&self, bank: &Bank) -> bool {
+        self.0.iter().all(|(pubkey, version)| {
+            match (Self::account_version(bank, pubkey), version) {
+                (Some(current), Some(version)) => Arc::ptr_eq(&current, version),
+                (current, version) => current.is_none() && version.is_none(),
+            }
+        })
+    }
+
+    fn account_version(bank: &Bank, pubkey: &Pubkey) -> Option<CachedAccount> {
+        bank.accounts()
+            .accounts_db
+            .accounts_cache
+            .load(bank.slot(), pubkey)
+    }
+
+    /// An upgrade writes the program's programdata account instead of the program account
+    fn programdata_address(bank: &Bank, program_id: &Pubkey) -> Option<Pubkey> {
+        let program = bank.get_account(program_id)?;
+        if !bpf_loader_upgradeable::check_id(program.owner()) {
+            return None;
+        }
+        match program.deserialize_data() {
+            Ok(UpgradeableLoaderState::Program {
+                programdata_address,
+            }) => Some(programdata_address),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CachedBundleFailure {
+    accounts_state: BundleAccountsState,
+    allowed_to_fail_tx_indexes: HashSet<usize>,
+    error: LoadAndExecuteBundleError,
+}
+
+#[derive(Debug, Default)]
+pub struct BundleSimulationCache {
+    failures: Mutex<BTreeMap<BankKey, HashMap<String, CachedBundleFailure>>>,
+}
+
+impl BundleSimulationCache {
+    /// Returns the error the bundle failed with if it's known to fail against the bank's current
+    /// state. Failures recorded against accounts that have since been written are evicted.
+    pub fn get(
+        &self,
+        bank: &Bank,
+        bundle_id: &str,
+        allowed_to_fail_tx_indexes: &HashSet<usize>,
+    ) -> Option<LoadAndExecuteBundleError> {
+        let bank_key = Self::bank_key(bank);
+        // validate the accounts outside of the lock, loading them may hit disk
+        let failure = self
+            .failures
+            .lock()
+            .unwrap()
+            .get(&bank_key)?
+            .get(bundle_id)
+            .filter(|failure| {
+                allowed_to_fail_tx_indexes.is_subset(&failure.allowed_to_fail_tx_indexes)
+            })
+            .cloned()?;
+
+        if failure.accounts_state.is_current(bank) {
+            Some(failure.error)
+        } else {
+            if let Some(failures) = self.failures.lock().unwrap().get_mut(&bank_key) {
+                failures.remove(bundle_id);
+            }
+            None
+        }
+    }
+
+    /// Records a bundle failure. `accounts_state` must be loaded from the bank before the bundle
+    /// was executed or while its accounts were still locked. Only transaction failures are
+    /// recorded because the other errors don't depend on the state of the bank.
+    pub fn insert(
+        &self,
+        bank: &Bank,
+        bundle_id: &str,
+        allowed_to_fail_tx_indexes: &HashSet<usize>,
+        accounts_state: BundleAccountsState,
+        error: &LoadAndExecuteBundleError,
+    ) {
+        let LoadAndExecuteBundleError::TransactionError {
+            signature,
+            execution_result,
+        } = error
+        else {
+            return;
+        };
+        let Err(transaction_error) = execution_result.flattened_result() else {
+            return;
+        };
+        // don't hold onto the executed transaction details, only the error is needed
+        let error = LoadAndExecuteBundleError::TransactionError {
+            signature: *signature,
+            execution_result: Box::new(Err(transaction_error)),
+        };
+
+        let mut failures = self.failures.lock().unwrap();
+        let bank_key = Self::bank_key(bank);
+        if !failures.contains_key(&bank_key) {
+            while failures.len() >= MAX_CACHED_BANKS {
+                failures.pop_first();
+            }
+        }
+        let bank_failures = failures.entry(bank_key).or_default();
+        if bank_failures.len() < MAX_CACHED_BUNDLES_PER_BANK
+            || bank_failures.contains_key(bundle_id)
+        {
+            bank_failures.insert(
+                bundle_id.to_string(),
+                CachedBundleFailure {
+                    accounts_state,
+                    allowed_to_fail_tx_indexes: allowed_to_fail_tx_indexes.clone(),
+                    error,
+                },
+            );
+        }
+    }
+
+    fn bank_key(bank: &Bank) -> BankKey {
+        (bank.slot(), bank.parent_hash())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            bundle_execution::load_and_execute_bundle, derive_bundle_id_from_sanitized_transactions,
+        },
+        solana_ledger::genesis_utils::create_genesis_config,
+        solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
+        solana_sdk::{
+            account::AccountSharedData,
+            clock::MAX_PROCESSING_AGE,
+            instruction::Instruction,
+            signature::{Keypair, Signer},
+            system_transaction::transfer,
+            transaction::{MessageHash, Transaction, TransactionError, VersionedTransaction},
+        },
+        std::time::Duration,
+    };
+
+    const MAX_PROCESSING_TIME: Duration = Duration::from_secs(1);
+
+    fn make_bundle(txs: &[Transaction], bank: &Bank) -> SanitizedBundle {
+        let transactions: Vec<_> = txs
+            .iter()
+            .map(|tx| {
+                RuntimeTransaction::try_create(
+                    VersionedTransaction::from(tx.clone()),
+                    MessageHash::Compute,
+                    None,
+                    bank,
+                    bank.get_reserved_account_keys(),
+                )
+                .unwrap()
+            })
+            .collect();
+        let bundle_id = derive_bundle_id_from_sanitized_transactions(&transactions);
+        SanitizedBundle {
+            transactions,
+            bundle_id,
+            allowed_to_fail_tx_indexes: HashSet::new(),
+        }
+    }
+
+    fn execute_bundle(bank: &Bank, bundle: &SanitizedBundle) -> LoadAndExecuteBundleError {
+        let default_accounts = vec![None; bundle.transactions.len()];
+        load_and_execute_bundle(
+            bank,
+            bundle,
+            MAX_PROCESSING_AGE,
+            &MAX_PROCESSING_TIME,
+            false,
+            &None,
+            true,
+            None,
+            &default_accounts,
+            &default_accounts,
+        )
+        .result()
+        .clone()
+        .unwrap_err()
+    }
+
+    #[test]
+    fn test_cached_failure_invalidated_on_account_write() {
+        let genesis_config_info = create_genesis_config(1_000_000);
+        let (bank, _bank_forks) =
+            Bank::new_with_bank_forks_for_tests(&genesis_config_info.genesis_config);
+
+        // kp has no funds, transfer will fail
+        let kp = Keypair::new();
+        let bundle = make_bundle(
+            &[transfer(
+                &kp,
+                &kp.pubkey(),
+                1_000,
+                genesis_config_info.genesis_config.hash(),
+            )],
+            &bank,
+        );
+
+        let cache = BundleSimulationCache::default();
+        assert!(cache
+            .get(&bank, &bundle.bundle_id, &bundle.allowed_to_fail_tx_indexes)
+            .is_none());
+
+        let accounts_state = BundleAccountsState::load(&bank, &bundle);
+        let error = execute_bundle(&bank, &bundle);
+        cache.insert(
+            &bank,
+            &bundle.bundle_id,
+            &bundle.allowed_to_fail_tx_indexes,
+            accounts_state,
+            &error,
+        );
+
+        match cache.get(&bank, &bundle.bundle_id, &bundle.allowed_to_fail_tx_indexes) {
+            Some(LoadAndExecuteBundleError::TransactionError {
+                signature,
+                execution_result,
+            }) => {
+                assert_eq!(signature, *bundle.transactions[0].signature());
+                assert_eq!(
+                    execution_result.unwrap_err(),
+                    TransactionError::AccountNotFound
+                );
+            }
+            _ => panic!("expected a cached transaction error"),
+        }
+
+        // the bundle could succeed if the failing transaction is allowed to fail
+        assert!(cache
+            .get(&bank, &bundle.bundle_id, &HashSet::from([0]))
+            .is_none());
+
+        // funding kp could make the bundle succeed, so the failure is evicted
+        bank.transfer(100_000, &genesis_config_info.mint_keypair, &kp.pubkey())
+            .unwrap();
+        assert!(cache
+            .get(&bank, &bundle.bundle_id, &bundle.allowed_to_fail_tx_indexes)
+            .is_none());
+        assert!(cache.failures.lock().unwrap()[&BundleSimulationCache::bank_key(&bank)].is_empty());
+    }
+
+    #[test]
+    fn test_cached_failure_invalidated_on_program_upgrade() {
+        let genesis_config_info = create_genesis_config(1_000_000);
+        let (bank, _bank_forks) =
+            Bank::new_with_bank_forks_for_tests(&genesis_config_info.genesis_config);
+
+        let program_id = Pubkey::new_unique();
+        let programdata_address = Pubkey::new_unique();
+        bank.store_account(
+            &program_id,
+            &AccountSharedData::new_data(
+                1,
+                &UpgradeableLoaderState::Program {
+                    programdata_address,
+                },
+                &bpf_loader_upgradeable::id(),
+            )
+            .unwrap(),
+        );
+
+        // kp has no funds, the transaction will fail
+        let kp = Keypair::new();
+        let bundle = make_bundle(
+            &[Transaction::new_signed_with_payer(
+                &[Instruction::new_with_bytes(program_id, &[], vec![])],
+                Some(&kp.pubkey()),
+                &[&kp],
+                genesis_config_info.genesis_config.hash(),
+            )],
+            &bank,
+        );
+
+        let cache = BundleSimulationCache::default();
+        let accounts_state = BundleAccountsState::load(&bank, &bundle);
+        let error = execute_bundle(&bank, &bundle);
+        cache.insert(
+            &bank,
+            &bundle.bundle_id,
+            &bundle.allowed_to_fail_tx_indexes,
+            accounts_state,
+            &error,
+        );
+        assert!(cache
+            .get(&bank, &bundle.bundle_id, &bundle.allowed_to_fail_tx_indexes)
+            .is_some());
+
+        // upgrading the program writes its programdata account, which the bundle doesn't reference
+        bank.store_account(
+            &programdata_address,
+            &AccountSharedData::new(1, 0, &bpf_loader_upgradeable::id()),
+        );
+        assert!(cache
+            .get(&bank, &bundle.bundle_id, &bundle.allowed_to_fail_tx_indexes)
+            .is_none());
+    }
+
+    #[test]
+    fn test_cached_failure_is_per_bank() {
+        let genesis_config_info = create_genesis_config(1_000_000);
+        let (bank, bank_forks) =
+            Bank::new_with_bank_forks_for_tests(&genesis_config_info.genesis_config);
+
+        let kp = Keypair::new();
+        let bundle = make_bundle(
+            &[transfer(
+                &kp,
+                &kp.pubkey(),
+                1_000,
+                genesis_config_info.genesis_config.hash(),
+            )],
+            &bank,
+        );
+
+        let cache = BundleSimulationCache::default();
+        let accounts_state = BundleAccountsState::load(&bank, &bundle);
+        let error = execute_bundle(&bank, &bundle);
+        cache.insert(
+            &bank,
+            &bundle.bundle_id,
+            &bundle.allowed_to_fail_tx_indexes,
+            accounts_state,
+            &error,
+        );
+        assert!(cache
+            .get(&bank, &bundle.bundle_id, &bundle.allowed_to_fail_tx_indexes)
+            .is_some());
+
+        let child_bank = Bank::new_from_parent(bank.clone(), &Pubkey::default(), 1);
+        let child_bank = bank_forks
+            .write()
+            .unwrap()
+            .insert(child_bank)
+            .clone_without_scheduler();
+        assert!(cache
+            .get(
+                &child_bank,
+                &bundle.bundle_id,
+                &bundle.allowed_to_fail_tx_indexes
+            )
+            .is_none());
+
+        // the oldest banks are evicted first
+        let mut parent = child_bank;
+        for slot in 2..=MAX_CACHED_BANKS as Slot + 1 {
+            let bank = Bank::new_from_parent(parent.clone(), &Pubkey::default(), slot);
+            let bank = bank_forks
+                .write()
+                .unwrap()
+                .insert(bank)
+                .clone_without_scheduler();
+            let accounts_state = BundleAccountsState::load(&bank, &bundle);
+            cache.insert(
+                &bank,
+                &bundle.bundle_id,
+                &bundle.allowed_to_fail_tx_indexes,
+                accounts_state,
+                &error,
+            );
+            parent = bank;
+        }
+        assert!(cache
+            .get(&bank, &bundle.bundle_id, &bundle.allowed_to_fail_tx_indexes)
+            .is_none());
+        assert!(cache
+            .get(
+                &parent,
+                &bundle.bundle_id,
+                &bundle.allowed_to_fail_tx_indexes
+            )
+            .is_some());
+        assert_eq!(cache.failures.lock().unwrap().len(), MAX_CACHED_BANKS);
+    }
+
+    #[test]
+    fn test_only_transaction_errors_cached() {
+        let genesis_config_info = create_genesis_config(1_000_000);
+        let (bank, _bank_forks) =
+            Bank::new_with_bank_forks_for_tests(&genesis_config_info.genesis_config);
+
+        let kp = Keypair::new();
+        let bundle = make_bundle(
+            &[transfer(
+                &kp,
+                &kp.pubkey(),
+                1_000,
+                genesis_config_info.genesis_config.hash(),
+            )],
+            &bank,
+        );
+
+        let cache = BundleSimulationCache::default();
+        for error in [
+            LoadAndExecuteBundleError::ProcessingTimeExceeded(MAX_PROCESSING_TIME),
+            LoadAndExecuteBundleError::InvalidPreOrPostAccounts,
+            LoadAndExecuteBundleError::LockError {
+                signature: *bundle.transactions[0].signature(),
+                transaction_error: TransactionError::AccountInUse,
+            },
+        ] {
+            let accounts_state = BundleAccountsState::load(&bank, &bundle);
+            cache.insert(
+                &bank,
+                &bundle.bundle_id,
+                &bundle.allowed_to_fail_tx_indexes,
+                accounts_state,
+                &error,
+            );
+            assert!(cache
+                .get(&bank, &bundle.bundle_id, &bundle.allowed_to_fail_tx_indexes)
+                .is_none());
+        }
+    }
+}
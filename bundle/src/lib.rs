@@ -7,10 +7,12 @@ use {
     solana_poh::poh_recorder::PohRecorderError,
     solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
     solana_sdk::{pubkey::Pubkey, transaction::SanitizedTransaction},
+    std::collections::HashSet,
     thiserror::Error,
 };
 
 pub mod bundle_execution;
+pub mod bundle_simulation_cache;
 
 #[derive(Error, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TipError {
@@ -28,6 +30,9 @@ pub enum TipError {
 
     #[error("Error cranking tip programs")]
     CrankTipError,
+
+    #[error("Tip of {0} lamports is below the minimum tip")]
+    BelowMinimumTip(u64),
 }
 
 impl From<anchor_lang::error::Error> for TipError {
@@ -66,6 +71,9 @@ pub enum BundleExecutionError {
 pub struct SanitizedBundle {
     pub transactions: Vec<RuntimeTransaction<SanitizedTransaction>>,
     pub bundle_id: String,
+    /// Indexes of the transactions that may fail without failing the bundle. Failed transactions
+    /// are still committed if they were processed, so their fees are paid.
+    pub allowed_to_fail_tx_indexes: HashSet<usize>,
 }
 
 pub fn derive_bundle_id_from_sanitized_transactions(
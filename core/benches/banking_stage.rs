@@ -320,6 +320,7 @@ fn bench_banking(bencher: &mut Bencher, tx_type: TransactionType) {
         tpu_vote_receiver,
         gossip_vote_receiver,
         None,
+        None,
         s,
         None,
         Arc::new(ConnectionCache::new("connection_cache_test")),
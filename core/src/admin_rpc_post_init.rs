@@ -1,7 +1,15 @@
 use {
     crate::{
+        bundle_stage::{bundle_policy::BundlePolicy, min_tip_policy::MinTipPolicy},
         cluster_slots_service::cluster_slots::ClusterSlots,
-        proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
+        proxy::{
+            block_engine_region_selector::BlockEngineRegions,
+            block_engine_stage::{BlockEngineConfig, BlockEngineHealth},
+            packet_source_accounting::PacketSourceAccounting,
+            packet_trust::PacketTrust,
+            relayer_stage::RelayerConfig,
+            vortexor_receiver::VortexorPeers,
+        },
         repair::{outstanding_requests::OutstandingRequests, serve_repair::ShredRepairType},
     },
     solana_gossip::cluster_info::ClusterInfo,
@@ -28,4 +36,11 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub relayer_config: Arc<Mutex<RelayerConfig>>,
     pub shred_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
     pub shred_retransmit_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
+    pub min_tip_policy: Arc<RwLock<MinTipPolicy>>,
+    pub bundle_policy: Arc<RwLock<BundlePolicy>>,
+    pub block_engine_health: Arc<RwLock<Vec<BlockEngineHealth>>>,
+    pub block_engine_regions: Arc<RwLock<BlockEngineRegions>>,
+    pub packet_trust: Arc<PacketTrust>,
+    pub packet_source_accounting: Arc<PacketSourceAccounting>,
+    pub vortexor_peers: Arc<VortexorPeers>,
 }
@@ -3,11 +3,17 @@ use {
     crate::{
         banking_stage::{BankingStage, LikeClusterInfo},
         banking_trace::{
-            BankingPacketBatch, BankingTracer, ChannelLabel, Channels, TimedTracedEvent,
-            TracedEvent, TracedSender, TracerThread, BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT,
-            BASENAME,
+            BankingBundleBatch, BankingPacketBatch, BankingTracer, ChannelLabel, Channels,
+            TimedTracedEvent, TracedEvent, TracedSender, TracerThread,
+            BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT, BASENAME,
         },
-        bundle_stage::bundle_account_locker::BundleAccountLocker,
+        bundle_stage::{
+            bundle_account_locker::BundleAccountLocker, BundleStage,
+            DEFAULT_NUM_BUNDLE_CONSUME_WORKERS,
+        },
+        packet_bundle::PacketBundle,
+        proxy::block_engine_stage::BlockBuilderFeeInfo,
+        tip_manager::{TipManager, TipManagerConfig},
         validator::BlockProductionMethod,
     },
     bincode::deserialize_from,
@@ -53,7 +59,7 @@ use {
         path::PathBuf,
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc, RwLock,
+            Arc, Mutex, RwLock,
         },
         thread::{self, sleep, JoinHandle},
         time::{Duration, SystemTime},
@@ -115,6 +121,10 @@ use {
 ///
 /// Warm-up starts at T=-WARMUP_DURATION (~ 13 secs). As soon as warm up is initiated, we invoke
 /// `BankingStage::new_num_threads()` as well to simulate the pre-leader slot's tx-buffering time.
+///
+/// Bundles are traced as `TracedEvent::Bundle` when `BundleStage` receives them. Given a
+/// `BundleSimulationConfig`, they're sent to a simulated `BundleStage` at their traced arrival
+/// times, alongside the packet batches. Without it, traced bundles are ignored.
 pub struct BankingSimulator {
     banking_trace_events: BankingTraceEvents,
     first_simulated_slot: Slot,
@@ -127,27 +137,73 @@ pub enum SimulateError {
 
     #[error("Deserialization Error: {0}")]
     DeserializeError(#[from] bincode::Error),
+
+    #[error("Identity {identity} isn't the simulated leader {simulated_leader}")]
+    IdentityMismatch {
+        identity: Pubkey,
+        simulated_leader: Pubkey,
+    },
 }
 
 // Defined to be enough to cover the holding phase prior to leader slots with some idling (+5 secs)
 const WARMUP_DURATION: Duration =
     Duration::from_millis(HOLD_TRANSACTIONS_SLOT_OFFSET * DEFAULT_MS_PER_SLOT + 5000);
 
+/// What the simulated `BundleStage` needs to act as the simulated leader
+pub struct BundleSimulationConfig {
+    /// Identity of the simulated leader, which signs the tip program cranks
+    pub identity_keypair: Arc<Keypair>,
+    pub tip_manager_config: TipManagerConfig,
+}
+
+/// A traced batch to be sent to `BankingStage` or `BundleStage`
+enum TracedBatch {
+    Packets(ChannelLabel, BankingPacketBatch),
+    Bundles(BankingBundleBatch),
+}
+
+impl TracedBatch {
+    fn label(&self) -> ChannelLabel {
+        match self {
+            Self::Packets(label, _) => *label,
+            Self::Bundles(_) => ChannelLabel::Bundle,
+        }
+    }
+
+    fn batch_and_tx_counts(&self) -> (usize, usize) {
+        match self {
+            Self::Packets(_, batches) => (
+                batches.len(),
+                batches.iter().map(|batch| batch.len()).sum::<usize>(),
+            ),
+            Self::Bundles(bundles) => (
+                bundles.len(),
+                bundles
+                    .iter()
+                    .map(|bundle| bundle.batch.len())
+                    .sum::<usize>(),
+            ),
+        }
+    }
+}
+
 /// BTreeMap is intentional because events could be unordered slightly due to tracing jitter.
-type PacketBatchesByTime = BTreeMap<SystemTime, (ChannelLabel, BankingPacketBatch)>;
+type BatchesByTime = BTreeMap<SystemTime, TracedBatch>;
 
 type FreezeTimeBySlot = BTreeMap<Slot, SystemTime>;
 
-type TimedBatchesToSend = Vec<(
-    (Duration, (ChannelLabel, BankingPacketBatch)),
-    (usize, usize),
-)>;
+type TimedBatchesToSend = Vec<((Duration, TracedBatch), (usize, usize))>;
 
-type EventSenderThread = JoinHandle<(TracedSender, TracedSender, TracedSender)>;
+type EventSenderThread = JoinHandle<(
+    TracedSender,
+    TracedSender,
+    TracedSender,
+    Option<Sender<Vec<PacketBundle>>>,
+)>;
 
 #[derive(Default)]
 pub struct BankingTraceEvents {
-    packet_batches_by_time: PacketBatchesByTime,
+    batches_by_time: BatchesByTime,
     freeze_time_by_slot: FreezeTimeBySlot,
     hash_overrides: HashOverrides,
 }
@@ -218,8 +274,15 @@ impl BankingTraceEvents {
                 // Somewhat naively assume that event_times (nanosecond resolution) won't
                 // collide.
                 let is_new = self
-                    .packet_batches_by_time
-                    .insert(event_time, (label, batch))
+                    .batches_by_time
+                    .insert(event_time, TracedBatch::Packets(label, batch))
+                    .is_none();
+                assert!(is_new);
+            }
+            TracedEvent::Bundle(_label, bundles) => {
+                let is_new = self
+                    .batches_by_time
+                    .insert(event_time, TracedBatch::Bundles(bundles))
                     .is_none();
                 assert!(is_new);
             }
@@ -329,6 +392,7 @@ struct SenderLoop {
     non_vote_sender: TracedSender,
     tpu_vote_sender: TracedSender,
     gossip_vote_sender: TracedSender,
+    bundle_sender: Option<Sender<Vec<PacketBundle>>>,
     exit: Arc<AtomicBool>,
     raw_base_event_time: SystemTime,
     total_batch_count: usize,
@@ -355,14 +419,19 @@ impl SenderLoop {
     fn start(
         mut self,
         base_simulation_time: SystemTime,
-    ) -> (TracedSender, TracedSender, TracedSender) {
+    ) -> (
+        TracedSender,
+        TracedSender,
+        TracedSender,
+        Option<Sender<Vec<PacketBundle>>>,
+    ) {
         let mut logger = SenderLoopLogger::new(
             &self.non_vote_sender,
             &self.tpu_vote_sender,
             &self.gossip_vote_sender,
         );
         let mut simulation_duration = Duration::default();
-        for ((required_duration, (label, batches_with_stats)), (batch_count, tx_count)) in
+        for ((required_duration, batch), (batch_count, tx_count)) in
             self.timed_batches_to_send.drain(..)
         {
             // Busy loop for most accurate sending timings
@@ -373,13 +442,26 @@ impl SenderLoop {
                     .unwrap();
             }
 
-            let sender = match label {
-                ChannelLabel::NonVote => &self.non_vote_sender,
-                ChannelLabel::TpuVote => &self.tpu_vote_sender,
-                ChannelLabel::GossipVote => &self.gossip_vote_sender,
-                ChannelLabel::Dummy => unreachable!(),
-            };
-            sender.send(batches_with_stats).unwrap();
+            let label = batch.label();
+            match batch {
+                TracedBatch::Packets(label, batches_with_stats) => {
+                    let sender = match label {
+                        ChannelLabel::NonVote => &self.non_vote_sender,
+                        ChannelLabel::TpuVote => &self.tpu_vote_sender,
+                        ChannelLabel::GossipVote => &self.gossip_vote_sender,
+                        ChannelLabel::Dummy | ChannelLabel::Bundle => unreachable!(),
+                    };
+                    sender.send(batches_with_stats).unwrap();
+                }
+                TracedBatch::Bundles(bundles) => {
+                    // bundles are only left to send when there's a simulated BundleStage
+                    self.bundle_sender
+                        .as_ref()
+                        .unwrap()
+                        .send(Arc::unwrap_or_clone(bundles))
+                        .unwrap();
+                }
+            }
 
             logger.on_sending_batches(&simulation_duration, label, batch_count, tx_count);
             if self.exit.load(Ordering::Relaxed) {
@@ -393,6 +475,7 @@ impl SenderLoop {
             self.non_vote_sender,
             self.tpu_vote_sender,
             self.gossip_vote_sender,
+            self.bundle_sender,
         )
     }
 }
@@ -510,6 +593,7 @@ impl SimulatorLoop {
 struct SimulatorThreads {
     poh_service: PohService,
     banking_stage: BankingStage,
+    bundle_stage: Option<BundleStage>,
     broadcast_stage: BroadcastStage,
     retracer_thread: TracerThread,
     exit: Arc<AtomicBool>,
@@ -525,6 +609,9 @@ impl SimulatorThreads {
         // triggers termination of banking_stage, in turn retracer thread will be terminated.
         sender_thread.join().unwrap();
         self.banking_stage.join().unwrap();
+        if let Some(bundle_stage) = self.bundle_stage {
+            bundle_stage.join().unwrap();
+        }
         self.poh_service.join().unwrap();
         if let Some(retracer_thread) = self.retracer_thread {
             retracer_thread.join().unwrap().unwrap();
@@ -551,6 +638,8 @@ struct SenderLoopLogger<'a> {
     tpu_vote_tx_count: usize,
     gossip_vote_batch_count: usize,
     gossip_vote_tx_count: usize,
+    bundle_count: usize,
+    bundle_tx_count: usize,
 }
 
 impl<'a> SenderLoopLogger<'a> {
@@ -574,6 +663,8 @@ impl<'a> SenderLoopLogger<'a> {
             tpu_vote_tx_count: 0,
             gossip_vote_batch_count: 0,
             gossip_vote_tx_count: 0,
+            bundle_count: 0,
+            bundle_tx_count: 0,
         }
     }
 
@@ -597,6 +688,7 @@ impl<'a> SenderLoopLogger<'a> {
                 &mut self.gossip_vote_batch_count,
                 &mut self.gossip_vote_tx_count,
             ),
+            Bundle => (&mut self.bundle_count, &mut self.bundle_tx_count),
             Dummy => unreachable!(),
         };
         *total_batch_count += batch_count;
@@ -635,13 +727,16 @@ impl<'a> SenderLoopLogger<'a> {
 
     fn on_terminating(self) {
         info!(
-            "terminating to send...: non_vote: {} ({}), tpu_vote: {} ({}), gossip_vote: {} ({})",
+            "terminating to send...: non_vote: {} ({}), tpu_vote: {} ({}), gossip_vote: {} ({}), \
+             bundle: {} ({})",
             self.non_vote_batch_count,
             self.non_vote_tx_count,
             self.tpu_vote_batch_count,
             self.tpu_vote_tx_count,
             self.gossip_vote_batch_count,
             self.gossip_vote_tx_count,
+            self.bundle_count,
+            self.bundle_tx_count,
         );
     }
 
@@ -674,9 +769,10 @@ impl BankingSimulator {
         bank_forks: Arc<RwLock<BankForks>>,
         blockstore: Arc<Blockstore>,
         block_production_method: BlockProductionMethod,
-    ) -> (SenderLoop, SimulatorLoop, SimulatorThreads) {
+        bundle_simulation_config: Option<BundleSimulationConfig>,
+    ) -> Result<(SenderLoop, SimulatorLoop, SimulatorThreads), SimulateError> {
         let parent_slot = self.parent_slot().unwrap();
-        let mut packet_batches_by_time = self.banking_trace_events.packet_batches_by_time;
+        let mut batches_by_time = self.banking_trace_events.batches_by_time;
         let freeze_time_by_slot = self.banking_trace_events.freeze_time_by_slot;
         let bank = bank_forks.read().unwrap().working_bank_with_scheduler();
 
@@ -690,6 +786,16 @@ impl BankingSimulator {
             "Simulated leader and slot: {}, {}",
             simulated_leader, self.first_simulated_slot,
         );
+        // BundleStage cranks the tip programs with the identity, so it has to be the leader
+        if let Some(bundle_simulation_config) = &bundle_simulation_config {
+            let identity = bundle_simulation_config.identity_keypair.pubkey();
+            if identity != simulated_leader {
+                return Err(SimulateError::IdentityMismatch {
+                    identity,
+                    simulated_leader,
+                });
+            }
+        }
 
         let exit = Arc::new(AtomicBool::default());
 
@@ -804,6 +910,62 @@ impl BankingSimulator {
             id: simulated_leader.into(),
         });
         let prioritization_fee_cache = &Arc::new(PrioritizationFeeCache::new(0u64));
+        let bundle_account_locker = BundleAccountLocker::default();
+        let (bundle_sender, bundle_stage) = match bundle_simulation_config {
+            Some(BundleSimulationConfig {
+                identity_keypair,
+                tip_manager_config,
+            }) => {
+                info!("Start bundle stage!...");
+                let (bundle_sender, bundle_receiver) = unbounded();
+                let block_builder_fee_info = Arc::new(Mutex::new(BlockBuilderFeeInfo {
+                    block_builder: identity_keypair.pubkey(),
+                    block_builder_commission: 0,
+                }));
+                // BundleStage needs the real identity to crank the tip programs as the leader
+                let cluster_info = Arc::new(ClusterInfo::new(
+                    Node::new_localhost_with_pubkey(&identity_keypair.pubkey()).info,
+                    identity_keypair,
+                    SocketAddrSpace::Unspecified,
+                ));
+                let bundle_stage = BundleStage::new(
+                    &cluster_info,
+                    &poh_recorder,
+                    bundle_receiver,
+                    None,
+                    None,
+                    replay_vote_sender.clone(),
+                    None,
+                    exit.clone(),
+                    TipManager::new(tip_manager_config),
+                    bundle_account_locker.clone(),
+                    &block_builder_fee_info,
+                    prioritization_fee_cache,
+                    None,
+                    DEFAULT_NUM_BUNDLE_CONSUME_WORKERS,
+                    None,
+                    Arc::default(),
+                    Arc::default(),
+                    Arc::default(),
+                    retracer.clone(),
+                );
+                (Some(bundle_sender), Some(bundle_stage))
+            }
+            None => {
+                let num_bundle_batches = batches_by_time
+                    .values()
+                    .filter(|batch| matches!(batch, TracedBatch::Bundles(_)))
+                    .count();
+                if num_bundle_batches > 0 {
+                    warn!(
+                        "ignoring {num_bundle_batches} traced bundle batches without a bundle \
+                         simulation config"
+                    );
+                    batches_by_time.retain(|_, batch| !matches!(batch, TracedBatch::Bundles(_)));
+                }
+                (None, None)
+            }
+        };
         let banking_stage = BankingStage::new_num_threads(
             block_production_method.clone(),
             &cluster_info,
@@ -813,6 +975,7 @@ impl BankingSimulator {
             gossip_vote_receiver,
             BankingStage::num_threads(),
             None,
+            None,
             replay_vote_sender,
             None,
             connection_cache,
@@ -820,7 +983,7 @@ impl BankingSimulator {
             prioritization_fee_cache,
             false,
             collections::HashSet::default(),
-            BundleAccountLocker::default(),
+            bundle_account_locker,
             |_| 0,
         );
 
@@ -830,16 +993,11 @@ impl BankingSimulator {
             .expect("timed hashes");
         let base_event_time = raw_base_event_time - WARMUP_DURATION;
 
-        let total_batch_count = packet_batches_by_time.len();
-        let timed_batches_to_send = packet_batches_by_time.split_off(&base_event_time);
+        let total_batch_count = batches_by_time.len();
+        let timed_batches_to_send = batches_by_time.split_off(&base_event_time);
         let batch_and_tx_counts = timed_batches_to_send
             .values()
-            .map(|(_label, batches)| {
-                (
-                    batches.len(),
-                    batches.iter().map(|batch| batch.len()).sum::<usize>(),
-                )
-            })
+            .map(TracedBatch::batch_and_tx_counts)
             .collect::<Vec<_>>();
         // Convert to a large plain old Vec and drain on it, finally dropping it outside
         // the simulation loop to avoid jitter due to interleaved deallocs of BTreeMap.
@@ -857,6 +1015,7 @@ impl BankingSimulator {
             non_vote_sender,
             tpu_vote_sender,
             gossip_vote_sender,
+            bundle_sender,
             exit: exit.clone(),
             raw_base_event_time,
             total_batch_count,
@@ -881,12 +1040,13 @@ impl BankingSimulator {
         let simulator_threads = SimulatorThreads {
             poh_service,
             banking_stage,
+            bundle_stage,
             broadcast_stage,
             retracer_thread,
             exit,
         };
 
-        (sender_loop, simulator_loop, simulator_threads)
+        Ok((sender_loop, simulator_loop, simulator_threads))
     }
 
     pub fn start(
@@ -895,13 +1055,15 @@ impl BankingSimulator {
         bank_forks: Arc<RwLock<BankForks>>,
         blockstore: Arc<Blockstore>,
         block_production_method: BlockProductionMethod,
+        bundle_simulation_config: Option<BundleSimulationConfig>,
     ) -> Result<(), SimulateError> {
         let (sender_loop, simulator_loop, simulator_threads) = self.prepare_simulation(
             genesis_config,
             bank_forks,
             blockstore,
             block_production_method,
-        );
+            bundle_simulation_config,
+        )?;
 
         sender_loop.log_starting();
         let base_simulation_time = SystemTime::now();
@@ -924,3 +1086,51 @@ impl BankingSimulator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_ledger::{
+            genesis_utils::{create_genesis_config, GenesisConfigInfo},
+            get_tmp_ledger_path_auto_delete,
+        },
+    };
+
+    #[test]
+    fn test_bundle_simulation_identity_mismatch() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank_forks = BankForks::new_rw_arc(Bank::new_for_tests(&genesis_config));
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+
+        let banking_trace_events = BankingTraceEvents {
+            freeze_time_by_slot: FreezeTimeBySlot::from([(0, SystemTime::now())]),
+            ..BankingTraceEvents::default()
+        };
+        let simulator = BankingSimulator::new(banking_trace_events, 1);
+        let simulated_leader =
+            LeaderScheduleCache::new_from_bank(&bank_forks.read().unwrap().root_bank())
+                .slot_leader_at(1, None)
+                .unwrap();
+
+        let identity_keypair = Arc::new(Keypair::new());
+        let identity = identity_keypair.pubkey();
+        assert_matches!(
+            simulator.start(
+                genesis_config,
+                bank_forks,
+                blockstore,
+                BlockProductionMethod::default(),
+                Some(BundleSimulationConfig {
+                    identity_keypair,
+                    tip_manager_config: TipManagerConfig::default(),
+                }),
+            ),
+            Err(SimulateError::IdentityMismatch {
+                identity: mismatched_identity,
+                simulated_leader: mismatched_leader,
+            }) if mismatched_identity == identity && mismatched_leader == simulated_leader
+        );
+    }
+}
@@ -19,6 +19,7 @@ use {
             consume_worker::ConsumeWorker,
             packet_deserializer::PacketDeserializer,
             transaction_scheduler::{
+                banking_transaction_notification::spawn_banking_transaction_notifier,
                 prio_graph_scheduler::PrioGraphScheduler,
                 scheduler_controller::SchedulerController, scheduler_error::SchedulerError,
             },
@@ -30,6 +31,7 @@ use {
     crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender},
     histogram::Histogram,
     solana_client::connection_cache::ConnectionCache,
+    solana_geyser_plugin_manager::banking_transaction_notifier_interface::BankingTransactionNotifierArc,
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
     solana_ledger::blockstore_processor::TransactionStatusSender,
     solana_measure::measure_us,
@@ -358,6 +360,7 @@ impl BankingStage {
         tpu_vote_receiver: BankingPacketReceiver,
         gossip_vote_receiver: BankingPacketReceiver,
         transaction_status_sender: Option<TransactionStatusSender>,
+        banking_transaction_notifier: Option<BankingTransactionNotifierArc>,
         replay_vote_sender: ReplayVoteSender,
         log_messages_bytes_limit: Option<usize>,
         connection_cache: Arc<ConnectionCache>,
@@ -378,6 +381,7 @@ impl BankingStage {
             gossip_vote_receiver,
             Self::num_threads(),
             transaction_status_sender,
+            banking_transaction_notifier,
             replay_vote_sender,
             log_messages_bytes_limit,
             connection_cache,
@@ -400,6 +404,7 @@ impl BankingStage {
         gossip_vote_receiver: BankingPacketReceiver,
         num_threads: u32,
         transaction_status_sender: Option<TransactionStatusSender>,
+        banking_transaction_notifier: Option<BankingTransactionNotifierArc>,
         replay_vote_sender: ReplayVoteSender,
         log_messages_bytes_limit: Option<usize>,
         connection_cache: Arc<ConnectionCache>,
@@ -419,6 +424,7 @@ impl BankingStage {
                 gossip_vote_receiver,
                 num_threads,
                 transaction_status_sender,
+                banking_transaction_notifier,
                 replay_vote_sender,
                 log_messages_bytes_limit,
                 connection_cache,
@@ -441,6 +447,7 @@ impl BankingStage {
         gossip_vote_receiver: BankingPacketReceiver,
         num_threads: u32,
         transaction_status_sender: Option<TransactionStatusSender>,
+        banking_transaction_notifier: Option<BankingTransactionNotifierArc>,
         replay_vote_sender: ReplayVoteSender,
         log_messages_bytes_limit: Option<usize>,
         connection_cache: Arc<ConnectionCache>,
@@ -549,6 +556,16 @@ impl BankingStage {
             )
         });
 
+        // Only the transactions buffered by the central scheduler are notified, the legacy voting
+        // threads don't track the votes they process.
+        let notification_sender = banking_transaction_notifier.map(|notifier| {
+            warn!("Banking transaction notifications are enabled, vote transactions are excluded");
+            let (notification_sender, notifier_thread_hdl) =
+                spawn_banking_transaction_notifier(notifier);
+            bank_thread_hdls.push(notifier_thread_hdl);
+            notification_sender
+        });
+
         // Spawn the central scheduler thread
         bank_thread_hdls.push({
             let packet_deserializer = PacketDeserializer::new(non_vote_receiver);
@@ -569,6 +586,7 @@ impl BankingStage {
                 scheduler,
                 worker_metrics,
                 forwarder,
+                notification_sender,
             );
             Builder::new()
                 .name("solBnkTxSched".to_string())
@@ -846,6 +864,7 @@ mod tests {
                 tpu_vote_receiver,
                 gossip_vote_receiver,
                 None,
+                None,
                 replay_vote_sender,
                 None,
                 Arc::new(ConnectionCache::new("connection_cache_test")),
@@ -909,6 +928,7 @@ mod tests {
                 tpu_vote_receiver,
                 gossip_vote_receiver,
                 None,
+                None,
                 replay_vote_sender,
                 None,
                 Arc::new(ConnectionCache::new("connection_cache_test")),
@@ -1001,6 +1021,7 @@ mod tests {
                 tpu_vote_receiver,
                 gossip_vote_receiver,
                 None,
+                None,
                 replay_vote_sender,
                 None,
                 Arc::new(ConnectionCache::new("connection_cache_test")),
@@ -1179,6 +1200,7 @@ mod tests {
                     tpu_vote_receiver,
                     gossip_vote_receiver,
                     None,
+                    None,
                     replay_vote_sender,
                     None,
                     Arc::new(ConnectionCache::new("connection_cache_test")),
@@ -1387,6 +1409,7 @@ mod tests {
                 tpu_vote_receiver,
                 gossip_vote_receiver,
                 None,
+                None,
                 replay_vote_sender,
                 None,
                 Arc::new(ConnectionCache::new("connection_cache_test")),
@@ -1,8 +1,9 @@
 use {
     super::{
+        committer::CommitTransactionDetails,
         consumer::{Consumer, ExecuteAndCommitTransactionsOutput, ProcessTransactionBatchOutput},
         leader_slot_timing_metrics::LeaderExecuteAndCommitTimings,
-        scheduler_messages::{ConsumeWork, FinishedConsumeWork},
+        scheduler_messages::{ConsumeWork, FinishedConsumeWork, TransactionOutcome},
     },
     crossbeam_channel::{Receiver, RecvError, SendError, Sender},
     solana_measure::measure_us,
@@ -124,11 +125,30 @@ impl<Tx: TransactionWithMeta> ConsumeWorker<Tx> {
         self.metrics.update_for_consume(&output);
         self.metrics.has_data.store(true, Ordering::Relaxed);
 
+        let ExecuteAndCommitTransactionsOutput {
+            retryable_transaction_indexes,
+            commit_transactions_result,
+            transaction_results,
+            ..
+        } = output.execute_and_commit_transactions_output;
+        let commit_transaction_details = commit_transactions_result.unwrap_or_default();
+        let outcomes = transaction_results
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| TransactionOutcome {
+                committed: matches!(
+                    commit_transaction_details.get(index),
+                    Some(CommitTransactionDetails::Committed { .. })
+                ),
+                result,
+            })
+            .collect();
+
         self.consumed_sender.send(FinishedConsumeWork {
             work,
-            retryable_indexes: output
-                .execute_and_commit_transactions_output
-                .retryable_transaction_indexes,
+            retryable_indexes: retryable_transaction_indexes,
+            slot: Some(bank.slot()),
+            outcomes,
         })?;
         Ok(())
     }
@@ -164,6 +184,8 @@ impl<Tx: TransactionWithMeta> ConsumeWorker<Tx> {
         self.consumed_sender.send(FinishedConsumeWork {
             work,
             retryable_indexes,
+            slot: None,
+            outcomes: vec![],
         })?;
         Ok(())
     }
@@ -938,6 +960,8 @@ mod tests {
         assert_eq!(consumed.work.ids, vec![id]);
         assert_eq!(consumed.work.max_ages, vec![max_age]);
         assert_eq!(consumed.retryable_indexes, vec![0]);
+        assert_eq!(consumed.slot, None);
+        assert!(consumed.outcomes.is_empty());
 
         drop(test_frame);
         let _ = worker_thread.join().unwrap();
@@ -987,6 +1011,14 @@ mod tests {
         assert_eq!(consumed.work.ids, vec![id]);
         assert_eq!(consumed.work.max_ages, vec![max_age]);
         assert_eq!(consumed.retryable_indexes, Vec::<usize>::new());
+        assert_eq!(consumed.slot, Some(bank.slot()));
+        assert_eq!(
+            consumed.outcomes,
+            vec![TransactionOutcome {
+                committed: true,
+                result: Ok(()),
+            }]
+        );
 
         drop(test_frame);
         let _ = worker_thread.join().unwrap();
@@ -70,6 +70,9 @@ pub struct ExecuteAndCommitTransactionsOutput {
     // A result that indicates whether transactions were successfully
     // committed into the Poh stream.
     pub commit_transactions_result: Result<Vec<CommitTransactionDetails>, PohRecorderError>,
+    // The result of processing each transaction, reported to geyser plugins: why it wasn't
+    // processed, or the execution error of a processed transaction.
+    pub(crate) transaction_results: Vec<transaction::Result<()>>,
     pub(crate) execute_and_commit_timings: LeaderExecuteAndCommitTimings,
     pub(crate) error_counters: TransactionErrorMetrics,
     pub(crate) min_prioritization_fees: u64,
@@ -683,6 +686,11 @@ impl Consumer {
             processed_counts,
         } = load_and_execute_transactions_output;
 
+        let transaction_results = processing_results
+            .iter()
+            .map(|processing_result| processing_result.flattened_result())
+            .collect();
+
         let transaction_counts = LeaderProcessedTransactionCounts {
             processed_count: processed_counts.processed_transactions_count,
             processed_with_successful_result_count: processed_counts
@@ -732,6 +740,7 @@ impl Consumer {
                 transaction_counts,
                 retryable_transaction_indexes,
                 commit_transactions_result: Err(recorder_err),
+                transaction_results,
                 execute_and_commit_timings,
                 error_counters,
                 min_prioritization_fees,
@@ -782,6 +791,7 @@ impl Consumer {
             transaction_counts,
             retryable_transaction_indexes,
             commit_transactions_result: Ok(commit_transaction_statuses),
+            transaction_results,
             execute_and_commit_timings,
             error_counters,
             min_prioritization_fees,
@@ -105,6 +105,11 @@ impl ImmutableDeserializedPacket {
         &self.transaction
     }
 
+    /// The first signature of the transaction, identifying it
+    pub fn signature(&self) -> Signature {
+        packet_signature(&self.original_packet).unwrap_or_default()
+    }
+
     pub fn message_hash(&self) -> &Hash {
         &self.message_hash
     }
@@ -204,6 +209,16 @@ fn packet_message(packet: &Packet) -> Result<&[u8], DeserializedPacketError> {
         .ok_or(DeserializedPacketError::SignatureOverflowed(sig_size))
 }
 
+/// Read the first signature from packet data
+fn packet_signature(packet: &Packet) -> Option<Signature> {
+    let (_sig_len, sig_size) = packet
+        .data(..)
+        .and_then(|bytes| decode_shortu16_len(bytes).ok())?;
+    packet
+        .data(sig_size..sig_size.checked_add(size_of::<Signature>())?)
+        .and_then(|bytes| Signature::try_from(bytes).ok())
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -222,10 +237,11 @@ mod tests {
             1,
             Hash::new_unique(),
         );
-        let packet = Packet::from_data(None, tx).unwrap();
+        let packet = Packet::from_data(None, &tx).unwrap();
         let deserialized_packet = ImmutableDeserializedPacket::new(packet);
 
         assert!(deserialized_packet.is_ok());
+        assert_eq!(deserialized_packet.unwrap().signature(), tx.signatures[0]);
     }
 
     #[test]
@@ -1,5 +1,8 @@
 use {
-    solana_sdk::clock::{Epoch, Slot},
+    solana_sdk::{
+        clock::{Epoch, Slot},
+        transaction,
+    },
     std::fmt::Display,
 };
 
@@ -43,9 +46,21 @@ pub struct ConsumeWork<Tx> {
     pub max_ages: Vec<MaxAge>,
 }
 
+/// The outcome of processing a transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionOutcome {
+    pub committed: bool,
+    /// The execution error of a committed transaction, or why it wasn't committed.
+    pub result: transaction::Result<()>,
+}
+
 /// Message: [Worker -> Scheduler]
 /// Processed transactions.
 pub struct FinishedConsumeWork<Tx> {
     pub work: ConsumeWork<Tx>,
     pub retryable_indexes: Vec<usize>,
+    /// The slot the work was processed in, `None` if it was retried without processing.
+    pub slot: Option<Slot>,
+    /// The outcome of each transaction, empty if the work wasn't processed.
+    pub outcomes: Vec<TransactionOutcome>,
 }
@@ -0,0 +1,195 @@
+//! Geyser plugins are notified of the transactions leaving the scheduler on a dedicated thread,
+//! so slow plugins don't hold up scheduling. Notifications are dropped, and counted, when the
+//! plugins fall behind.
+use {
+    agave_geyser_plugin_interface::geyser_plugin_interface::BankingTransactionDisposition,
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender},
+    solana_geyser_plugin_manager::banking_transaction_notifier_interface::BankingTransactionNotifierArc,
+    solana_sdk::{
+        clock::Slot, saturating_add_assign, signature::Signature, transaction::TransactionError,
+    },
+    std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread::{Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+/// Notifications waiting to be sent to the plugins, about a second's worth at full load
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 100_000;
+const METRICS_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+pub(crate) struct BankingTransactionNotification {
+    pub(crate) signature: Signature,
+    pub(crate) slot: Option<Slot>,
+    pub(crate) received_timestamp_us: u64,
+    pub(crate) scheduled_timestamp_us: Option<u64>,
+    pub(crate) disposition: BankingTransactionDisposition,
+    pub(crate) error: Option<TransactionError>,
+}
+
+#[derive(Clone)]
+pub(crate) struct BankingTransactionNotificationSender {
+    sender: Sender<BankingTransactionNotification>,
+    num_dropped: Arc<AtomicUsize>,
+}
+
+impl BankingTransactionNotificationSender {
+    pub(crate) fn new(capacity: usize) -> (Self, Receiver<BankingTransactionNotification>) {
+        let (sender, receiver) = bounded(capacity);
+        let sender = Self {
+            sender,
+            num_dropped: Arc::default(),
+        };
+        (sender, receiver)
+    }
+
+    /// Queues `notification` for the notifier thread, dropping it if the queue is full
+    pub(crate) fn send(&self, notification: BankingTransactionNotification) {
+        if self.sender.try_send(notification).is_err() {
+            self.num_dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Spawns the thread notifying `notifier` of the notifications queued on the returned sender.
+/// The thread exits once every sender is dropped.
+pub(crate) fn spawn_banking_transaction_notifier(
+    notifier: BankingTransactionNotifierArc,
+) -> (BankingTransactionNotificationSender, JoinHandle<()>) {
+    let (sender, receiver) =
+        BankingTransactionNotificationSender::new(NOTIFICATION_CHANNEL_CAPACITY);
+    let num_dropped = sender.num_dropped.clone();
+    let thread_hdl = Builder::new()
+        .name("solBnkTxNotify".to_string())
+        .spawn(move || run_notifier(receiver, notifier, num_dropped))
+        .unwrap();
+    (sender, thread_hdl)
+}
+
+fn run_notifier(
+    receiver: Receiver<BankingTransactionNotification>,
+    notifier: BankingTransactionNotifierArc,
+    num_dropped: Arc<AtomicUsize>,
+) {
+    let mut num_notified: usize = 0;
+    let mut last_report = Instant::now();
+    loop {
+        match receiver.recv_timeout(METRICS_REPORT_INTERVAL) {
+            Ok(notification) => {
+                notifier.notify_banking_transaction(
+                    &notification.signature,
+                    notification.slot,
+                    notification.received_timestamp_us,
+                    notification.scheduled_timestamp_us,
+                    notification.disposition,
+                    notification.error.as_ref(),
+                );
+                saturating_add_assign!(num_notified, 1);
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if last_report.elapsed() >= METRICS_REPORT_INTERVAL {
+            datapoint_info!(
+                "banking_transaction_notifier",
+                ("num_notified", num_notified, i64),
+                ("num_dropped", num_dropped.swap(0, Ordering::Relaxed), i64),
+                ("queue_len", receiver.len(), i64),
+            );
+            num_notified = 0;
+            last_report = Instant::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_geyser_plugin_manager::banking_transaction_notifier_interface::BankingTransactionNotifier,
+        std::sync::Mutex,
+    };
+
+    #[derive(Default)]
+    struct TestBankingTransactionNotifier {
+        notifications: Mutex<Vec<(Signature, BankingTransactionDisposition)>>,
+    }
+
+    impl BankingTransactionNotifier for TestBankingTransactionNotifier {
+        fn notify_banking_transaction(
+            &self,
+            signature: &Signature,
+            _slot: Option<Slot>,
+            _received_timestamp_us: u64,
+            _scheduled_timestamp_us: Option<u64>,
+            disposition: BankingTransactionDisposition,
+            _error: Option<&TransactionError>,
+        ) {
+            self.notifications
+                .lock()
+                .unwrap()
+                .push((*signature, disposition));
+        }
+    }
+
+    fn notification(
+        signature: Signature,
+        disposition: BankingTransactionDisposition,
+    ) -> BankingTransactionNotification {
+        BankingTransactionNotification {
+            signature,
+            slot: None,
+            received_timestamp_us: 0,
+            scheduled_timestamp_us: None,
+            disposition,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_send_drops_when_full() {
+        let (sender, receiver) = BankingTransactionNotificationSender::new(1);
+        let signature = Signature::new_unique();
+        sender.send(notification(
+            signature,
+            BankingTransactionDisposition::Committed,
+        ));
+        sender.send(notification(
+            Signature::new_unique(),
+            BankingTransactionDisposition::Evicted,
+        ));
+
+        assert_eq!(sender.num_dropped.load(Ordering::Relaxed), 1);
+        assert_eq!(receiver.len(), 1);
+        assert_eq!(receiver.try_recv().unwrap().signature, signature);
+    }
+
+    #[test]
+    fn test_notifier_thread() {
+        let notifier = Arc::new(TestBankingTransactionNotifier::default());
+        let (sender, thread_hdl) = spawn_banking_transaction_notifier(notifier.clone());
+        let notifications = [
+            (
+                Signature::new_unique(),
+                BankingTransactionDisposition::Retried,
+            ),
+            (
+                Signature::new_unique(),
+                BankingTransactionDisposition::Expired,
+            ),
+        ];
+        for (signature, disposition) in notifications {
+            sender.send(notification(signature, disposition));
+        }
+
+        // the thread exits once the queued notifications are sent and the sender is dropped
+        drop(sender);
+        thread_hdl.join().unwrap();
+        assert_eq!(*notifier.notifications.lock().unwrap(), notifications);
+    }
+}
@@ -1,3 +1,4 @@
+pub(crate) mod banking_transaction_notification;
 mod batch_id_generator;
 mod in_flight_tracker;
 pub(crate) mod prio_graph_scheduler;
@@ -12,17 +12,19 @@ use {
             ConsumeWork, FinishedConsumeWork, MaxAge, TransactionBatchId, TransactionId,
         },
         transaction_scheduler::{
-            transaction_priority_id::TransactionPriorityId, transaction_state::TransactionState,
-            transaction_state_container::StateContainer,
+            transaction_priority_id::TransactionPriorityId,
+            transaction_state::TransactionState,
+            transaction_state_container::{disposition_for_error, StateContainer},
         },
     },
+    agave_geyser_plugin_interface::geyser_plugin_interface::BankingTransactionDisposition,
     crossbeam_channel::{Receiver, Sender, TryRecvError},
     itertools::izip,
     prio_graph::{AccessKind, GraphNode, PrioGraph},
     solana_cost_model::block_cost_limits::MAX_BLOCK_UNITS,
     solana_measure::measure_us,
     solana_runtime_transaction::transaction_with_meta::TransactionWithMeta,
-    solana_sdk::{pubkey::Pubkey, saturating_add_assign},
+    solana_sdk::{pubkey::Pubkey, saturating_add_assign, transaction::TransactionError},
     solana_svm_transaction::svm_message::SVMMessage,
 };
 
@@ -90,7 +92,7 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
     /// error.
     /// `pre_graph_filter` is used to filter out transactions that should be
     /// skipped and dropped before insertion to the prio-graph. This fn should
-    /// set `false` for transactions that should be dropped, and `true`
+    /// set the error of transactions that should be dropped, and `Ok(())`
     /// otherwise.
     /// `pre_lock_filter` is used to filter out transactions after they have
     /// made it to the top of the prio-graph, and immediately before locks are
@@ -104,7 +106,7 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
     pub(crate) fn schedule<S: StateContainer<Tx>>(
         &mut self,
         container: &mut S,
-        pre_graph_filter: impl Fn(&[&Tx], &mut [bool]),
+        pre_graph_filter: impl Fn(&[&Tx], &mut [Result<(), TransactionError>]),
         pre_lock_filter: impl Fn(&Tx) -> bool,
     ) -> Result<SchedulingSummary, SchedulerError> {
         let num_threads = self.consume_work_senders.len();
@@ -143,7 +145,8 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
                                 window_budget: &mut usize| {
             while *window_budget > 0 {
                 const MAX_FILTER_CHUNK_SIZE: usize = 128;
-                let mut filter_array = [true; MAX_FILTER_CHUNK_SIZE];
+                let mut filter_array: [Result<(), TransactionError>; MAX_FILTER_CHUNK_SIZE] =
+                    core::array::from_fn(|_| Ok(()));
                 let mut ids = Vec::with_capacity(MAX_FILTER_CHUNK_SIZE);
                 let mut txs = Vec::with_capacity(MAX_FILTER_CHUNK_SIZE);
 
@@ -167,15 +170,20 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
                 saturating_add_assign!(total_filter_time_us, filter_us);
 
                 for (id, filter_result) in ids.iter().zip(&filter_array[..chunk_size]) {
-                    if *filter_result {
+                    if let Err(err) = filter_result {
+                        saturating_add_assign!(num_filtered_out, 1);
+                        container.remove_by_id(
+                            id.id,
+                            None,
+                            disposition_for_error(err, BankingTransactionDisposition::Dropped),
+                            Some(err),
+                        );
+                    } else {
                         let transaction = container.get_transaction_ttl(id.id).unwrap();
                         prio_graph.insert_transaction(
                             *id,
                             Self::get_transaction_account_access(transaction),
                         );
-                    } else {
-                        saturating_add_assign!(num_filtered_out, 1);
-                        container.remove_by_id(id.id);
                     }
                 }
 
@@ -229,7 +237,12 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
 
                 match maybe_schedule_info {
                     Err(TransactionSchedulingError::Filtered) => {
-                        container.remove_by_id(id.id);
+                        container.remove_by_id(
+                            id.id,
+                            None,
+                            BankingTransactionDisposition::Dropped,
+                            None,
+                        );
                     }
                     Err(TransactionSchedulingError::UnschedulableConflicts) => {
                         unschedulable_ids.push(id);
@@ -353,6 +366,8 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
                         max_ages,
                     },
                 retryable_indexes,
+                slot,
+                outcomes,
             }) => {
                 let num_transactions = ids.len();
                 let num_retryable = retryable_indexes.len();
@@ -365,6 +380,8 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
                 for (index, (id, transaction, max_age)) in
                     izip!(ids, transactions, max_ages).enumerate()
                 {
+                    let outcome = outcomes.get(index);
+                    let error = outcome.and_then(|outcome| outcome.result.as_ref().err());
                     if let Some(retryable_index) = retryable_iter.peek() {
                         if *retryable_index == index {
                             container.retry_transaction(
@@ -373,12 +390,24 @@ impl<Tx: TransactionWithMeta> PrioGraphScheduler<Tx> {
                                     transaction,
                                     max_age,
                                 },
+                                slot,
+                                error,
                             );
                             retryable_iter.next();
                             continue;
                         }
                     }
-                    container.remove_by_id(id);
+                    let disposition = if outcome.is_some_and(|outcome| outcome.committed) {
+                        BankingTransactionDisposition::Committed
+                    } else {
+                        error.map_or(BankingTransactionDisposition::NotCommitted, |error| {
+                            disposition_for_error(
+                                error,
+                                BankingTransactionDisposition::NotCommitted,
+                            )
+                        })
+                    };
+                    container.remove_by_id(id, slot, disposition, error);
                 }
 
                 Ok((num_transactions, num_retryable))
@@ -700,7 +729,7 @@ mod tests {
             ),
         >,
     ) -> TransactionStateContainer<RuntimeTransaction<SanitizedTransaction>> {
-        let mut container = TransactionStateContainer::with_capacity(10 * 1024);
+        let mut container = TransactionStateContainer::with_capacity(10 * 1024, None);
         for (from_keypair, to_pubkeys, lamports, compute_unit_price) in tx_infos.into_iter() {
             let transaction = prioritized_tranfers(
                 from_keypair.borrow(),
@@ -747,9 +776,9 @@ mod tests {
 
     fn test_pre_graph_filter(
         _txs: &[&RuntimeTransaction<SanitizedTransaction>],
-        results: &mut [bool],
+        results: &mut [Result<(), TransactionError>],
     ) {
-        results.fill(true);
+        results.fill(Ok(()));
     }
 
     fn test_pre_lock_filter(_tx: &RuntimeTransaction<SanitizedTransaction>) -> bool {
@@ -894,6 +923,8 @@ mod tests {
             .send(FinishedConsumeWork {
                 work: thread_0_work.into_iter().next().unwrap(),
                 retryable_indexes: vec![],
+                slot: None,
+                outcomes: vec![],
             })
             .unwrap();
         scheduler.receive_completed(&mut container).unwrap();
@@ -15,15 +15,20 @@ use {
         consumer::Consumer,
         decision_maker::{BufferedPacketsDecision, DecisionMaker},
         forwarder::Forwarder,
-        transaction_scheduler::transaction_state_container::StateContainer,
+        transaction_scheduler::{
+            banking_transaction_notification::BankingTransactionNotificationSender,
+            transaction_state_container::{disposition_for_error, StateContainer},
+        },
         ForwardOption, LikeClusterInfo, TOTAL_BUFFERED_PACKETS,
     },
+    agave_geyser_plugin_interface::geyser_plugin_interface::BankingTransactionDisposition,
     solana_measure::measure_us,
     solana_runtime::{bank::Bank, bank_forks::BankForks},
     solana_sdk::{
         self,
         clock::{FORWARD_TRANSACTIONS_TO_LEADER_AT_SLOT_OFFSET, MAX_PROCESSING_AGE},
         saturating_add_assign,
+        transaction::TransactionError,
     },
     solana_svm::transaction_error_metrics::TransactionErrorMetrics,
     std::{
@@ -65,12 +70,13 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
         scheduler: PrioGraphScheduler<R::Transaction>,
         worker_metrics: Vec<Arc<ConsumeWorkerMetrics>>,
         forwarder: Option<Forwarder<C>>,
+        notification_sender: Option<BankingTransactionNotificationSender>,
     ) -> Self {
         Self {
             decision_maker,
             receive_and_buffer,
             bank_forks,
-            container: R::Container::with_capacity(TOTAL_BUFFERED_PACKETS),
+            container: R::Container::with_capacity(TOTAL_BUFFERED_PACKETS, notification_sender),
             scheduler,
             leader_detection_metrics: SchedulerLeaderDetectionMetrics::default(),
             count_metrics: SchedulerCountMetrics::default(),
@@ -207,7 +213,7 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
 
     fn pre_graph_filter(
         transactions: &[&R::Transaction],
-        results: &mut [bool],
+        results: &mut [Result<(), TransactionError>],
         bank: &Bank,
         max_age: usize,
     ) {
@@ -226,8 +232,7 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
             .zip(results.iter_mut())
         {
             *result = check_result
-                .and_then(|_| Consumer::check_fee_payer_unlocked(bank, *tx, &mut error_counters))
-                .is_ok();
+                .and_then(|_| Consumer::check_fee_payer_unlocked(bank, *tx, &mut error_counters));
         }
     }
 
@@ -246,7 +251,8 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
         let mut ids_to_add_back = Vec::new();
         let mut max_time_reached = false;
         while !self.container.is_empty() {
-            let mut filter_array = [true; CHUNK_SIZE];
+            let mut filter_array: [Result<(), TransactionError>; CHUNK_SIZE] =
+                core::array::from_fn(|_| Ok(()));
             let mut ids = Vec::with_capacity(CHUNK_SIZE);
             let mut txs = Vec::with_capacity(CHUNK_SIZE);
 
@@ -274,8 +280,13 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
             );
 
             for (id, filter_result) in ids.iter().zip(&filter_array[..chunk_size]) {
-                if !*filter_result {
-                    self.container.remove_by_id(id.id);
+                if let Err(err) = filter_result {
+                    self.container.remove_by_id(
+                        id.id,
+                        None,
+                        disposition_for_error(err, BankingTransactionDisposition::Dropped),
+                        Some(err),
+                    );
                     continue;
                 }
 
@@ -312,7 +323,12 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
         // leader slot.
         if max_time_reached {
             while let Some(id) = self.container.pop() {
-                self.container.remove_by_id(id.id);
+                self.container.remove_by_id(
+                    id.id,
+                    None,
+                    BankingTransactionDisposition::Cleared,
+                    None,
+                );
             }
         }
 
@@ -322,7 +338,17 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
             }
         } else {
             for priority_id in ids_to_add_back {
-                self.container.remove_by_id(priority_id.id);
+                let forwarded = self
+                    .container
+                    .get_mut_transaction_state(priority_id.id)
+                    .is_some_and(|state| state.forwarded());
+                let disposition = if forwarded {
+                    BankingTransactionDisposition::Forwarded
+                } else {
+                    BankingTransactionDisposition::Cleared
+                };
+                self.container
+                    .remove_by_id(priority_id.id, None, disposition, None);
             }
         }
 
@@ -336,7 +362,8 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
     fn clear_container(&mut self) {
         let mut num_dropped_on_clear: usize = 0;
         while let Some(id) = self.container.pop() {
-            self.container.remove_by_id(id.id);
+            self.container
+                .remove_by_id(id.id, None, BankingTransactionDisposition::Cleared, None);
             saturating_add_assign!(num_dropped_on_clear, 1);
         }
 
@@ -384,9 +411,14 @@ impl<C: LikeClusterInfo, R: ReceiveAndBuffer> SchedulerController<C, R> {
             );
 
             for (result, id) in check_results.into_iter().zip(chunk.iter()) {
-                if result.is_err() {
+                if let Err(err) = result {
                     saturating_add_assign!(num_dropped_on_age_and_status, 1);
-                    self.container.remove_by_id(id.id);
+                    self.container.remove_by_id(
+                        id.id,
+                        None,
+                        disposition_for_error(&err, BankingTransactionDisposition::Dropped),
+                        Some(&err),
+                    );
                 } else {
                     self.container.push_id_into_queue(*id);
                 }
@@ -564,6 +596,7 @@ mod tests {
             scheduler,
             vec![], // no actual workers with metrics to report, this can be empty
             None,
+            None,
         );
 
         (test_frame, scheduler_controller)
@@ -639,6 +672,8 @@ mod tests {
                     max_ages: vec![],
                 },
                 retryable_indexes: vec![],
+                slot: None,
+                outcomes: vec![],
             })
             .unwrap();
 
@@ -952,6 +987,8 @@ mod tests {
             .send(FinishedConsumeWork {
                 work: consume_work,
                 retryable_indexes: vec![1],
+                slot: None,
+                outcomes: vec![],
             })
             .unwrap();
 
@@ -2,7 +2,10 @@ use {
     crate::banking_stage::{
         immutable_deserialized_packet::ImmutableDeserializedPacket, scheduler_messages::MaxAge,
     },
-    std::sync::Arc,
+    std::{
+        sync::Arc,
+        time::{SystemTime, UNIX_EPOCH},
+    },
 };
 
 /// Simple wrapper type to tie a sanitized transaction to max age slot.
@@ -30,6 +33,9 @@ pub(crate) struct SanitizedTransactionTTL<Tx> {
 ///   internal `SanitizedTransaction` is moved out of the `TransactionState` and sent
 ///   to the appropriate thread for processing. This is done to avoid cloning the
 ///  `SanitizedTransaction`.
+///
+/// The times the transaction was received and last scheduled are tracked, in
+///   microseconds since the UNIX epoch, to be reported to geyser plugins.
 #[allow(clippy::large_enum_variant)]
 pub(crate) enum TransactionState<Tx> {
     /// The transaction is available for scheduling.
@@ -39,6 +45,8 @@ pub(crate) enum TransactionState<Tx> {
         priority: u64,
         cost: u64,
         should_forward: bool,
+        received_timestamp_us: u64,
+        scheduled_timestamp_us: Option<u64>,
     },
     /// The transaction is currently scheduled or being processed.
     Pending {
@@ -46,6 +54,8 @@ pub(crate) enum TransactionState<Tx> {
         priority: u64,
         cost: u64,
         should_forward: bool,
+        received_timestamp_us: u64,
+        scheduled_timestamp_us: Option<u64>,
     },
     /// Only used during transition.
     Transitioning,
@@ -59,17 +69,24 @@ impl<Tx> TransactionState<Tx> {
         priority: u64,
         cost: u64,
     ) -> Self {
-        let should_forward = !packet.original_packet().meta().forwarded()
-            && packet.original_packet().meta().is_from_staked_node();
+        let should_forward = Self::is_forwardable(&packet);
         Self::Unprocessed {
             transaction_ttl,
             packet,
             priority,
             cost,
             should_forward,
+            received_timestamp_us: timestamp_us(),
+            scheduled_timestamp_us: None,
         }
     }
 
+    /// Return whether the packet can be forwarded by this node.
+    fn is_forwardable(packet: &ImmutableDeserializedPacket) -> bool {
+        !packet.original_packet().meta().forwarded()
+            && packet.original_packet().meta().is_from_staked_node()
+    }
+
     /// Return the priority of the transaction.
     /// This is *not* the same as the `compute_unit_price` of the transaction.
     /// The priority is used to order transactions for processing.
@@ -105,6 +122,11 @@ impl<Tx> TransactionState<Tx> {
         }
     }
 
+    /// Return whether the packet was forwarded by this node.
+    pub(crate) fn forwarded(&self) -> bool {
+        Self::is_forwardable(self.packet()) && !self.should_forward()
+    }
+
     /// Mark the packet as forwarded.
     /// This is used to prevent the packet from being forwarded multiple times.
     pub(crate) fn mark_forwarded(&mut self) {
@@ -124,6 +146,36 @@ impl<Tx> TransactionState<Tx> {
         }
     }
 
+    /// Return when the transaction was received.
+    pub(crate) fn received_timestamp_us(&self) -> u64 {
+        match self {
+            Self::Unprocessed {
+                received_timestamp_us,
+                ..
+            } => *received_timestamp_us,
+            Self::Pending {
+                received_timestamp_us,
+                ..
+            } => *received_timestamp_us,
+            Self::Transitioning => unreachable!(),
+        }
+    }
+
+    /// Return when the transaction was last scheduled, if ever.
+    pub(crate) fn scheduled_timestamp_us(&self) -> Option<u64> {
+        match self {
+            Self::Unprocessed {
+                scheduled_timestamp_us,
+                ..
+            } => *scheduled_timestamp_us,
+            Self::Pending {
+                scheduled_timestamp_us,
+                ..
+            } => *scheduled_timestamp_us,
+            Self::Transitioning => unreachable!(),
+        }
+    }
+
     /// Intended to be called when a transaction is scheduled. This method will
     /// transition the transaction from `Unprocessed` to `Pending` and return the
     /// `SanitizedTransactionTTL` for processing.
@@ -139,12 +191,16 @@ impl<Tx> TransactionState<Tx> {
                 priority,
                 cost,
                 should_forward: forwarded,
+                received_timestamp_us,
+                scheduled_timestamp_us: _,
             } => {
                 *self = TransactionState::Pending {
                     packet,
                     priority,
                     cost,
                     should_forward: forwarded,
+                    received_timestamp_us,
+                    scheduled_timestamp_us: Some(timestamp_us()),
                 };
                 transaction_ttl
             }
@@ -172,6 +228,8 @@ impl<Tx> TransactionState<Tx> {
                 priority,
                 cost,
                 should_forward: forwarded,
+                received_timestamp_us,
+                scheduled_timestamp_us,
             } => {
                 *self = Self::Unprocessed {
                     transaction_ttl,
@@ -179,6 +237,8 @@ impl<Tx> TransactionState<Tx> {
                     priority,
                     cost,
                     should_forward: forwarded,
+                    received_timestamp_us,
+                    scheduled_timestamp_us,
                 }
             }
             Self::Transitioning => unreachable!(),
@@ -206,6 +266,14 @@ impl<Tx> TransactionState<Tx> {
     }
 }
 
+/// Microseconds since the UNIX epoch.
+fn timestamp_us() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_micros() as u64)
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -321,6 +389,28 @@ mod tests {
         assert_eq!(transaction_state.priority(), priority);
     }
 
+    #[test]
+    fn test_timestamps() {
+        let mut transaction_state = create_transaction_state(0);
+        let received_timestamp_us = transaction_state.received_timestamp_us();
+        assert!(received_timestamp_us > 0);
+        assert_eq!(transaction_state.scheduled_timestamp_us(), None);
+
+        // scheduling records the time, which is kept on retry
+        let transaction_ttl = transaction_state.transition_to_pending();
+        let scheduled_timestamp_us = transaction_state.scheduled_timestamp_us().unwrap();
+        assert!(scheduled_timestamp_us >= received_timestamp_us);
+        transaction_state.transition_to_unprocessed(transaction_ttl);
+        assert_eq!(
+            transaction_state.received_timestamp_us(),
+            received_timestamp_us
+        );
+        assert_eq!(
+            transaction_state.scheduled_timestamp_us(),
+            Some(scheduled_timestamp_us)
+        );
+    }
+
     #[test]
     #[should_panic(expected = "transaction is pending")]
     fn test_transaction_ttl_panic() {
@@ -1,5 +1,8 @@
 use {
     super::{
+        banking_transaction_notification::{
+            BankingTransactionNotification, BankingTransactionNotificationSender,
+        },
         transaction_priority_id::TransactionPriorityId,
         transaction_state::{SanitizedTransactionTTL, TransactionState},
     },
@@ -7,10 +10,12 @@ use {
         immutable_deserialized_packet::ImmutableDeserializedPacket,
         scheduler_messages::TransactionId,
     },
+    agave_geyser_plugin_interface::geyser_plugin_interface::BankingTransactionDisposition,
     itertools::MinMaxResult,
     min_max_heap::MinMaxHeap,
     slab::Slab,
     solana_runtime_transaction::transaction_with_meta::TransactionWithMeta,
+    solana_sdk::{clock::Slot, transaction::TransactionError},
     std::sync::Arc,
 };
 
@@ -39,14 +44,21 @@ use {
 ///
 /// The container maintains a fixed capacity. If the queue is full when pushing
 /// a new transaction, the lowest priority transaction will be dropped.
+///
+/// Geyser plugins are notified of every transaction removed from the container,
+/// along with how it left BankingStage, and of every retried transaction.
 pub(crate) struct TransactionStateContainer<Tx: TransactionWithMeta> {
     priority_queue: MinMaxHeap<TransactionPriorityId>,
     id_to_transaction_state: Slab<TransactionState<Tx>>,
+    notification_sender: Option<BankingTransactionNotificationSender>,
 }
 
 pub(crate) trait StateContainer<Tx: TransactionWithMeta> {
     /// Create a new `TransactionStateContainer` with the given capacity.
-    fn with_capacity(capacity: usize) -> Self;
+    fn with_capacity(
+        capacity: usize,
+        notification_sender: Option<BankingTransactionNotificationSender>,
+    ) -> Self;
 
     /// Returns true if the queue is empty.
     fn is_empty(&self) -> bool;
@@ -77,10 +89,13 @@ pub(crate) trait StateContainer<Tx: TransactionWithMeta> {
 
     /// Retries a transaction - inserts transaction back into map (but not packet).
     /// This transitions the transaction to `Unprocessed` state.
+    /// Geyser plugins are notified of the retry, with the slot it was attempted in.
     fn retry_transaction(
         &mut self,
         transaction_id: TransactionId,
         transaction_ttl: SanitizedTransactionTTL<Tx>,
+        slot: Option<Slot>,
+        error: Option<&TransactionError>,
     );
 
     /// Pushes a transaction id into the priority queue. If the queue is full, the lowest priority
@@ -88,20 +103,31 @@ pub(crate) trait StateContainer<Tx: TransactionWithMeta> {
     /// Returns `true` if a packet was dropped due to capacity limits.
     fn push_id_into_queue(&mut self, priority_id: TransactionPriorityId) -> bool;
 
-    /// Remove transaction by id.
-    fn remove_by_id(&mut self, id: TransactionId);
+    /// Remove transaction by id, notifying geyser plugins of its `disposition`.
+    /// `slot` is the slot the transaction was processed in, if it was.
+    fn remove_by_id(
+        &mut self,
+        id: TransactionId,
+        slot: Option<Slot>,
+        disposition: BankingTransactionDisposition,
+        error: Option<&TransactionError>,
+    );
 
     fn get_min_max_priority(&self) -> MinMaxResult<u64>;
 }
 
 impl<Tx: TransactionWithMeta> StateContainer<Tx> for TransactionStateContainer<Tx> {
-    fn with_capacity(capacity: usize) -> Self {
+    fn with_capacity(
+        capacity: usize,
+        notification_sender: Option<BankingTransactionNotificationSender>,
+    ) -> Self {
         // Extra capacity is added because some additional space is needed when
         // pushing a new transaction into the container to avoid reallocation.
         const EXTRA_CAPACITY: usize = 64;
         Self {
             priority_queue: MinMaxHeap::with_capacity(capacity),
             id_to_transaction_state: Slab::with_capacity(capacity + EXTRA_CAPACITY),
+            notification_sender,
         }
     }
 
@@ -161,10 +187,20 @@ impl<Tx: TransactionWithMeta> StateContainer<Tx> for TransactionStateContainer<T
         &mut self,
         transaction_id: TransactionId,
         transaction_ttl: SanitizedTransactionTTL<Tx>,
+        slot: Option<Slot>,
+        error: Option<&TransactionError>,
     ) {
         let transaction_state = self
-            .get_mut_transaction_state(transaction_id)
+            .id_to_transaction_state
+            .get_mut(transaction_id)
             .expect("transaction must exist");
+        Self::notify(
+            &self.notification_sender,
+            transaction_state,
+            slot,
+            BankingTransactionDisposition::Retried,
+            error,
+        );
         let priority_id = TransactionPriorityId::new(transaction_state.priority(), transaction_id);
         transaction_state.transition_to_unprocessed(transaction_ttl);
         self.push_id_into_queue(priority_id);
@@ -174,8 +210,21 @@ impl<Tx: TransactionWithMeta> StateContainer<Tx> for TransactionStateContainer<T
         self.push_id_into_queue_with_remaining_capacity(priority_id, self.remaining_capacity())
     }
 
-    fn remove_by_id(&mut self, id: TransactionId) {
-        self.id_to_transaction_state.remove(id);
+    fn remove_by_id(
+        &mut self,
+        id: TransactionId,
+        slot: Option<Slot>,
+        disposition: BankingTransactionDisposition,
+        error: Option<&TransactionError>,
+    ) {
+        let transaction_state = self.id_to_transaction_state.remove(id);
+        Self::notify(
+            &self.notification_sender,
+            &transaction_state,
+            slot,
+            disposition,
+            error,
+        );
     }
 
     fn get_min_max_priority(&self) -> MinMaxResult<u64> {
@@ -190,6 +239,25 @@ impl<Tx: TransactionWithMeta> StateContainer<Tx> for TransactionStateContainer<T
 }
 
 impl<Tx: TransactionWithMeta> TransactionStateContainer<Tx> {
+    fn notify(
+        notification_sender: &Option<BankingTransactionNotificationSender>,
+        transaction_state: &TransactionState<Tx>,
+        slot: Option<Slot>,
+        disposition: BankingTransactionDisposition,
+        error: Option<&TransactionError>,
+    ) {
+        if let Some(notification_sender) = notification_sender {
+            notification_sender.send(BankingTransactionNotification {
+                signature: transaction_state.packet().signature(),
+                slot,
+                received_timestamp_us: transaction_state.received_timestamp_us(),
+                scheduled_timestamp_us: transaction_state.scheduled_timestamp_us(),
+                disposition,
+                error: error.cloned(),
+            });
+        }
+    }
+
     fn push_id_into_queue_with_remaining_capacity(
         &mut self,
         priority_id: TransactionPriorityId,
@@ -197,7 +265,12 @@ impl<Tx: TransactionWithMeta> TransactionStateContainer<Tx> {
     ) -> bool {
         if remaining_capacity == 0 {
             let popped_id = self.priority_queue.push_pop_min(priority_id);
-            self.remove_by_id(popped_id.id);
+            self.remove_by_id(
+                popped_id.id,
+                None,
+                BankingTransactionDisposition::Evicted,
+                None,
+            );
             true
         } else {
             self.priority_queue.push(priority_id);
@@ -206,6 +279,25 @@ impl<Tx: TransactionWithMeta> TransactionStateContainer<Tx> {
     }
 }
 
+/// The disposition of a transaction leaving BankingStage because of `error`, `otherwise`
+/// unless it expired or was dropped by the cost model.
+pub(crate) fn disposition_for_error(
+    error: &TransactionError,
+    otherwise: BankingTransactionDisposition,
+) -> BankingTransactionDisposition {
+    match error {
+        TransactionError::BlockhashNotFound => BankingTransactionDisposition::Expired,
+        TransactionError::WouldExceedMaxBlockCostLimit
+        | TransactionError::WouldExceedMaxVoteCostLimit
+        | TransactionError::WouldExceedMaxAccountCostLimit
+        | TransactionError::WouldExceedAccountDataBlockLimit
+        | TransactionError::WouldExceedAccountDataTotalLimit => {
+            BankingTransactionDisposition::DroppedByCostModel
+        }
+        _ => otherwise,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -270,7 +362,7 @@ mod tests {
 
     #[test]
     fn test_is_empty() {
-        let mut container = TransactionStateContainer::with_capacity(1);
+        let mut container = TransactionStateContainer::with_capacity(1, None);
         assert!(container.is_empty());
 
         push_to_container(&mut container, 1);
@@ -279,7 +371,7 @@ mod tests {
 
     #[test]
     fn test_priority_queue_capacity() {
-        let mut container = TransactionStateContainer::with_capacity(1);
+        let mut container = TransactionStateContainer::with_capacity(1, None);
         push_to_container(&mut container, 5);
 
         assert_eq!(container.priority_queue.len(), 1);
@@ -297,7 +389,7 @@ mod tests {
 
     #[test]
     fn test_get_mut_transaction_state() {
-        let mut container = TransactionStateContainer::with_capacity(5);
+        let mut container = TransactionStateContainer::with_capacity(5, None);
         push_to_container(&mut container, 5);
 
         let existing_id = 3;
@@ -308,4 +400,98 @@ mod tests {
             .get_mut_transaction_state(non_existing_id)
             .is_none());
     }
+
+    #[test]
+    fn test_notify_transactions() {
+        let (notification_sender, notification_receiver) =
+            BankingTransactionNotificationSender::new(10);
+        let mut container = TransactionStateContainer::with_capacity(1, Some(notification_sender));
+
+        // the lower priority transaction is evicted when the container is full
+        let (transaction_ttl, packet, priority, cost) = test_transaction(0);
+        let evicted_signature = packet.signature();
+        assert!(!container.insert_new_transaction(transaction_ttl, packet, priority, cost));
+        let (transaction_ttl, packet, priority, cost) = test_transaction(1);
+        let signature = packet.signature();
+        assert!(container.insert_new_transaction(transaction_ttl, packet, priority, cost));
+
+        let id = container.pop().unwrap().id;
+        let transaction_ttl = container
+            .get_mut_transaction_state(id)
+            .unwrap()
+            .transition_to_pending();
+        container.retry_transaction(
+            id,
+            transaction_ttl,
+            Some(1),
+            Some(&TransactionError::AccountInUse),
+        );
+
+        let id = container.pop().unwrap().id;
+        container.remove_by_id(
+            id,
+            None,
+            disposition_for_error(
+                &TransactionError::BlockhashNotFound,
+                BankingTransactionDisposition::Dropped,
+            ),
+            Some(&TransactionError::BlockhashNotFound),
+        );
+        assert!(container.is_empty());
+
+        let notifications: Vec<_> = notification_receiver
+            .try_iter()
+            .map(|notification| {
+                (
+                    notification.signature,
+                    notification.slot,
+                    notification.disposition,
+                    notification.error,
+                )
+            })
+            .collect();
+        assert_eq!(
+            notifications,
+            vec![
+                (
+                    evicted_signature,
+                    None,
+                    BankingTransactionDisposition::Evicted,
+                    None
+                ),
+                (
+                    signature,
+                    Some(1),
+                    BankingTransactionDisposition::Retried,
+                    Some(TransactionError::AccountInUse)
+                ),
+                (
+                    signature,
+                    None,
+                    BankingTransactionDisposition::Expired,
+                    Some(TransactionError::BlockhashNotFound)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disposition_for_error() {
+        let otherwise = BankingTransactionDisposition::NotCommitted;
+        assert_eq!(
+            disposition_for_error(&TransactionError::BlockhashNotFound, otherwise),
+            BankingTransactionDisposition::Expired
+        );
+        assert_eq!(
+            disposition_for_error(
+                &TransactionError::WouldExceedAccountDataTotalLimit,
+                otherwise
+            ),
+            BankingTransactionDisposition::DroppedByCostModel
+        );
+        assert_eq!(
+            disposition_for_error(&TransactionError::AlreadyProcessed, otherwise),
+            otherwise
+        );
+    }
 }
@@ -16,8 +16,12 @@ use {
         BankingStageStats, FilterForwardingResults, ForwardOption,
     },
     crate::{
-        bundle_stage::bundle_stage_leader_metrics::BundleStageLeaderMetrics,
-        immutable_deserialized_bundle::ImmutableDeserializedBundle,
+        bundle_stage::{
+            bundle_policy::BundlePolicy,
+            bundle_priority_queue::{BundlePriorityQueue, InsertBundleOutcome},
+            bundle_stage_leader_metrics::BundleStageLeaderMetrics,
+        },
+        immutable_deserialized_bundle::{DeserializedBundleError, ImmutableDeserializedBundle},
     },
     itertools::Itertools,
     min_max_heap::MinMaxHeap,
@@ -38,7 +42,7 @@ use {
     },
     solana_svm::transaction_error_metrics::TransactionErrorMetrics,
     std::{
-        collections::{HashMap, HashSet, VecDeque},
+        collections::{HashMap, HashSet},
         sync::{atomic::Ordering, Arc},
         time::Instant,
     },
@@ -291,15 +295,17 @@ impl UnprocessedTransactionStorage {
         })
     }
 
-    pub fn new_bundle_storage() -> Self {
+    /// Bundles are prioritized by the lamports they transfer to `tip_accounts` per compute unit
+    pub fn new_bundle_storage(tip_accounts: HashSet<Pubkey>) -> Self {
         Self::BundleStorage(BundleStorage {
             last_update_slot: Slot::default(),
-            unprocessed_bundle_storage: VecDeque::with_capacity(
+            unprocessed_bundle_storage: BundlePriorityQueue::with_capacity(
                 BundleStorage::BUNDLE_STORAGE_CAPACITY,
             ),
-            cost_model_buffered_bundle_storage: VecDeque::with_capacity(
+            cost_model_buffered_bundle_storage: BundlePriorityQueue::with_capacity(
                 BundleStorage::BUNDLE_STORAGE_CAPACITY,
             ),
+            tip_accounts,
         })
     }
 
@@ -490,6 +496,7 @@ impl UnprocessedTransactionStorage {
         bank: Arc<Bank>,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
         blacklisted_accounts: &HashSet<Pubkey>,
+        bundle_policy: &BundlePolicy,
         processing_function: F,
     ) -> bool
     where
@@ -504,6 +511,7 @@ impl UnprocessedTransactionStorage {
                     bank,
                     bundle_stage_leader_metrics,
                     blacklisted_accounts,
+                    bundle_policy,
                     processing_function,
                 ),
             _ => panic!("class does not support processing bundles"),
@@ -1122,22 +1130,33 @@ pub struct InsertPacketBundlesSummary {
     pub insert_packets_summary: InsertPacketBatchSummary,
     pub num_bundles_inserted: usize,
     pub num_packets_inserted: usize,
+    /// Includes duplicates and lower priority bundles evicted to make room
     pub num_bundles_dropped: usize,
+    pub num_duplicate_bundles: usize,
 }
 
-/// Bundle storage has two deques: one for unprocessed bundles and another for ones that exceeded
-/// the cost model and need to get retried next slot.
+/// Bundle storage has two priority queues: one for unprocessed bundles and another for ones that
+/// exceeded the cost model and need to get retried next slot. Bundles are ordered by their estimated
+/// tip per compute unit so the most valuable bundles are executed first and the least valuable ones
+/// are evicted when the storage is full.
 #[derive(Debug)]
 pub struct BundleStorage {
     last_update_slot: Slot,
-    unprocessed_bundle_storage: VecDeque<ImmutableDeserializedBundle>,
+    unprocessed_bundle_storage: BundlePriorityQueue,
     // Storage for bundles that exceeded the cost model for the slot they were last attempted
     // execution on
-    cost_model_buffered_bundle_storage: VecDeque<ImmutableDeserializedBundle>,
+    cost_model_buffered_bundle_storage: BundlePriorityQueue,
+    // Transfers to these accounts are counted as tips when prioritizing bundles
+    tip_accounts: HashSet<Pubkey>,
 }
 
 impl BundleStorage {
     pub const BUNDLE_STORAGE_CAPACITY: usize = 1000;
+    // Bundles keep being received when the storage is full so higher tip bundles can evict the
+    // lowest tip ones
+    const MIN_BUNDLE_RECEIVE_SIZE: usize = 100;
+    const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
     fn is_empty(&self) -> bool {
         self.unprocessed_bundle_storage.is_empty()
     }
@@ -1147,10 +1166,7 @@ impl BundleStorage {
     }
 
     pub fn unprocessed_packets_len(&self) -> usize {
-        self.unprocessed_bundle_storage
-            .iter()
-            .map(|b| b.len())
-            .sum::<usize>()
+        self.unprocessed_bundle_storage.num_packets()
     }
 
     pub(crate) fn cost_model_buffered_bundles_len(&self) -> usize {
@@ -1158,14 +1174,14 @@ impl BundleStorage {
     }
 
     pub(crate) fn cost_model_buffered_packets_len(&self) -> usize {
-        self.cost_model_buffered_bundle_storage
-            .iter()
-            .map(|b| b.len())
-            .sum()
+        self.cost_model_buffered_bundle_storage.num_packets()
     }
 
     pub(crate) fn max_receive_size(&self) -> usize {
-        self.unprocessed_bundle_storage.capacity() - self.unprocessed_bundle_storage.len()
+        self.unprocessed_bundle_storage
+            .capacity()
+            .saturating_sub(self.unprocessed_bundle_storage.len())
+            .max(Self::MIN_BUNDLE_RECEIVE_SIZE)
     }
 
     fn forward_option(&self) -> ForwardOption {
@@ -1174,45 +1190,85 @@ impl BundleStorage {
 
     /// Returns the number of unprocessed bundles + cost model buffered cleared
     pub fn reset(&mut self) -> (usize, usize) {
-        let num_unprocessed_bundles = self.unprocessed_bundle_storage.len();
-        let num_cost_model_buffered_bundles = self.cost_model_buffered_bundle_storage.len();
-        self.unprocessed_bundle_storage.clear();
-        self.cost_model_buffered_bundle_storage.clear();
+        let num_unprocessed_bundles = self.unprocessed_bundle_storage.clear();
+        let num_cost_model_buffered_bundles = self.cost_model_buffered_bundle_storage.clear();
         (num_unprocessed_bundles, num_cost_model_buffered_bundles)
     }
 
+    /// Sum of the compute unit limits requested by the buffered bundles, including the ones that
+    /// didn't fit in the last slot, with an estimated tip of at least `min_micro_lamports_per_cu`
+    pub(crate) fn buffered_compute_unit_limit(&self, min_micro_lamports_per_cu: u64) -> u64 {
+        self.unprocessed_bundle_storage
+            .compute_unit_limit(min_micro_lamports_per_cu)
+            .saturating_add(
+                self.cost_model_buffered_bundle_storage
+                    .compute_unit_limit(min_micro_lamports_per_cu),
+            )
+    }
+
+    /// Estimated tip per compute unit in micro-lamports
+    fn bundle_priority(&self, bundle: &ImmutableDeserializedBundle) -> u64 {
+        let tip_lamports = u128::from(bundle.estimated_tip_lamports(&self.tip_accounts));
+        let compute_unit_limit = u128::from(bundle.compute_unit_limit().max(1));
+        u64::try_from(
+            tip_lamports.saturating_mul(Self::MICRO_LAMPORTS_PER_LAMPORT) / compute_unit_limit,
+        )
+        .unwrap_or(u64::MAX)
+    }
+
     fn insert_bundles(
-        deque: &mut VecDeque<ImmutableDeserializedBundle>,
+        &mut self,
         deserialized_bundles: Vec<ImmutableDeserializedBundle>,
-        push_back: bool,
+        push_front: bool,
+        cost_model_buffered: bool,
     ) -> InsertPacketBundlesSummary {
-        // deque should be initialized with size [Self::BUNDLE_STORAGE_CAPACITY]
-        let deque_free_space = Self::BUNDLE_STORAGE_CAPACITY
-            .checked_sub(deque.len())
-            .unwrap();
-        let bundles_to_insert_count = std::cmp::min(deque_free_space, deserialized_bundles.len());
-        let num_bundles_dropped = deserialized_bundles
-            .len()
-            .checked_sub(bundles_to_insert_count)
-            .unwrap();
-        let num_packets_inserted = deserialized_bundles
-            .iter()
-            .take(bundles_to_insert_count)
-            .map(|b| b.len())
-            .sum::<usize>();
-        let num_packets_dropped = deserialized_bundles
-            .iter()
-            .skip(bundles_to_insert_count)
-            .map(|b| b.len())
-            .sum::<usize>();
+        let mut num_bundles_inserted: usize = 0;
+        let mut num_packets_inserted: usize = 0;
+        let mut num_bundles_dropped: usize = 0;
+        let mut num_packets_dropped: usize = 0;
+        let mut num_duplicate_bundles: usize = 0;
+
+        for bundle in deserialized_bundles {
+            let priority = self.bundle_priority(&bundle);
+            let (queue, other_queue) = if cost_model_buffered {
+                (
+                    &mut self.cost_model_buffered_bundle_storage,
+                    &self.unprocessed_bundle_storage,
+                )
+            } else {
+                (
+                    &mut self.unprocessed_bundle_storage,
+                    &self.cost_model_buffered_bundle_storage,
+                )
+            };
 
-        let to_insert = deserialized_bundles
-            .into_iter()
-            .take(bundles_to_insert_count);
-        if push_back {
-            deque.extend(to_insert)
-        } else {
-            to_insert.for_each(|b| deque.push_front(b));
+            let num_packets = bundle.len();
+            let outcome = if other_queue.contains(bundle.bundle_id()) {
+                InsertBundleOutcome::Duplicate
+            } else {
+                queue.insert(bundle, priority, push_front)
+            };
+            match outcome {
+                InsertBundleOutcome::Inserted => {
+                    saturating_add_assign!(num_bundles_inserted, 1);
+                    saturating_add_assign!(num_packets_inserted, num_packets);
+                }
+                InsertBundleOutcome::InsertedWithEviction(evicted) => {
+                    saturating_add_assign!(num_bundles_inserted, 1);
+                    saturating_add_assign!(num_packets_inserted, num_packets);
+                    saturating_add_assign!(num_bundles_dropped, 1);
+                    saturating_add_assign!(num_packets_dropped, evicted.len());
+                }
+                InsertBundleOutcome::Duplicate => {
+                    saturating_add_assign!(num_duplicate_bundles, 1);
+                    saturating_add_assign!(num_bundles_dropped, 1);
+                    saturating_add_assign!(num_packets_dropped, num_packets);
+                }
+                InsertBundleOutcome::Dropped => {
+                    saturating_add_assign!(num_bundles_dropped, 1);
+                    saturating_add_assign!(num_packets_dropped, num_packets);
+                }
+            }
         }
 
         InsertPacketBundlesSummary {
@@ -1220,9 +1276,10 @@ impl BundleStorage {
                 num_dropped_packets: num_packets_dropped,
             }
             .into(),
-            num_bundles_inserted: bundles_to_insert_count,
+            num_bundles_inserted,
             num_packets_inserted,
             num_bundles_dropped,
+            num_duplicate_bundles,
         }
     }
 
@@ -1230,22 +1287,14 @@ impl BundleStorage {
         &mut self,
         deserialized_bundles: Vec<ImmutableDeserializedBundle>,
     ) -> InsertPacketBundlesSummary {
-        Self::insert_bundles(
-            &mut self.unprocessed_bundle_storage,
-            deserialized_bundles,
-            false,
-        )
+        self.insert_bundles(deserialized_bundles, true, false)
     }
 
     fn push_back_cost_model_buffered_bundles(
         &mut self,
         deserialized_bundles: Vec<ImmutableDeserializedBundle>,
     ) -> InsertPacketBundlesSummary {
-        Self::insert_bundles(
-            &mut self.cost_model_buffered_bundle_storage,
-            deserialized_bundles,
-            true,
-        )
+        self.insert_bundles(deserialized_bundles, false, true)
     }
 
     fn insert_unprocessed_bundles(
@@ -1253,11 +1302,7 @@ impl BundleStorage {
         deserialized_bundles: Vec<ImmutableDeserializedBundle>,
         push_back: bool,
     ) -> InsertPacketBundlesSummary {
-        Self::insert_bundles(
-            &mut self.unprocessed_bundle_storage,
-            deserialized_bundles,
-            push_back,
-        )
+        self.insert_bundles(deserialized_bundles, !push_back, false)
     }
 
     /// Drains bundles from the queue, sanitizes them to prepare for execution, executes them by
@@ -1267,6 +1312,7 @@ impl BundleStorage {
         bank: Arc<Bank>,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
         blacklisted_accounts: &HashSet<Pubkey>,
+        bundle_policy: &BundlePolicy,
         mut processing_function: F,
     ) -> bool
     where
@@ -1279,6 +1325,7 @@ impl BundleStorage {
             bank,
             bundle_stage_leader_metrics,
             blacklisted_accounts,
+            bundle_policy,
         );
 
         debug!("processing {} bundles", sanitized_bundles.len());
@@ -1351,7 +1398,8 @@ impl BundleStorage {
                 },
             );
 
-        // rebuffered bundles are pushed onto deque in reverse order so the first bundle is at the front
+        // rebuffered bundles are pushed to the front in reverse order so the first bundle stays ahead of
+        // any others with the same priority
         for bundle in rebuffered_bundles.into_iter().rev() {
             self.push_front_unprocessed_bundles(vec![bundle]);
         }
@@ -1359,74 +1407,74 @@ impl BundleStorage {
         is_slot_over
     }
 
-    /// Drains the unprocessed_bundle_storage, converting bundle packets into SanitizedBundles
+    /// Drains the unprocessed_bundle_storage in priority order, converting bundle packets into SanitizedBundles
     fn drain_and_sanitize_bundles(
         &mut self,
         bank: Arc<Bank>,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
         blacklisted_accounts: &HashSet<Pubkey>,
+        bundle_policy: &BundlePolicy,
     ) -> Vec<(ImmutableDeserializedBundle, SanitizedBundle)> {
         let mut error_metrics = TransactionErrorMetrics::default();
 
         let start = Instant::now();
 
-        let mut sanitized_bundles = Vec::new();
-
-        // on new slot, drain anything that was buffered from last slot
+        // on new slot, move anything that was buffered from last slot back in with the unprocessed
+        // bundles so everything is executed in priority order. they're pushed to the front in reverse
+        // so they stay ahead of newer bundles with the same priority.
         if bank.slot() != self.last_update_slot {
-            sanitized_bundles.extend(
-                self.cost_model_buffered_bundle_storage
-                    .drain(..)
-                    .filter_map(|packet_bundle| {
-                        let r = packet_bundle.build_sanitized_bundle(
-                            &bank,
-                            blacklisted_accounts,
-                            &mut error_metrics,
-                        );
-                        bundle_stage_leader_metrics
-                            .bundle_stage_metrics_tracker()
-                            .increment_sanitize_transaction_result(&r);
-
-                        match r {
-                            Ok(sanitized_bundle) => Some((packet_bundle, sanitized_bundle)),
-                            Err(e) => {
-                                debug!(
-                                    "bundle id: {} error sanitizing: {}",
-                                    packet_bundle.bundle_id(),
-                                    e
-                                );
-                                None
-                            }
-                        }
-                    }),
-            );
+            let mut cost_model_buffered_bundles =
+                self.cost_model_buffered_bundle_storage.drain_by_priority();
+            cost_model_buffered_bundles.reverse();
+            self.push_front_unprocessed_bundles(cost_model_buffered_bundles);
 
             self.last_update_slot = bank.slot();
         }
 
-        sanitized_bundles.extend(self.unprocessed_bundle_storage.drain(..).filter_map(
-            |packet_bundle| {
-                let r = packet_bundle.build_sanitized_bundle(
-                    &bank,
-                    blacklisted_accounts,
-                    &mut error_metrics,
-                );
+        let sanitized_bundles: Vec<_> = self
+            .unprocessed_bundle_storage
+            .drain_by_priority()
+            .into_iter()
+            .filter_map(|packet_bundle| {
+                let r = packet_bundle
+                    .build_sanitized_bundle(&bank, blacklisted_accounts, &mut error_metrics)
+                    .and_then(|sanitized_bundle| {
+                        Self::check_allowed_to_fail_transactions(
+                            &sanitized_bundle,
+                            &self.tip_accounts,
+                        )?;
+                        Ok(sanitized_bundle)
+                    });
                 bundle_stage_leader_metrics
                     .bundle_stage_metrics_tracker()
                     .increment_sanitize_transaction_result(&r);
-                match r {
-                    Ok(sanitized_bundle) => Some((packet_bundle, sanitized_bundle)),
+                let sanitized_bundle = match r {
+                    Ok(sanitized_bundle) => sanitized_bundle,
                     Err(e) => {
                         debug!(
                             "bundle id: {} error sanitizing: {}",
                             packet_bundle.bundle_id(),
                             e
                         );
-                        None
+                        return None;
                     }
+                };
+                if let Err(e) =
+                    bundle_policy.check(&sanitized_bundle, packet_bundle.compute_unit_limit())
+                {
+                    bundle_stage_leader_metrics
+                        .bundle_stage_metrics_tracker()
+                        .increment_bundle_policy_violation(&e);
+                    debug!(
+                        "bundle id: {} rejected by bundle policy: {}",
+                        packet_bundle.bundle_id(),
+                        e
+                    );
+                    return None;
                 }
-            },
-        ));
+                Some((packet_bundle, sanitized_bundle))
+            })
+            .collect();
 
         let elapsed = start.elapsed().as_micros();
         bundle_stage_leader_metrics
@@ -1442,6 +1490,33 @@ impl BundleStorage {
 
         sanitized_bundles
     }
+
+    /// Transactions that are allowed to fail can't touch the tip accounts, otherwise the bundle
+    /// could land without paying its tip. Addresses loaded from lookup tables are included.
+    fn check_allowed_to_fail_transactions(
+        sanitized_bundle: &SanitizedBundle,
+        tip_accounts: &HashSet<Pubkey>,
+    ) -> Result<(), DeserializedBundleError> {
+        match sanitized_bundle
+            .allowed_to_fail_tx_indexes
+            .iter()
+            .filter(|index| {
+                sanitized_bundle
+                    .transactions
+                    .get(**index)
+                    .is_some_and(|tx| {
+                        tx.message()
+                            .account_keys()
+                            .iter()
+                            .any(|a| tip_accounts.contains(a))
+                    })
+            })
+            .min()
+        {
+            Some(index) => Err(DeserializedBundleError::TipTransactionAllowedToFail(*index)),
+            None => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1464,6 +1539,44 @@ mod tests {
         std::error::Error,
     };
 
+    #[test]
+    fn test_check_allowed_to_fail_transactions() {
+        let tip_account = Pubkey::new_unique();
+        let keypair = Keypair::new();
+        let transactions = vec![
+            RuntimeTransaction::from_transaction_for_tests(system_transaction::transfer(
+                &keypair,
+                &Pubkey::new_unique(),
+                1,
+                Hash::new_unique(),
+            )),
+            RuntimeTransaction::from_transaction_for_tests(system_transaction::transfer(
+                &keypair,
+                &tip_account,
+                1,
+                Hash::new_unique(),
+            )),
+        ];
+        let tip_accounts = HashSet::from([tip_account]);
+
+        let mut sanitized_bundle = SanitizedBundle {
+            transactions,
+            bundle_id: String::default(),
+            allowed_to_fail_tx_indexes: HashSet::from([0]),
+        };
+        assert!(BundleStorage::check_allowed_to_fail_transactions(
+            &sanitized_bundle,
+            &tip_accounts
+        )
+        .is_ok());
+
+        sanitized_bundle.allowed_to_fail_tx_indexes = HashSet::from([1]);
+        assert_matches!(
+            BundleStorage::check_allowed_to_fail_transactions(&sanitized_bundle, &tip_accounts),
+            Err(DeserializedBundleError::TipTransactionAllowedToFail(1))
+        );
+    }
+
     #[test]
     fn test_filter_processed_packets() {
         let retryable_indexes = [0, 1, 2, 3];
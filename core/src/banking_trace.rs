@@ -1,4 +1,5 @@
 use {
+    crate::packet_bundle::PacketBundle,
     bincode::serialize_into,
     chrono::{DateTime, Local},
     crossbeam_channel::{unbounded, Receiver, SendError, Sender, TryRecvError},
@@ -20,6 +21,7 @@ use {
 };
 
 pub type BankingPacketBatch = Arc<Vec<PacketBatch>>;
+pub type BankingBundleBatch = Arc<Vec<PacketBundle>>;
 pub type BankingPacketSender = TracedSender;
 pub type BankingPacketReceiver = Receiver<BankingPacketBatch>;
 pub type TracerThreadResult = Result<(), TraceError>;
@@ -69,6 +71,8 @@ pub struct TimedTracedEvent(pub std::time::SystemTime, pub TracedEvent);
 pub enum TracedEvent {
     PacketBatch(ChannelLabel, BankingPacketBatch),
     BlockAndBankHash(Slot, Hash, Hash),
+    /// Bundles as received by BundleStage; the event time is their arrival time
+    Bundle(ChannelLabel, BankingBundleBatch),
 }
 
 #[cfg_attr(feature = "frozen-abi", derive(AbiExample, AbiEnumVisitor))]
@@ -78,6 +82,7 @@ pub enum ChannelLabel {
     TpuVote,
     GossipVote,
     Dummy,
+    Bundle,
 }
 
 struct RollingConditionGrouped {
@@ -311,6 +316,18 @@ impl BankingTracer {
         })
     }
 
+    pub fn bundle_event(&self, bundles: &[PacketBundle]) {
+        self.trace_event(|| {
+            TimedTracedEvent(
+                SystemTime::now(),
+                TracedEvent::Bundle(
+                    ChannelLabel::Bundle,
+                    BankingBundleBatch::new(bundles.to_vec()),
+                ),
+            )
+        })
+    }
+
     fn trace_event(&self, on_trace: impl Fn() -> TimedTracedEvent) {
         if let Some(ActiveTracer { trace_sender, exit }) = &self.active_tracer {
             if !exit.load(Ordering::Relaxed) {
@@ -457,6 +474,15 @@ pub mod for_test {
         BankingPacketBatch::new(to_packet_batches(&vec![test_tx(); 4], 10))
     }
 
+    pub fn sample_packet_bundle() -> PacketBundle {
+        PacketBundle {
+            batch: to_packet_batches(&vec![test_tx(); 2], 10).remove(0),
+            bundle_id: "bundle".to_string(),
+            allowed_to_fail_tx_indexes: vec![],
+            source: None,
+        }
+    }
+
     pub fn drop_and_clean_temp_dir_unless_suppressed(temp_dir: TempDir) {
         std::env::var("BANKING_TRACE_LEAVE_FILES").is_ok().then(|| {
             warn!("prevented to remove {:?}", temp_dir.path());
@@ -580,6 +606,7 @@ mod tests {
         let blockhash = Hash::from_str("B1ockhash1111111111111111111111111111111111").unwrap();
         let bank_hash = Hash::from_str("BankHash11111111111111111111111111111111111").unwrap();
         tracer.hash_event(4, &blockhash, &bank_hash);
+        tracer.bundle_event(&[for_test::sample_packet_bundle()]);
 
         for_test::terminate_tracer(
             tracer,
@@ -611,6 +638,14 @@ mod tests {
             )) if actual_blockhash == blockhash && actual_bank_hash == bank_hash
         );
         i += 1;
+        assert_matches!(
+            results[i],
+            Ok(TimedTracedEvent(
+                _,
+                TracedEvent::Bundle(ChannelLabel::Bundle, ref bundles)
+            )) if bundles.len() == 1 && bundles[0].bundle_id == "bundle" && bundles[0].batch.len() == 2
+        );
+        i += 1;
         assert_matches!(
             results[i],
             Err(ref err) if matches!(
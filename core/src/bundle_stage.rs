@@ -7,16 +7,22 @@ use {
             qos_service::QosService,
             unprocessed_transaction_storage::UnprocessedTransactionStorage,
         },
+        banking_trace::BankingTracer,
         bundle_stage::{
             bundle_account_locker::BundleAccountLocker, bundle_consumer::BundleConsumer,
-            bundle_packet_receiver::BundleReceiver,
+            bundle_notification::spawn_bundle_notifier, bundle_packet_receiver::BundleReceiver,
+            bundle_policy::BundlePolicy, bundle_reservation::BundleReservation,
+            bundle_result::BundleResultSender,
             bundle_stage_leader_metrics::BundleStageLeaderMetrics, committer::Committer,
+            min_tip_policy::MinTipPolicy,
         },
         packet_bundle::PacketBundle,
         proxy::block_engine_stage::BlockBuilderFeeInfo,
         tip_manager::TipManager,
     },
     crossbeam_channel::{Receiver, RecvTimeoutError},
+    solana_bundle::bundle_simulation_cache::BundleSimulationCache,
+    solana_geyser_plugin_manager::bundle_notifier_interface::BundleNotifierArc,
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::blockstore_processor::TransactionStatusSender,
     solana_measure::measure_us,
@@ -37,12 +43,21 @@ use {
 
 pub mod bundle_account_locker;
 mod bundle_consumer;
+mod bundle_notification;
 mod bundle_packet_deserializer;
 mod bundle_packet_receiver;
+pub mod bundle_policy;
+mod bundle_priority_queue;
+pub mod bundle_reservation;
+pub mod bundle_result;
+mod bundle_scheduler;
 pub(crate) mod bundle_stage_leader_metrics;
 mod committer;
+pub mod min_tip_policy;
 
 const MAX_BUNDLE_RETRY_DURATION: Duration = Duration::from_millis(40);
+// Default number of workers that execute non-conflicting bundles in parallel
+pub const DEFAULT_NUM_BUNDLE_CONSUME_WORKERS: usize = 4;
 const SLOT_BOUNDARY_CHECK_PERIOD: Duration = Duration::from_millis(10);
 
 // Stats emitted periodically
@@ -69,6 +84,9 @@ pub struct BundleStageLoopMetrics {
     // number of bundles dropped during insertion
     num_bundles_dropped: AtomicU64,
 
+    // number of bundles dropped during insertion because they were already buffered
+    num_duplicate_bundles: AtomicU64,
+
     // timings
     receive_and_buffer_bundles_elapsed_us: AtomicU64,
     process_buffered_bundles_elapsed_us: AtomicU64,
@@ -121,6 +139,11 @@ impl BundleStageLoopMetrics {
         self.num_bundles_dropped.fetch_add(count, Ordering::Relaxed);
     }
 
+    pub fn increment_num_duplicate_bundles(&mut self, count: u64) {
+        self.num_duplicate_bundles
+            .fetch_add(count, Ordering::Relaxed);
+    }
+
     pub fn increment_receive_and_buffer_bundles_elapsed_us(&mut self, count: u64) {
         self.receive_and_buffer_bundles_elapsed_us
             .fetch_add(count, Ordering::Relaxed);
@@ -170,6 +193,11 @@ impl BundleStageLoopMetrics {
                     self.num_bundles_dropped.swap(0, Ordering::Acquire) as i64,
                     i64
                 ),
+                (
+                    "num_duplicate_bundles",
+                    self.num_duplicate_bundles.swap(0, Ordering::Acquire) as i64,
+                    i64
+                ),
                 (
                     "receive_and_buffer_bundles_elapsed_us",
                     self.receive_and_buffer_bundles_elapsed_us
@@ -189,6 +217,7 @@ impl BundleStageLoopMetrics {
 
 pub struct BundleStage {
     bundle_thread: JoinHandle<()>,
+    bundle_notifier_thread: Option<JoinHandle<()>>,
 }
 
 impl BundleStage {
@@ -199,6 +228,7 @@ impl BundleStage {
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         bundle_receiver: Receiver<Vec<PacketBundle>>,
         transaction_status_sender: Option<TransactionStatusSender>,
+        bundle_notifier: Option<BundleNotifierArc>,
         replay_vote_sender: ReplayVoteSender,
         log_messages_bytes_limit: Option<usize>,
         exit: Arc<AtomicBool>,
@@ -206,12 +236,20 @@ impl BundleStage {
         bundle_account_locker: BundleAccountLocker,
         block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        bundle_result_sender: Option<BundleResultSender>,
+        num_workers: usize,
+        bundle_simulation_cache: Option<Arc<BundleSimulationCache>>,
+        min_tip_policy: Arc<RwLock<MinTipPolicy>>,
+        bundle_policy: Arc<RwLock<BundlePolicy>>,
+        bundle_reservation: Arc<BundleReservation>,
+        banking_tracer: Arc<BankingTracer>,
     ) -> Self {
         Self::start_bundle_thread(
             cluster_info,
             poh_recorder,
             bundle_receiver,
             transaction_status_sender,
+            bundle_notifier,
             replay_vote_sender,
             log_messages_bytes_limit,
             exit,
@@ -220,11 +258,23 @@ impl BundleStage {
             MAX_BUNDLE_RETRY_DURATION,
             block_builder_fee_info,
             prioritization_fee_cache,
+            bundle_result_sender,
+            num_workers,
+            bundle_simulation_cache,
+            min_tip_policy,
+            bundle_policy,
+            bundle_reservation,
+            banking_tracer,
         )
     }
 
     pub fn join(self) -> thread::Result<()> {
-        self.bundle_thread.join()
+        self.bundle_thread.join()?;
+        // exits once the bundle thread drops its sender
+        if let Some(bundle_notifier_thread) = self.bundle_notifier_thread {
+            bundle_notifier_thread.join()?;
+        }
+        Ok(())
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -233,6 +283,7 @@ impl BundleStage {
         poh_recorder: &Arc<RwLock<PohRecorder>>,
         bundle_receiver: Receiver<Vec<PacketBundle>>,
         transaction_status_sender: Option<TransactionStatusSender>,
+        bundle_notifier: Option<BundleNotifierArc>,
         replay_vote_sender: ReplayVoteSender,
         log_message_bytes_limit: Option<usize>,
         exit: Arc<AtomicBool>,
@@ -241,21 +292,33 @@ impl BundleStage {
         max_bundle_retry_duration: Duration,
         block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        bundle_result_sender: Option<BundleResultSender>,
+        num_workers: usize,
+        bundle_simulation_cache: Option<Arc<BundleSimulationCache>>,
+        min_tip_policy: Arc<RwLock<MinTipPolicy>>,
+        bundle_policy: Arc<RwLock<BundlePolicy>>,
+        bundle_reservation: Arc<BundleReservation>,
+        banking_tracer: Arc<BankingTracer>,
     ) -> Self {
         const BUNDLE_STAGE_ID: u32 = 10_000;
         let poh_recorder = poh_recorder.clone();
         let cluster_info = cluster_info.clone();
 
-        let mut bundle_receiver = BundleReceiver::new(BUNDLE_STAGE_ID, bundle_receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(BUNDLE_STAGE_ID, bundle_receiver, Some(5), banking_tracer);
 
+        let (bundle_notification_sender, bundle_notifier_thread) =
+            bundle_notifier.map(spawn_bundle_notifier).unzip();
         let committer = Committer::new(
             transaction_status_sender,
             replay_vote_sender,
             prioritization_fee_cache.clone(),
+            bundle_notification_sender,
         );
         let decision_maker = DecisionMaker::new(cluster_info.id(), poh_recorder.clone());
 
-        let unprocessed_bundle_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let unprocessed_bundle_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(tip_manager.get_tip_accounts());
 
         let consumer = BundleConsumer::new(
             committer,
@@ -267,6 +330,12 @@ impl BundleStage {
             block_builder_fee_info.clone(),
             max_bundle_retry_duration,
             cluster_info,
+            bundle_result_sender,
+            num_workers,
+            bundle_simulation_cache,
+            min_tip_policy,
+            bundle_policy,
+            bundle_reservation,
         );
 
         let bundle_thread = Builder::new()
@@ -283,7 +352,10 @@ impl BundleStage {
             })
             .unwrap();
 
-        Self { bundle_thread }
+        Self {
+            bundle_thread,
+            bundle_notifier_thread,
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -32,6 +32,8 @@ pub struct LockedBundle<'a, 'b> {
     bundle_account_locker: &'a BundleAccountLocker,
     sanitized_bundle: &'b SanitizedBundle,
     bank: Arc<Bank>,
+    // the accounts locked by this bundle alone
+    account_locks: BundleAccountLocks,
 }
 
 impl<'a, 'b> LockedBundle<'a, 'b> {
@@ -39,17 +41,25 @@ impl<'a, 'b> LockedBundle<'a, 'b> {
         bundle_account_locker: &'a BundleAccountLocker,
         sanitized_bundle: &'b SanitizedBundle,
         bank: &Arc<Bank>,
+        account_locks: BundleAccountLocks,
     ) -> Self {
         Self {
             bundle_account_locker,
             sanitized_bundle,
             bank: bank.clone(),
+            account_locks,
         }
     }
 
     pub fn sanitized_bundle(&self) -> &SanitizedBundle {
         self.sanitized_bundle
     }
+
+    /// The read and write locks held by this bundle, used to find bundles that can be executed
+    /// in parallel
+    pub fn account_locks(&self) -> &BundleAccountLocks {
+        &self.account_locks
+    }
 }
 
 // Automatically unlock bundle accounts when destructed
@@ -152,11 +162,19 @@ impl BundleAccountLocker {
     ) -> BundleAccountLockerResult<LockedBundle<'a, 'b>> {
         let (read_locks, write_locks) = Self::get_read_write_locks(sanitized_bundle, bank)?;
 
+        let mut bundle_account_locks = BundleAccountLocks::default();
+        bundle_account_locks.lock_accounts(read_locks.clone(), write_locks.clone());
+
         self.account_locks
             .lock()
             .unwrap()
             .lock_accounts(read_locks, write_locks);
-        Ok(LockedBundle::new(self, sanitized_bundle, bank))
+        Ok(LockedBundle::new(
+            self,
+            sanitized_bundle,
+            bank,
+            bundle_account_locks,
+        ))
     }
 
     /// Unlocks bundle accounts. Note that LockedBundle::drop will auto-drop the bundle account locks
@@ -268,10 +286,14 @@ mod tests {
         let mut packet_bundle0 = PacketBundle {
             batch: PacketBatch::new(vec![Packet::from_data(None, &tx0).unwrap()]),
             bundle_id: tx0.signatures[0].to_string(),
+            allowed_to_fail_tx_indexes: vec![],
+            source: None,
         };
         let mut packet_bundle1 = PacketBundle {
             batch: PacketBatch::new(vec![Packet::from_data(None, &tx1).unwrap()]),
             bundle_id: tx1.signatures[0].to_string(),
+            allowed_to_fail_tx_indexes: vec![],
+            source: None,
         };
 
         let mut transaction_errors = TransactionErrorMetrics::default();
@@ -301,6 +323,14 @@ mod tests {
         let locked_bundle1 = bundle_account_locker
             .prepare_locked_bundle(&sanitized_bundle1, &bank)
             .unwrap();
+        assert_eq!(
+            locked_bundle1.account_locks().write_locks(),
+            HashSet::from_iter([mint_keypair.pubkey(), kp1.pubkey()])
+        );
+        assert_eq!(
+            locked_bundle1.account_locks().read_locks(),
+            HashSet::from_iter([system_program::id()])
+        );
         assert_eq!(
             bundle_account_locker.write_locks(),
             HashSet::from_iter([mint_keypair.pubkey(), kp0.pubkey(), kp1.pubkey()])
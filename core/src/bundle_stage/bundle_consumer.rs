@@ -9,16 +9,27 @@ use {
         },
         bundle_stage::{
             bundle_account_locker::{BundleAccountLocker, LockedBundle},
+            bundle_policy::BundlePolicy,
+            bundle_reservation::BundleReservation,
+            bundle_result::{BundleOutcome, BundleResult, BundleResultSender},
+            bundle_scheduler::BundleScheduler,
             bundle_stage_leader_metrics::BundleStageLeaderMetrics,
             committer::Committer,
+            min_tip_policy::MinTipPolicy,
         },
         immutable_deserialized_bundle::ImmutableDeserializedBundle,
         proxy::block_engine_stage::BlockBuilderFeeInfo,
         tip_manager::TipManager,
     },
+    rayon::{prelude::*, ThreadPool},
     solana_bundle::{
-        bundle_execution::{load_and_execute_bundle, BundleExecutionMetrics},
-        BundleExecutionError, BundleExecutionResult, SanitizedBundle, TipError,
+        bundle_execution::{
+            load_and_execute_bundle, BundleExecutionMetrics, LoadAndExecuteBundleError,
+            LoadAndExecuteBundleOutput,
+        },
+        bundle_simulation_cache::{BundleAccountsState, BundleSimulationCache},
+        derive_bundle_id_from_sanitized_transactions, BundleExecutionError, BundleExecutionResult,
+        SanitizedBundle, TipError,
     },
     solana_cost_model::transaction_cost::TransactionCost,
     solana_gossip::cluster_info::ClusterInfo,
@@ -27,17 +38,19 @@ use {
     solana_runtime::bank::Bank,
     solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
     solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
         clock::{Slot, MAX_PROCESSING_AGE},
         pubkey::Pubkey,
+        timing::timestamp,
         transaction::{
             SanitizedTransaction, {self},
         },
     },
     solana_svm::transaction_error_metrics::TransactionErrorMetrics,
     std::{
-        collections::HashSet,
+        collections::{HashMap, HashSet},
         num::Saturating,
-        sync::{Arc, Mutex},
+        sync::{Arc, Mutex, RwLock},
         time::{Duration, Instant},
     },
 };
@@ -50,10 +63,31 @@ type ReserveBundleBlockspaceResult<'a> = BundleExecutionResult<(
 
 pub struct ExecuteRecordCommitResult {
     commit_transaction_details: Vec<CommitTransactionDetails>,
+    // committed transactions that were allowed to fail and failed
+    num_committed_with_failed_result: u64,
     result: BundleExecutionResult<()>,
     execution_metrics: BundleExecutionMetrics,
     execute_and_commit_timings: LeaderExecuteAndCommitTimings,
     transaction_error_counter: TransactionErrorMetrics,
+    tip_lamports: u64,
+    tips: Vec<(Pubkey, u64)>,
+}
+
+/// A bundle executed by a consume worker along with the measurements needed to report its metrics
+struct ExecutedBundle {
+    execute_record_commit_result: ExecuteRecordCommitResult,
+    cost_model_us: u64,
+    process_transactions_us: u64,
+    compute_units_consumed: u64,
+}
+
+/// Details of a successfully committed bundle that get reported back to the block engine
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BundleExecutionDetails {
+    compute_units_consumed: u64,
+    tip_lamports: u64,
+    // lamports paid to each tip account, for the geyser plugins
+    tips: Vec<(Pubkey, u64)>,
 }
 
 pub struct BundleConsumer {
@@ -76,6 +110,25 @@ pub struct BundleConsumer {
     max_bundle_retry_duration: Duration,
 
     cluster_info: Arc<ClusterInfo>,
+
+    // Reports the outcome of every bundle execution attempt back to the block engine
+    bundle_result_sender: Option<BundleResultSender>,
+
+    // Non-conflicting bundles are executed in parallel on the consume workers in this pool
+    thread_pool: ThreadPool,
+    bundle_scheduler: BundleScheduler,
+
+    // Bundles known to fail against the working bank are rejected without being executed
+    bundle_simulation_cache: Option<Arc<BundleSimulationCache>>,
+
+    // Bundles that don't pay the minimum tip are rejected instead of committed
+    min_tip_policy: Arc<RwLock<MinTipPolicy>>,
+
+    // Bundles that break a rule of the policy are dropped before they're executed
+    bundle_policy: Arc<RwLock<BundlePolicy>>,
+
+    // Grows the blockspace reserved from BankingStage while high-tip bundles are waiting
+    bundle_reservation: Arc<BundleReservation>,
 }
 
 impl BundleConsumer {
@@ -90,6 +143,12 @@ impl BundleConsumer {
         block_builder_fee_info: Arc<Mutex<BlockBuilderFeeInfo>>,
         max_bundle_retry_duration: Duration,
         cluster_info: Arc<ClusterInfo>,
+        bundle_result_sender: Option<BundleResultSender>,
+        num_workers: usize,
+        bundle_simulation_cache: Option<Arc<BundleSimulationCache>>,
+        min_tip_policy: Arc<RwLock<MinTipPolicy>>,
+        bundle_policy: Arc<RwLock<BundlePolicy>>,
+        bundle_reservation: Arc<BundleReservation>,
     ) -> Self {
         let mut blacklisted_accounts = HashSet::from_iter([tip_manager.tip_payment_program_id()]);
         blacklisted_accounts
@@ -388,6 +447,17 @@ impl BundleConsumer {
             block_builder_fee_info,
             max_bundle_retry_duration,
             cluster_info,
+            bundle_result_sender,
+            thread_pool: rayon::ThreadPoolBuilder::new()
+                .num_threads(num_workers)
+                .thread_name(|i| format!("solBundleWrk{i:02}"))
+                .build()
+                .expect("new rayon threadpool"),
+            bundle_scheduler: BundleScheduler::new(num_workers),
+            bundle_simulation_cache,
+            min_tip_policy,
+            bundle_policy,
+            bundle_reservation,
         }
     }
 
@@ -414,10 +484,20 @@ impl BundleConsumer {
         unprocessed_transaction_storage: &mut UnprocessedTransactionStorage,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
     ) {
+        self.update_bundle_reservation(
+            bank_start,
+            unprocessed_transaction_storage,
+            bundle_stage_leader_metrics,
+        );
+
+        // the policies can be updated over admin rpc, so a copy is taken for this pass over the buffer
+        let min_tip_policy = self.min_tip_policy.read().unwrap().clone();
+        let bundle_policy = self.bundle_policy.read().unwrap().clone();
         let reached_end_of_slot = unprocessed_transaction_storage.process_bundles(
             bank_start.working_bank.clone(),
             bundle_stage_leader_metrics,
             &self.blacklisted_accounts,
+            &bundle_policy,
             |bundles, bundle_stage_leader_metrics| {
                 Self::do_process_bundles(
                     &self.bundle_account_locker,
@@ -430,6 +510,11 @@ impl BundleConsumer {
                     &self.qos_service,
                     &self.log_messages_bytes_limit,
                     self.max_bundle_retry_duration,
+                    &self.bundle_result_sender,
+                    &self.thread_pool,
+                    &mut self.bundle_scheduler,
+                    self.bundle_simulation_cache.as_deref(),
+                    &min_tip_policy,
                     bundles,
                     bank_start,
                     bundle_stage_leader_metrics,
@@ -446,6 +531,35 @@ impl BundleConsumer {
         }
     }
 
+    /// Reports the CUs wanted by the high-tip bundles waiting to be executed, so BankingStage
+    /// leaves room for them in the rest of the slot
+    fn update_bundle_reservation(
+        &self,
+        bank_start: &BankStart,
+        unprocessed_transaction_storage: &mut UnprocessedTransactionStorage,
+        bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
+    ) {
+        let demand = unprocessed_transaction_storage
+            .bundle_storage()
+            .map(|bundle_storage| {
+                bundle_storage.buffered_compute_unit_limit(
+                    self.bundle_reservation
+                        .config()
+                        .high_tip_micro_lamports_per_cu,
+                )
+            })
+            .unwrap_or_default();
+        self.bundle_reservation
+            .update_demand(bank_start.working_bank.slot(), demand);
+
+        let reservation = self
+            .bundle_reservation
+            .block_cost_limit_reservation(&bank_start.working_bank);
+        bundle_stage_leader_metrics
+            .bundle_stage_metrics_tracker()
+            .update_bundle_reservation(demand, reservation);
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn do_process_bundles(
         bundle_account_locker: &BundleAccountLocker,
@@ -458,6 +572,11 @@ impl BundleConsumer {
         qos_service: &QosService,
         log_messages_bytes_limit: &Option<usize>,
         max_bundle_retry_duration: Duration,
+        bundle_result_sender: &Option<BundleResultSender>,
+        thread_pool: &ThreadPool,
+        bundle_scheduler: &mut BundleScheduler,
+        bundle_simulation_cache: Option<&BundleSimulationCache>,
+        min_tip_policy: &MinTipPolicy,
         bundles: &[(ImmutableDeserializedBundle, SanitizedBundle)],
         bank_start: &BankStart,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
@@ -478,65 +597,239 @@ impl BundleConsumer {
             .bundle_stage_metrics_tracker()
             .increment_locked_bundle_elapsed_us(locked_bundles_elapsed_us);
 
-        let (execution_results, execute_locked_bundles_elapsed_us) =
-            measure_us!(locked_bundle_results
-                .into_iter()
-                .map(|r| match r {
-                    Ok(locked_bundle) => {
-                        let (r, measure) = measure_us!(Self::process_bundle(
-                            bundle_account_locker,
-                            tip_manager,
-                            last_tip_updated_slot,
-                            cluster_info,
-                            block_builder_fee_info,
+        let tip_accounts = tip_manager.get_tip_accounts();
+        let touches_tip_pdas: Vec<bool> = bundles
+            .iter()
+            .map(|(_, sanitized_bundle)| {
+                Self::bundle_touches_tip_pdas(sanitized_bundle, &tip_accounts)
+            })
+            .collect();
+        let enforce_min_tip: Vec<bool> = bundles
+            .iter()
+            .map(|(deserialized_bundle, _)| min_tip_policy.applies_to(deserialized_bundle.source()))
+            .collect();
+
+        // The tip programs need to be cranked before any bundle touching the tip accounts executes
+        // in a slot. It's done up front on this thread so the workers don't race to crank them.
+        let tip_programs_result = if bank_start.working_bank.slot() != *last_tip_updated_slot
+            && locked_bundle_results
+                .iter()
+                .zip(touches_tip_pdas.iter())
+                .any(|(locked_bundle, touches_tip_pdas)| locked_bundle.is_ok() && *touches_tip_pdas)
+        {
+            let start = Instant::now();
+            let result = if Bank::should_bank_still_be_processing_txs(
+                &bank_start.bank_creation_time,
+                bank_start.working_bank.ns_per_slot,
+            ) {
+                Self::handle_tip_programs(
+                    bundle_account_locker,
+                    tip_manager,
+                    cluster_info,
+                    block_builder_fee_info,
+                    committer,
+                    recorder,
+                    qos_service,
+                    log_messages_bytes_limit,
+                    max_bundle_retry_duration,
+                    bank_start,
+                    bundle_stage_leader_metrics,
+                )
+            } else {
+                Err(BundleExecutionError::BankProcessingTimeLimitReached)
+            };
+
+            bundle_stage_leader_metrics
+                .bundle_stage_metrics_tracker()
+                .increment_change_tip_receiver_elapsed_us(start.elapsed().as_micros() as u64);
+
+            if result.is_ok() {
+                *last_tip_updated_slot = bank_start.working_bank.slot();
+            }
+            result
+        } else {
+            Ok(())
+        };
+
+        let mut num_simulation_cache_hits = 0;
+        let mut execution_results: Vec<Option<BundleExecutionResult<BundleExecutionDetails>>> =
+            locked_bundle_results
+                .iter()
+                .zip(touches_tip_pdas.iter().zip(enforce_min_tip.iter()))
+                .map(
+                    |(locked_bundle, (touches_tip_pdas, enforce_min_tip))| match locked_bundle {
+                        Err(_) => Some(Err(BundleExecutionError::LockError)),
+                        Ok(_) if *touches_tip_pdas && tip_programs_result.is_err() => {
+                            tip_programs_result.clone().err().map(Err)
+                        }
+                        // a bundle that doesn't touch the tip accounts can't pay a tip
+                        Ok(_) if *enforce_min_tip && !*touches_tip_pdas => {
+                            Some(Err(TipError::BelowMinimumTip(0).into()))
+                        }
+                        // a cached failure is only used if none of the bundle's accounts have
+                        // been written since it was recorded, see BundleAccountsState::is_current
+                        Ok(locked_bundle) => bundle_simulation_cache
+                            .and_then(|cache| {
+                                let sanitized_bundle = locked_bundle.sanitized_bundle();
+                                cache.get(
+                                    &bank_start.working_bank,
+                                    &derive_bundle_id_from_sanitized_transactions(
+                                        &sanitized_bundle.transactions,
+                                    ),
+                                    &sanitized_bundle.allowed_to_fail_tx_indexes,
+                                )
+                            })
+                            .map(|e| {
+                                num_simulation_cache_hits += 1;
+                                Err(BundleExecutionError::TransactionFailure(e))
+                            }),
+                    },
+                )
+                .collect();
+        bundle_stage_leader_metrics
+            .bundle_stage_metrics_tracker()
+            .increment_num_simulation_cache_hits(num_simulation_cache_hits);
+
+        // Bundles that don't conflict with each other are executed in parallel on the consume
+        // workers. Conflicting bundles end up in later batches and are executed in priority order.
+        let bundle_account_locks: Vec<_> = locked_bundle_results
+            .iter()
+            .zip(execution_results.iter())
+            .map(
+                |(locked_bundle, execution_result)| match (locked_bundle, execution_result) {
+                    (Ok(locked_bundle), None) => Some(locked_bundle.account_locks()),
+                    _ => None,
+                },
+            )
+            .collect();
+        let (batches, schedule_elapsed_us) =
+            measure_us!(bundle_scheduler.schedule(&bundle_account_locks));
+        bundle_stage_leader_metrics
+            .bundle_stage_metrics_tracker()
+            .increment_schedule_bundles_elapsed_us(schedule_elapsed_us);
+
+        let (_, execute_locked_bundles_elapsed_us) = measure_us!(for batch in batches {
+            bundle_stage_leader_metrics
+                .bundle_stage_metrics_tracker()
+                .increment_num_parallel_batches(1);
+
+            let batch_results: Vec<_> = thread_pool.install(|| {
+                batch
+                    .par_iter()
+                    .map(|index| {
+                        let locked_bundle = locked_bundle_results[*index].as_ref().unwrap();
+                        measure_us!(Self::execute_locked_bundle(
                             committer,
                             recorder,
                             qos_service,
                             log_messages_bytes_limit,
                             max_bundle_retry_duration,
-                            &locked_bundle,
+                            locked_bundle,
+                            touches_tip_pdas[*index].then_some(&tip_accounts),
+                            enforce_min_tip[*index].then_some(min_tip_policy),
+                            bundle_simulation_cache,
                             bank_start,
-                            bundle_stage_leader_metrics,
-                        ));
-                        bundle_stage_leader_metrics
-                            .leader_slot_metrics_tracker()
-                            .increment_process_packets_transactions_us(measure);
-                        r
-                    }
-                    Err(_) => {
-                        Err(BundleExecutionError::LockError)
-                    }
-                })
-                .collect::<Vec<_>>());
+                        ))
+                    })
+                    .collect()
+            });
+
+            for (index, (result, process_bundle_us)) in batch.into_iter().zip(batch_results) {
+                bundle_stage_leader_metrics
+                    .leader_slot_metrics_tracker()
+                    .increment_process_packets_transactions_us(process_bundle_us);
+                execution_results[index] = Some(result.and_then(|executed_bundle| {
+                    Self::report_executed_bundle(
+                        executed_bundle,
+                        &bundles[index].1,
+                        bundle_stage_leader_metrics,
+                    )
+                }));
+            }
+        });
 
         bundle_stage_leader_metrics
             .bundle_stage_metrics_tracker()
             .increment_execute_locked_bundles_elapsed_us(execute_locked_bundles_elapsed_us);
+        // failed bundles aren't committed, so they don't consume any compute or pay any tips
+        let (execution_results, execution_details): (Vec<_>, Vec<_>) = execution_results
+            .into_iter()
+            .map(|result| match result.expect("every bundle has a result") {
+                Ok(details) => (Ok(()), details),
+                Err(e) => (Err(e), BundleExecutionDetails::default()),
+            })
+            .unzip();
         execution_results.iter().for_each(|result| {
             bundle_stage_leader_metrics
                 .bundle_stage_metrics_tracker()
                 .increment_bundle_execution_result(result);
         });
 
+        let slot = bank_start.working_bank.slot();
+        for ((_, sanitized_bundle), (result, details)) in bundles
+            .iter()
+            .zip(execution_results.iter().zip(execution_details.iter()))
+        {
+            committer.notify_bundle(sanitized_bundle, slot, result, &details.tips);
+        }
+        if let Some(bundle_result_sender) = bundle_result_sender {
+            bundles
+                .iter()
+                .zip(execution_results.iter().zip(execution_details.iter()))
+                .for_each(
+                    |((deserialized_bundle, sanitized_bundle), (result, details))| {
+                        bundle_result_sender.send(Self::build_bundle_result(
+                            sanitized_bundle,
+                            deserialized_bundle.source(),
+                            slot,
+                            result,
+                            details,
+                        ));
+                    },
+                );
+            bundle_stage_leader_metrics
+                .bundle_stage_metrics_tracker()
+                .increment_num_bundle_results_dropped(bundle_result_sender.take_num_dropped());
+        }
+
         execution_results
     }
 
+    fn build_bundle_result(
+        sanitized_bundle: &SanitizedBundle,
+        source: Option<&str>,
+        slot: Slot,
+        result: &BundleExecutionResult<()>,
+        details: &BundleExecutionDetails,
+    ) -> BundleResult {
+        BundleResult {
+            bundle_id: derive_bundle_id_from_sanitized_transactions(&sanitized_bundle.transactions),
+            uuid: sanitized_bundle.bundle_id.clone(),
+            source: source.map(str::to_string),
+            slot,
+            outcome: BundleOutcome::from(result),
+            compute_units_consumed: details.compute_units_consumed,
+            tip_lamports: details.tip_lamports,
+            error: result.as_ref().err().map(|e| e.to_string()),
+            timestamp_ms: timestamp(),
+        }
+    }
+
+    /// Runs on a consume worker. Metrics are reported by the scheduling thread with
+    /// [Self::report_executed_bundle] once the bundle's batch is done.
     #[allow(clippy::too_many_arguments)]
-    fn process_bundle(
-        bundle_account_locker: &BundleAccountLocker,
-        tip_manager: &TipManager,
-        last_tip_updated_slot: &mut Slot,
-        cluster_info: &Arc<ClusterInfo>,
-        block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
+    fn execute_locked_bundle(
         committer: &Committer,
         recorder: &TransactionRecorder,
         qos_service: &QosService,
         log_messages_bytes_limit: &Option<usize>,
         max_bundle_retry_duration: Duration,
         locked_bundle: &LockedBundle,
+        tip_accounts: Option<&HashSet<Pubkey>>,
+        min_tip_policy: Option<&MinTipPolicy>,
+        bundle_simulation_cache: Option<&BundleSimulationCache>,
         bank_start: &BankStart,
-        bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
-    ) -> Result<(), BundleExecutionError> {
+    ) -> BundleExecutionResult<ExecutedBundle> {
         if !Bank::should_bank_still_be_processing_txs(
             &bank_start.bank_creation_time,
             bank_start.working_bank.ns_per_slot,
@@ -544,48 +837,40 @@ impl BundleConsumer {
             return Err(BundleExecutionError::BankProcessingTimeLimitReached);
         }
 
-        if bank_start.working_bank.slot() != *last_tip_updated_slot
-            && Self::bundle_touches_tip_pdas(
-                locked_bundle.sanitized_bundle(),
-                &tip_manager.get_tip_accounts(),
-            )
-        {
-            let start = Instant::now();
-            let result = Self::handle_tip_programs(
-                bundle_account_locker,
-                tip_manager,
-                cluster_info,
-                block_builder_fee_info,
-                committer,
-                recorder,
-                qos_service,
-                log_messages_bytes_limit,
-                max_bundle_retry_duration,
-                bank_start,
-                bundle_stage_leader_metrics,
-            );
-
-            bundle_stage_leader_metrics
-                .bundle_stage_metrics_tracker()
-                .increment_change_tip_receiver_elapsed_us(start.elapsed().as_micros() as u64);
-
-            result?;
-
-            *last_tip_updated_slot = bank_start.working_bank.slot();
-        }
-
-        Self::update_qos_and_execute_record_commit_bundle(
+        let executed_bundle = Self::reserve_and_execute_record_commit_bundle(
             committer,
             recorder,
             qos_service,
             log_messages_bytes_limit,
             max_bundle_retry_duration,
             locked_bundle.sanitized_bundle(),
+            tip_accounts,
+            min_tip_policy,
             bank_start,
-            bundle_stage_leader_metrics,
         )?;
 
-        Ok(())
+        if let (
+            Some(bundle_simulation_cache),
+            Err(BundleExecutionError::TransactionFailure(
+                e @ LoadAndExecuteBundleError::TransactionError { .. },
+            )),
+        ) = (
+            bundle_simulation_cache,
+            &executed_bundle.execute_record_commit_result.result,
+        ) {
+            // failed bundles aren't committed and the accounts are still locked, so this is the
+            // state the bundle failed against
+            let sanitized_bundle = locked_bundle.sanitized_bundle();
+            bundle_simulation_cache.insert(
+                &bank_start.working_bank,
+                &derive_bundle_id_from_sanitized_transactions(&sanitized_bundle.transactions),
+                &sanitized_bundle.allowed_to_fail_tx_indexes,
+                BundleAccountsState::load(&bank_start.working_bank, sanitized_bundle),
+                e,
+            );
+        }
+
+        Ok(executed_bundle)
     }
 
     /// The validator needs to manage state on two programs related to tips
@@ -629,6 +914,7 @@ impl BundleConsumer {
                 log_messages_bytes_limit,
                 max_bundle_retry_duration,
                 locked_init_tip_programs_bundle.sanitized_bundle(),
+                None,
                 bank_start,
                 bundle_stage_leader_metrics,
             )
@@ -681,6 +967,7 @@ impl BundleConsumer {
                 log_messages_bytes_limit,
                 max_bundle_retry_duration,
                 locked_tip_crank_bundle.sanitized_bundle(),
+                None,
                 bank_start,
                 bundle_stage_leader_metrics,
             )
@@ -732,6 +1019,10 @@ impl BundleConsumer {
         ))
     }
 
+    /// Reserves blockspace for the bundle then executes, records, and commits it.
+    /// If `tip_accounts` is set, the lamports paid to those accounts over the course of the bundle
+    /// are returned in the execution details.
+    #[allow(clippy::too_many_arguments)]
     fn update_qos_and_execute_record_commit_bundle(
         committer: &Committer,
         recorder: &TransactionRecorder,
@@ -739,9 +1030,42 @@ impl BundleConsumer {
         log_messages_bytes_limit: &Option<usize>,
         max_bundle_retry_duration: Duration,
         sanitized_bundle: &SanitizedBundle,
+        tip_accounts: Option<&HashSet<Pubkey>>,
         bank_start: &BankStart,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
-    ) -> BundleExecutionResult<()> {
+    ) -> BundleExecutionResult<BundleExecutionDetails> {
+        let executed_bundle = Self::reserve_and_execute_record_commit_bundle(
+            committer,
+            recorder,
+            qos_service,
+            log_messages_bytes_limit,
+            max_bundle_retry_duration,
+            sanitized_bundle,
+            tip_accounts,
+            None,
+            bank_start,
+        )?;
+        Self::report_executed_bundle(
+            executed_bundle,
+            sanitized_bundle,
+            bundle_stage_leader_metrics,
+        )
+    }
+
+    /// Reserves blockspace for the bundle then executes, records, and commits it. The reserved
+    /// blockspace is updated with the actual costs or rolled back on failure.
+    #[allow(clippy::too_many_arguments)]
+    fn reserve_and_execute_record_commit_bundle(
+        committer: &Committer,
+        recorder: &TransactionRecorder,
+        qos_service: &QosService,
+        log_messages_bytes_limit: &Option<usize>,
+        max_bundle_retry_duration: Duration,
+        sanitized_bundle: &SanitizedBundle,
+        tip_accounts: Option<&HashSet<Pubkey>>,
+        min_tip_policy: Option<&MinTipPolicy>,
+        bank_start: &BankStart,
+    ) -> BundleExecutionResult<ExecutedBundle> {
         debug!(
             "bundle: {} reserving blockspace for {} transactions",
             sanitized_bundle.bundle_id,
@@ -750,7 +1074,7 @@ impl BundleConsumer {
 
         let (
             (transaction_qos_cost_results, _cost_model_throttled_transactions_count),
-            cost_model_elapsed_us,
+            cost_model_us,
         ) = measure_us!(Self::reserve_bundle_blockspace(
             qos_service,
             sanitized_bundle,
@@ -762,14 +1086,58 @@ impl BundleConsumer {
             sanitized_bundle.bundle_id
         );
 
-        let (result, process_transactions_us) = measure_us!(Self::execute_record_commit_bundle(
-            committer,
-            recorder,
-            log_messages_bytes_limit,
-            max_bundle_retry_duration,
-            sanitized_bundle,
-            bank_start,
-        ));
+        let (execute_record_commit_result, process_transactions_us) =
+            measure_us!(Self::execute_record_commit_bundle(
+                committer,
+                recorder,
+                log_messages_bytes_limit,
+                max_bundle_retry_duration,
+                sanitized_bundle,
+                tip_accounts,
+                min_tip_policy,
+                bank_start,
+            ));
+
+        let (compute_units_consumed, execute_us) = execute_record_commit_result
+            .execute_and_commit_timings
+            .execute_timings
+            .accumulate_execute_units_and_time();
+        qos_service.accumulate_actual_execute_cu(compute_units_consumed);
+        qos_service.accumulate_actual_execute_time(execute_us);
+
+        // on bundle failure, none of the transactions are committed, so need to revert
+        // all compute reserved
+        let commit_transaction_details = execute_record_commit_result
+            .result
+            .is_ok()
+            .then_some(&execute_record_commit_result.commit_transaction_details);
+        QosService::remove_or_update_costs(
+            transaction_qos_cost_results.iter(),
+            commit_transaction_details,
+            &bank_start.working_bank,
+        );
+        qos_service.report_metrics(bank_start.working_bank.slot());
+
+        Ok(ExecutedBundle {
+            execute_record_commit_result,
+            cost_model_us,
+            process_transactions_us,
+            compute_units_consumed,
+        })
+    }
+
+    /// Accumulates the leader slot metrics for an executed bundle and returns its result
+    fn report_executed_bundle(
+        executed_bundle: ExecutedBundle,
+        sanitized_bundle: &SanitizedBundle,
+        bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
+    ) -> BundleExecutionResult<BundleExecutionDetails> {
+        let ExecutedBundle {
+            execute_record_commit_result: result,
+            cost_model_us,
+            process_transactions_us,
+            compute_units_consumed,
+        } = executed_bundle;
 
         bundle_stage_leader_metrics
             .bundle_stage_metrics_tracker()
@@ -781,13 +1149,6 @@ impl BundleConsumer {
             .leader_slot_metrics_tracker()
             .increment_process_transactions_us(process_transactions_us);
 
-        let (cu, us) = result
-            .execute_and_commit_timings
-            .execute_timings
-            .accumulate_execute_units_and_time();
-        qos_service.accumulate_actual_execute_cu(cu);
-        qos_service.accumulate_actual_execute_time(us);
-
         let num_committed = result
             .commit_transaction_details
             .iter()
@@ -804,43 +1165,25 @@ impl BundleConsumer {
                 transaction_counts: CommittedTransactionsCounts {
                     attempted_processing_count: sanitized_bundle.transactions.len() as u64,
                     committed_transactions_count: num_committed as u64,
-                    // NOTE: this assumes that bundles are committed all-or-nothing
-                    committed_transactions_with_successful_result_count: num_committed as u64,
+                    // transactions that were allowed to fail are committed with a failed result
+                    committed_transactions_with_successful_result_count: (num_committed as u64)
+                        .saturating_sub(result.num_committed_with_failed_result),
                     processed_but_failed_commit: 0,
                 },
                 retryable_transaction_indexes: vec![],
                 cost_model_throttled_transactions_count: 0,
-                cost_model_us: cost_model_elapsed_us,
+                cost_model_us,
                 execute_and_commit_timings: result.execute_and_commit_timings,
                 error_counters: result.transaction_error_counter,
                 min_prioritization_fees: 0, // TODO (LB)
                 max_prioritization_fees: 0, // TODO (LB)
             });
 
-        match result.result {
-            Ok(_) => {
-                QosService::remove_or_update_costs(
-                    transaction_qos_cost_results.iter(),
-                    Some(&result.commit_transaction_details),
-                    &bank_start.working_bank,
-                );
-
-                qos_service.report_metrics(bank_start.working_bank.slot());
-                Ok(())
-            }
-            Err(e) => {
-                // on bundle failure, none of the transactions are committed, so need to revert
-                // all compute reserved
-                QosService::remove_or_update_costs(
-                    transaction_qos_cost_results.iter(),
-                    None,
-                    &bank_start.working_bank,
-                );
-                qos_service.report_metrics(bank_start.working_bank.slot());
-
-                Err(e)
-            }
-        }
+        result.result.map(|_| BundleExecutionDetails {
+            compute_units_consumed,
+            tip_lamports: result.tip_lamports,
+            tips: result.tips,
+        })
     }
 
     fn execute_record_commit_bundle(
@@ -849,6 +1192,8 @@ impl BundleConsumer {
         log_messages_bytes_limit: &Option<usize>,
         max_bundle_retry_duration: Duration,
         sanitized_bundle: &SanitizedBundle,
+        tip_accounts: Option<&HashSet<Pubkey>>,
+        min_tip_policy: Option<&MinTipPolicy>,
         bank_start: &BankStart,
     ) -> ExecuteRecordCommitResult {
         let transaction_status_sender_enabled = committer.transaction_status_sender_enabled();
@@ -856,7 +1201,18 @@ impl BundleConsumer {
         let mut execute_and_commit_timings = LeaderExecuteAndCommitTimings::default();
 
         debug!("bundle: {} executing", sanitized_bundle.bundle_id);
-        let default_accounts = vec![None; sanitized_bundle.transactions.len()];
+        // load the tip accounts before the first and after the last transaction to measure the tip paid
+        let mut pre_execution_accounts = vec![None; sanitized_bundle.transactions.len()];
+        let mut post_execution_accounts = vec![None; sanitized_bundle.transactions.len()];
+        if let Some(tip_accounts) = tip_accounts {
+            let tip_accounts: Vec<Pubkey> = tip_accounts.iter().cloned().collect();
+            if let Some(first) = pre_execution_accounts.first_mut() {
+                *first = Some(tip_accounts.clone());
+            }
+            if let Some(last) = post_execution_accounts.last_mut() {
+                *last = Some(tip_accounts);
+            }
+        }
         let mut bundle_execution_results = load_and_execute_bundle(
             &bank_start.working_bank,
             sanitized_bundle,
@@ -866,8 +1222,8 @@ impl BundleConsumer {
             log_messages_bytes_limit,
             false,
             None,
-            &default_accounts,
-            &default_accounts,
+            &pre_execution_accounts,
+            &post_execution_accounts,
         );
 
         let execution_metrics = bundle_execution_results.metrics();
@@ -889,13 +1245,71 @@ impl BundleConsumer {
         if let Err(e) = bundle_execution_results.result() {
             return ExecuteRecordCommitResult {
                 commit_transaction_details: vec![],
+                num_committed_with_failed_result: 0,
                 result: Err(e.clone().into()),
                 execution_metrics,
                 execute_and_commit_timings,
                 transaction_error_counter,
+                tip_lamports: 0,
+                tips: vec![],
             };
         }
 
+        // transactions that are allowed to fail can't pay the tip, otherwise the bundle could land
+        // without paying it
+        if let Some(tip_accounts) = tip_accounts {
+            if let Some((failed_tx, execution_result)) = bundle_execution_results
+                .bundle_transaction_results()
+                .iter()
+                .flat_map(|r| r.failed_transactions())
+                .find(|(tx, _)| {
+                    tx.message()
+                        .account_keys()
+                        .iter()
+                        .any(|a| tip_accounts.contains(a))
+                })
+            {
+                return ExecuteRecordCommitResult {
+                    commit_transaction_details: vec![],
+                    num_committed_with_failed_result: 0,
+                    result: Err(BundleExecutionError::TransactionFailure(
+                        LoadAndExecuteBundleError::TransactionError {
+                            signature: *failed_tx.signature(),
+                            execution_result: Box::new(execution_result.clone()),
+                        },
+                    )),
+                    execution_metrics,
+                    execute_and_commit_timings,
+                    transaction_error_counter,
+                    tip_lamports: 0,
+                    tips: vec![],
+                };
+            }
+        }
+
+        let tip_lamports = Self::tip_lamports_paid(&bundle_execution_results);
+        let tips = Self::tips_paid(&bundle_execution_results);
+
+        // don't commit bundle if it doesn't pay the minimum tip
+        if let Some(min_tip_policy) = min_tip_policy {
+            let (compute_units_consumed, _) = execute_and_commit_timings
+                .execute_timings
+                .accumulate_execute_units_and_time();
+            if let Err(e) = min_tip_policy.check(tip_lamports, compute_units_consumed) {
+                debug!("bundle: {} {}", sanitized_bundle.bundle_id, e);
+                return ExecuteRecordCommitResult {
+                    commit_transaction_details: vec![],
+                    num_committed_with_failed_result: 0,
+                    result: Err(e.into()),
+                    execution_metrics,
+                    execute_and_commit_timings,
+                    transaction_error_counter,
+                    tip_lamports: 0,
+                    tips: vec![],
+                };
+            }
+        }
+
         let (executed_batches, execution_results_to_transactions_us) =
             measure_us!(bundle_execution_results.executed_transaction_batches());
 
@@ -940,10 +1354,13 @@ impl BundleConsumer {
         if let Err(e) = record_transactions_result {
             return ExecuteRecordCommitResult {
                 commit_transaction_details: vec![],
+                num_committed_with_failed_result: 0,
                 result: Err(e.into()),
                 execution_metrics,
                 execute_and_commit_timings,
                 transaction_error_counter,
+                tip_lamports: 0,
+                tips: vec![],
             };
         }
 
@@ -958,10 +1375,10 @@ impl BundleConsumer {
 
         drop(freeze_lock);
 
-        // commit_bundle_details contains transactions that were and were not committed
-        // given the current implementation only executes, records, and commits bundles
-        // where all transactions executed, we can filter out the non-committed
-        // TODO (LB): does this make more sense in commit_bundle for future when failing bundles are accepted?
+        // commit_bundle_details contains transactions that were and were not committed.
+        // Transactions that weren't processed in a batch because of a lock conflict with an earlier
+        // transaction in the bundle are executed in a later batch, so only the committed ones are
+        // kept. Transactions that were allowed to fail and failed are committed to pay fees.
         let commit_transaction_details = commit_bundle_details
             .commit_transaction_details
             .into_iter()
@@ -978,13 +1395,70 @@ impl BundleConsumer {
 
         ExecuteRecordCommitResult {
             commit_transaction_details,
+            num_committed_with_failed_result: commit_bundle_details
+                .num_committed_with_failed_result,
             result: Ok(()),
             execution_metrics,
             execute_and_commit_timings,
             transaction_error_counter,
+            tip_lamports,
+            tips,
         }
     }
 
+    /// Returns the increase in lamports across the accounts loaded before the first transaction and
+    /// after the last transaction in the bundle.
+    fn tip_lamports_paid(bundle_execution_results: &LoadAndExecuteBundleOutput) -> u64 {
+        let sum_lamports = |accounts: Option<&Vec<(Pubkey, AccountSharedData)>>| -> u64 {
+            accounts
+                .map(|accounts| {
+                    accounts
+                        .iter()
+                        .map(|(_, account)| account.lamports())
+                        .fold(0u64, |sum, lamports| sum.saturating_add(lamports))
+                })
+                .unwrap_or_default()
+        };
+
+        let results = bundle_execution_results.bundle_transaction_results();
+        let pre_lamports = sum_lamports(
+            results
+                .first()
+                .and_then(|r| r.pre_tx_execution_accounts().iter().flatten().next()),
+        );
+        let post_lamports = sum_lamports(
+            results
+                .last()
+                .and_then(|r| r.post_tx_execution_accounts().iter().flatten().last()),
+        );
+        post_lamports.saturating_sub(pre_lamports)
+    }
+
+    /// Returns the lamports paid to each of the accounts loaded before the first transaction and
+    /// after the last transaction in the bundle, leaving out the accounts that weren't paid.
+    fn tips_paid(bundle_execution_results: &LoadAndExecuteBundleOutput) -> Vec<(Pubkey, u64)> {
+        let results = bundle_execution_results.bundle_transaction_results();
+        let pre_lamports: HashMap<&Pubkey, u64> = results
+            .first()
+            .and_then(|r| r.pre_tx_execution_accounts().iter().flatten().next())
+            .into_iter()
+            .flatten()
+            .map(|(pubkey, account)| (pubkey, account.lamports()))
+            .collect();
+        results
+            .last()
+            .and_then(|r| r.post_tx_execution_accounts().iter().flatten().last())
+            .into_iter()
+            .flatten()
+            .filter_map(|(pubkey, account)| {
+                let tip = account
+                    .lamports()
+                    .saturating_sub(pre_lamports.get(pubkey).copied().unwrap_or_default());
+                (tip > 0).then_some((*pubkey, tip))
+            })
+            .collect()
+    }
+
     /// Returns true if any of the transactions in a bundle mention one of the tip PDAs
     fn bundle_touches_tip_pdas(bundle: &SanitizedBundle, tip_pdas: &HashSet<Pubkey>) -> bool {
         bundle.transactions.iter().any(|tx| {
@@ -1001,15 +1475,21 @@ mod tests {
     use {
         crate::{
             bundle_stage::{
-                bundle_account_locker::BundleAccountLocker, bundle_consumer::BundleConsumer,
+                bundle_account_locker::BundleAccountLocker,
+                bundle_consumer::BundleConsumer,
+                bundle_notification::{BundleNotification, BundleNotificationSender},
                 bundle_packet_deserializer::BundlePacketDeserializer,
-                bundle_stage_leader_metrics::BundleStageLeaderMetrics, committer::Committer,
+                bundle_result::{bundle_result_channel, BundleOutcome},
+                bundle_stage_leader_metrics::BundleStageLeaderMetrics,
+                committer::Committer,
+                min_tip_policy::MinTipPolicy,
                 QosService, UnprocessedTransactionStorage,
             },
             packet_bundle::PacketBundle,
             proxy::block_engine_stage::BlockBuilderFeeInfo,
             tip_manager::{TipDistributionAccountConfig, TipManager, TipManagerConfig},
         },
+        agave_geyser_plugin_interface::geyser_plugin_interface::BundleOutcome as GeyserBundleOutcome,
         crossbeam_channel::{unbounded, Receiver},
         jito_tip_distribution::sdk::derive_tip_distribution_account_address,
         rand::{thread_rng, RngCore},
@@ -1045,7 +1525,7 @@ mod tests {
             poh_config::PohConfig,
             pubkey::Pubkey,
             rent::Rent,
-            signature::{Keypair, Signer},
+            signature::{Keypair, Signature, Signer},
             system_transaction::transfer,
             transaction::{TransactionError, VersionedTransaction},
             vote::state::VoteState,
@@ -1078,6 +1558,22 @@ mod tests {
         bank_forks: Arc<RwLock<BankForks>>,
     }
 
+    /// Returns the bundle id, signatures and outcome of every queued bundle notification
+    fn received_bundle_notifications(
+        receiver: &Receiver<BundleNotification>,
+    ) -> Vec<(String, Vec<Signature>, GeyserBundleOutcome)> {
+        receiver
+            .try_iter()
+            .map(|notification| {
+                (
+                    notification.bundle_id,
+                    notification.signatures,
+                    notification.outcome,
+                )
+            })
+            .collect()
+    }
+
     pub(crate) fn simulate_poh(
         record_receiver: Receiver<Record>,
         poh_recorder: &Arc<RwLock<PohRecorder>>,
@@ -1229,6 +1725,8 @@ mod tests {
                             .collect(),
                     ),
                     bundle_id,
+                    allowed_to_fail_tx_indexes: vec![],
+                    source: None,
                 }
             })
             .collect()
@@ -1277,6 +1775,7 @@ mod tests {
             None,
             replay_vote_sender,
             Arc::new(PrioritizationFeeCache::new(0u64)),
+            None,
         );
 
         let block_builder_pubkey = Pubkey::new_unique();
@@ -1302,11 +1801,18 @@ mod tests {
             block_builder_info,
             Duration::from_secs(10),
             cluster_info,
+            None,
+            1,
+            None,
+            Arc::default(),
+            Arc::default(),
+            Arc::default(),
         );
 
         let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
 
-        let mut bundle_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut bundle_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
         let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(1);
 
         let mut packet_bundles = make_random_overlapping_bundles(
@@ -1393,6 +1899,288 @@ mod tests {
         // TODO (LB): cleanup blockstore
     }
 
+    #[test]
+    fn test_non_conflicting_bundles_executed_in_parallel() {
+        solana_logger::setup();
+        let TestFixture {
+            genesis_config_info,
+            leader_keypair,
+            bank,
+            exit,
+            poh_recorder,
+            poh_simulator,
+            entry_receiver: _entry_receiver,
+            bank_forks: _bank_forks,
+        } = create_test_fixture(1_000_000);
+        let recorder = poh_recorder.read().unwrap().new_recorder();
+
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let committer = Committer::new(
+            None,
+            replay_vote_sender,
+            Arc::new(PrioritizationFeeCache::new(0u64)),
+            None,
+        );
+        let tip_manager = get_tip_manager(&genesis_config_info.voting_keypair.pubkey());
+        let block_builder_info = Arc::new(Mutex::new(BlockBuilderFeeInfo {
+            block_builder: Pubkey::new_unique(),
+            block_builder_commission: 10,
+        }));
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new(leader_keypair.pubkey(), 0, 0),
+            Arc::new(leader_keypair),
+            SocketAddrSpace::new(true),
+        ));
+
+        let mut consumer = BundleConsumer::new(
+            committer,
+            recorder,
+            QosService::new(1),
+            None,
+            tip_manager,
+            BundleAccountLocker::default(),
+            block_builder_info,
+            Duration::from_secs(10),
+            cluster_info,
+            None,
+            2,
+            None,
+            Arc::default(),
+            Arc::default(),
+            Arc::default(),
+        );
+
+        let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
+        let mint_keypair = &genesis_config_info.mint_keypair;
+        let hash = genesis_config_info.genesis_config.hash();
+        let payer = Keypair::new();
+        bank.transfer(sol_to_lamports(10.0), mint_keypair, &payer.pubkey())
+            .unwrap();
+
+        // the first two bundles don't conflict. the third one spends the lamports the first one
+        // transfers, so it only succeeds if it's executed after the first one.
+        let funded = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let other_recipient = Pubkey::new_unique();
+        let mut packet_bundles: Vec<_> = [
+            transfer(mint_keypair, &funded.pubkey(), sol_to_lamports(1.0), hash),
+            transfer(&payer, &other_recipient, sol_to_lamports(1.0), hash),
+            transfer(&funded, &recipient, sol_to_lamports(0.5), hash),
+        ]
+        .into_iter()
+        .map(|tx| {
+            let tx = VersionedTransaction::from(tx);
+            PacketBundle {
+                batch: PacketBatch::new(vec![Packet::from_data(None, &tx).unwrap()]),
+                bundle_id: derive_bundle_id(&[tx]),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
+            }
+        })
+        .collect();
+        let deserialized_bundles = packet_bundles
+            .iter_mut()
+            .map(|packet_bundle| {
+                BundlePacketDeserializer::deserialize_bundle(packet_bundle, None, &Ok).unwrap()
+            })
+            .collect();
+
+        let mut bundle_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
+        let summary = bundle_storage.insert_bundles(deserialized_bundles);
+        assert_eq!(summary.num_bundles_inserted, 3);
+
+        let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(1);
+        let (banking_stage_metrics_action, bundle_stage_metrics_action) =
+            bundle_stage_leader_metrics
+                .check_leader_slot_boundary(Some(&bank_start), Some(&bundle_storage));
+        bundle_stage_leader_metrics
+            .apply_action(banking_stage_metrics_action, bundle_stage_metrics_action);
+
+        consumer.consume_buffered_bundles(
+            &bank_start,
+            &mut bundle_storage,
+            &mut bundle_stage_leader_metrics,
+        );
+
+        // the non-conflicting bundles were executed together, then the conflicting one
+        assert_eq!(
+            bundle_stage_leader_metrics
+                .bundle_stage_metrics_tracker()
+                .num_parallel_batches(),
+            2
+        );
+        assert_eq!(bank.get_balance(&other_recipient), sol_to_lamports(1.0));
+        assert_eq!(bank.get_balance(&recipient), sol_to_lamports(0.5));
+        assert_eq!(
+            bundle_storage
+                .bundle_storage()
+                .unwrap()
+                .unprocessed_bundles_len(),
+            0
+        );
+
+        poh_recorder
+            .write()
+            .unwrap()
+            .is_exited
+            .store(true, Ordering::Relaxed);
+        exit.store(true, Ordering::Relaxed);
+        poh_simulator.join().unwrap();
+    }
+
+    #[test]
+    fn test_bundle_below_min_tip_not_committed() {
+        solana_logger::setup();
+        let TestFixture {
+            genesis_config_info,
+            leader_keypair,
+            bank,
+            exit,
+            poh_recorder,
+            poh_simulator,
+            entry_receiver: _entry_receiver,
+            bank_forks: _bank_forks,
+        } = create_test_fixture(1_000_000);
+        let recorder = poh_recorder.read().unwrap().new_recorder();
+
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let (bundle_notification_sender, bundle_notification_receiver) =
+            BundleNotificationSender::new(10);
+        let committer = Committer::new(
+            None,
+            replay_vote_sender,
+            Arc::new(PrioritizationFeeCache::new(0u64)),
+            Some(bundle_notification_sender),
+        );
+
+        let tip_manager = get_tip_manager(&genesis_config_info.voting_keypair.pubkey());
+        let block_builder_info = Arc::new(Mutex::new(BlockBuilderFeeInfo {
+            block_builder: Pubkey::new_unique(),
+            block_builder_commission: 10,
+        }));
+
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new(leader_keypair.pubkey(), 0, 0),
+            Arc::new(leader_keypair),
+            SocketAddrSpace::new(true),
+        ));
+
+        let (bundle_result_sender, mut bundle_result_receiver) = bundle_result_channel(10);
+        let min_tip_policy = Arc::new(RwLock::new(MinTipPolicy {
+            min_tip_lamports: 1,
+            ..MinTipPolicy::default()
+        }));
+        let mut consumer = BundleConsumer::new(
+            committer,
+            recorder,
+            QosService::new(1),
+            None,
+            tip_manager,
+            BundleAccountLocker::default(),
+            block_builder_info,
+            Duration::from_secs(10),
+            cluster_info,
+            Some(bundle_result_sender),
+            1,
+            None,
+            min_tip_policy.clone(),
+            Arc::default(),
+            Arc::default(),
+        );
+
+        let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
+
+        let mut bundle_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
+        let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(1);
+
+        let mut packet_bundles = make_random_overlapping_bundles(
+            &genesis_config_info.mint_keypair,
+            1,
+            3,
+            genesis_config_info.genesis_config.hash(),
+            10_000,
+        );
+        let packet_bundle = packet_bundles.get_mut(0).unwrap();
+        packet_bundle.source = Some("http://block-engine".to_string());
+
+        // the bundle doesn't tip, so it's rejected without being committed
+        let deserialized_bundle =
+            BundlePacketDeserializer::deserialize_bundle(packet_bundle, None, &Ok).unwrap();
+        let mut error_metrics = TransactionErrorMetrics::default();
+        let sanitized_bundle = deserialized_bundle
+            .build_sanitized_bundle(
+                &bank_start.working_bank,
+                &HashSet::default(),
+                &mut error_metrics,
+            )
+            .unwrap();
+        bundle_storage.insert_bundles(vec![deserialized_bundle]);
+        consumer.consume_buffered_bundles(
+            &bank_start,
+            &mut bundle_storage,
+            &mut bundle_stage_leader_metrics,
+        );
+
+        let bundle_result = bundle_result_receiver.try_recv().unwrap();
+        assert_eq!(bundle_result.outcome, BundleOutcome::TipFailure);
+        let signatures: Vec<_> = sanitized_bundle
+            .transactions
+            .iter()
+            .map(|tx| *tx.signature())
+            .collect();
+        assert_eq!(
+            received_bundle_notifications(&bundle_notification_receiver),
+            vec![(
+                bundle_result.bundle_id.clone(),
+                signatures.clone(),
+                GeyserBundleOutcome::TipFailure
+            )]
+        );
+        let check_results = bank.check_transactions(
+            &sanitized_bundle.transactions,
+            &vec![Ok(()); sanitized_bundle.transactions.len()],
+            MAX_PROCESSING_AGE,
+            &mut error_metrics,
+        );
+        assert!(check_results.iter().all(|r| r.is_ok()));
+
+        // bundles from an exempt source are committed
+        min_tip_policy
+            .write()
+            .unwrap()
+            .exempt_sources
+            .insert("http://block-engine".to_string());
+        let deserialized_bundle =
+            BundlePacketDeserializer::deserialize_bundle(packet_bundle, None, &Ok).unwrap();
+        bundle_storage.insert_bundles(vec![deserialized_bundle]);
+        consumer.consume_buffered_bundles(
+            &bank_start,
+            &mut bundle_storage,
+            &mut bundle_stage_leader_metrics,
+        );
+
+        let bundle_result = bundle_result_receiver.try_recv().unwrap();
+        assert_eq!(bundle_result.outcome, BundleOutcome::Landed);
+        assert_eq!(
+            received_bundle_notifications(&bundle_notification_receiver).last(),
+            Some(&(
+                bundle_result.bundle_id,
+                signatures,
+                GeyserBundleOutcome::Landed
+            ))
+        );
+
+        poh_recorder
+            .write()
+            .unwrap()
+            .is_exited
+            .store(true, Ordering::Relaxed);
+        exit.store(true, Ordering::Relaxed);
+        poh_simulator.join().unwrap();
+    }
+
     /// Happy-path bundle execution to ensure tip management works.
     /// Tip management involves cranking setup bundles before executing the test bundle
     #[test]
@@ -1415,6 +2203,7 @@ mod tests {
             None,
             replay_vote_sender,
             Arc::new(PrioritizationFeeCache::new(0u64)),
+            None,
         );
 
         let block_builder_pubkey = Pubkey::new_unique();
@@ -1440,11 +2229,18 @@ mod tests {
             block_builder_info,
             Duration::from_secs(10),
             cluster_info.clone(),
+            None,
+            1,
+            None,
+            Arc::default(),
+            Arc::default(),
+            Arc::default(),
         );
 
         let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
 
-        let mut bundle_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut bundle_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
         let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(1);
         // MAIN LOGIC
 
@@ -1463,6 +2259,8 @@ mod tests {
             )
             .unwrap()]),
             bundle_id: "test_transfer".to_string(),
+            allowed_to_fail_tx_indexes: vec![],
+            source: None,
         };
 
         let deserialized_bundle =
@@ -1583,6 +2381,7 @@ mod tests {
             None,
             replay_vote_sender,
             Arc::new(PrioritizationFeeCache::new(0u64)),
+            None,
         );
 
         let block_builder_pubkey = Pubkey::new_unique();
@@ -1698,6 +2497,7 @@ mod tests {
         let sanitized_bundle = SanitizedBundle {
             transactions: vec![transfer_tx],
             bundle_id: String::default(),
+            allowed_to_fail_tx_indexes: HashSet::new(),
         };
 
         let transfer_cost =
@@ -1736,6 +2536,7 @@ mod tests {
         let sanitized_bundle = SanitizedBundle {
             transactions: vec![transfer_tx1, transfer_tx2],
             bundle_id: String::default(),
+            allowed_to_fail_tx_indexes: HashSet::new(),
         };
 
         // set block cost limit to 1 transfer transaction, try to process 2, should return an error
@@ -0,0 +1,176 @@
+//! Geyser plugins are notified of the bundles executed by BundleStage on a dedicated thread, so
+//! slow plugins don't hold up the leader. Notifications are dropped, and counted, when the plugins
+//! fall behind.
+use {
+    agave_geyser_plugin_interface::geyser_plugin_interface::BundleOutcome,
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender},
+    solana_geyser_plugin_manager::bundle_notifier_interface::BundleNotifierArc,
+    solana_sdk::{clock::Slot, pubkey::Pubkey, saturating_add_assign, signature::Signature},
+    std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread::{Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+/// Notifications waiting to be sent to the plugins, several seconds' worth at full load
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 10_000;
+const METRICS_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+pub(crate) struct BundleNotification {
+    pub(crate) bundle_id: String,
+    pub(crate) slot: Slot,
+    pub(crate) signatures: Vec<Signature>,
+    /// Lamports paid to each tip account, empty unless the bundle was committed
+    pub(crate) tips: Vec<(Pubkey, u64)>,
+    pub(crate) outcome: BundleOutcome,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Clone)]
+pub(crate) struct BundleNotificationSender {
+    sender: Sender<BundleNotification>,
+    num_dropped: Arc<AtomicUsize>,
+}
+
+impl BundleNotificationSender {
+    pub(crate) fn new(capacity: usize) -> (Self, Receiver<BundleNotification>) {
+        let (sender, receiver) = bounded(capacity);
+        let sender = Self {
+            sender,
+            num_dropped: Arc::default(),
+        };
+        (sender, receiver)
+    }
+
+    /// Queues `notification` for the notifier thread, dropping it if the queue is full
+    pub(crate) fn send(&self, notification: BundleNotification) {
+        if self.sender.try_send(notification).is_err() {
+            self.num_dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Spawns the thread notifying `notifier` of the notifications queued on the returned sender.
+/// The thread exits once every sender is dropped.
+pub(crate) fn spawn_bundle_notifier(
+    notifier: BundleNotifierArc,
+) -> (BundleNotificationSender, JoinHandle<()>) {
+    let (sender, receiver) = BundleNotificationSender::new(NOTIFICATION_CHANNEL_CAPACITY);
+    let num_dropped = sender.num_dropped.clone();
+    let thread_hdl = Builder::new()
+        .name("solBundleNotify".to_string())
+        .spawn(move || run_notifier(receiver, notifier, num_dropped))
+        .unwrap();
+    (sender, thread_hdl)
+}
+
+fn run_notifier(
+    receiver: Receiver<BundleNotification>,
+    notifier: BundleNotifierArc,
+    num_dropped: Arc<AtomicUsize>,
+) {
+    let mut num_notified: usize = 0;
+    let mut last_report = Instant::now();
+    loop {
+        match receiver.recv_timeout(METRICS_REPORT_INTERVAL) {
+            Ok(notification) => {
+                notifier.notify_bundle(
+                    &notification.bundle_id,
+                    notification.slot,
+                    &notification.signatures,
+                    &notification.tips,
+                    notification.outcome,
+                    notification.error.as_deref(),
+                );
+                saturating_add_assign!(num_notified, 1);
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if last_report.elapsed() >= METRICS_REPORT_INTERVAL {
+            datapoint_info!(
+                "bundle_notifier",
+                ("num_notified", num_notified, i64),
+                ("num_dropped", num_dropped.swap(0, Ordering::Relaxed), i64),
+                ("queue_len", receiver.len(), i64),
+            );
+            num_notified = 0;
+            last_report = Instant::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_geyser_plugin_manager::bundle_notifier_interface::BundleNotifier,
+        std::sync::Mutex,
+    };
+
+    #[derive(Default)]
+    struct TestBundleNotifier {
+        notifications: Mutex<Vec<(String, BundleOutcome)>>,
+    }
+
+    impl BundleNotifier for TestBundleNotifier {
+        fn notify_bundle(
+            &self,
+            bundle_id: &str,
+            _slot: Slot,
+            _signatures: &[Signature],
+            _tips: &[(Pubkey, u64)],
+            outcome: BundleOutcome,
+            _error: Option<&str>,
+        ) {
+            self.notifications
+                .lock()
+                .unwrap()
+                .push((bundle_id.to_string(), outcome));
+        }
+    }
+
+    fn notification(bundle_id: &str, outcome: BundleOutcome) -> BundleNotification {
+        BundleNotification {
+            bundle_id: bundle_id.to_string(),
+            slot: 0,
+            signatures: vec![],
+            tips: vec![],
+            outcome,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_send_drops_when_full() {
+        let (sender, receiver) = BundleNotificationSender::new(1);
+        sender.send(notification("landed", BundleOutcome::Landed));
+        sender.send(notification("tip_failure", BundleOutcome::TipFailure));
+
+        assert_eq!(sender.num_dropped.load(Ordering::Relaxed), 1);
+        assert_eq!(receiver.len(), 1);
+        assert_eq!(receiver.try_recv().unwrap().bundle_id, "landed");
+    }
+
+    #[test]
+    fn test_notifier_thread() {
+        let notifier = Arc::new(TestBundleNotifier::default());
+        let (sender, thread_hdl) = spawn_bundle_notifier(notifier.clone());
+        let notifications = [
+            ("landed".to_string(), BundleOutcome::Landed),
+            ("tip_failure".to_string(), BundleOutcome::TipFailure),
+        ];
+        for (bundle_id, outcome) in &notifications {
+            sender.send(notification(bundle_id, *outcome));
+        }
+
+        // the thread exits once the queued notifications are sent and the sender is dropped
+        drop(sender);
+        thread_hdl.join().unwrap();
+        assert_eq!(*notifier.notifications.lock().unwrap(), notifications);
+    }
+}
@@ -5,12 +5,16 @@ use {
             immutable_deserialized_packet::ImmutableDeserializedPacket,
             packet_filter::PacketFilterFailure,
         },
+        banking_trace::BankingTracer,
         immutable_deserialized_bundle::{DeserializedBundleError, ImmutableDeserializedBundle},
         packet_bundle::PacketBundle,
     },
     crossbeam_channel::{Receiver, RecvTimeoutError},
     solana_sdk::saturating_add_assign,
-    std::time::{Duration, Instant},
+    std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    },
 };
 
 /// Results from deserializing packet batches.
@@ -27,16 +31,20 @@ pub struct BundlePacketDeserializer {
     bundle_packet_receiver: Receiver<Vec<PacketBundle>>,
     /// Max packets per bundle
     max_packets_per_bundle: Option<usize>,
+    /// Traces received bundles so block production can be simulated
+    banking_tracer: Arc<BankingTracer>,
 }
 
 impl BundlePacketDeserializer {
     pub fn new(
         bundle_packet_receiver: Receiver<Vec<PacketBundle>>,
         max_packets_per_bundle: Option<usize>,
+        banking_tracer: Arc<BankingTracer>,
     ) -> Self {
         Self {
             bundle_packet_receiver,
             max_packets_per_bundle,
+            banking_tracer,
         }
     }
 
@@ -99,12 +107,14 @@ impl BundlePacketDeserializer {
         let start = Instant::now();
 
         let mut bundles = self.bundle_packet_receiver.recv_timeout(recv_timeout)?;
+        self.banking_tracer.bundle_event(&bundles);
         let mut num_packets_received: usize = bundles.iter().map(|pb| pb.batch.len()).sum();
         let mut num_bundles_received: usize = bundles.len();
 
         if num_bundles_received <= bundle_count_upperbound {
             while let Ok(bundle_packets) = self.bundle_packet_receiver.try_recv() {
                 trace!("got more packet batches in bundle packet deserializer");
+                self.banking_tracer.bundle_event(&bundle_packets);
 
                 saturating_add_assign!(
                     num_packets_received,
@@ -173,7 +183,8 @@ mod tests {
         } = create_genesis_config(10_000);
         let (sender, receiver) = unbounded();
 
-        let deserializer = BundlePacketDeserializer::new(receiver, Some(10));
+        let deserializer =
+            BundlePacketDeserializer::new(receiver, Some(10), BankingTracer::new_disabled());
 
         let packet_bundles: Vec<_> = (0..10)
             .map(|_| PacketBundle {
@@ -188,6 +199,8 @@ mod tests {
                 )
                 .unwrap()]),
                 bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
             })
             .collect();
 
@@ -232,12 +245,15 @@ mod tests {
         solana_logger::setup();
         let (sender, receiver) = unbounded();
 
-        let deserializer = BundlePacketDeserializer::new(receiver, Some(10));
+        let deserializer =
+            BundlePacketDeserializer::new(receiver, Some(10), BankingTracer::new_disabled());
 
         let packet_bundles: Vec<_> = (0..10)
             .map(|_| PacketBundle {
                 batch: PacketBatch::new(vec![]),
                 bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
             })
             .collect();
         sender.send(packet_bundles).unwrap();
@@ -5,6 +5,7 @@ use {
             immutable_deserialized_packet::ImmutableDeserializedPacket,
             unprocessed_transaction_storage::UnprocessedTransactionStorage,
         },
+        banking_trace::BankingTracer,
         bundle_stage::{
             bundle_packet_deserializer::{BundlePacketDeserializer, ReceiveBundleResults},
             bundle_stage_leader_metrics::BundleStageLeaderMetrics,
@@ -15,7 +16,7 @@ use {
     crossbeam_channel::{Receiver, RecvTimeoutError},
     solana_measure::{measure::Measure, measure_us},
     solana_sdk::timing::timestamp,
-    std::time::Duration,
+    std::{sync::Arc, time::Duration},
 };
 
 pub struct BundleReceiver {
@@ -28,12 +29,14 @@ impl BundleReceiver {
         id: u32,
         bundle_packet_receiver: Receiver<Vec<PacketBundle>>,
         max_packets_per_bundle: Option<usize>,
+        banking_tracer: Arc<BankingTracer>,
     ) -> Self {
         Self {
             id,
             bundle_packet_deserializer: BundlePacketDeserializer::new(
                 bundle_packet_receiver,
                 max_packets_per_bundle,
+                banking_tracer,
             ),
         }
     }
@@ -150,6 +153,9 @@ impl BundleReceiver {
             );
             bundle_stage_stats
                 .increment_num_bundles_dropped(insert_bundles_summary.num_bundles_dropped as u64);
+            bundle_stage_stats.increment_num_duplicate_bundles(
+                insert_bundles_summary.num_duplicate_bundles as u64,
+            );
 
             bundle_stage_leader_metrics
                 .leader_slot_metrics_tracker()
@@ -172,7 +178,10 @@ impl BundleReceiver {
 mod tests {
     use {
         super::*,
-        crate::banking_stage::unprocessed_transaction_storage::BundleStorage,
+        crate::{
+            banking_stage::unprocessed_transaction_storage::BundleStorage,
+            bundle_stage::bundle_policy::BundlePolicy,
+        },
         crossbeam_channel::unbounded,
         rand::{thread_rng, RngCore},
         solana_bundle::{
@@ -187,6 +196,7 @@ mod tests {
             bundle::derive_bundle_id,
             hash::Hash,
             packet::Packet,
+            pubkey::Pubkey,
             signature::{Keypair, Signer},
             system_transaction::transfer,
             transaction::VersionedTransaction,
@@ -225,6 +235,8 @@ mod tests {
                             .collect(),
                     ),
                     bundle_id,
+                    allowed_to_fail_tx_indexes: vec![],
+                    source: None,
                 }
             })
             .collect()
@@ -258,10 +270,12 @@ mod tests {
         } = create_genesis_config(10_000);
         let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
-        let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
 
         let bundles = make_random_bundles(&mint_keypair, 10, 2, genesis_config.hash());
         sender.send(bundles.clone()).unwrap();
@@ -286,6 +300,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles, bundles_to_process);
                 (0..bundles_to_process.len()).map(|_| Ok(())).collect()
@@ -298,6 +313,139 @@ mod tests {
         assert_eq!(bundle_storage.max_receive_size(), 1000);
     }
 
+    #[test]
+    fn test_receive_bundles_dropped_by_bundle_policy() {
+        solana_logger::setup();
+
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
+
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
+
+        let (sender, receiver) = unbounded();
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
+
+        let bundles = make_random_bundles(&mint_keypair, 10, 2, genesis_config.hash());
+        sender.send(bundles).unwrap();
+
+        let mut bundle_stage_stats = BundleStageLoopMetrics::default();
+        let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(0);
+        let result = bundle_receiver.receive_and_buffer_bundles(
+            &mut unprocessed_storage,
+            &mut bundle_stage_stats,
+            &mut bundle_stage_leader_metrics,
+        );
+        assert!(result.is_ok());
+
+        let bundle_policy = BundlePolicy {
+            max_transactions: Some(1),
+            ..BundlePolicy::default()
+        };
+        let bundle_storage = unprocessed_storage.bundle_storage().unwrap();
+        assert!(!bundle_storage.process_bundles(
+            bank_forks.read().unwrap().working_bank(),
+            &mut bundle_stage_leader_metrics,
+            &HashSet::default(),
+            &bundle_policy,
+            |bundles_to_process, _stats| {
+                assert!(bundles_to_process.is_empty());
+                vec![]
+            }
+        ));
+        assert_eq!(bundle_storage.unprocessed_bundles_len(), 0);
+        assert_eq!(bundle_storage.cost_model_buffered_bundles_len(), 0);
+    }
+
+    #[test]
+    fn test_receive_bundles_ordered_by_tip_and_deduped() {
+        solana_logger::setup();
+
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
+
+        let tip_account = Pubkey::new_unique();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::from([tip_account]));
+
+        let (sender, receiver) = unbounded();
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
+
+        let tip_bundle = |lamports: u64| {
+            let tx = VersionedTransaction::from(transfer(
+                &mint_keypair,
+                &tip_account,
+                lamports,
+                genesis_config.hash(),
+            ));
+            PacketBundle {
+                bundle_id: derive_bundle_id(&[tx.clone()]),
+                batch: PacketBatch::new(vec![Packet::from_data(None, &tx).unwrap()]),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
+            }
+        };
+        let no_tip_bundles = make_random_bundles(&mint_keypair, 1, 1, genesis_config.hash());
+        let low_tip_bundle = tip_bundle(100);
+        let high_tip_bundle = tip_bundle(1_000);
+
+        sender
+            .send(vec![
+                no_tip_bundles[0].clone(),
+                low_tip_bundle.clone(),
+                high_tip_bundle.clone(),
+                low_tip_bundle.clone(),
+            ])
+            .unwrap();
+
+        let mut bundle_stage_stats = BundleStageLoopMetrics::default();
+        let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(0);
+        let result = bundle_receiver.receive_and_buffer_bundles(
+            &mut unprocessed_storage,
+            &mut bundle_stage_stats,
+            &mut bundle_stage_leader_metrics,
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            bundle_stage_stats
+                .num_duplicate_bundles
+                .load(std::sync::atomic::Ordering::Relaxed),
+            1
+        );
+
+        let bundle_storage = unprocessed_storage.bundle_storage().unwrap();
+        assert_eq!(bundle_storage.unprocessed_bundles_len(), 3);
+
+        assert!(!bundle_storage.process_bundles(
+            bank_forks.read().unwrap().working_bank(),
+            &mut bundle_stage_leader_metrics,
+            &HashSet::default(),
+            &BundlePolicy::default(),
+            |bundles_to_process, _stats| {
+                assert_bundles_same(
+                    &[
+                        high_tip_bundle.clone(),
+                        low_tip_bundle.clone(),
+                        no_tip_bundles[0].clone(),
+                    ],
+                    bundles_to_process,
+                );
+                vec![Ok(()); bundles_to_process.len()]
+            }
+        ));
+        assert_eq!(bundle_storage.unprocessed_bundles_len(), 0);
+    }
+
     #[test]
     fn test_receive_more_bundles_than_capacity() {
         solana_logger::setup();
@@ -309,10 +457,12 @@ mod tests {
         } = create_genesis_config(10_000);
         let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
-        let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
 
         // send 5 more than capacity
         let bundles = make_random_bundles(
@@ -344,6 +494,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 // make sure the first 1000 bundles are the ones to process
                 assert_bundles_same(
@@ -357,6 +508,92 @@ mod tests {
         assert_eq!(bundle_storage.cost_model_buffered_bundles_len(), 0);
     }
 
+    #[test]
+    fn test_receive_higher_tip_bundle_when_full() {
+        solana_logger::setup();
+
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+        let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
+
+        let tip_account = Pubkey::new_unique();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::from([tip_account]));
+
+        let (sender, receiver) = unbounded();
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
+        let mut bundle_stage_stats = BundleStageLoopMetrics::default();
+        let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(0);
+
+        // fill the storage with bundles that don't tip
+        let no_tip_bundles = make_random_bundles(
+            &mint_keypair,
+            BundleStorage::BUNDLE_STORAGE_CAPACITY,
+            1,
+            genesis_config.hash(),
+        );
+        sender.send(no_tip_bundles).unwrap();
+        assert!(bundle_receiver
+            .receive_and_buffer_bundles(
+                &mut unprocessed_storage,
+                &mut bundle_stage_stats,
+                &mut bundle_stage_leader_metrics,
+            )
+            .is_ok());
+        let bundle_storage = unprocessed_storage.bundle_storage().unwrap();
+        assert_eq!(
+            bundle_storage.unprocessed_bundles_len(),
+            BundleStorage::BUNDLE_STORAGE_CAPACITY
+        );
+        assert!(bundle_storage.max_receive_size() > 0);
+
+        let tip_tx = VersionedTransaction::from(transfer(
+            &mint_keypair,
+            &tip_account,
+            1_000,
+            genesis_config.hash(),
+        ));
+        let tip_bundle = PacketBundle {
+            bundle_id: derive_bundle_id(&[tip_tx.clone()]),
+            batch: PacketBatch::new(vec![Packet::from_data(None, &tip_tx).unwrap()]),
+            allowed_to_fail_tx_indexes: vec![],
+            source: None,
+        };
+        sender.send(vec![tip_bundle.clone()]).unwrap();
+        assert!(bundle_receiver
+            .receive_and_buffer_bundles(
+                &mut unprocessed_storage,
+                &mut bundle_stage_stats,
+                &mut bundle_stage_leader_metrics,
+            )
+            .is_ok());
+
+        // the tip bundle evicted one of the bundles that don't tip
+        let bundle_storage = unprocessed_storage.bundle_storage().unwrap();
+        assert_eq!(
+            bundle_storage.unprocessed_bundles_len(),
+            BundleStorage::BUNDLE_STORAGE_CAPACITY
+        );
+        assert!(!bundle_storage.process_bundles(
+            bank_forks.read().unwrap().working_bank(),
+            &mut bundle_stage_leader_metrics,
+            &HashSet::default(),
+            &BundlePolicy::default(),
+            |bundles_to_process, _stats| {
+                assert_eq!(
+                    bundles_to_process.len(),
+                    BundleStorage::BUNDLE_STORAGE_CAPACITY
+                );
+                assert_eq!(bundles_to_process[0].1.bundle_id, tip_bundle.bundle_id);
+                vec![Ok(()); bundles_to_process.len()]
+            }
+        ));
+    }
+
     #[test]
     fn test_process_bundles_poh_record_error_rebuffered() {
         solana_logger::setup();
@@ -368,10 +605,12 @@ mod tests {
         } = create_genesis_config(10_000);
         let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
-        let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -396,6 +635,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles, bundles_to_process);
 
@@ -416,6 +656,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles[poh_max_height_reached_index..], bundles_to_process);
                 vec![Ok(()); bundles_to_process.len()]
@@ -435,10 +676,12 @@ mod tests {
         } = create_genesis_config(10_000);
         let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
-        let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -462,6 +705,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles, bundles_to_process);
 
@@ -481,6 +725,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles[bank_processing_done_index..], bundles_to_process);
                 vec![Ok(()); bundles_to_process.len()]
@@ -500,10 +745,12 @@ mod tests {
         } = create_genesis_config(10_000);
         let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
-        let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -524,6 +771,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles, bundles_to_process);
                 vec![
@@ -548,10 +796,12 @@ mod tests {
         } = create_genesis_config(10_000);
         let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
-        let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -572,6 +822,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles, bundles_to_process);
                 vec![
@@ -594,10 +845,12 @@ mod tests {
         } = create_genesis_config(10_000);
         let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
-        let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -618,6 +871,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 vec![Err(BundleExecutionError::LockError); bundles_to_process.len()]
             }
@@ -636,10 +890,12 @@ mod tests {
         } = create_genesis_config(10_000);
         let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
-        let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -661,6 +917,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles, bundles_to_process);
                 vec![Err(BundleExecutionError::ExceedsCostModel); bundles_to_process.len()]
@@ -674,6 +931,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert!(bundles_to_process.is_empty());
                 vec![Ok(()); bundles_to_process.len()]
@@ -692,6 +950,7 @@ mod tests {
             new_bank,
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 // make sure same order as original
                 assert_bundles_same(&bundles, bundles_to_process);
@@ -713,10 +972,12 @@ mod tests {
         } = create_genesis_config(10_000);
         let (_, bank_forks) = Bank::new_no_wallclock_throttle_for_tests(&genesis_config);
 
-        let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut unprocessed_storage =
+            UnprocessedTransactionStorage::new_bundle_storage(HashSet::default());
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, Some(5));
+        let mut bundle_receiver =
+            BundleReceiver::new(0, receiver, Some(5), BankingTracer::new_disabled());
 
         // send 500 bundles across the queue
         let bundles0 = make_random_bundles(
@@ -744,6 +1005,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles0, bundles_to_process);
                 vec![Err(BundleExecutionError::ExceedsCostModel); bundles_to_process.len()]
@@ -773,6 +1035,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles1, bundles_to_process);
                 vec![Err(BundleExecutionError::ExceedsCostModel); bundles_to_process.len()]
@@ -799,6 +1062,7 @@ mod tests {
             bank_forks.read().unwrap().working_bank(),
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 assert_bundles_same(&bundles2, bundles_to_process);
                 vec![Err(BundleExecutionError::ExceedsCostModel); bundles_to_process.len()]
@@ -818,6 +1082,7 @@ mod tests {
             new_bank,
             &mut bundle_stage_leader_metrics,
             &HashSet::default(),
+            &BundlePolicy::default(),
             |bundles_to_process, _stats| {
                 // make sure same order as original
                 let expected_bundles: Vec<_> =
@@ -0,0 +1,425 @@
+//! Validator-side rules a bundle must pass before it's executed.
+//!
+//! The policy is checked right after a bundle is sanitized, so bundles that break a rule never
+//! take account locks or execute. It's loaded from a YAML file at startup and can be swapped out
+//! over admin rpc. Every rule is off by default.
+//!
+//! The program rules only see the program ids of a transaction's top-level instructions. Programs
+//! called through CPI aren't known until the transaction executes, so the program rules can't be
+//! relied on to keep a bundle from running a program.
+use {
+    serde::{Deserialize, Deserializer},
+    solana_bundle::SanitizedBundle,
+    solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
+    solana_sdk::{pubkey::Pubkey, transaction::SanitizedTransaction},
+    std::collections::HashSet,
+    thiserror::Error,
+};
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct BundlePolicy {
+    /// Programs a bundle's top-level instructions may not call. Calls through CPI aren't checked.
+    #[serde(deserialize_with = "deserialize_pubkey_set")]
+    pub denied_programs: HashSet<Pubkey>,
+    /// When not empty, the only programs a bundle's top-level instructions may call. Calls
+    /// through CPI aren't checked.
+    #[serde(deserialize_with = "deserialize_pubkey_set")]
+    pub allowed_programs: HashSet<Pubkey>,
+    /// Maximum number of transactions in a bundle
+    pub max_transactions: Option<usize>,
+    /// Maximum compute units a bundle may request
+    pub max_compute_units: Option<u64>,
+    /// Accounts a bundle may not write to, e.g. the validator's own vote and stake accounts
+    #[serde(deserialize_with = "deserialize_pubkey_set")]
+    pub protected_accounts: HashSet<Pubkey>,
+    /// Rejects bundles where a transaction from another signer is bracketed by two transactions
+    /// from the same signer that all write to the same account
+    pub reject_sandwiches: bool,
+    /// Programs (e.g. AMMs) whose writable accounts are looked at by the sandwich rule, when
+    /// called by a top-level instruction. All writable accounts are looked at when empty.
+    #[serde(deserialize_with = "deserialize_pubkey_set")]
+    pub sandwich_programs: HashSet<Pubkey>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BundlePolicyViolation {
+    #[error("bundle has a top-level instruction for denied program {0}")]
+    DeniedProgram(Pubkey),
+
+    #[error("bundle has a top-level instruction for program {0} that isn't allowed")]
+    ProgramNotAllowed(Pubkey),
+
+    #[error("bundle has {0} transactions")]
+    TooManyTransactions(usize),
+
+    #[error("bundle requests {0} compute units")]
+    TooManyComputeUnits(u64),
+
+    #[error("bundle writes to protected account {0}")]
+    WritesProtectedAccount(Pubkey),
+
+    #[error("bundle sandwiches transaction {victim} around account {account}")]
+    Sandwich { victim: usize, account: Pubkey },
+}
+
+impl BundlePolicy {
+    /// Checks a sanitized bundle requesting `compute_unit_limit` compute units against the policy
+    pub fn check(
+        &self,
+        bundle: &SanitizedBundle,
+        compute_unit_limit: u64,
+    ) -> Result<(), BundlePolicyViolation> {
+        if let Some(max_transactions) = self.max_transactions {
+            if bundle.transactions.len() > max_transactions {
+                return Err(BundlePolicyViolation::TooManyTransactions(
+                    bundle.transactions.len(),
+                ));
+            }
+        }
+        if let Some(max_compute_units) = self.max_compute_units {
+            if compute_unit_limit > max_compute_units {
+                return Err(BundlePolicyViolation::TooManyComputeUnits(
+                    compute_unit_limit,
+                ));
+            }
+        }
+
+        for transaction in &bundle.transactions {
+            for (program_id, _) in transaction.message().program_instructions_iter() {
+                if self.denied_programs.contains(program_id) {
+                    return Err(BundlePolicyViolation::DeniedProgram(*program_id));
+                }
+                if !self.allowed_programs.is_empty() && !self.allowed_programs.contains(program_id)
+                {
+                    return Err(BundlePolicyViolation::ProgramNotAllowed(*program_id));
+                }
+            }
+
+            if !self.protected_accounts.is_empty() {
+                let message = transaction.message();
+                if let Some(account) = message
+                    .account_keys()
+                    .iter()
+                    .enumerate()
+                    .find(|(index, account)| {
+                        message.is_writable(*index) && self.protected_accounts.contains(account)
+                    })
+                    .map(|(_, account)| account)
+                {
+                    return Err(BundlePolicyViolation::WritesProtectedAccount(*account));
+                }
+            }
+        }
+
+        if self.reject_sandwiches {
+            self.check_sandwich(&bundle.transactions)?;
+        }
+        Ok(())
+    }
+
+    fn check_sandwich(
+        &self,
+        transactions: &[RuntimeTransaction<SanitizedTransaction>],
+    ) -> Result<(), BundlePolicyViolation> {
+        let writable_accounts: Vec<HashSet<Pubkey>> = transactions
+            .iter()
+            .map(|transaction| self.sandwich_writable_accounts(transaction))
+            .collect();
+
+        for (front, front_transaction) in transactions.iter().enumerate() {
+            let signer = front_transaction.message().fee_payer();
+            for (back, back_transaction) in transactions.iter().enumerate().skip(front + 2) {
+                if back_transaction.message().fee_payer() != signer {
+                    continue;
+                }
+                for (victim, victim_transaction) in
+                    transactions.iter().enumerate().take(back).skip(front + 1)
+                {
+                    if is_signer(victim_transaction, signer) {
+                        continue;
+                    }
+                    if let Some(account) = writable_accounts[victim].iter().find(|account| {
+                        writable_accounts[front].contains(account)
+                            && writable_accounts[back].contains(account)
+                    }) {
+                        return Err(BundlePolicyViolation::Sandwich {
+                            victim,
+                            account: *account,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writable accounts of the transaction the sandwich rule looks at. Only the accounts of
+    /// top-level instructions are attributed to the sandwich programs.
+    fn sandwich_writable_accounts(
+        &self,
+        transaction: &RuntimeTransaction<SanitizedTransaction>,
+    ) -> HashSet<Pubkey> {
+        let message = transaction.message();
+        let account_keys = message.account_keys();
+        if self.sandwich_programs.is_empty() {
+            return account_keys
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| message.is_writable(*index))
+                .map(|(_, account)| *account)
+                .collect();
+        }
+        message
+            .program_instructions_iter()
+            .filter(|(program_id, _)| self.sandwich_programs.contains(program_id))
+            .flat_map(|(_, instruction)| instruction.accounts.iter())
+            .map(|index| usize::from(*index))
+            .filter(|index| message.is_writable(*index))
+            .filter_map(|index| account_keys.get(index).copied())
+            .collect()
+    }
+}
+
+fn is_signer(transaction: &RuntimeTransaction<SanitizedTransaction>, signer: &Pubkey) -> bool {
+    let message = transaction.message();
+    message
+        .account_keys()
+        .iter()
+        .enumerate()
+        .any(|(index, account)| message.is_signer(index) && account == signer)
+}
+
+fn deserialize_pubkey_set<'de, D>(des: D) -> Result<HashSet<Pubkey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let container: Vec<String> = Deserialize::deserialize(des)?;
+    container
+        .iter()
+        .map(|pubkey| {
+            Pubkey::try_from(pubkey.as_str()).map_err(|_| {
+                serde::de::Error::invalid_value(serde::de::Unexpected::Str(pubkey), &"a pubkey")
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash,
+            signature::{Keypair, Signer},
+            system_program, system_transaction,
+        },
+    };
+
+    fn bundle(transactions: Vec<solana_sdk::transaction::Transaction>) -> SanitizedBundle {
+        SanitizedBundle {
+            transactions: transactions
+                .into_iter()
+                .map(RuntimeTransaction::from_transaction_for_tests)
+                .collect(),
+            bundle_id: String::default(),
+            allowed_to_fail_tx_indexes: HashSet::default(),
+        }
+    }
+
+    fn transfer(from: &Keypair, to: &Pubkey) -> solana_sdk::transaction::Transaction {
+        system_transaction::transfer(from, to, 1, Hash::new_unique())
+    }
+
+    #[test]
+    fn test_default_policy() {
+        let (kp, to) = (Keypair::new(), Pubkey::new_unique());
+        let policy = BundlePolicy::default();
+        let bundle = bundle(vec![
+            transfer(&kp, &to),
+            transfer(&Keypair::new(), &to),
+            transfer(&kp, &to),
+        ]);
+        assert_eq!(policy.check(&bundle, u64::MAX), Ok(()));
+    }
+
+    #[test]
+    fn test_program_lists() {
+        let bundle = bundle(vec![transfer(&Keypair::new(), &Pubkey::new_unique())]);
+
+        let policy = BundlePolicy {
+            denied_programs: HashSet::from([system_program::id()]),
+            ..BundlePolicy::default()
+        };
+        assert_eq!(
+            policy.check(&bundle, 0),
+            Err(BundlePolicyViolation::DeniedProgram(system_program::id()))
+        );
+
+        let policy = BundlePolicy {
+            allowed_programs: HashSet::from([Pubkey::new_unique()]),
+            ..BundlePolicy::default()
+        };
+        assert_eq!(
+            policy.check(&bundle, 0),
+            Err(BundlePolicyViolation::ProgramNotAllowed(
+                system_program::id()
+            ))
+        );
+
+        let policy = BundlePolicy {
+            allowed_programs: HashSet::from([system_program::id()]),
+            ..BundlePolicy::default()
+        };
+        assert_eq!(policy.check(&bundle, 0), Ok(()));
+    }
+
+    #[test]
+    fn test_limits() {
+        let bundle = bundle(vec![
+            transfer(&Keypair::new(), &Pubkey::new_unique()),
+            transfer(&Keypair::new(), &Pubkey::new_unique()),
+        ]);
+        let policy = BundlePolicy {
+            max_transactions: Some(1),
+            ..BundlePolicy::default()
+        };
+        assert_eq!(
+            policy.check(&bundle, 0),
+            Err(BundlePolicyViolation::TooManyTransactions(2))
+        );
+
+        let policy = BundlePolicy {
+            max_transactions: Some(2),
+            max_compute_units: Some(1_000),
+            ..BundlePolicy::default()
+        };
+        assert_eq!(policy.check(&bundle, 1_000), Ok(()));
+        assert_eq!(
+            policy.check(&bundle, 1_001),
+            Err(BundlePolicyViolation::TooManyComputeUnits(1_001))
+        );
+    }
+
+    #[test]
+    fn test_protected_accounts() {
+        let (kp, vote_account) = (Keypair::new(), Pubkey::new_unique());
+        let policy = BundlePolicy {
+            protected_accounts: HashSet::from([vote_account, kp.pubkey()]),
+            ..BundlePolicy::default()
+        };
+        assert_eq!(
+            policy.check(&bundle(vec![transfer(&Keypair::new(), &vote_account)]), 0),
+            Err(BundlePolicyViolation::WritesProtectedAccount(vote_account))
+        );
+        assert_eq!(
+            policy.check(&bundle(vec![transfer(&kp, &Pubkey::new_unique())]), 0),
+            Err(BundlePolicyViolation::WritesProtectedAccount(kp.pubkey()))
+        );
+        assert_eq!(
+            policy.check(
+                &bundle(vec![transfer(&Keypair::new(), &Pubkey::new_unique())]),
+                0
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_sandwich() {
+        let (searcher, victim, pool) = (Keypair::new(), Keypair::new(), Pubkey::new_unique());
+        let policy = BundlePolicy {
+            reject_sandwiches: true,
+            ..BundlePolicy::default()
+        };
+
+        let sandwich = bundle(vec![
+            transfer(&searcher, &pool),
+            transfer(&victim, &pool),
+            transfer(&searcher, &pool),
+        ]);
+        assert_eq!(
+            policy.check(&sandwich, 0),
+            Err(BundlePolicyViolation::Sandwich {
+                victim: 1,
+                account: pool
+            })
+        );
+
+        // the bracketing transactions can be further apart
+        let sandwich = bundle(vec![
+            transfer(&searcher, &pool),
+            transfer(&searcher, &Pubkey::new_unique()),
+            transfer(&victim, &pool),
+            transfer(&searcher, &pool),
+        ]);
+        assert_eq!(
+            policy.check(&sandwich, 0),
+            Err(BundlePolicyViolation::Sandwich {
+                victim: 2,
+                account: pool
+            })
+        );
+
+        // a backrun isn't a sandwich
+        let backrun = bundle(vec![transfer(&victim, &pool), transfer(&searcher, &pool)]);
+        assert_eq!(policy.check(&backrun, 0), Ok(()));
+
+        // neither are transactions from the same signer
+        let same_signer = bundle(vec![
+            transfer(&searcher, &pool),
+            transfer(&searcher, &pool),
+            transfer(&searcher, &pool),
+        ]);
+        assert_eq!(policy.check(&same_signer, 0), Ok(()));
+
+        // the middle transaction has to touch the same account
+        let unrelated = bundle(vec![
+            transfer(&searcher, &pool),
+            transfer(&victim, &Pubkey::new_unique()),
+            transfer(&searcher, &pool),
+        ]);
+        assert_eq!(policy.check(&unrelated, 0), Ok(()));
+
+        // only the accounts of the sandwich programs are looked at when there are some
+        let sandwich = bundle(vec![
+            transfer(&searcher, &pool),
+            transfer(&victim, &pool),
+            transfer(&searcher, &pool),
+        ]);
+        let policy = BundlePolicy {
+            reject_sandwiches: true,
+            sandwich_programs: HashSet::from([Pubkey::new_unique()]),
+            ..BundlePolicy::default()
+        };
+        assert_eq!(policy.check(&sandwich, 0), Ok(()));
+        let policy = BundlePolicy {
+            reject_sandwiches: true,
+            sandwich_programs: HashSet::from([system_program::id()]),
+            ..BundlePolicy::default()
+        };
+        assert!(policy.check(&sandwich, 0).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_policy() {
+        let program = Pubkey::new_unique();
+        let policy: BundlePolicy = serde_json::from_str(&format!(
+            r#"{{"denied_programs": ["{program}"], "max_transactions": 3, "reject_sandwiches": true}}"#
+        ))
+        .unwrap();
+        assert_eq!(
+            policy,
+            BundlePolicy {
+                denied_programs: HashSet::from([program]),
+                max_transactions: Some(3),
+                reject_sandwiches: true,
+                ..BundlePolicy::default()
+            }
+        );
+        assert!(
+            serde_json::from_str::<BundlePolicy>(r#"{"denied_programs": ["not-a-pubkey"]}"#)
+                .is_err()
+        );
+    }
+}
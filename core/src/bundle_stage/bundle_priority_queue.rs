@@ -0,0 +1,287 @@
+//! Bounded, priority-ordered buffer of bundles waiting to be executed.
+//!
+//! Bundles are ordered by their priority (estimated tip per compute unit) so the most valuable
+//! bundles are executed first and the least valuable ones are evicted when the buffer is full.
+//! Bundles with the same priority keep their insertion order.
+use {
+    crate::immutable_deserialized_bundle::ImmutableDeserializedBundle,
+    min_max_heap::MinMaxHeap,
+    std::{cmp::Ordering, collections::HashSet},
+};
+
+#[derive(Debug)]
+struct PrioritizedBundle {
+    priority: u64,
+    sequence: u64,
+    bundle: ImmutableDeserializedBundle,
+}
+
+impl PartialEq for PrioritizedBundle {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PrioritizedBundle {}
+
+impl PartialOrd for PrioritizedBundle {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PrioritizedBundle {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // lower sequence numbers were inserted first and are considered higher priority
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum InsertBundleOutcome {
+    Inserted,
+    /// Inserted after evicting the returned lowest priority bundle
+    InsertedWithEviction(ImmutableDeserializedBundle),
+    /// A bundle with the same id is already buffered
+    Duplicate,
+    /// The buffer is full of bundles with a higher or equal priority
+    Dropped,
+}
+
+#[derive(Debug)]
+pub(crate) struct BundlePriorityQueue {
+    heap: MinMaxHeap<PrioritizedBundle>,
+    bundle_ids: HashSet<String>,
+    capacity: usize,
+    // Bundles pushed to the front count down from the midpoint and bundles pushed to the back count
+    // up, so bundles pushed to the front are taken before bundles with the same priority.
+    next_front_sequence: u64,
+    next_back_sequence: u64,
+}
+
+impl BundlePriorityQueue {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        const MIDPOINT: u64 = u64::MAX / 2;
+        Self {
+            heap: MinMaxHeap::with_capacity(capacity),
+            bundle_ids: HashSet::with_capacity(capacity),
+            capacity,
+            next_front_sequence: MIDPOINT,
+            next_back_sequence: MIDPOINT.saturating_add(1),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn num_packets(&self) -> usize {
+        self.heap.iter().map(|b| b.bundle.len()).sum()
+    }
+
+    /// Sum of the compute unit limits of the bundles with at least `min_priority`
+    pub(crate) fn compute_unit_limit(&self, min_priority: u64) -> u64 {
+        self.heap
+            .iter()
+            .filter(|b| b.priority >= min_priority)
+            .map(|b| b.bundle.compute_unit_limit())
+            .fold(0, u64::saturating_add)
+    }
+
+    pub(crate) fn contains(&self, bundle_id: &str) -> bool {
+        self.bundle_ids.contains(bundle_id)
+    }
+
+    /// Inserts a bundle. When full, the lowest priority bundle is evicted if the new bundle has a
+    /// strictly higher priority, otherwise the new bundle is dropped.
+    pub(crate) fn insert(
+        &mut self,
+        bundle: ImmutableDeserializedBundle,
+        priority: u64,
+        push_front: bool,
+    ) -> InsertBundleOutcome {
+        if self.bundle_ids.contains(bundle.bundle_id()) {
+            return InsertBundleOutcome::Duplicate;
+        }
+
+        let evicted = if self.heap.len() >= self.capacity {
+            match self.heap.peek_min() {
+                Some(lowest) if lowest.priority < priority => {
+                    let evicted = self.heap.pop_min().unwrap().bundle;
+                    self.bundle_ids.remove(evicted.bundle_id());
+                    Some(evicted)
+                }
+                _ => return InsertBundleOutcome::Dropped,
+            }
+        } else {
+            None
+        };
+
+        let sequence = if push_front {
+            let sequence = self.next_front_sequence;
+            self.next_front_sequence = self.next_front_sequence.saturating_sub(1);
+            sequence
+        } else {
+            let sequence = self.next_back_sequence;
+            self.next_back_sequence = self.next_back_sequence.saturating_add(1);
+            sequence
+        };
+
+        self.bundle_ids.insert(bundle.bundle_id().to_string());
+        self.heap.push(PrioritizedBundle {
+            priority,
+            sequence,
+            bundle,
+        });
+
+        match evicted {
+            Some(evicted) => InsertBundleOutcome::InsertedWithEviction(evicted),
+            None => InsertBundleOutcome::Inserted,
+        }
+    }
+
+    /// Removes all bundles, returning them from highest to lowest priority
+    pub(crate) fn drain_by_priority(&mut self) -> Vec<ImmutableDeserializedBundle> {
+        self.bundle_ids.clear();
+        let mut bundles = Vec::with_capacity(self.heap.len());
+        while let Some(b) = self.heap.pop_max() {
+            bundles.push(b.bundle);
+        }
+        bundles
+    }
+
+    /// Removes all bundles, returning the number removed
+    pub(crate) fn clear(&mut self) -> usize {
+        let len = self.heap.len();
+        self.heap.clear();
+        self.bundle_ids.clear();
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::packet_bundle::PacketBundle,
+        solana_perf::packet::{Packet, PacketBatch},
+        solana_sdk::{
+            hash::Hash,
+            signature::{Keypair, Signer},
+            system_transaction::transfer,
+        },
+    };
+
+    fn make_bundle(bundle_id: &str) -> ImmutableDeserializedBundle {
+        let kp = Keypair::new();
+        let tx = transfer(&kp, &kp.pubkey(), 1, Hash::default());
+        ImmutableDeserializedBundle::new(
+            &mut PacketBundle {
+                batch: PacketBatch::new(vec![Packet::from_data(None, &tx).unwrap()]),
+                bundle_id: bundle_id.to_string(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
+            },
+            None,
+            &Ok,
+        )
+        .unwrap()
+    }
+
+    fn drained_ids(queue: &mut BundlePriorityQueue) -> Vec<String> {
+        queue
+            .drain_by_priority()
+            .iter()
+            .map(|b| b.bundle_id().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_drain_by_priority_then_insertion_order() {
+        let mut queue = BundlePriorityQueue::with_capacity(10);
+        assert_matches!(
+            queue.insert(make_bundle("a"), 1, false),
+            InsertBundleOutcome::Inserted
+        );
+        assert_matches!(
+            queue.insert(make_bundle("b"), 5, false),
+            InsertBundleOutcome::Inserted
+        );
+        assert_matches!(
+            queue.insert(make_bundle("c"), 1, false),
+            InsertBundleOutcome::Inserted
+        );
+        assert_matches!(
+            queue.insert(make_bundle("d"), 1, true),
+            InsertBundleOutcome::Inserted
+        );
+        assert_eq!(queue.len(), 4);
+
+        assert_eq!(drained_ids(&mut queue), vec!["b", "d", "a", "c"]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_bundle_id() {
+        let mut queue = BundlePriorityQueue::with_capacity(10);
+        assert_matches!(
+            queue.insert(make_bundle("a"), 1, false),
+            InsertBundleOutcome::Inserted
+        );
+        assert_matches!(
+            queue.insert(make_bundle("a"), 100, false),
+            InsertBundleOutcome::Duplicate
+        );
+        assert_eq!(queue.len(), 1);
+
+        // ids can be reused once the bundle leaves the queue
+        queue.drain_by_priority();
+        assert_matches!(
+            queue.insert(make_bundle("a"), 1, false),
+            InsertBundleOutcome::Inserted
+        );
+    }
+
+    #[test]
+    fn test_evicts_lowest_priority_when_full() {
+        let mut queue = BundlePriorityQueue::with_capacity(2);
+        queue.insert(make_bundle("a"), 10, false);
+        queue.insert(make_bundle("b"), 5, false);
+
+        // equal priority to the lowest doesn't evict
+        assert_matches!(
+            queue.insert(make_bundle("c"), 5, false),
+            InsertBundleOutcome::Dropped
+        );
+        assert_matches!(
+            queue.insert(make_bundle("d"), 7, false),
+            InsertBundleOutcome::InsertedWithEviction(evicted) if evicted.bundle_id() == "b"
+        );
+        assert!(!queue.contains("b"));
+        assert_eq!(drained_ids(&mut queue), vec!["a", "d"]);
+    }
+
+    #[test]
+    fn test_compute_unit_limit() {
+        let mut queue = BundlePriorityQueue::with_capacity(10);
+        let bundle_compute_unit_limit = make_bundle("a").compute_unit_limit();
+        assert!(bundle_compute_unit_limit > 0);
+        queue.insert(make_bundle("a"), 1, false);
+        queue.insert(make_bundle("b"), 5, false);
+        queue.insert(make_bundle("c"), 10, true);
+
+        assert_eq!(queue.compute_unit_limit(0), 3 * bundle_compute_unit_limit);
+        assert_eq!(queue.compute_unit_limit(5), 2 * bundle_compute_unit_limit);
+        assert_eq!(queue.compute_unit_limit(11), 0);
+    }
+}
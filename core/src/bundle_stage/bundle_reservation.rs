@@ -0,0 +1,168 @@
+//! Compute units of every block that BankingStage leaves unused so bundles have room.
+//!
+//! At the start of a slot `preallocated_bundle_cost` CUs are reserved. Once `release_slot_percent`
+//! of the slot's ticks have elapsed, the preallocated part is released back to BankingStage. When
+//! bundles paying at least `high_tip_micro_lamports_per_cu` are waiting in BundleStage, the
+//! reservation grows to the CUs they request, up to `max_preallocated_bundle_cost`, and stays at
+//! that size after the release point.
+//!
+//! The reservation is headroom on the block cost limit: BankingStage can't grow the block past
+//! the limit minus the reservation, while bundles can use the whole limit.
+use {solana_runtime::bank::Bank, solana_sdk::clock::Slot, std::sync::Mutex};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleReservationConfig {
+    /// CUs reserved for bundles at the start of every slot
+    pub preallocated_bundle_cost: u64,
+    /// Upper bound on the reservation when it grows for waiting high-tip bundles
+    pub max_preallocated_bundle_cost: u64,
+    /// Percent of the slot, in ticks, after which the preallocated CUs are released
+    pub release_slot_percent: u64,
+    /// Bundles paying at least this tip per compute unit, in micro-lamports, grow the reservation
+    pub high_tip_micro_lamports_per_cu: u64,
+}
+
+impl Default for BundleReservationConfig {
+    fn default() -> Self {
+        Self {
+            preallocated_bundle_cost: 0,
+            max_preallocated_bundle_cost: 0,
+            release_slot_percent: 80,
+            high_tip_micro_lamports_per_cu: 0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BundleReservation {
+    config: BundleReservationConfig,
+    // CUs requested by the high-tip bundles last seen waiting in BundleStage, and the slot they
+    // were seen in
+    demand: Mutex<(Slot, u64)>,
+}
+
+impl BundleReservation {
+    pub fn new(config: BundleReservationConfig) -> Self {
+        Self {
+            config,
+            demand: Mutex::default(),
+        }
+    }
+
+    pub fn config(&self) -> &BundleReservationConfig {
+        &self.config
+    }
+
+    /// Records the CUs requested by the high-tip bundles waiting to be executed in `slot`
+    pub fn update_demand(&self, slot: Slot, compute_units: u64) {
+        *self.demand.lock().unwrap() = (slot, compute_units);
+    }
+
+    /// CUs BankingStage must leave unused in `bank`'s block
+    pub fn block_cost_limit_reservation(&self, bank: &Bank) -> u64 {
+        let demand = match *self.demand.lock().unwrap() {
+            (slot, compute_units) if slot == bank.slot() => compute_units,
+            _ => 0,
+        };
+        self.reservation(
+            bank.tick_height() % bank.ticks_per_slot(),
+            bank.ticks_per_slot(),
+            demand,
+        )
+    }
+
+    fn reservation(&self, tick_in_slot: u64, ticks_per_slot: u64, demand: u64) -> u64 {
+        let release_tick = ticks_per_slot
+            .saturating_mul(self.config.release_slot_percent)
+            .saturating_div(100);
+        let max_reservation = self
+            .config
+            .max_preallocated_bundle_cost
+            .max(self.config.preallocated_bundle_cost);
+
+        let reservation = if tick_in_slot < release_tick {
+            demand.max(self.config.preallocated_bundle_cost)
+        } else {
+            demand
+        };
+        reservation.min(max_reservation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_ledger::genesis_utils::create_genesis_config, solana_pubkey::Pubkey,
+        std::sync::Arc,
+    };
+
+    #[test]
+    fn test_reservation() {
+        let reservation = BundleReservation::new(BundleReservationConfig {
+            preallocated_bundle_cost: 100,
+            max_preallocated_bundle_cost: 250,
+            release_slot_percent: 80,
+            high_tip_micro_lamports_per_cu: 0,
+        });
+
+        // preallocated until 80% of the slot, then released
+        assert_eq!(reservation.reservation(0, 64, 0), 100);
+        assert_eq!(reservation.reservation(50, 64, 0), 100);
+        assert_eq!(reservation.reservation(51, 64, 0), 0);
+
+        // grows for waiting bundles, up to the max, and is kept after the release point
+        assert_eq!(reservation.reservation(0, 64, 50), 100);
+        assert_eq!(reservation.reservation(0, 64, 200), 200);
+        assert_eq!(reservation.reservation(0, 64, 1_000), 250);
+        assert_eq!(reservation.reservation(60, 64, 200), 200);
+        assert_eq!(reservation.reservation(60, 64, 1_000), 250);
+
+        // a max below the preallocated cost never shrinks the preallocated cost
+        let reservation = BundleReservation::new(BundleReservationConfig {
+            preallocated_bundle_cost: 100,
+            ..BundleReservationConfig::default()
+        });
+        assert_eq!(reservation.reservation(0, 64, 1_000), 100);
+        assert_eq!(reservation.reservation(60, 64, 1_000), 100);
+    }
+
+    #[test]
+    fn test_block_cost_limit_reservation() {
+        const PREALLOCATED_BUNDLE_COST: u64 = 100;
+        let genesis_config_info = create_genesis_config(100);
+        let ticks_per_slot = genesis_config_info.genesis_config.ticks_per_slot;
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config_info.genesis_config));
+
+        for _ in 0..ticks_per_slot {
+            bank.register_default_tick_for_test();
+        }
+        assert!(bank.is_complete());
+        bank.freeze();
+        let bank1 = Arc::new(Bank::new_from_parent(bank.clone(), &Pubkey::default(), 1));
+
+        let reservation = BundleReservation::new(BundleReservationConfig {
+            preallocated_bundle_cost: PREALLOCATED_BUNDLE_COST,
+            max_preallocated_bundle_cost: 2 * PREALLOCATED_BUNDLE_COST,
+            ..BundleReservationConfig::default()
+        });
+        // demand seen in an earlier slot doesn't carry over
+        reservation.update_demand(0, 2 * PREALLOCATED_BUNDLE_COST);
+
+        // wait for reservation to be over
+        let release_tick = ticks_per_slot * 8 / 10;
+        (0..release_tick).for_each(|_| {
+            assert_eq!(
+                reservation.block_cost_limit_reservation(&bank1),
+                PREALLOCATED_BUNDLE_COST
+            );
+            bank1.register_default_tick_for_test();
+        });
+        assert_eq!(reservation.block_cost_limit_reservation(&bank1), 0);
+
+        reservation.update_demand(1, PREALLOCATED_BUNDLE_COST / 2);
+        assert_eq!(
+            reservation.block_cost_limit_reservation(&bank1),
+            PREALLOCATED_BUNDLE_COST / 2
+        );
+    }
+}
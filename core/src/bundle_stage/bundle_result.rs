@@ -0,0 +1,201 @@
+//! Per-bundle execution outcomes reported back to the Block Engine.
+//!
+//! BundleConsumer produces a [BundleResult] for every bundle execution attempt and pushes it
+//! into a bounded buffer. BlockEngineStage drains the buffer and streams the results to the
+//! connected Block Engine, falling back to a local file when the connection is down.
+use {
+    agave_geyser_plugin_interface::geyser_plugin_interface::BundleOutcome as GeyserBundleOutcome,
+    solana_bundle::{
+        bundle_execution::LoadAndExecuteBundleError, BundleExecutionError, BundleExecutionResult,
+    },
+    solana_sdk::clock::Slot,
+    std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    tokio::sync::mpsc::{self, error::TrySendError},
+};
+
+/// Maximum number of results buffered between BundleStage and BlockEngineStage.
+pub const BUNDLE_RESULT_BUFFER_CAPACITY: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BundleOutcome {
+    Landed,
+    TransactionFailure,
+    ExecutionTimeout,
+    TipFailure,
+    ExceedsCostModel,
+    LockFailure,
+    SlotExpired,
+    InternalError,
+}
+
+impl From<&BundleExecutionResult<()>> for BundleOutcome {
+    fn from(result: &BundleExecutionResult<()>) -> Self {
+        match result {
+            Ok(_) => BundleOutcome::Landed,
+            Err(BundleExecutionError::BankProcessingTimeLimitReached)
+            | Err(BundleExecutionError::PohRecordError(_)) => BundleOutcome::SlotExpired,
+            Err(BundleExecutionError::ExceedsCostModel) => BundleOutcome::ExceedsCostModel,
+            Err(BundleExecutionError::LockError)
+            | Err(BundleExecutionError::TransactionFailure(
+                LoadAndExecuteBundleError::LockError { .. },
+            )) => BundleOutcome::LockFailure,
+            Err(BundleExecutionError::TransactionFailure(
+                LoadAndExecuteBundleError::ProcessingTimeExceeded(_),
+            )) => BundleOutcome::ExecutionTimeout,
+            Err(BundleExecutionError::TransactionFailure(
+                LoadAndExecuteBundleError::TransactionError { .. },
+            )) => BundleOutcome::TransactionFailure,
+            Err(BundleExecutionError::TransactionFailure(
+                LoadAndExecuteBundleError::InvalidPreOrPostAccounts,
+            )) => BundleOutcome::InternalError,
+            Err(BundleExecutionError::TipError(_)) => BundleOutcome::TipFailure,
+        }
+    }
+}
+
+impl From<BundleOutcome> for GeyserBundleOutcome {
+    fn from(outcome: BundleOutcome) -> Self {
+        match outcome {
+            BundleOutcome::Landed => GeyserBundleOutcome::Landed,
+            BundleOutcome::TransactionFailure => GeyserBundleOutcome::TransactionFailure,
+            BundleOutcome::ExecutionTimeout => GeyserBundleOutcome::ExecutionTimeout,
+            BundleOutcome::TipFailure => GeyserBundleOutcome::TipFailure,
+            BundleOutcome::ExceedsCostModel => GeyserBundleOutcome::ExceedsCostModel,
+            BundleOutcome::LockFailure => GeyserBundleOutcome::LockFailure,
+            BundleOutcome::SlotExpired => GeyserBundleOutcome::SlotExpired,
+            BundleOutcome::InternalError => GeyserBundleOutcome::InternalError,
+        }
+    }
+}
+
+/// The outcome of a single bundle execution attempt.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleResult {
+    /// Derived from the bundle's transaction signatures, see `derive_bundle_id_from_sanitized_transactions`
+    pub bundle_id: String,
+    /// The id the Block Engine assigned to the bundle
+    pub uuid: String,
+    /// The Block Engine the bundle was received from, the result is streamed back to it
+    pub source: Option<String>,
+    pub slot: Slot,
+    pub outcome: BundleOutcome,
+    pub compute_units_consumed: u64,
+    pub tip_lamports: u64,
+    pub error: Option<String>,
+    pub timestamp_ms: u64,
+}
+
+pub type BundleResultReceiver = mpsc::Receiver<BundleResult>;
+
+/// Non-blocking, bounded sender used by BundleStage. Results are dropped when the buffer is full
+/// so a slow or disconnected Block Engine never stalls bundle execution.
+#[derive(Clone)]
+pub struct BundleResultSender {
+    sender: mpsc::Sender<BundleResult>,
+    num_dropped: Arc<AtomicU64>,
+}
+
+impl BundleResultSender {
+    pub fn send(&self, result: BundleResult) {
+        match self.sender.try_send(result) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.num_dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Closed(_)) => {}
+        }
+    }
+
+    /// Returns the number of results dropped since the last call
+    pub fn take_num_dropped(&self) -> u64 {
+        self.num_dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+pub fn bundle_result_channel(capacity: usize) -> (BundleResultSender, BundleResultReceiver) {
+    let (sender, receiver) = mpsc::channel(capacity);
+    (
+        BundleResultSender {
+            sender,
+            num_dropped: Arc::default(),
+        },
+        receiver,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_bundle::TipError,
+        solana_sdk::{signature::Signature, transaction::TransactionError},
+        std::time::Duration,
+    };
+
+    fn test_result(bundle_id: &str) -> BundleResult {
+        BundleResult {
+            bundle_id: bundle_id.to_string(),
+            uuid: String::default(),
+            source: None,
+            slot: 0,
+            outcome: BundleOutcome::Landed,
+            compute_units_consumed: 0,
+            tip_lamports: 0,
+            error: None,
+            timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_outcome_from_execution_result() {
+        assert_eq!(BundleOutcome::from(&Ok(())), BundleOutcome::Landed);
+        assert_eq!(
+            BundleOutcome::from(&Err(BundleExecutionError::BankProcessingTimeLimitReached)),
+            BundleOutcome::SlotExpired
+        );
+        assert_eq!(
+            BundleOutcome::from(&Err(BundleExecutionError::ExceedsCostModel)),
+            BundleOutcome::ExceedsCostModel
+        );
+        assert_eq!(
+            BundleOutcome::from(&Err(BundleExecutionError::LockError)),
+            BundleOutcome::LockFailure
+        );
+        assert_eq!(
+            BundleOutcome::from(&Err(BundleExecutionError::TransactionFailure(
+                LoadAndExecuteBundleError::LockError {
+                    signature: Signature::default(),
+                    transaction_error: TransactionError::AccountInUse,
+                }
+            ))),
+            BundleOutcome::LockFailure
+        );
+        assert_eq!(
+            BundleOutcome::from(&Err(BundleExecutionError::TransactionFailure(
+                LoadAndExecuteBundleError::ProcessingTimeExceeded(Duration::from_millis(50))
+            ))),
+            BundleOutcome::ExecutionTimeout
+        );
+        assert_eq!(
+            BundleOutcome::from(&Err(BundleExecutionError::TipError(TipError::LockError))),
+            BundleOutcome::TipFailure
+        );
+    }
+
+    #[test]
+    fn test_sender_drops_when_full() {
+        let (sender, mut receiver) = bundle_result_channel(2);
+        sender.send(test_result("a"));
+        sender.send(test_result("b"));
+        sender.send(test_result("c"));
+        assert_eq!(sender.take_num_dropped(), 1);
+        assert_eq!(sender.take_num_dropped(), 0);
+
+        assert_eq!(receiver.try_recv().unwrap().bundle_id, "a");
+        assert_eq!(receiver.try_recv().unwrap().bundle_id, "b");
+        assert!(receiver.try_recv().is_err());
+    }
+}
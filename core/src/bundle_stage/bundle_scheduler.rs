@@ -0,0 +1,173 @@
+//! Schedules bundles onto BundleStage's consume workers.
+//!
+//! Bundles are inserted into a [PrioGraph] in priority order using the read and write locks held
+//! by each bundle. Bundles that are unblocked in the graph don't conflict with each other, so they
+//! can be executed in parallel. Bundles that conflict are executed in priority order.
+use {
+    crate::bundle_stage::bundle_account_locker::BundleAccountLocks,
+    prio_graph::{AccessKind, GraphNode, PrioGraph, TopLevelId},
+    solana_sdk::pubkey::Pubkey,
+    std::cmp::Ordering,
+};
+
+/// Index of a bundle in the slice passed to [BundleScheduler::schedule]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct BundleIndex(usize);
+
+impl Ord for BundleIndex {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // bundles are passed in priority order, so lower indexes have a higher priority
+        other.0.cmp(&self.0)
+    }
+}
+
+impl PartialOrd for BundleIndex {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl TopLevelId<Self> for BundleIndex {
+    fn id(&self) -> Self {
+        *self
+    }
+}
+
+#[inline(always)]
+fn passthrough_priority(id: &BundleIndex, _graph_node: &GraphNode<BundleIndex>) -> BundleIndex {
+    *id
+}
+
+type BundlePrioGraph = PrioGraph<
+    BundleIndex,
+    Pubkey,
+    BundleIndex,
+    fn(&BundleIndex, &GraphNode<BundleIndex>) -> BundleIndex,
+>;
+
+pub(crate) struct BundleScheduler {
+    prio_graph: BundlePrioGraph,
+    max_batch_size: usize,
+}
+
+impl BundleScheduler {
+    /// `max_batch_size` is typically the number of consume workers
+    pub(crate) fn new(max_batch_size: usize) -> Self {
+        Self {
+            prio_graph: PrioGraph::new(passthrough_priority),
+            max_batch_size: max_batch_size.max(1),
+        }
+    }
+
+    /// Splits the bundles into batches that can each be executed in parallel. The batches must be
+    /// executed one after another in the order they're returned. Bundles are identified by their
+    /// index in `bundle_account_locks` and `None` entries are skipped.
+    pub(crate) fn schedule(
+        &mut self,
+        bundle_account_locks: &[Option<&BundleAccountLocks>],
+    ) -> Vec<Vec<usize>> {
+        for (index, account_locks) in bundle_account_locks.iter().enumerate() {
+            let Some(account_locks) = account_locks else {
+                continue;
+            };
+            let write_locks = account_locks.write_locks();
+            let read_locks = account_locks.read_locks();
+            // an account that's read by one transaction and written by another is write-locked
+            let account_access = write_locks
+                .iter()
+                .map(|account| (*account, AccessKind::Write))
+                .chain(
+                    read_locks
+                        .difference(&write_locks)
+                        .map(|account| (*account, AccessKind::Read)),
+                );
+            self.prio_graph
+                .insert_transaction(BundleIndex(index), account_access);
+        }
+
+        let mut batches = Vec::new();
+        while !self.prio_graph.is_empty() {
+            let mut batch = Vec::with_capacity(self.max_batch_size);
+            while batch.len() < self.max_batch_size {
+                match self.prio_graph.pop() {
+                    Some(id) => batch.push(id),
+                    None => break,
+                }
+            }
+            // bundles blocked by this batch can be scheduled in the next one
+            for id in &batch {
+                self.prio_graph.unblock(id);
+            }
+            batches.push(batch.into_iter().map(|id| id.0).collect());
+        }
+        self.prio_graph.clear();
+
+        batches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_locks(reads: &[Pubkey], writes: &[Pubkey]) -> BundleAccountLocks {
+        let mut account_locks = BundleAccountLocks::default();
+        account_locks.lock_accounts(
+            reads.iter().map(|account| (*account, 1)).collect(),
+            writes.iter().map(|account| (*account, 1)).collect(),
+        );
+        account_locks
+    }
+
+    #[test]
+    fn test_schedule_non_conflicting() {
+        let [a, b, c] = [(); 3].map(|_| Pubkey::new_unique());
+        let locks = [
+            account_locks(&[], &[a]),
+            account_locks(&[], &[b]),
+            account_locks(&[], &[c]),
+        ];
+
+        let mut scheduler = BundleScheduler::new(4);
+        let batches = scheduler.schedule(&locks.iter().map(Some).collect::<Vec<_>>());
+        assert_eq!(batches, vec![vec![0, 1, 2]]);
+
+        // batches are capped at the max batch size and keep priority order
+        let mut scheduler = BundleScheduler::new(2);
+        let batches = scheduler.schedule(&locks.iter().map(Some).collect::<Vec<_>>());
+        assert_eq!(batches, vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn test_schedule_conflicting() {
+        let [a, b, c] = [(); 3].map(|_| Pubkey::new_unique());
+        let locks = [
+            // write-write conflict with 1
+            account_locks(&[], &[a]),
+            account_locks(&[b], &[a]),
+            // read-read doesn't conflict with 1
+            account_locks(&[b], &[c]),
+            // read-write conflict with 1 and 2
+            account_locks(&[], &[b]),
+        ];
+
+        let mut scheduler = BundleScheduler::new(4);
+        let batches = scheduler.schedule(&locks.iter().map(Some).collect::<Vec<_>>());
+        assert_eq!(batches, vec![vec![0, 2], vec![1], vec![3]]);
+    }
+
+    #[test]
+    fn test_schedule_skips_missing_bundles() {
+        let a = Pubkey::new_unique();
+        let locks = account_locks(&[], &[a]);
+
+        let mut scheduler = BundleScheduler::new(4);
+        let batches = scheduler.schedule(&[None, Some(&locks), None, Some(&locks)]);
+        assert_eq!(batches, vec![vec![1], vec![3]]);
+
+        // the scheduler is reusable
+        assert!(scheduler.schedule(&[]).is_empty());
+        let batches = scheduler.schedule(&[Some(&locks)]);
+        assert_eq!(batches, vec![vec![0]]);
+    }
+}
@@ -4,6 +4,7 @@ use {
             leader_slot_metrics::{self, LeaderSlotMetricsTracker},
             unprocessed_transaction_storage::UnprocessedTransactionStorage,
         },
+        bundle_stage::bundle_policy::BundlePolicyViolation,
         immutable_deserialized_bundle::DeserializedBundleError,
     },
     solana_bundle::{
@@ -214,11 +215,56 @@ impl BundleStageStatsMetricsTracker {
                             1
                         );
                     }
+                    DeserializedBundleError::InvalidAllowedToFailIndex(_)
+                    | DeserializedBundleError::AllTransactionsAllowedToFail
+                    | DeserializedBundleError::TipTransactionAllowedToFail(_) => {
+                        saturating_add_assign!(
+                            bundle_stage_metrics.sanitize_transaction_invalid_allowed_to_fail,
+                            1
+                        );
+                    }
                 },
             }
         }
     }
 
+    pub(crate) fn increment_bundle_policy_violation(&mut self, violation: &BundlePolicyViolation) {
+        if let Some(bundle_stage_metrics) = self.bundle_stage_metrics.as_mut() {
+            match violation {
+                BundlePolicyViolation::DeniedProgram(_) => {
+                    saturating_add_assign!(bundle_stage_metrics.bundle_policy_denied_program, 1);
+                }
+                BundlePolicyViolation::ProgramNotAllowed(_) => {
+                    saturating_add_assign!(
+                        bundle_stage_metrics.bundle_policy_program_not_allowed,
+                        1
+                    );
+                }
+                BundlePolicyViolation::TooManyTransactions(_) => {
+                    saturating_add_assign!(
+                        bundle_stage_metrics.bundle_policy_too_many_transactions,
+                        1
+                    );
+                }
+                BundlePolicyViolation::TooManyComputeUnits(_) => {
+                    saturating_add_assign!(
+                        bundle_stage_metrics.bundle_policy_too_many_compute_units,
+                        1
+                    );
+                }
+                BundlePolicyViolation::WritesProtectedAccount(_) => {
+                    saturating_add_assign!(
+                        bundle_stage_metrics.bundle_policy_protected_account_write,
+                        1
+                    );
+                }
+                BundlePolicyViolation::Sandwich { .. } => {
+                    saturating_add_assign!(bundle_stage_metrics.bundle_policy_sandwich, 1);
+                }
+            }
+        }
+    }
+
     pub fn increment_bundle_execution_result(&mut self, result: &Result<(), BundleExecutionError>) {
         if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
             match result {
@@ -317,6 +363,49 @@ impl BundleStageStatsMetricsTracker {
         }
     }
 
+    pub(crate) fn increment_num_bundle_results_dropped(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(bundle_stage_metrics.num_bundle_results_dropped, count);
+        }
+    }
+
+    pub(crate) fn increment_schedule_bundles_elapsed_us(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(bundle_stage_metrics.schedule_bundles_elapsed_us, count);
+        }
+    }
+
+    pub(crate) fn increment_num_parallel_batches(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(bundle_stage_metrics.num_parallel_batches, count);
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn num_parallel_batches(&self) -> u64 {
+        self.bundle_stage_metrics
+            .as_ref()
+            .map(|bundle_stage_metrics| bundle_stage_metrics.num_parallel_batches)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn increment_num_simulation_cache_hits(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(bundle_stage_metrics.num_simulation_cache_hits, count);
+        }
+    }
+
+    /// Keeps the largest bundle reservation and demand seen during the slot
+    pub(crate) fn update_bundle_reservation(&mut self, demand: u64, reservation: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            bundle_stage_metrics.max_bundle_cost_demand =
+                bundle_stage_metrics.max_bundle_cost_demand.max(demand);
+            bundle_stage_metrics.max_bundle_cost_reservation = bundle_stage_metrics
+                .max_bundle_cost_reservation
+                .max(reservation);
+        }
+    }
+
     pub(crate) fn increment_execute_locked_bundles_elapsed_us(&mut self, count: u64) {
         if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
             saturating_add_assign!(
@@ -339,6 +428,7 @@ pub struct BundleStageStats {
     sanitize_transaction_failed_to_serialize: u64,
     sanitize_transaction_duplicate_transaction: u64,
     sanitize_transaction_failed_check: u64,
+    sanitize_transaction_invalid_allowed_to_fail: u64,
     sanitize_bundle_elapsed_us: u64,
     sanitize_transaction_failed_empty_batch: u64,
     sanitize_transaction_failed_too_many_packets: u64,
@@ -346,6 +436,14 @@ pub struct BundleStageStats {
     sanitize_transaction_failed_sig_verify_failed: u64,
     packet_filter_failure: u64,
 
+    // bundles dropped by the bundle policy, by rule
+    bundle_policy_denied_program: u64,
+    bundle_policy_program_not_allowed: u64,
+    bundle_policy_too_many_transactions: u64,
+    bundle_policy_too_many_compute_units: u64,
+    bundle_policy_protected_account_write: u64,
+    bundle_policy_sandwich: u64,
+
     locked_bundle_elapsed_us: u64,
 
     num_lock_errors: u64,
@@ -360,8 +458,19 @@ pub struct BundleStageStats {
     num_execution_timeouts: u64,
     num_execution_retries: u64,
 
+    num_bundle_results_dropped: u64,
+
+    schedule_bundles_elapsed_us: u64,
+    // batches of non-conflicting bundles executed in parallel
+    num_parallel_batches: u64,
+    // bundles rejected without executing because they're known to fail against the bank
+    num_simulation_cache_hits: u64,
     execute_locked_bundles_elapsed_us: u64,
 
+    // CUs requested by waiting high-tip bundles and CUs reserved from BankingStage, at their peak
+    max_bundle_cost_demand: u64,
+    max_bundle_cost_reservation: u64,
+
     execution_results_ok: u64,
     execution_results_poh_max_height: u64,
     execution_results_transaction_failures: u64,
@@ -424,6 +533,11 @@ impl BundleStageStats {
                 self.sanitize_transaction_failed_check,
                 i64
             ),
+            (
+                "sanitize_transaction_invalid_allowed_to_fail",
+                self.sanitize_transaction_invalid_allowed_to_fail,
+                i64
+            ),
             (
                 "sanitize_bundle_elapsed_us",
                 self.sanitize_bundle_elapsed_us,
@@ -450,6 +564,32 @@ impl BundleStageStats {
                 i64
             ),
             ("packet_filter_failure", self.packet_filter_failure, i64),
+            (
+                "bundle_policy_denied_program",
+                self.bundle_policy_denied_program,
+                i64
+            ),
+            (
+                "bundle_policy_program_not_allowed",
+                self.bundle_policy_program_not_allowed,
+                i64
+            ),
+            (
+                "bundle_policy_too_many_transactions",
+                self.bundle_policy_too_many_transactions,
+                i64
+            ),
+            (
+                "bundle_policy_too_many_compute_units",
+                self.bundle_policy_too_many_compute_units,
+                i64
+            ),
+            (
+                "bundle_policy_protected_account_write",
+                self.bundle_policy_protected_account_write,
+                i64
+            ),
+            ("bundle_policy_sandwich", self.bundle_policy_sandwich, i64),
             (
                 "locked_bundle_elapsed_us",
                 self.locked_bundle_elapsed_us,
@@ -479,11 +619,33 @@ impl BundleStageStats {
             ),
             ("num_execution_timeouts", self.num_execution_timeouts, i64),
             ("num_execution_retries", self.num_execution_retries, i64),
+            (
+                "num_bundle_results_dropped",
+                self.num_bundle_results_dropped,
+                i64
+            ),
+            (
+                "schedule_bundles_elapsed_us",
+                self.schedule_bundles_elapsed_us,
+                i64
+            ),
+            ("num_parallel_batches", self.num_parallel_batches, i64),
+            (
+                "num_simulation_cache_hits",
+                self.num_simulation_cache_hits,
+                i64
+            ),
             (
                 "execute_locked_bundles_elapsed_us",
                 self.execute_locked_bundles_elapsed_us,
                 i64
             ),
+            ("max_bundle_cost_demand", self.max_bundle_cost_demand, i64),
+            (
+                "max_bundle_cost_reservation",
+                self.max_bundle_cost_reservation,
+                i64
+            ),
             ("execution_results_ok", self.execution_results_ok, i64),
             (
                 "execution_results_poh_max_height",
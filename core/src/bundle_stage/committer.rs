@@ -1,9 +1,18 @@
 use {
-    crate::banking_stage::{
-        committer::CommitTransactionDetails,
-        leader_slot_timing_metrics::LeaderExecuteAndCommitTimings,
+    crate::{
+        banking_stage::{
+            committer::CommitTransactionDetails,
+            leader_slot_timing_metrics::LeaderExecuteAndCommitTimings,
+        },
+        bundle_stage::{
+            bundle_notification::{BundleNotification, BundleNotificationSender},
+            bundle_result::BundleOutcome,
+        },
+    },
+    solana_bundle::{
+        bundle_execution::LoadAndExecuteBundleOutput, derive_bundle_id_from_sanitized_transactions,
+        BundleExecutionResult, SanitizedBundle,
     },
-    solana_bundle::bundle_execution::LoadAndExecuteBundleOutput,
     solana_ledger::blockstore_processor::TransactionStatusSender,
     solana_measure::measure_us,
     solana_runtime::{
@@ -13,7 +22,9 @@ use {
         vote_sender_types::ReplayVoteSender,
     },
     solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
-    solana_sdk::{saturating_add_assign, transaction::SanitizedTransaction},
+    solana_sdk::{
+        clock::Slot, pubkey::Pubkey, saturating_add_assign, transaction::SanitizedTransaction,
+    },
     solana_svm::transaction_commit_result::TransactionCommitResult,
     solana_transaction_status::{
         token_balances::{TransactionTokenBalances, TransactionTokenBalancesSet},
@@ -25,12 +36,15 @@ use {
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct CommitBundleDetails {
     pub commit_transaction_details: Vec<Vec<CommitTransactionDetails>>,
+    /// Transactions that were allowed to fail, failed, and were committed to pay their fees
+    pub num_committed_with_failed_result: u64,
 }
 
 pub struct Committer {
     transaction_status_sender: Option<TransactionStatusSender>,
     replay_vote_sender: ReplayVoteSender,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    bundle_notification_sender: Option<BundleNotificationSender>,
 }
 
 impl Committer {
@@ -38,11 +52,13 @@ impl Committer {
         transaction_status_sender: Option<TransactionStatusSender>,
         replay_vote_sender: ReplayVoteSender,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        bundle_notification_sender: Option<BundleNotificationSender>,
     ) -> Self {
         Self {
             transaction_status_sender,
             replay_vote_sender,
             prioritization_fee_cache,
+            bundle_notification_sender,
         }
     }
 
@@ -50,9 +66,38 @@ impl Committer {
         self.transaction_status_sender.is_some()
     }
 
+    /// Queues a geyser plugin notification of a bundle execution attempt. `tips` are the lamports
+    /// paid to each tip account, empty unless the bundle was committed.
+    pub(crate) fn notify_bundle(
+        &self,
+        sanitized_bundle: &SanitizedBundle,
+        slot: Slot,
+        result: &BundleExecutionResult<()>,
+        tips: &[(Pubkey, u64)],
+    ) {
+        if let Some(bundle_notification_sender) = &self.bundle_notification_sender {
+            bundle_notification_sender.send(BundleNotification {
+                bundle_id: derive_bundle_id_from_sanitized_transactions(
+                    &sanitized_bundle.transactions,
+                ),
+                slot,
+                signatures: sanitized_bundle
+                    .transactions
+                    .iter()
+                    .map(|tx| *tx.signature())
+                    .collect(),
+                tips: tips.to_vec(),
+                outcome: BundleOutcome::from(result).into(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+            });
+        }
+    }
+
     /// Very similar to Committer::commit_transactions, but works with bundles.
     /// The main difference is there's multiple non-parallelizable transaction vectors to commit
     /// and post-balances are collected after execution instead of from the bank in Self::collect_balances_and_send_status_batch.
+    /// Transactions in the bundle that were allowed to fail and failed are committed like they
+    /// would be in BankingStage: fees are collected and the rest of their changes are rolled back.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn commit_bundle<'a>(
         &self,
@@ -63,6 +108,7 @@ impl Committer {
     ) -> (u64, CommitBundleDetails) {
         let transaction_output = bundle_execution_output.bundle_transaction_results_mut();
 
+        let mut num_committed_with_failed_result = 0;
         let (commit_transaction_details, commit_times): (Vec<_>, Vec<_>) = transaction_output
             .iter_mut()
             .map(|bundle_results| {
@@ -97,6 +143,15 @@ impl Committer {
                     .iter()
                     .filter(|status| matches!(status, CommitTransactionDetails::Committed { .. }))
                     .count();
+                saturating_add_assign!(
+                    num_committed_with_failed_result,
+                    commit_results
+                        .iter()
+                        .filter(|commit_result| {
+                            matches!(commit_result, Ok(committed_tx) if committed_tx.status.is_err())
+                        })
+                        .count() as u64
+                );
 
                 let ((), find_and_send_votes_us) = measure_us!({
                     bank_utils::find_and_send_votes(
@@ -139,6 +194,7 @@ impl Committer {
             commit_times.iter().sum(),
             CommitBundleDetails {
                 commit_transaction_details,
+                num_committed_with_failed_result,
             },
         )
     }
@@ -0,0 +1,106 @@
+//! Validator-side policy for the minimum tip a bundle must pay to be committed.
+//!
+//! The tip paid is measured from the balances of the tip accounts before the first and after the
+//! last transaction in the bundle. Bundles that pay less than the minimum absolute tip, or less
+//! than the minimum tip per compute unit consumed, are rejected instead of committed. Bundles
+//! received from an exempt source are never rejected by the policy.
+use {solana_bundle::TipError, std::collections::HashSet};
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MinTipPolicy {
+    /// Minimum tip a bundle must pay, in lamports
+    pub min_tip_lamports: u64,
+    /// Minimum tip a bundle must pay per compute unit it consumes, in micro-lamports
+    pub min_tip_micro_lamports_per_cu: u64,
+    /// Bundle sources (block engine urls) that are exempt from the policy
+    pub exempt_sources: HashSet<String>,
+}
+
+impl MinTipPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.min_tip_lamports > 0 || self.min_tip_micro_lamports_per_cu > 0
+    }
+
+    /// Returns true if the policy needs to be checked for a bundle received from `source`
+    pub fn applies_to(&self, source: Option<&str>) -> bool {
+        self.is_enabled()
+            && !source
+                .map(|source| self.exempt_sources.contains(source))
+                .unwrap_or_default()
+    }
+
+    /// Checks the tip paid by a bundle against the policy
+    pub fn check(&self, tip_lamports: u64, compute_units: u64) -> Result<(), TipError> {
+        let min_tip_for_compute = (compute_units as u128)
+            .saturating_mul(self.min_tip_micro_lamports_per_cu as u128)
+            .div_ceil(MICRO_LAMPORTS_PER_LAMPORT);
+        if tip_lamports < self.min_tip_lamports || (tip_lamports as u128) < min_tip_for_compute {
+            return Err(TipError::BelowMinimumTip(tip_lamports));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_policy() {
+        let policy = MinTipPolicy::default();
+        assert!(!policy.is_enabled());
+        assert!(!policy.applies_to(None));
+        assert!(!policy.applies_to(Some("http://block-engine")));
+        assert_eq!(policy.check(0, 1_000_000), Ok(()));
+    }
+
+    #[test]
+    fn test_exempt_sources() {
+        let policy = MinTipPolicy {
+            min_tip_lamports: 1,
+            min_tip_micro_lamports_per_cu: 0,
+            exempt_sources: HashSet::from_iter(["http://trusted".to_string()]),
+        };
+        assert!(policy.applies_to(None));
+        assert!(policy.applies_to(Some("http://block-engine")));
+        assert!(!policy.applies_to(Some("http://trusted")));
+    }
+
+    #[test]
+    fn test_check_min_tip_lamports() {
+        let policy = MinTipPolicy {
+            min_tip_lamports: 1_000,
+            ..MinTipPolicy::default()
+        };
+        assert_eq!(policy.check(999, 0), Err(TipError::BelowMinimumTip(999)));
+        assert_eq!(policy.check(1_000, 0), Ok(()));
+        assert_eq!(policy.check(1_000, u64::MAX), Ok(()));
+    }
+
+    #[test]
+    fn test_check_min_tip_per_cu() {
+        let policy = MinTipPolicy {
+            min_tip_micro_lamports_per_cu: 10,
+            ..MinTipPolicy::default()
+        };
+        // 150_000 CUs * 10 micro-lamports = 1.5 lamports, rounded up
+        assert_eq!(policy.check(1, 150_000), Err(TipError::BelowMinimumTip(1)));
+        assert_eq!(policy.check(2, 150_000), Ok(()));
+        assert_eq!(policy.check(0, 0), Ok(()));
+
+        // both minimums must be met
+        let policy = MinTipPolicy {
+            min_tip_lamports: 5,
+            min_tip_micro_lamports_per_cu: 10,
+            ..MinTipPolicy::default()
+        };
+        assert_eq!(policy.check(2, 150_000), Err(TipError::BelowMinimumTip(2)));
+        assert_eq!(policy.check(5, 150_000), Ok(()));
+        assert_eq!(
+            policy.check(5, 1_000_000),
+            Err(TipError::BelowMinimumTip(5))
+        );
+    }
+}
@@ -11,7 +11,8 @@ use {
     solana_runtime::bank::Bank,
     solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
     solana_sdk::{
-        clock::MAX_PROCESSING_AGE, pubkey::Pubkey, signature::Signature,
+        clock::MAX_PROCESSING_AGE, program_utils::limited_deserialize, pubkey::Pubkey,
+        signature::Signature, system_instruction::SystemInstruction, system_program,
         transaction::SanitizedTransaction,
     },
     solana_svm::transaction_error_metrics::TransactionErrorMetrics,
@@ -56,12 +57,23 @@ pub enum DeserializedBundleError {
 
     #[error("PacketFilterFailure: {0}")]
     PacketFilterFailure(#[from] PacketFilterFailure),
+
+    #[error("Transaction allowed to fail is out of bounds: {0}")]
+    InvalidAllowedToFailIndex(usize),
+
+    #[error("All transactions in the bundle are allowed to fail")]
+    AllTransactionsAllowedToFail,
+
+    #[error("Transaction allowed to fail touches a tip account: {0}")]
+    TipTransactionAllowedToFail(usize),
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ImmutableDeserializedBundle {
     bundle_id: String,
     packets: Vec<ImmutableDeserializedPacket>,
+    allowed_to_fail_tx_indexes: HashSet<usize>,
+    source: Option<String>,
 }
 
 impl ImmutableDeserializedBundle {
@@ -86,6 +98,19 @@ impl ImmutableDeserializedBundle {
         if bundle.batch.iter().any(|p| p.meta().discard()) {
             return Err(DeserializedBundleError::MarkedDiscard);
         }
+        if let Some(index) = bundle
+            .allowed_to_fail_tx_indexes
+            .iter()
+            .find(|index| **index >= bundle.batch.len())
+        {
+            return Err(DeserializedBundleError::InvalidAllowedToFailIndex(*index));
+        }
+        let allowed_to_fail_tx_indexes =
+            HashSet::from_iter(bundle.allowed_to_fail_tx_indexes.iter().copied());
+        // a bundle where every transaction may fail has nothing to protect
+        if allowed_to_fail_tx_indexes.len() == bundle.batch.len() {
+            return Err(DeserializedBundleError::AllTransactionsAllowedToFail);
+        }
         if bundle.batch.iter_mut().any(|p| !verify_packet(p, false)) {
             return Err(DeserializedBundleError::SignatureVerificationFailure);
         }
@@ -100,6 +125,8 @@ impl ImmutableDeserializedBundle {
         Ok(Self {
             bundle_id: bundle.bundle_id.clone(),
             packets: immutable_packets,
+            allowed_to_fail_tx_indexes,
+            source: bundle.source.clone(),
         })
     }
 
@@ -112,6 +139,48 @@ impl ImmutableDeserializedBundle {
         &self.bundle_id
     }
 
+    pub fn allowed_to_fail_tx_indexes(&self) -> &HashSet<usize> {
+        &self.allowed_to_fail_tx_indexes
+    }
+
+    /// The block engine the bundle was received from, if known
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Sum of the compute unit limits requested by the transactions in the bundle
+    pub fn compute_unit_limit(&self) -> u64 {
+        self.packets
+            .iter()
+            .map(|p| p.compute_unit_limit())
+            .fold(0, u64::saturating_add)
+    }
+
+    /// Estimates the tip paid by the bundle by summing the system transfers to any of the tip accounts.
+    /// Only the static account keys are inspected, so transfers to tip accounts referenced through
+    /// an address lookup table aren't counted.
+    pub fn estimated_tip_lamports(&self, tip_accounts: &HashSet<Pubkey>) -> u64 {
+        self.packets
+            .iter()
+            .flat_map(|p| {
+                let message = p.transaction().get_message();
+                let account_keys = message.message.static_account_keys();
+                message
+                    .program_instructions_iter()
+                    .filter(|(program_id, _)| system_program::check_id(program_id))
+                    .filter_map(move |(_, ix)| {
+                        let SystemInstruction::Transfer { lamports } =
+                            limited_deserialize(&ix.data).ok()?
+                        else {
+                            return None;
+                        };
+                        let to = account_keys.get(usize::from(*ix.accounts.get(1)?))?;
+                        tip_accounts.contains(to).then_some(lamports)
+                    })
+            })
+            .fold(0, u64::saturating_add)
+    }
+
     /// A bundle has the following requirements:
     /// - all transactions must be sanitiz-able
     /// - no duplicate signatures
@@ -177,6 +246,7 @@ impl ImmutableDeserializedBundle {
         Ok(SanitizedBundle {
             transactions,
             bundle_id: self.bundle_id.clone(),
+            allowed_to_fail_tx_indexes: self.allowed_to_fail_tx_indexes.clone(),
         })
     }
 }
@@ -228,6 +298,8 @@ mod tests {
                     Packet::from_data(None, &tx1).unwrap(),
                 ]),
                 bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
             },
             None,
             &Ok,
@@ -247,6 +319,45 @@ mod tests {
             sanitized_bundle.transactions[1].signature(),
             tx1.get_signature()
         );
+        assert!(sanitized_bundle.allowed_to_fail_tx_indexes.is_empty());
+    }
+
+    #[test]
+    fn test_estimated_tip_lamports() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10_000);
+
+        let tip_account = Pubkey::new_unique();
+        let other_account = Pubkey::new_unique();
+
+        let tx0 = transfer(&mint_keypair, &other_account, 500, genesis_config.hash());
+        let tx1 = transfer(&mint_keypair, &tip_account, 100, genesis_config.hash());
+        let tx2 = transfer(&mint_keypair, &tip_account, 50, genesis_config.hash());
+
+        let bundle = ImmutableDeserializedBundle::new(
+            &mut PacketBundle {
+                batch: PacketBatch::new(vec![
+                    Packet::from_data(None, &tx0).unwrap(),
+                    Packet::from_data(None, &tx1).unwrap(),
+                    Packet::from_data(None, &tx2).unwrap(),
+                ]),
+                bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
+            },
+            None,
+            &Ok,
+        )
+        .unwrap();
+
+        assert_eq!(
+            bundle.estimated_tip_lamports(&HashSet::from([tip_account])),
+            150
+        );
+        assert_eq!(bundle.estimated_tip_lamports(&HashSet::default()), 0);
     }
 
     #[test]
@@ -256,6 +367,8 @@ mod tests {
                 &mut PacketBundle {
                     batch: PacketBatch::new(vec![]),
                     bundle_id: String::default(),
+                    allowed_to_fail_tx_indexes: vec![],
+                    source: None,
                 },
                 None,
                 &Ok
@@ -283,6 +396,8 @@ mod tests {
                             .collect()
                     ),
                     bundle_id: String::default(),
+                    allowed_to_fail_tx_indexes: vec![],
+                    source: None,
                 },
                 Some(5),
                 &Ok
@@ -304,6 +419,8 @@ mod tests {
                 &mut PacketBundle {
                     batch: PacketBatch::new(vec![packet]),
                     bundle_id: String::default(),
+                    allowed_to_fail_tx_indexes: vec![],
+                    source: None,
                 },
                 Some(5),
                 &Ok
@@ -312,6 +429,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_invalid_allowed_to_fail_indexes_fail_to_init() {
+        let kp = Keypair::new();
+        let packets = vec![
+            Packet::from_data(None, transfer(&kp, &kp.pubkey(), 100, Hash::default())).unwrap(),
+            Packet::from_data(None, transfer(&kp, &kp.pubkey(), 101, Hash::default())).unwrap(),
+        ];
+
+        assert_matches!(
+            ImmutableDeserializedBundle::new(
+                &mut PacketBundle {
+                    batch: PacketBatch::new(packets.clone()),
+                    bundle_id: String::default(),
+                    allowed_to_fail_tx_indexes: vec![2],
+                    source: None,
+                },
+                None,
+                &Ok
+            ),
+            Err(DeserializedBundleError::InvalidAllowedToFailIndex(2))
+        );
+        assert_matches!(
+            ImmutableDeserializedBundle::new(
+                &mut PacketBundle {
+                    batch: PacketBatch::new(packets.clone()),
+                    bundle_id: String::default(),
+                    allowed_to_fail_tx_indexes: vec![1, 0],
+                    source: None,
+                },
+                None,
+                &Ok
+            ),
+            Err(DeserializedBundleError::AllTransactionsAllowedToFail)
+        );
+
+        let bundle = ImmutableDeserializedBundle::new(
+            &mut PacketBundle {
+                batch: PacketBatch::new(packets),
+                bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![1, 1],
+                source: None,
+            },
+            None,
+            &Ok,
+        )
+        .unwrap();
+        assert_eq!(bundle.allowed_to_fail_tx_indexes(), &HashSet::from([1]));
+    }
+
     #[test]
     fn test_bad_signature_fails_to_init() {
         let kp0 = Keypair::new();
@@ -326,6 +492,8 @@ mod tests {
                 &mut PacketBundle {
                     batch: PacketBatch::new(vec![Packet::from_data(None, tx0).unwrap()]),
                     bundle_id: String::default(),
+                    allowed_to_fail_tx_indexes: vec![],
+                    source: None,
                 },
                 None,
                 &Ok
@@ -359,6 +527,8 @@ mod tests {
             &mut PacketBundle {
                 batch: PacketBatch::new(vec![Packet::from_data(None, tx0).unwrap()]),
                 bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
             },
             None,
             &Ok,
@@ -396,6 +566,8 @@ mod tests {
                     Packet::from_data(None, &tx0).unwrap(),
                 ]),
                 bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
             },
             None,
             &Ok,
@@ -426,6 +598,8 @@ mod tests {
             &mut PacketBundle {
                 batch: PacketBatch::new(vec![Packet::from_data(None, tx0).unwrap()]),
                 bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
             },
             None,
             &Ok,
@@ -462,6 +636,8 @@ mod tests {
             &mut PacketBundle {
                 batch: PacketBatch::new(vec![Packet::from_data(None, tx0).unwrap()]),
                 bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
             },
             None,
             &Ok,
@@ -492,6 +668,8 @@ mod tests {
             &mut PacketBundle {
                 batch: PacketBatch::new(vec![Packet::from_data(None, tx0).unwrap()]),
                 bundle_id: String::default(),
+                allowed_to_fail_tx_indexes: vec![],
+                source: None,
             },
             None,
             &Ok,
@@ -1,7 +1,12 @@
 use solana_perf::packet::PacketBatch;
 
-#[derive(Clone, Debug)]
+#[cfg_attr(feature = "frozen-abi", derive(AbiExample))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PacketBundle {
     pub batch: PacketBatch,
     pub bundle_id: String,
+    /// Indexes into `batch` of the transactions that may fail without failing the bundle
+    pub allowed_to_fail_tx_indexes: Vec<usize>,
+    /// The block engine the bundle was received from
+    pub source: Option<String>,
 }
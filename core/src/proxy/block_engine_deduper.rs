@@ -0,0 +1,189 @@
+//! De-duplicates bundles and packets that arrive from more than one Block Engine.
+//!
+//! BlockEngineStage keeps streams open to several Block Engines at once and they usually forward
+//! the same bundles and transactions. Each Block Engine assigns its own uuid to a bundle, so
+//! bundles are identified by the signatures of their transactions and the transactions that are
+//! allowed to fail, and packets by their first signature. Entries expire after a short window so a bundle that's resent for a later slot isn't
+//! dropped.
+use {
+    crate::packet_bundle::PacketBundle,
+    lru::LruCache,
+    solana_perf::packet::Packet,
+    solana_sdk::{
+        hash::{Hash, Hasher},
+        signature::{Signature, SIGNATURE_BYTES},
+    },
+    solana_short_vec::decode_shortu16_len,
+    std::time::{Duration, Instant},
+};
+
+const DEDUP_WINDOW: Duration = Duration::from_secs(1);
+const MAX_CACHED_BUNDLES: usize = 100_000;
+const MAX_CACHED_PACKETS: usize = 500_000;
+
+pub(crate) struct BlockEngineDeduper {
+    window: Duration,
+    bundles: LruCache<Hash, Instant>,
+    packets: LruCache<Signature, Instant>,
+}
+
+impl Default for BlockEngineDeduper {
+    fn default() -> Self {
+        Self::new(DEDUP_WINDOW, MAX_CACHED_BUNDLES, MAX_CACHED_PACKETS)
+    }
+}
+
+impl BlockEngineDeduper {
+    pub(crate) fn new(window: Duration, bundle_capacity: usize, packet_capacity: usize) -> Self {
+        Self {
+            window,
+            bundles: LruCache::new(bundle_capacity),
+            packets: LruCache::new(packet_capacity),
+        }
+    }
+
+    /// Returns false if the same bundle was received within the dedup window. Bundles containing a
+    /// malformed packet are always let through, they're rejected by BundleStage.
+    pub(crate) fn check_bundle(&mut self, bundle: &PacketBundle, now: Instant) -> bool {
+        let mut hasher = Hasher::default();
+        for packet in bundle.batch.iter() {
+            let Some(signature) = first_signature(packet) else {
+                return true;
+            };
+            hasher.hash(signature.as_ref());
+        }
+        // the same transactions with different allowed to fail transactions are different bundles
+        let mut allowed_to_fail_tx_indexes = bundle.allowed_to_fail_tx_indexes.clone();
+        allowed_to_fail_tx_indexes.sort_unstable();
+        allowed_to_fail_tx_indexes.dedup();
+        for index in allowed_to_fail_tx_indexes {
+            hasher.hash(&(index as u64).to_le_bytes());
+        }
+        check_and_insert(&mut self.bundles, hasher.result(), now, self.window)
+    }
+
+    /// Returns false if a packet with the same signature was received within the dedup window
+    pub(crate) fn check_packet(&mut self, packet: &Packet, now: Instant) -> bool {
+        let Some(signature) = first_signature(packet) else {
+            return true;
+        };
+        check_and_insert(&mut self.packets, signature, now, self.window)
+    }
+}
+
+pub(crate) fn check_and_insert<K: std::hash::Hash + Eq>(
+    cache: &mut LruCache<K, Instant>,
+    key: K,
+    now: Instant,
+    window: Duration,
+) -> bool {
+    match cache.get(&key) {
+        Some(first_seen) if now.saturating_duration_since(*first_seen) < window => false,
+        _ => {
+            cache.put(key, now);
+            true
+        }
+    }
+}
+
+pub(crate) fn first_signature(packet: &Packet) -> Option<Signature> {
+    let (num_signatures, signatures_offset) = packet
+        .data(..)
+        .and_then(|bytes| decode_shortu16_len(bytes).ok())?;
+    if num_signatures == 0 {
+        return None;
+    }
+    packet
+        .data(signatures_offset..signatures_offset.checked_add(SIGNATURE_BYTES)?)
+        .and_then(|bytes| Signature::try_from(bytes).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_perf::packet::PacketBatch,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, signer::Signer, system_transaction::transfer,
+        },
+    };
+
+    fn make_packet(keypair: &Keypair, lamports: u64) -> Packet {
+        Packet::from_data(
+            None,
+            transfer(keypair, &keypair.pubkey(), lamports, Hash::default()),
+        )
+        .unwrap()
+    }
+
+    fn make_bundle(packets: Vec<Packet>, uuid: &str) -> PacketBundle {
+        PacketBundle {
+            batch: PacketBatch::new(packets),
+            bundle_id: uuid.to_string(),
+            allowed_to_fail_tx_indexes: vec![],
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_dedup_bundles() {
+        let keypair = Keypair::new();
+        let mut deduper = BlockEngineDeduper::default();
+        let now = Instant::now();
+
+        let bundle = make_bundle(
+            vec![make_packet(&keypair, 1), make_packet(&keypair, 2)],
+            "a",
+        );
+        assert!(deduper.check_bundle(&bundle, now));
+        // the same transactions from another block engine with a different uuid
+        let duplicate = make_bundle(bundle.batch.iter().cloned().collect(), "b");
+        assert!(!deduper.check_bundle(&duplicate, now));
+        // a bundle with a subset of the transactions is a different bundle
+        let subset = make_bundle(vec![make_packet(&keypair, 1)], "c");
+        assert!(deduper.check_bundle(&subset, now));
+
+        // bundles can be resent after the window
+        assert!(deduper.check_bundle(&duplicate, now + DEDUP_WINDOW));
+    }
+
+    #[test]
+    fn test_dedup_bundles_allowed_to_fail() {
+        let keypair = Keypair::new();
+        let mut deduper = BlockEngineDeduper::default();
+        let now = Instant::now();
+        let packets = vec![make_packet(&keypair, 1), make_packet(&keypair, 2)];
+
+        let mut bundle = make_bundle(packets.clone(), "a");
+        bundle.allowed_to_fail_tx_indexes = vec![1, 0];
+        assert!(deduper.check_bundle(&bundle, now));
+
+        // the same allowed to fail transactions in a different order are the same bundle
+        let mut duplicate = make_bundle(packets.clone(), "b");
+        duplicate.allowed_to_fail_tx_indexes = vec![0, 1];
+        assert!(!deduper.check_bundle(&duplicate, now));
+
+        // the same transactions with different allowed to fail transactions aren't merged
+        let mut different = make_bundle(packets.clone(), "c");
+        different.allowed_to_fail_tx_indexes = vec![0];
+        assert!(deduper.check_bundle(&different, now));
+        assert!(deduper.check_bundle(&make_bundle(packets, "d"), now));
+    }
+
+    #[test]
+    fn test_dedup_packets() {
+        let keypair = Keypair::new();
+        let mut deduper = BlockEngineDeduper::default();
+        let now = Instant::now();
+
+        let packet = make_packet(&keypair, 1);
+        assert!(deduper.check_packet(&packet, now));
+        assert!(!deduper.check_packet(&packet, now));
+        assert!(deduper.check_packet(&make_packet(&keypair, 2), now));
+
+        // malformed packets are let through
+        let malformed = Packet::default();
+        assert!(deduper.check_packet(&malformed, now));
+        assert!(deduper.check_packet(&malformed, now));
+    }
+}
@@ -0,0 +1,207 @@
+//! Picks the Block Engine region with the lowest latency from a set of candidates.
+//!
+//! Each candidate is measured by connecting to it and running the auth handshake. Candidates are
+//! re-evaluated periodically and the region is only switched when another one is faster by more
+//! than [REGION_SWITCH_THRESHOLD_PCT] percent, so the validator doesn't flap between regions with
+//! similar latency.
+use {
+    crate::proxy::{
+        auth::generate_auth_tokens, block_engine_stage::block_engine_endpoint, ProxyError,
+    },
+    futures::future::join_all,
+    jito_protos::proto::auth::auth_service_client::AuthServiceClient,
+    solana_sdk::signer::keypair::Keypair,
+    std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    },
+    tokio::time::timeout,
+};
+
+pub const REGION_EVALUATION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// A region must be this much faster than the current one before switching to it
+const REGION_SWITCH_THRESHOLD_PCT: u64 = 20;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockEngineRegionLatency {
+    pub block_engine_url: String,
+    /// Time to establish a connection to the Block Engine
+    pub connect_us: Option<u64>,
+    /// Time to complete the auth handshake
+    pub auth_us: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl BlockEngineRegionLatency {
+    /// Connection and auth handshake time combined, None if the Block Engine couldn't be reached
+    pub fn total_us(&self) -> Option<u64> {
+        Some(self.connect_us?.saturating_add(self.auth_us?))
+    }
+}
+
+/// Latest region measurements, reported by the admin rpc
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockEngineRegions {
+    /// The candidate used as the primary Block Engine
+    pub selected_block_engine_url: Option<String>,
+    pub latencies: Vec<BlockEngineRegionLatency>,
+    /// Unix timestamp of the last evaluation
+    pub last_evaluated_timestamp_ms: Option<u64>,
+}
+
+/// Measures all candidates concurrently
+pub(crate) async fn measure_block_engine_regions(
+    block_engine_urls: &[String],
+    keypair: &Arc<Keypair>,
+    connection_timeout: &Duration,
+) -> Vec<BlockEngineRegionLatency> {
+    join_all(block_engine_urls.iter().map(|block_engine_url| async move {
+        let mut latency = BlockEngineRegionLatency {
+            block_engine_url: block_engine_url.clone(),
+            ..BlockEngineRegionLatency::default()
+        };
+        if let Err(e) = measure_block_engine_region(&mut latency, keypair, connection_timeout).await
+        {
+            latency.error = Some(e.to_string());
+        }
+        latency
+    }))
+    .await
+}
+
+async fn measure_block_engine_region(
+    latency: &mut BlockEngineRegionLatency,
+    keypair: &Keypair,
+    connection_timeout: &Duration,
+) -> crate::proxy::Result<()> {
+    let endpoint = block_engine_endpoint(&latency.block_engine_url)?;
+
+    let start = Instant::now();
+    let channel = timeout(*connection_timeout, endpoint.connect())
+        .await
+        .map_err(|_| ProxyError::AuthenticationConnectionTimeout)?
+        .map_err(|e| ProxyError::AuthenticationConnectionError(e.to_string()))?;
+    latency.connect_us = Some(start.elapsed().as_micros() as u64);
+
+    let mut auth_client = AuthServiceClient::new(channel);
+    let start = Instant::now();
+    match timeout(
+        *connection_timeout,
+        generate_auth_tokens(&mut auth_client, keypair),
+    )
+    .await
+    .map_err(|_| ProxyError::AuthenticationTimeout)?
+    {
+        // Hot spares aren't on the leader schedule so they're denied after the challenge. Every
+        // candidate denies them the same way, which keeps the measurements comparable.
+        Ok(_) | Err(ProxyError::AuthenticationPermissionDenied) => {}
+        Err(e) => return Err(e),
+    }
+    latency.auth_us = Some(start.elapsed().as_micros() as u64);
+
+    Ok(())
+}
+
+/// Returns the region to use given the latest measurements. The current region is kept unless
+/// another one is faster by more than the switch threshold or the current one can't be reached.
+/// If none of the regions can be reached the current one is kept.
+pub(crate) fn select_block_engine_region(
+    current: Option<&str>,
+    latencies: &[BlockEngineRegionLatency],
+) -> Option<String> {
+    let Some((fastest, fastest_us)) = latencies
+        .iter()
+        .filter_map(|latency| Some((latency, latency.total_us()?)))
+        .min_by_key(|(_, total_us)| *total_us)
+    else {
+        return current.map(str::to_string);
+    };
+
+    let current_us = current.and_then(|current| {
+        latencies
+            .iter()
+            .find(|latency| latency.block_engine_url == current)
+            .and_then(BlockEngineRegionLatency::total_us)
+    });
+    match current_us {
+        Some(current_us)
+            if fastest_us.saturating_mul(100)
+                >= current_us.saturating_mul(100 - REGION_SWITCH_THRESHOLD_PCT) =>
+        {
+            current.map(str::to_string)
+        }
+        _ => Some(fastest.block_engine_url.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn latency(block_engine_url: &str, total_us: Option<u64>) -> BlockEngineRegionLatency {
+        BlockEngineRegionLatency {
+            block_engine_url: block_engine_url.to_string(),
+            connect_us: total_us.map(|total_us| total_us / 2),
+            auth_us: total_us.map(|total_us| total_us - total_us / 2),
+            error: total_us.is_none().then(|| "unreachable".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_select_initial_region() {
+        let latencies = [
+            latency("http://ny", Some(10_000)),
+            latency("http://ams", Some(2_000)),
+            latency("http://tokyo", None),
+        ];
+        assert_eq!(
+            select_block_engine_region(None, &latencies),
+            Some("http://ams".to_string())
+        );
+        assert_eq!(
+            select_block_engine_region(None, &[latency("http://ny", None)]),
+            None
+        );
+        assert_eq!(select_block_engine_region(None, &[]), None);
+    }
+
+    #[test]
+    fn test_select_region_hysteresis() {
+        // 15% faster isn't enough to switch
+        let latencies = [
+            latency("http://ny", Some(10_000)),
+            latency("http://ams", Some(8_500)),
+        ];
+        assert_eq!(
+            select_block_engine_region(Some("http://ny"), &latencies),
+            Some("http://ny".to_string())
+        );
+
+        // 25% faster is
+        let latencies = [
+            latency("http://ny", Some(10_000)),
+            latency("http://ams", Some(7_500)),
+        ];
+        assert_eq!(
+            select_block_engine_region(Some("http://ny"), &latencies),
+            Some("http://ams".to_string())
+        );
+
+        // switches when the current region can't be reached
+        let latencies = [
+            latency("http://ny", None),
+            latency("http://ams", Some(9_999)),
+        ];
+        assert_eq!(
+            select_block_engine_region(Some("http://ny"), &latencies),
+            Some("http://ams".to_string())
+        );
+
+        // keeps the current region when nothing can be reached
+        let latencies = [latency("http://ny", None), latency("http://ams", None)];
+        assert_eq!(
+            select_block_engine_region(Some("http://ny"), &latencies),
+            Some("http://ny".to_string())
+        );
+    }
+}
@@ -1,15 +1,33 @@
-//! Maintains a connection to the Block Engine.
+//! Maintains connections to one or more Block Engines.
 //!
 //! The Block Engine is responsible for the following:
 //! - Acts as a system that sends high profit bundles and transactions to a validator.
 //! - Sends transactions and bundles to the validator.
+//!
+//! Connections to the primary and failover Block Engines are kept open at the same time. Packets
+//! from all of them are merged and de-duplicated before being forwarded. Bundles are only
+//! forwarded from the active Block Engine, since its block builder is the one paid by the tip
+//! crank; the others are hot standbys. The active Block Engine is the most preferred one that's
+//! healthy: connected, sending heartbeats and responding within [MAX_HEALTHY_LATENCY].
+//!
+//! If candidate Block Engines are configured, the primary Block Engine is picked from them by
+//! latency, see [crate::proxy::block_engine_region_selector].
 use {
     crate::{
         banking_trace::BankingPacketSender,
+        bundle_stage::bundle_result::BundleResultReceiver,
         packet_bundle::PacketBundle,
         proto_packet_to_packet,
         proxy::{
             auth::{generate_auth_tokens, maybe_refresh_auth_tokens, AuthInterceptor},
+            block_engine_deduper::BlockEngineDeduper,
+            block_engine_region_selector::{
+                measure_block_engine_regions, select_block_engine_region, BlockEngineRegionLatency,
+                BlockEngineRegions, REGION_EVALUATION_INTERVAL,
+            },
+            bundle_result_forwarder::BundleResultForwarder,
+            packet_source_accounting::PacketSourceAccounting,
+            packet_trust::{PacketSource, PacketTrust},
             ProxyError,
         },
     },
@@ -18,26 +36,30 @@ use {
         auth::{auth_service_client::AuthServiceClient, Token},
         block_engine::{
             self, block_engine_validator_client::BlockEngineValidatorClient,
-            BlockBuilderFeeInfoRequest,
+            BlockBuilderFeeInfoRequest, BlockBuilderFeeInfoResponse,
         },
+        bundle_feedback::bundle_feedback_client::BundleFeedbackClient,
     },
     solana_gossip::cluster_info::ClusterInfo,
-    solana_perf::packet::PacketBatch,
+    solana_perf::packet::{Packet, PacketBatch},
     solana_sdk::{
         pubkey::Pubkey, saturating_add_assign, signature::Signer, signer::keypair::Keypair,
+        timing::timestamp,
     },
     std::{
+        path::PathBuf,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc, Mutex,
+            Arc, Mutex, RwLock,
         },
         thread::{self, Builder, JoinHandle},
-        time::Duration,
+        time::{Duration, Instant},
     },
     tokio::{
+        sync::mpsc,
         task,
-        time::{interval, sleep, timeout},
+        time::{interval, interval_at, sleep, timeout},
     },
     tonic::{
         codegen::InterceptedService,
@@ -49,22 +71,42 @@ use {
 const CONNECTION_TIMEOUT_S: u64 = 10;
 const CONNECTION_BACKOFF_S: u64 = 5;
 
+/// A Block Engine that hasn't sent a bundle, packet batch or heartbeat in this long is unhealthy
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(10);
+/// A Block Engine with a higher round trip latency than this is unhealthy
+const MAX_HEALTHY_LATENCY: Duration = Duration::from_millis(500);
+
 #[derive(Default)]
 struct BlockEngineStageStats {
     num_bundles: u64,
     num_bundle_packets: u64,
+    num_duplicate_bundles: u64,
+    num_standby_bundles: u64,
     num_packets: u64,
+    num_duplicate_packets: u64,
     num_empty_packets: u64,
+    num_sampled_packets: u64,
+    num_failed_sampled_packets: u64,
 }
 
 impl BlockEngineStageStats {
-    pub(crate) fn report(&self) {
+    pub(crate) fn report(&self, block_engine_url: &str) {
         datapoint_info!(
             "block_engine_stage-stats",
+            ("url", block_engine_url, String),
             ("num_bundles", self.num_bundles, i64),
             ("num_bundle_packets", self.num_bundle_packets, i64),
+            ("num_duplicate_bundles", self.num_duplicate_bundles, i64),
+            ("num_standby_bundles", self.num_standby_bundles, i64),
             ("num_packets", self.num_packets, i64),
-            ("num_empty_packets", self.num_empty_packets, i64)
+            ("num_duplicate_packets", self.num_duplicate_packets, i64),
+            ("num_empty_packets", self.num_empty_packets, i64),
+            ("num_sampled_packets", self.num_sampled_packets, i64),
+            (
+                "num_failed_sampled_packets",
+                self.num_failed_sampled_packets,
+                i64
+            )
         );
     }
 }
@@ -79,15 +121,147 @@ pub struct BlockEngineConfig {
     /// Block Engine URL
     pub block_engine_url: String,
 
+    /// Block Engines connected to alongside the primary one, in order of preference. Packets from
+    /// all connected Block Engines are merged, bundles are only taken from the active one.
+    pub failover_block_engine_urls: Vec<String>,
+
+    /// Block Engines in different regions to pick the primary one from by latency, along with
+    /// `block_engine_url`. Region selection is disabled when empty.
+    pub candidate_block_engine_urls: Vec<String>,
+
     /// If set then it will be assumed the backend verified packets so signature verification will be bypassed in the validator.
     pub trust_packets: bool,
 }
 
+impl BlockEngineConfig {
+    /// The Block Engines to connect to in order of preference, without duplicates
+    pub fn block_engine_urls(&self) -> Vec<String> {
+        unique_urls(std::iter::once(&self.block_engine_url).chain(&self.failover_block_engine_urls))
+    }
+
+    /// The Block Engines to pick the primary one from, empty if region selection is disabled
+    pub fn region_candidate_urls(&self) -> Vec<String> {
+        if self.candidate_block_engine_urls.is_empty() {
+            return vec![];
+        }
+        unique_urls(
+            std::iter::once(&self.block_engine_url).chain(&self.candidate_block_engine_urls),
+        )
+    }
+}
+
+fn unique_urls<'a>(urls: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut unique_urls: Vec<String> = Vec::new();
+    for url in urls {
+        if !url.is_empty() && !unique_urls.contains(url) {
+            unique_urls.push(url.clone());
+        }
+    }
+    unique_urls
+}
+
+pub(crate) fn block_engine_endpoint(block_engine_url: &str) -> crate::proxy::Result<Endpoint> {
+    let mut endpoint = Endpoint::from_shared(block_engine_url.to_string())
+        .map_err(|_| {
+            ProxyError::BlockEngineConnectionError(format!(
+                "invalid block engine url value: {}",
+                block_engine_url
+            ))
+        })?
+        .tcp_keepalive(Some(Duration::from_secs(60)));
+    if block_engine_url.starts_with("https") {
+        endpoint = endpoint
+            .tls_config(tonic::transport::ClientTlsConfig::new())
+            .map_err(|_| {
+                ProxyError::BlockEngineConnectionError(
+                    "failed to set tls_config for block engine service".to_string(),
+                )
+            })?;
+    }
+    Ok(endpoint)
+}
+
+/// Health of the connection to a Block Engine, reported by the admin rpc
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockEngineHealth {
+    pub block_engine_url: String,
+    pub connected: bool,
+    /// Bundles and the block builder fee info are taken from the active Block Engine
+    pub active: bool,
+    /// Round trip time of the last request to the Block Engine
+    pub latency_us: Option<u64>,
+    /// Unix timestamp of the last bundle, packet batch or heartbeat received
+    pub last_message_timestamp_ms: Option<u64>,
+    pub num_bundles: u64,
+    pub num_duplicate_bundles: u64,
+    pub num_packets: u64,
+    pub num_duplicate_packets: u64,
+    pub num_connection_errors: u64,
+    pub last_error: Option<String>,
+}
+
+/// Sent from the task maintaining a connection to a Block Engine to the task that merges them
+enum BlockEngineEvent {
+    Connected {
+        index: usize,
+        bundle_feedback_client: BundleFeedbackClient<InterceptedService<Channel, AuthInterceptor>>,
+    },
+    /// Sent after connecting and then periodically. The round trip time of the request is used as
+    /// the Block Engine's latency.
+    BlockBuilderFeeInfo {
+        index: usize,
+        latency: Duration,
+        block_builder_fee_info: BlockBuilderFeeInfoResponse,
+    },
+    Stats {
+        index: usize,
+        stats: BlockEngineStageStats,
+        last_message: Option<Instant>,
+    },
+    Disconnected {
+        index: usize,
+        error: String,
+    },
+}
+
+#[derive(Default)]
+struct BlockEngineState {
+    health: BlockEngineHealth,
+    latency: Option<Duration>,
+    last_message: Option<Instant>,
+    block_builder_fee_info: Option<BlockBuilderFeeInfoResponse>,
+    /// Shared with the connection's task, which drops bundles while this is unset
+    forward_bundles: Arc<AtomicBool>,
+}
+
+impl BlockEngineState {
+    fn new(block_engine_url: String) -> Self {
+        Self {
+            health: BlockEngineHealth {
+                block_engine_url,
+                ..BlockEngineHealth::default()
+            },
+            ..Self::default()
+        }
+    }
+
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.health.connected
+            && self.last_message.is_some_and(|last_message| {
+                now.saturating_duration_since(last_message) < HEARTBEAT_TIMEOUT
+            })
+            && self
+                .latency
+                .is_some_and(|latency| latency <= MAX_HEALTHY_LATENCY)
+    }
+}
+
 pub struct BlockEngineStage {
     t_hdls: Vec<JoinHandle<()>>,
 }
 
 impl BlockEngineStage {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         block_engine_config: Arc<Mutex<BlockEngineConfig>>,
         // Channel that bundles get piped through.
@@ -98,8 +272,20 @@ impl BlockEngineStage {
         packet_tx: Sender<PacketBatch>,
         // Channel that trusted packets get piped through.
         banking_packet_sender: BankingPacketSender,
+        // Revoked when a sample of the trusted packets fails sigverify.
+        packet_trust: Arc<PacketTrust>,
+        // Counts and rate limits the packets received from each source.
+        packet_source_accounting: Arc<PacketSourceAccounting>,
         exit: Arc<AtomicBool>,
         block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
+        // Bundle execution results from BundleStage that get streamed back to the block engine.
+        bundle_result_receiver: BundleResultReceiver,
+        // Results are appended here when they can't be streamed to the block engine.
+        bundle_result_fallback_path: Option<PathBuf>,
+        // Health of each block engine connection, updated every second.
+        block_engine_health: Arc<RwLock<Vec<BlockEngineHealth>>>,
+        // Latency of each candidate block engine, updated on every region evaluation.
+        block_engine_regions: Arc<RwLock<BlockEngineRegions>>,
     ) -> Self {
         let block_builder_fee_info = block_builder_fee_info.clone();
 
@@ -110,14 +296,21 @@ impl BlockEngineStage {
                     .enable_all()
                     .build()
                     .unwrap();
+                let bundle_result_forwarder =
+                    BundleResultForwarder::new(bundle_result_receiver, bundle_result_fallback_path);
                 rt.block_on(Self::start(
                     block_engine_config,
                     cluster_info,
                     bundle_tx,
                     packet_tx,
                     banking_packet_sender,
+                    packet_trust,
+                    packet_source_accounting,
                     exit,
                     block_builder_fee_info,
+                    bundle_result_forwarder,
+                    block_engine_health,
+                    block_engine_regions,
                 ));
             })
             .unwrap();
@@ -141,16 +334,20 @@ impl BlockEngineStage {
         bundle_tx: Sender<Vec<PacketBundle>>,
         packet_tx: Sender<PacketBatch>,
         banking_packet_sender: BankingPacketSender,
+        packet_trust: Arc<PacketTrust>,
+        packet_source_accounting: Arc<PacketSourceAccounting>,
         exit: Arc<AtomicBool>,
         block_builder_fee_info: Arc<Mutex<BlockBuilderFeeInfo>>,
+        mut bundle_result_forwarder: BundleResultForwarder,
+        block_engine_health: Arc<RwLock<Vec<BlockEngineHealth>>>,
+        block_engine_regions: Arc<RwLock<BlockEngineRegions>>,
     ) {
         const CONNECTION_TIMEOUT: Duration = Duration::from_secs(CONNECTION_TIMEOUT_S);
         const CONNECTION_BACKOFF: Duration = Duration::from_secs(CONNECTION_BACKOFF_S);
-        let mut error_count: u64 = 0;
+        let mut block_engine_region: Option<String> = None;
 
         while !exit.load(Ordering::Relaxed) {
             // Wait until a valid config is supplied (either initially or by admin rpc)
-            // Use if!/else here to avoid extra CONNECTION_BACKOFF wait on successful termination
             let local_block_engine_config = {
                 let block_engine_config = block_engine_config.clone();
                 task::spawn_blocking(move || block_engine_config.lock().unwrap().clone())
@@ -158,20 +355,397 @@ impl BlockEngineStage {
                     .unwrap()
             };
             if !Self::is_valid_block_engine_config(&local_block_engine_config) {
+                bundle_result_forwarder.drain_to_fallback();
                 sleep(CONNECTION_BACKOFF).await;
-            } else if let Err(e) = Self::connect_auth_and_stream(
+                continue;
+            }
+
+            let region_candidate_urls = local_block_engine_config.region_candidate_urls();
+            if region_candidate_urls.is_empty() {
+                block_engine_region = None;
+                *block_engine_regions.write().unwrap() = BlockEngineRegions::default();
+            } else if !block_engine_region
+                .as_ref()
+                .is_some_and(|region| region_candidate_urls.contains(region))
+            {
+                let keypair = cluster_info.keypair().clone();
+                let latencies = measure_block_engine_regions(
+                    &region_candidate_urls,
+                    &keypair,
+                    &CONNECTION_TIMEOUT,
+                )
+                .await;
+                block_engine_region =
+                    Self::update_block_engine_region(None, latencies, &block_engine_regions);
+                if block_engine_region.is_none() {
+                    warn!("can't connect to block engine. none of the candidate block engines are reachable.");
+                    bundle_result_forwarder.drain_to_fallback();
+                    sleep(CONNECTION_BACKOFF).await;
+                    continue;
+                }
+            }
+
+            // the selected region replaces the configured primary block engine
+            let block_engine_urls = BlockEngineConfig {
+                block_engine_url: block_engine_region
+                    .clone()
+                    .unwrap_or_else(|| local_block_engine_config.block_engine_url.clone()),
+                ..local_block_engine_config.clone()
+            }
+            .block_engine_urls();
+
+            // returns when the config or region changes or on exit
+            Self::run_block_engine_connections(
                 &local_block_engine_config,
                 &block_engine_config,
+                block_engine_urls,
+                &region_candidate_urls,
+                &mut block_engine_region,
+                &block_engine_regions,
                 &cluster_info,
                 &bundle_tx,
                 &packet_tx,
                 &banking_packet_sender,
+                &packet_trust,
+                &packet_source_accounting,
                 &exit,
                 &block_builder_fee_info,
+                &mut bundle_result_forwarder,
+                &block_engine_health,
+            )
+            .await;
+        }
+
+        bundle_result_forwarder.stop_streams();
+        bundle_result_forwarder.drain_to_fallback();
+    }
+
+    /// Spawns a task maintaining the connection to each Block Engine and merges their events until
+    /// the config or the selected region changes.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_block_engine_connections(
+        local_config: &BlockEngineConfig, // local copy of config with current connections
+        global_config: &Arc<Mutex<BlockEngineConfig>>, // guarded reference for detecting run-time updates
+        block_engine_urls: Vec<String>,
+        region_candidate_urls: &[String],
+        block_engine_region: &mut Option<String>,
+        block_engine_regions: &Arc<RwLock<BlockEngineRegions>>,
+        cluster_info: &Arc<ClusterInfo>,
+        bundle_tx: &Sender<Vec<PacketBundle>>,
+        packet_tx: &Sender<PacketBatch>,
+        banking_packet_sender: &BankingPacketSender,
+        packet_trust: &Arc<PacketTrust>,
+        packet_source_accounting: &Arc<PacketSourceAccounting>,
+        exit: &Arc<AtomicBool>,
+        block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
+        bundle_result_forwarder: &mut BundleResultForwarder,
+        block_engine_health: &Arc<RwLock<Vec<BlockEngineHealth>>>,
+    ) {
+        const METRICS_TICK: Duration = Duration::from_secs(1);
+        const CONNECTION_TIMEOUT: Duration = Duration::from_secs(CONNECTION_TIMEOUT_S);
+
+        let deduper = Arc::new(Mutex::new(BlockEngineDeduper::default()));
+        let (event_sender, mut event_receiver) = mpsc::unbounded_channel();
+        let mut block_engines: Vec<BlockEngineState> = block_engine_urls
+            .into_iter()
+            .map(BlockEngineState::new)
+            .collect();
+        let connections: Vec<_> = block_engines
+            .iter()
+            .enumerate()
+            .map(|(index, block_engine)| {
+                tokio::spawn(Self::maintain_connection(
+                    index,
+                    block_engine.health.block_engine_url.clone(),
+                    local_config.trust_packets,
+                    cluster_info.clone(),
+                    bundle_tx.clone(),
+                    packet_tx.clone(),
+                    banking_packet_sender.clone(),
+                    packet_trust.clone(),
+                    packet_source_accounting.clone(),
+                    deduper.clone(),
+                    block_engine.forward_bundles.clone(),
+                    event_sender.clone(),
+                    exit.clone(),
+                ))
+            })
+            .collect();
+        drop(event_sender);
+
+        let mut active_block_engine = None;
+        let mut metrics_tick = interval(METRICS_TICK);
+        let (region_latency_sender, mut region_latency_receiver) = mpsc::unbounded_channel();
+        let mut region_evaluation_tick = interval_at(
+            tokio::time::Instant::now() + REGION_EVALUATION_INTERVAL,
+            REGION_EVALUATION_INTERVAL,
+        );
+
+        while !exit.load(Ordering::Relaxed) {
+            tokio::select! {
+                Some(event) = event_receiver.recv() => {
+                    Self::handle_block_engine_event(event, &mut block_engines, bundle_result_forwarder);
+                }
+                Some(bundle_result) = bundle_result_forwarder.recv() => {
+                    bundle_result_forwarder.forward(bundle_result);
+                }
+                _ = region_evaluation_tick.tick(), if !region_candidate_urls.is_empty() => {
+                    // measured in the background so results keep streaming
+                    let region_candidate_urls = region_candidate_urls.to_vec();
+                    let keypair = cluster_info.keypair().clone();
+                    let region_latency_sender = region_latency_sender.clone();
+                    tokio::spawn(async move {
+                        let latencies = measure_block_engine_regions(&region_candidate_urls, &keypair, &CONNECTION_TIMEOUT).await;
+                        let _ = region_latency_sender.send(latencies);
+                    });
+                }
+                Some(latencies) = region_latency_receiver.recv() => {
+                    let selected_region = Self::update_block_engine_region(block_engine_region.as_deref(), latencies, block_engine_regions);
+                    if selected_region != *block_engine_region {
+                        *block_engine_region = selected_region;
+                        break;
+                    }
+                }
+                _ = metrics_tick.tick() => {
+                    bundle_result_forwarder.report();
+                    bundle_result_forwarder.flush();
+
+                    let selected_block_engine = Self::select_active_block_engine(&block_engines, Instant::now());
+                    if selected_block_engine != active_block_engine {
+                        let url = selected_block_engine
+                            .map(|index| block_engines[index].health.block_engine_url.clone())
+                            .unwrap_or_default();
+                        info!("active block engine changed to: {url:?}");
+                        datapoint_info!(
+                            "block_engine_stage-active_block_engine_changed",
+                            ("url", url, String),
+                            ("count", 1, i64),
+                        );
+                        // the previous block engine's bundles mustn't be paid to the next builder
+                        Self::update_bundle_forwarding(&mut block_engines, None);
+                        active_block_engine = selected_block_engine;
+                    }
+                    if let Some(block_builder_info) = active_block_engine
+                        .and_then(|index| block_engines[index].block_builder_fee_info.clone())
+                    {
+                        Self::update_block_builder_fee_info(block_builder_fee_info, block_builder_info).await;
+                    }
+                    Self::update_bundle_forwarding(&mut block_engines, active_block_engine);
+                    *block_engine_health.write().unwrap() = block_engines
+                        .iter()
+                        .map(|block_engine| block_engine.health.clone())
+                        .collect();
+
+                    let global_config = global_config.clone();
+                    if *local_config != task::spawn_blocking(move || global_config.lock().unwrap().clone())
+                        .await
+                        .unwrap() {
+                        info!("block engine config changed");
+                        break;
+                    }
+                }
+            }
+        }
+
+        for connection in connections {
+            connection.abort();
+        }
+        // results can't be streamed until the next connections are established
+        bundle_result_forwarder.stop_streams();
+        bundle_result_forwarder.drain_to_fallback();
+        block_engine_health.write().unwrap().clear();
+    }
+
+    /// Selects the region to use from the latest measurements and reports them
+    fn update_block_engine_region(
+        current: Option<&str>,
+        latencies: Vec<BlockEngineRegionLatency>,
+        block_engine_regions: &Arc<RwLock<BlockEngineRegions>>,
+    ) -> Option<String> {
+        for latency in &latencies {
+            datapoint_info!(
+                "block_engine_stage-region_latency",
+                ("url", latency.block_engine_url, String),
+                ("connect_us", latency.connect_us, Option<i64>),
+                ("auth_us", latency.auth_us, Option<i64>),
+                ("error", latency.error.as_ref(), Option<String>),
+            );
+        }
+
+        let selected = select_block_engine_region(current, &latencies);
+        if selected.as_deref() != current {
+            info!("block engine region changed from {current:?} to {selected:?}");
+            datapoint_info!(
+                "block_engine_stage-region_selected",
+                ("url", selected.clone().unwrap_or_default(), String),
+                ("previous_url", current.unwrap_or_default(), String),
+                ("count", 1, i64),
+            );
+        }
+
+        *block_engine_regions.write().unwrap() = BlockEngineRegions {
+            selected_block_engine_url: selected.clone(),
+            latencies,
+            last_evaluated_timestamp_ms: Some(timestamp()),
+        };
+        selected
+    }
+
+    fn handle_block_engine_event(
+        event: BlockEngineEvent,
+        block_engines: &mut [BlockEngineState],
+        bundle_result_forwarder: &mut BundleResultForwarder,
+    ) {
+        match event {
+            BlockEngineEvent::Connected {
+                index,
+                bundle_feedback_client,
+            } => {
+                let block_engine = &mut block_engines[index];
+                block_engine.health.connected = true;
+                // give the block engine a full heartbeat timeout to start streaming
+                block_engine.last_message = Some(Instant::now());
+                bundle_result_forwarder.start_stream(
+                    block_engine.health.block_engine_url.clone(),
+                    bundle_feedback_client,
+                );
+            }
+            BlockEngineEvent::BlockBuilderFeeInfo {
+                index,
+                latency,
+                block_builder_fee_info,
+            } => {
+                let block_engine = &mut block_engines[index];
+                block_engine.latency = Some(latency);
+                block_engine.health.latency_us = Some(latency.as_micros() as u64);
+                block_engine.block_builder_fee_info = Some(block_builder_fee_info);
+            }
+            BlockEngineEvent::Stats {
+                index,
+                stats,
+                last_message,
+            } => {
+                let block_engine = &mut block_engines[index];
+                if let Some(last_message) = last_message {
+                    let elapsed = Instant::now().saturating_duration_since(last_message);
+                    block_engine.last_message = Some(last_message);
+                    block_engine.health.last_message_timestamp_ms =
+                        Some(timestamp().saturating_sub(elapsed.as_millis() as u64));
+                }
+                let health = &mut block_engine.health;
+                saturating_add_assign!(health.num_bundles, stats.num_bundles);
+                saturating_add_assign!(health.num_duplicate_bundles, stats.num_duplicate_bundles);
+                saturating_add_assign!(health.num_packets, stats.num_packets);
+                saturating_add_assign!(health.num_duplicate_packets, stats.num_duplicate_packets);
+            }
+            BlockEngineEvent::Disconnected { index, error } => {
+                let block_engine = &mut block_engines[index];
+                block_engine.health.connected = false;
+                block_engine.latency = None;
+                block_engine.health.latency_us = None;
+                block_engine.block_builder_fee_info = None;
+                saturating_add_assign!(block_engine.health.num_connection_errors, 1);
+                block_engine.health.last_error = Some(error);
+                bundle_result_forwarder.stop_stream(&block_engine.health.block_engine_url);
+            }
+        }
+    }
+
+    /// Returns the most preferred healthy Block Engine. If none are healthy, the connected Block
+    /// Engine with the lowest latency is used.
+    fn select_active_block_engine(
+        block_engines: &[BlockEngineState],
+        now: Instant,
+    ) -> Option<usize> {
+        block_engines
+            .iter()
+            .position(|block_engine| block_engine.is_healthy(now))
+            .or_else(|| {
+                block_engines
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, block_engine)| block_engine.health.connected)
+                    .min_by_key(|(_, block_engine)| block_engine.latency.unwrap_or(Duration::MAX))
+                    .map(|(index, _)| index)
+            })
+    }
+
+    /// Only the active Block Engine's bundles are forwarded, once its block builder fee info is
+    /// known, so the tip crank pays the builder that built them
+    fn update_bundle_forwarding(
+        block_engines: &mut [BlockEngineState],
+        active_block_engine: Option<usize>,
+    ) {
+        for (index, block_engine) in block_engines.iter_mut().enumerate() {
+            block_engine.health.active = active_block_engine == Some(index);
+            block_engine.forward_bundles.store(
+                block_engine.health.active && block_engine.block_builder_fee_info.is_some(),
+                Ordering::Relaxed,
+            );
+        }
+    }
+
+    async fn update_block_builder_fee_info(
+        block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
+        block_builder_info: BlockBuilderFeeInfoResponse,
+    ) {
+        let block_builder_fee_info = block_builder_fee_info.clone();
+        task::spawn_blocking(move || {
+            let mut bb_fee = block_builder_fee_info.lock().unwrap();
+            bb_fee.block_builder_commission = block_builder_info.commission;
+            if let Ok(pk) = Pubkey::from_str(&block_builder_info.pubkey) {
+                bb_fee.block_builder = pk
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    /// Connects to a single Block Engine, reconnecting with a backoff on errors until exit
+    #[allow(clippy::too_many_arguments)]
+    async fn maintain_connection(
+        index: usize,
+        block_engine_url: String,
+        trust_packets: bool,
+        cluster_info: Arc<ClusterInfo>,
+        bundle_tx: Sender<Vec<PacketBundle>>,
+        packet_tx: Sender<PacketBatch>,
+        banking_packet_sender: BankingPacketSender,
+        packet_trust: Arc<PacketTrust>,
+        packet_source_accounting: Arc<PacketSourceAccounting>,
+        deduper: Arc<Mutex<BlockEngineDeduper>>,
+        forward_bundles: Arc<AtomicBool>,
+        event_sender: mpsc::UnboundedSender<BlockEngineEvent>,
+        exit: Arc<AtomicBool>,
+    ) {
+        const CONNECTION_TIMEOUT: Duration = Duration::from_secs(CONNECTION_TIMEOUT_S);
+        const CONNECTION_BACKOFF: Duration = Duration::from_secs(CONNECTION_BACKOFF_S);
+        let mut error_count: u64 = 0;
+
+        while !exit.load(Ordering::Relaxed) {
+            if let Err(e) = Self::connect_auth_and_stream(
+                index,
+                &block_engine_url,
+                trust_packets,
+                &cluster_info,
+                &bundle_tx,
+                &packet_tx,
+                &banking_packet_sender,
+                &packet_trust,
+                &packet_source_accounting,
+                &deduper,
+                &forward_bundles,
+                &event_sender,
+                &exit,
                 &CONNECTION_TIMEOUT,
             )
             .await
             {
+                let _ = event_sender.send(BlockEngineEvent::Disconnected {
+                    index,
+                    error: e.to_string(),
+                });
+
                 match e {
                     // This error is frequent on hot spares, and the parsed string does not work
                     // with datapoints (incorrect escaping).
@@ -182,6 +756,7 @@ impl BlockEngineStage {
                         error_count += 1;
                         datapoint_warn!(
                             "block_engine_stage-proxy_error",
+                            ("url", block_engine_url, String),
                             ("count", error_count, i64),
                             ("error", e.to_string(), String),
                         );
@@ -192,46 +767,29 @@ impl BlockEngineStage {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn connect_auth_and_stream(
-        local_block_engine_config: &BlockEngineConfig,
-        global_block_engine_config: &Arc<Mutex<BlockEngineConfig>>,
+        index: usize,
+        block_engine_url: &str,
+        trust_packets: bool,
         cluster_info: &Arc<ClusterInfo>,
         bundle_tx: &Sender<Vec<PacketBundle>>,
         packet_tx: &Sender<PacketBatch>,
         banking_packet_sender: &BankingPacketSender,
+        packet_trust: &PacketTrust,
+        packet_source_accounting: &PacketSourceAccounting,
+        deduper: &Arc<Mutex<BlockEngineDeduper>>,
+        forward_bundles: &AtomicBool,
+        event_sender: &mpsc::UnboundedSender<BlockEngineEvent>,
         exit: &Arc<AtomicBool>,
-        block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
         connection_timeout: &Duration,
     ) -> crate::proxy::Result<()> {
         // Get a copy of configs here in case they have changed at runtime
         let keypair = cluster_info.keypair().clone();
 
-        let mut backend_endpoint =
-            Endpoint::from_shared(local_block_engine_config.block_engine_url.clone())
-                .map_err(|_| {
-                    ProxyError::BlockEngineConnectionError(format!(
-                        "invalid block engine url value: {}",
-                        local_block_engine_config.block_engine_url
-                    ))
-                })?
-                .tcp_keepalive(Some(Duration::from_secs(60)));
-        if local_block_engine_config
-            .block_engine_url
-            .starts_with("https")
-        {
-            backend_endpoint = backend_endpoint
-                .tls_config(tonic::transport::ClientTlsConfig::new())
-                .map_err(|_| {
-                    ProxyError::BlockEngineConnectionError(
-                        "failed to set tls_config for block engine service".to_string(),
-                    )
-                })?;
-        }
+        let backend_endpoint = block_engine_endpoint(block_engine_url)?;
 
-        debug!(
-            "connecting to auth: {}",
-            local_block_engine_config.block_engine_url
-        );
+        debug!("connecting to auth: {}", block_engine_url);
         let auth_channel = timeout(*connection_timeout, backend_endpoint.connect())
             .await
             .map_err(|_| ProxyError::AuthenticationConnectionTimeout)?
@@ -249,34 +807,41 @@ impl BlockEngineStage {
 
         datapoint_info!(
             "block_engine_stage-tokens_generated",
-            ("url", local_block_engine_config.block_engine_url, String),
+            ("url", block_engine_url, String),
             ("count", 1, i64),
         );
 
-        debug!(
-            "connecting to block engine: {}",
-            local_block_engine_config.block_engine_url
-        );
+        debug!("connecting to block engine: {}", block_engine_url);
         let block_engine_channel = timeout(*connection_timeout, backend_endpoint.connect())
             .await
             .map_err(|_| ProxyError::BlockEngineConnectionTimeout)?
             .map_err(|e| ProxyError::BlockEngineConnectionError(e.to_string()))?;
 
         let access_token = Arc::new(Mutex::new(access_token));
+        let bundle_feedback_client = BundleFeedbackClient::with_interceptor(
+            block_engine_channel.clone(),
+            AuthInterceptor::new(access_token.clone()),
+        );
         let block_engine_client = BlockEngineValidatorClient::with_interceptor(
             block_engine_channel,
             AuthInterceptor::new(access_token.clone()),
         );
 
         Self::start_consuming_block_engine_bundles_and_packets(
+            index,
+            block_engine_url,
+            trust_packets,
             bundle_tx,
             block_engine_client,
+            bundle_feedback_client,
             packet_tx,
-            local_block_engine_config,
-            global_block_engine_config,
             banking_packet_sender,
+            packet_trust,
+            packet_source_accounting,
+            deduper,
+            forward_bundles,
+            event_sender,
             exit,
-            block_builder_fee_info,
             auth_client,
             access_token,
             refresh_token,
@@ -289,14 +854,20 @@ impl BlockEngineStage {
 
     #[allow(clippy::too_many_arguments)]
     async fn start_consuming_block_engine_bundles_and_packets(
+        index: usize,
+        block_engine_url: &str,
+        trust_packets: bool,
         bundle_tx: &Sender<Vec<PacketBundle>>,
         mut client: BlockEngineValidatorClient<InterceptedService<Channel, AuthInterceptor>>,
+        bundle_feedback_client: BundleFeedbackClient<InterceptedService<Channel, AuthInterceptor>>,
         packet_tx: &Sender<PacketBatch>,
-        local_config: &BlockEngineConfig, // local copy of config with current connections
-        global_config: &Arc<Mutex<BlockEngineConfig>>, // guarded reference for detecting run-time updates
         banking_packet_sender: &BankingPacketSender,
+        packet_trust: &PacketTrust,
+        packet_source_accounting: &PacketSourceAccounting,
+        deduper: &Arc<Mutex<BlockEngineDeduper>>,
+        forward_bundles: &AtomicBool,
+        event_sender: &mpsc::UnboundedSender<BlockEngineEvent>,
         exit: &Arc<AtomicBool>,
-        block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
         auth_client: AuthServiceClient<Channel>,
         access_token: Arc<Mutex<Token>>,
         refresh_token: Token,
@@ -322,38 +893,34 @@ impl BlockEngineStage {
         .map_err(|e| ProxyError::MethodError(e.to_string()))?
         .into_inner();
 
-        let block_builder_info = timeout(
-            *connection_timeout,
-            client.get_block_builder_fee_info(BlockBuilderFeeInfoRequest {}),
-        )
-        .await
-        .map_err(|_| ProxyError::MethodTimeout("get_block_builder_fee_info".to_string()))?
-        .map_err(|e| ProxyError::MethodError(e.to_string()))?
-        .into_inner();
+        let (latency, block_builder_fee_info) =
+            Self::get_block_builder_fee_info(&mut client, connection_timeout).await?;
 
-        {
-            let block_builder_fee_info = block_builder_fee_info.clone();
-            task::spawn_blocking(move || {
-                let mut bb_fee = block_builder_fee_info.lock().unwrap();
-                bb_fee.block_builder_commission = block_builder_info.commission;
-                if let Ok(pk) = Pubkey::from_str(&block_builder_info.pubkey) {
-                    bb_fee.block_builder = pk
-                }
-            })
-            .await
-            .unwrap();
-        }
+        let _ = event_sender.send(BlockEngineEvent::Connected {
+            index,
+            bundle_feedback_client,
+        });
+        let _ = event_sender.send(BlockEngineEvent::BlockBuilderFeeInfo {
+            index,
+            latency,
+            block_builder_fee_info,
+        });
 
         Self::consume_bundle_and_packet_stream(
+            index,
+            block_engine_url,
+            trust_packets,
             client,
             (subscribe_bundles_stream, subscribe_packets_stream),
             bundle_tx,
             packet_tx,
-            local_config,
-            global_config,
             banking_packet_sender,
+            packet_trust,
+            packet_source_accounting,
+            deduper,
+            forward_bundles,
+            event_sender,
             exit,
-            block_builder_fee_info,
             auth_client,
             access_token,
             refresh_token,
@@ -364,8 +931,28 @@ impl BlockEngineStage {
         .await
     }
 
+    /// Returns the block builder fee info along with the round trip time of the request
+    async fn get_block_builder_fee_info(
+        client: &mut BlockEngineValidatorClient<InterceptedService<Channel, AuthInterceptor>>,
+        connection_timeout: &Duration,
+    ) -> crate::proxy::Result<(Duration, BlockBuilderFeeInfoResponse)> {
+        let start = Instant::now();
+        let block_builder_info = timeout(
+            *connection_timeout,
+            client.get_block_builder_fee_info(BlockBuilderFeeInfoRequest {}),
+        )
+        .await
+        .map_err(|_| ProxyError::MethodTimeout("get_block_builder_fee_info".to_string()))?
+        .map_err(|e| ProxyError::MethodError(e.to_string()))?
+        .into_inner();
+        Ok((start.elapsed(), block_builder_info))
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn consume_bundle_and_packet_stream(
+        index: usize,
+        block_engine_url: &str,
+        trust_packets: bool,
         mut client: BlockEngineValidatorClient<InterceptedService<Channel, AuthInterceptor>>,
         (mut bundle_stream, mut packet_stream): (
             Streaming<block_engine::SubscribeBundlesResponse>,
@@ -373,11 +960,13 @@ impl BlockEngineStage {
         ),
         bundle_tx: &Sender<Vec<PacketBundle>>,
         packet_tx: &Sender<PacketBatch>,
-        local_config: &BlockEngineConfig, // local copy of config with current connections
-        global_config: &Arc<Mutex<BlockEngineConfig>>, // guarded reference for detecting run-time updates
         banking_packet_sender: &BankingPacketSender,
+        packet_trust: &PacketTrust,
+        packet_source_accounting: &PacketSourceAccounting,
+        deduper: &Arc<Mutex<BlockEngineDeduper>>,
+        forward_bundles: &AtomicBool,
+        event_sender: &mpsc::UnboundedSender<BlockEngineEvent>,
         exit: &Arc<AtomicBool>,
-        block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
         mut auth_client: AuthServiceClient<Channel>,
         access_token: Arc<Mutex<Token>>,
         mut refresh_token: Token,
@@ -386,41 +975,41 @@ impl BlockEngineStage {
         connection_timeout: &Duration,
     ) -> crate::proxy::Result<()> {
         const METRICS_TICK: Duration = Duration::from_secs(1);
-        const MAINTENANCE_TICK: Duration = Duration::from_secs(10 * 60);
+        const LATENCY_PROBE_TICK: Duration = Duration::from_secs(10);
         let refresh_within_s: u64 = METRICS_TICK.as_secs().saturating_mul(3).saturating_div(2);
 
         let mut num_full_refreshes: u64 = 1;
         let mut num_refresh_access_token: u64 = 0;
         let mut block_engine_stats = BlockEngineStageStats::default();
+        let mut last_message = None;
         let mut metrics_and_auth_tick = interval(METRICS_TICK);
-        let mut maintenance_tick = interval(MAINTENANCE_TICK);
+        let mut latency_probe_tick = interval(LATENCY_PROBE_TICK);
 
-        info!("connected to packet and bundle stream");
+        info!("connected to packet and bundle stream: {block_engine_url}");
 
         while !exit.load(Ordering::Relaxed) {
             tokio::select! {
                 maybe_msg = packet_stream.message() => {
                     let resp = maybe_msg?.ok_or(ProxyError::GrpcStreamDisconnected)?;
-                    Self::handle_block_engine_packets(resp, packet_tx, banking_packet_sender, local_config.trust_packets, &mut block_engine_stats)?;
+                    last_message = Some(Instant::now());
+                    Self::handle_block_engine_packets(resp, block_engine_url, packet_tx, banking_packet_sender, trust_packets, packet_trust, packet_source_accounting, deduper, &mut block_engine_stats)?;
                 }
                 maybe_bundles = bundle_stream.message() => {
-                    Self::handle_block_engine_maybe_bundles(maybe_bundles, bundle_tx, &mut block_engine_stats)?;
+                    Self::handle_block_engine_maybe_bundles(maybe_bundles, bundle_tx, block_engine_url, deduper, forward_bundles, &mut block_engine_stats)?;
+                    last_message = Some(Instant::now());
                 }
                 _ = metrics_and_auth_tick.tick() => {
-                    block_engine_stats.report();
-                    block_engine_stats = BlockEngineStageStats::default();
+                    block_engine_stats.report(block_engine_url);
+                    let _ = event_sender.send(BlockEngineEvent::Stats {
+                        index,
+                        stats: std::mem::take(&mut block_engine_stats),
+                        last_message,
+                    });
 
                     if cluster_info.id() != keypair.pubkey() {
                         return Err(ProxyError::AuthenticationConnectionError("validator identity changed".to_string()));
                     }
 
-                    let global_config = global_config.clone();
-                    if *local_config != task::spawn_blocking(move || global_config.lock().unwrap().clone())
-                        .await
-                        .unwrap() {
-                        return Err(ProxyError::AuthenticationConnectionError("block engine config changed".to_string()));
-                    }
-
                     let (maybe_new_access, maybe_new_refresh) = maybe_refresh_auth_tokens(&mut auth_client,
                         &access_token,
                         &refresh_token,
@@ -433,7 +1022,7 @@ impl BlockEngineStage {
                         num_refresh_access_token += 1;
                         datapoint_info!(
                             "block_engine_stage-refresh_access_token",
-                            ("url", &local_config.block_engine_url, String),
+                            ("url", block_engine_url, String),
                             ("count", num_refresh_access_token, i64),
                         );
 
@@ -446,32 +1035,20 @@ impl BlockEngineStage {
                         num_full_refreshes += 1;
                         datapoint_info!(
                             "block_engine_stage-tokens_generated",
-                            ("url", &local_config.block_engine_url, String),
+                            ("url", block_engine_url, String),
                             ("count", num_full_refreshes, i64),
                         );
                         refresh_token = new_token;
                     }
                 }
-                _ = maintenance_tick.tick() => {
-                    let block_builder_info = timeout(
-                        *connection_timeout,
-                        client.get_block_builder_fee_info(BlockBuilderFeeInfoRequest{})
-                    )
-                    .await
-                    .map_err(|_| ProxyError::MethodTimeout("get_block_builder_fee_info".to_string()))?
-                    .map_err(|e| ProxyError::MethodError(e.to_string()))?
-                    .into_inner();
-
-                    let block_builder_fee_info = block_builder_fee_info.clone();
-                    task::spawn_blocking(move || {
-                        let mut bb_fee = block_builder_fee_info.lock().unwrap();
-                        bb_fee.block_builder_commission = block_builder_info.commission;
-                        if let Ok(pk) = Pubkey::from_str(&block_builder_info.pubkey) {
-                            bb_fee.block_builder = pk
-                        }
-                    })
-                    .await
-                    .unwrap();
+                _ = latency_probe_tick.tick() => {
+                    let (latency, block_builder_fee_info) =
+                        Self::get_block_builder_fee_info(&mut client, connection_timeout).await?;
+                    let _ = event_sender.send(BlockEngineEvent::BlockBuilderFeeInfo {
+                        index,
+                        latency,
+                        block_builder_fee_info,
+                    });
                 }
             }
         }
@@ -482,23 +1059,33 @@ impl BlockEngineStage {
     fn handle_block_engine_maybe_bundles(
         maybe_bundles_response: Result<Option<block_engine::SubscribeBundlesResponse>, Status>,
         bundle_sender: &Sender<Vec<PacketBundle>>,
+        block_engine_url: &str,
+        deduper: &Arc<Mutex<BlockEngineDeduper>>,
+        forward_bundles: &AtomicBool,
         block_engine_stats: &mut BlockEngineStageStats,
     ) -> crate::proxy::Result<()> {
         let bundles_response = maybe_bundles_response?.ok_or(ProxyError::GrpcStreamDisconnected)?;
         let bundles: Vec<PacketBundle> = bundles_response
             .bundles
             .into_iter()
-            .filter_map(|bundle| {
+            .filter_map(|bundle_uuid| {
+                let bundle = bundle_uuid.bundle?;
                 Some(PacketBundle {
                     batch: PacketBatch::new(
                         bundle
-                            .bundle?
                             .packets
                             .into_iter()
                             .map(proto_packet_to_packet)
                             .collect(),
                     ),
-                    bundle_id: bundle.uuid,
+                    bundle_id: bundle_uuid.uuid,
+                    // out of range indexes are rejected when the bundle is deserialized
+                    allowed_to_fail_tx_indexes: bundle
+                        .allowed_to_fail_tx_indexes
+                        .into_iter()
+                        .map(|index| index as usize)
+                        .collect(),
+                    source: Some(block_engine_url.to_string()),
                 })
             })
             .collect();
@@ -509,17 +1096,46 @@ impl BlockEngineStage {
             bundles.iter().map(|bundle| bundle.batch.len() as u64).sum()
         );
 
+        // dropped before de-duplication so the active block engine's copy is still forwarded
+        if !forward_bundles.load(Ordering::Relaxed) {
+            saturating_add_assign!(block_engine_stats.num_standby_bundles, bundles.len() as u64);
+            return Ok(());
+        }
+
+        // the same bundle is usually streamed by every connected block engine
+        let num_bundles = bundles.len();
+        let bundles: Vec<PacketBundle> = {
+            let now = Instant::now();
+            let mut deduper = deduper.lock().unwrap();
+            bundles
+                .into_iter()
+                .filter(|bundle| deduper.check_bundle(bundle, now))
+                .collect()
+        };
+        saturating_add_assign!(
+            block_engine_stats.num_duplicate_bundles,
+            num_bundles.saturating_sub(bundles.len()) as u64
+        );
+        if bundles.is_empty() {
+            return Ok(());
+        }
+
         // NOTE: bundles are sanitized in bundle_sanitizer module
         bundle_sender
             .send(bundles)
             .map_err(|_| ProxyError::PacketForwardError)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn handle_block_engine_packets(
         resp: block_engine::SubscribePacketsResponse,
+        block_engine_url: &str,
         packet_tx: &Sender<PacketBatch>,
         banking_packet_sender: &BankingPacketSender,
         trust_packets: bool,
+        packet_trust: &PacketTrust,
+        packet_source_accounting: &PacketSourceAccounting,
+        deduper: &Arc<Mutex<BlockEngineDeduper>>,
         block_engine_stats: &mut BlockEngineStageStats,
     ) -> crate::proxy::Result<()> {
         if let Some(batch) = resp.batch {
@@ -528,16 +1144,49 @@ impl BlockEngineStage {
                 return Ok(());
             }
 
-            let packet_batch = PacketBatch::new(
+            let num_packets = batch.packets.len();
+            let mut packet_batch = PacketBatch::new(
                 batch
                     .packets
                     .into_iter()
                     .map(proto_packet_to_packet)
                     .collect(),
             );
+            // accounted before the de-duplication across block engines so the duplicate
+            // signatures they send show up in the per-source stats
+            packet_source_accounting.record(PacketSource::BlockEngine, &mut packet_batch);
+            let num_allowed_packets = packet_batch.len();
+            let mut packets = Vec::<Packet>::from(packet_batch);
+            {
+                let now = Instant::now();
+                let mut deduper = deduper.lock().unwrap();
+                packets.retain(|packet| deduper.check_packet(packet, now));
+            }
+
+            saturating_add_assign!(block_engine_stats.num_packets, num_packets as u64);
+            saturating_add_assign!(
+                block_engine_stats.num_duplicate_packets,
+                num_allowed_packets.saturating_sub(packets.len()) as u64
+            );
+            if packets.is_empty() {
+                return Ok(());
+            }
 
-            saturating_add_assign!(block_engine_stats.num_packets, packet_batch.len() as u64);
+            let mut packet_batch = PacketBatch::new(packets);
 
+            let trust_packets = trust_packets && {
+                let sample = packet_trust.verify_sample(
+                    PacketSource::BlockEngine,
+                    block_engine_url,
+                    &mut packet_batch,
+                );
+                saturating_add_assign!(block_engine_stats.num_sampled_packets, sample.num_sampled);
+                saturating_add_assign!(
+                    block_engine_stats.num_failed_sampled_packets,
+                    sample.num_failed
+                );
+                packet_trust.is_trusted(PacketSource::BlockEngine)
+            };
             if trust_packets {
                 banking_packet_sender
                     .send(Arc::new(vec![packet_batch]))
@@ -555,17 +1204,208 @@ impl BlockEngineStage {
     }
 
     pub fn is_valid_block_engine_config(config: &BlockEngineConfig) -> bool {
-        if config.block_engine_url.is_empty() {
+        if config.block_engine_url.is_empty() && config.candidate_block_engine_urls.is_empty() {
             warn!("can't connect to block_engine. missing block_engine_url.");
             return false;
         }
-        if let Err(e) = Endpoint::from_str(&config.block_engine_url) {
-            error!(
-                "can't connect to block engine. error creating block engine endpoint - {}",
-                e.to_string()
-            );
-            return false;
+        for block_engine_url in config
+            .block_engine_urls()
+            .into_iter()
+            .chain(config.region_candidate_urls())
+        {
+            if let Err(e) = Endpoint::from_str(&block_engine_url) {
+                error!(
+                    "can't connect to block engine {}. error creating block engine endpoint - {}",
+                    block_engine_url,
+                    e.to_string()
+                );
+                return false;
+            }
         }
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crossbeam_channel::unbounded,
+        jito_protos::proto::bundle::{Bundle, BundleUuid},
+    };
+
+    fn block_engine_state(
+        connected: bool,
+        latency_ms: u64,
+        last_message: Instant,
+    ) -> BlockEngineState {
+        BlockEngineState {
+            health: BlockEngineHealth {
+                connected,
+                ..BlockEngineHealth::default()
+            },
+            latency: Some(Duration::from_millis(latency_ms)),
+            last_message: Some(last_message),
+            block_builder_fee_info: None,
+            forward_bundles: Arc::default(),
+        }
+    }
+
+    #[test]
+    fn test_block_engine_urls() {
+        let config = BlockEngineConfig {
+            block_engine_url: "http://a".to_string(),
+            failover_block_engine_urls: vec![
+                "http://b".to_string(),
+                "http://a".to_string(),
+                "".to_string(),
+                "http://c".to_string(),
+                "http://b".to_string(),
+            ],
+            candidate_block_engine_urls: vec![],
+            trust_packets: false,
+        };
+        assert_eq!(
+            config.block_engine_urls(),
+            vec!["http://a", "http://b", "http://c"]
+        );
+        assert!(config.region_candidate_urls().is_empty());
+        assert!(BlockEngineStage::is_valid_block_engine_config(&config));
+
+        // the primary block engine is one of the region candidates
+        let config = BlockEngineConfig {
+            candidate_block_engine_urls: vec!["http://d".to_string(), "http://a".to_string()],
+            ..config
+        };
+        assert_eq!(config.region_candidate_urls(), vec!["http://a", "http://d"]);
+        assert!(BlockEngineStage::is_valid_block_engine_config(&config));
+        let config = BlockEngineConfig {
+            block_engine_url: "".to_string(),
+            ..config
+        };
+        assert_eq!(config.region_candidate_urls(), vec!["http://d", "http://a"]);
+        assert!(BlockEngineStage::is_valid_block_engine_config(&config));
+
+        let config = BlockEngineConfig {
+            failover_block_engine_urls: vec!["not a url".to_string()],
+            ..config
+        };
+        assert!(!BlockEngineStage::is_valid_block_engine_config(&config));
+    }
+
+    #[test]
+    fn test_select_active_block_engine() {
+        let now = Instant::now();
+        let stale = now - HEARTBEAT_TIMEOUT;
+
+        assert_eq!(BlockEngineStage::select_active_block_engine(&[], now), None);
+
+        // the most preferred healthy block engine is used
+        let block_engines = [
+            block_engine_state(true, 100, now),
+            block_engine_state(true, 10, now),
+        ];
+        assert_eq!(
+            BlockEngineStage::select_active_block_engine(&block_engines, now),
+            Some(0)
+        );
+
+        // fails over when the preferred block engine disconnects, stops sending heartbeats or
+        // is too slow
+        for unhealthy in [
+            block_engine_state(false, 100, now),
+            block_engine_state(true, 100, stale),
+            block_engine_state(true, 1_000, now),
+        ] {
+            let block_engines = [unhealthy, block_engine_state(true, 100, now)];
+            assert_eq!(
+                BlockEngineStage::select_active_block_engine(&block_engines, now),
+                Some(1)
+            );
+        }
+
+        // falls back to the fastest connected block engine when none are healthy
+        let block_engines = [
+            block_engine_state(true, 2_000, now),
+            block_engine_state(false, 10, now),
+            block_engine_state(true, 1_000, stale),
+        ];
+        assert_eq!(
+            BlockEngineStage::select_active_block_engine(&block_engines, now),
+            Some(2)
+        );
+        let block_engines = [block_engine_state(false, 10, now)];
+        assert_eq!(
+            BlockEngineStage::select_active_block_engine(&block_engines, now),
+            None
+        );
+    }
+
+    #[test]
+    fn test_update_bundle_forwarding() {
+        let now = Instant::now();
+        let mut block_engines = [
+            block_engine_state(true, 10, now),
+            block_engine_state(true, 10, now),
+        ];
+        block_engines[1].block_builder_fee_info = Some(BlockBuilderFeeInfoResponse::default());
+
+        // bundles are only forwarded from the active block engine
+        BlockEngineStage::update_bundle_forwarding(&mut block_engines, Some(1));
+        assert!(!block_engines[0].health.active);
+        assert!(!block_engines[0].forward_bundles.load(Ordering::Relaxed));
+        assert!(block_engines[1].health.active);
+        assert!(block_engines[1].forward_bundles.load(Ordering::Relaxed));
+
+        // or not at all until the active block engine's fee info is known
+        BlockEngineStage::update_bundle_forwarding(&mut block_engines, Some(0));
+        assert!(block_engines[0].health.active);
+        assert!(!block_engines[0].forward_bundles.load(Ordering::Relaxed));
+        assert!(!block_engines[1].forward_bundles.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_handle_block_engine_maybe_bundles_from_standby() {
+        let (bundle_sender, bundle_receiver) = unbounded();
+        let deduper = Arc::new(Mutex::new(BlockEngineDeduper::default()));
+        let bundles_response = || {
+            Ok(Some(block_engine::SubscribeBundlesResponse {
+                bundles: vec![BundleUuid {
+                    bundle: Some(Bundle::default()),
+                    uuid: "bundle".to_string(),
+                }],
+            }))
+        };
+
+        let mut stats = BlockEngineStageStats::default();
+        BlockEngineStage::handle_block_engine_maybe_bundles(
+            bundles_response(),
+            &bundle_sender,
+            "http://standby",
+            &deduper,
+            &AtomicBool::new(false),
+            &mut stats,
+        )
+        .unwrap();
+        assert_eq!(stats.num_bundles, 1);
+        assert_eq!(stats.num_standby_bundles, 1);
+        assert!(bundle_receiver.is_empty());
+
+        // the standby block engine's copy doesn't make the active block engine's a duplicate
+        let mut stats = BlockEngineStageStats::default();
+        BlockEngineStage::handle_block_engine_maybe_bundles(
+            bundles_response(),
+            &bundle_sender,
+            "http://active",
+            &deduper,
+            &AtomicBool::new(true),
+            &mut stats,
+        )
+        .unwrap();
+        assert_eq!(stats.num_standby_bundles, 0);
+        assert_eq!(stats.num_duplicate_bundles, 0);
+        let bundles = bundle_receiver.try_recv().unwrap();
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].source.as_deref(), Some("http://active"));
+    }
+}
@@ -0,0 +1,294 @@
+//! Streams bundle execution results from BundleStage to the Block Engines.
+//!
+//! Each result is pushed over a client-streaming RPC to the Block Engine that sent the bundle
+//! while BlockEngineStage is connected to it. When that connection is down or the Block Engine
+//! doesn't accept the stream, results are appended to a local file as JSON lines (if configured)
+//! so they can be inspected or replayed later.
+use {
+    crate::{
+        bundle_stage::bundle_result::{BundleOutcome, BundleResult, BundleResultReceiver},
+        proxy::auth::AuthInterceptor,
+    },
+    futures::stream,
+    jito_protos::proto::bundle_feedback::{self, bundle_feedback_client::BundleFeedbackClient},
+    std::{
+        collections::HashMap,
+        fs::{File, OpenOptions},
+        io::{BufWriter, Write},
+        path::PathBuf,
+    },
+    tokio::sync::mpsc::{self, error::TrySendError},
+    tonic::{codegen::InterceptedService, transport::Channel},
+};
+
+/// Maximum number of results queued on an open stream before falling back to the file.
+const STREAM_BUFFER_CAPACITY: usize = 1_000;
+
+#[derive(Default)]
+struct BundleResultForwarderStats {
+    num_streamed: u64,
+    num_written_to_fallback: u64,
+    num_discarded: u64,
+}
+
+pub struct BundleResultForwarder {
+    receiver: BundleResultReceiver,
+    // open streams keyed by block engine url
+    stream_senders: HashMap<String, mpsc::Sender<bundle_feedback::BundleResult>>,
+    fallback_path: Option<PathBuf>,
+    fallback_writer: Option<BufWriter<File>>,
+    stats: BundleResultForwarderStats,
+}
+
+impl BundleResultForwarder {
+    pub fn new(receiver: BundleResultReceiver, fallback_path: Option<PathBuf>) -> Self {
+        Self {
+            receiver,
+            stream_senders: HashMap::default(),
+            fallback_path,
+            fallback_writer: None,
+            stats: BundleResultForwarderStats::default(),
+        }
+    }
+
+    pub async fn recv(&mut self) -> Option<BundleResult> {
+        self.receiver.recv().await
+    }
+
+    /// Opens a new client-streaming call to the Block Engine at `block_engine_url`. Any previously
+    /// open stream to it is closed.
+    pub fn start_stream(
+        &mut self,
+        block_engine_url: String,
+        mut client: BundleFeedbackClient<InterceptedService<Channel, AuthInterceptor>>,
+    ) {
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER_CAPACITY);
+        let results_stream = stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|result| (result, receiver))
+        });
+        tokio::spawn(async move {
+            match client.submit_bundle_results(results_stream).await {
+                Ok(response) => info!(
+                    "bundle result stream closed, num_results_received: {}",
+                    response.into_inner().num_results_received
+                ),
+                Err(e) => warn!("bundle result stream error: {e}"),
+            }
+        });
+        self.stream_senders.insert(block_engine_url, sender);
+    }
+
+    /// Closes the stream to the Block Engine at `block_engine_url`, if any. Dropping the sender
+    /// ends the RPC.
+    pub fn stop_stream(&mut self, block_engine_url: &str) {
+        self.stream_senders.remove(block_engine_url);
+    }
+
+    /// Closes the streams to all Block Engines
+    pub fn stop_streams(&mut self) {
+        self.stream_senders.clear();
+    }
+
+    /// Sends the result to the Block Engine the bundle was received from, falling back to the
+    /// local file if there's no open stream to it.
+    pub fn forward(&mut self, result: BundleResult) {
+        if let Some(source) = result.source.as_ref() {
+            if let Some(sender) = self.stream_senders.get(source) {
+                match sender.try_send(bundle_result_to_proto(&result)) {
+                    Ok(()) => {
+                        self.stats.num_streamed += 1;
+                        return;
+                    }
+                    Err(TrySendError::Full(_)) => {}
+                    Err(TrySendError::Closed(_)) => {
+                        self.stream_senders.remove(source);
+                    }
+                }
+            }
+        }
+        self.write_to_fallback(&result);
+    }
+
+    /// Drains all buffered results into the fallback file. Used while disconnected so the buffer
+    /// doesn't fill up and drop results.
+    pub fn drain_to_fallback(&mut self) {
+        while let Ok(result) = self.receiver.try_recv() {
+            self.write_to_fallback(&result);
+        }
+        self.flush();
+    }
+
+    pub fn flush(&mut self) {
+        if let Some(writer) = self.fallback_writer.as_mut() {
+            if let Err(e) = writer.flush() {
+                error!("error flushing bundle result fallback file: {e}");
+            }
+        }
+    }
+
+    pub fn report(&mut self) {
+        let stats = std::mem::take(&mut self.stats);
+        datapoint_info!(
+            "block_engine_stage-bundle_results",
+            ("num_streamed", stats.num_streamed, i64),
+            (
+                "num_written_to_fallback",
+                stats.num_written_to_fallback,
+                i64
+            ),
+            ("num_discarded", stats.num_discarded, i64),
+        );
+    }
+
+    fn write_to_fallback(&mut self, result: &BundleResult) {
+        if self.fallback_writer.is_none() {
+            let Some(path) = self.fallback_path.as_ref() else {
+                self.stats.num_discarded += 1;
+                return;
+            };
+            match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => self.fallback_writer = Some(BufWriter::new(file)),
+                Err(e) => {
+                    error!(
+                        "error opening bundle result fallback file {}: {e}, disabling it",
+                        path.display()
+                    );
+                    self.fallback_path = None;
+                    self.stats.num_discarded += 1;
+                    return;
+                }
+            }
+        }
+
+        let writer = self.fallback_writer.as_mut().unwrap();
+        let written = serde_json::to_writer(&mut *writer, result)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writer.write_all(b"\n"));
+        match written {
+            Ok(()) => self.stats.num_written_to_fallback += 1,
+            Err(e) => {
+                error!("error writing to bundle result fallback file: {e}");
+                self.stats.num_discarded += 1;
+            }
+        }
+    }
+}
+
+fn bundle_result_to_proto(result: &BundleResult) -> bundle_feedback::BundleResult {
+    let mut proto = bundle_feedback::BundleResult {
+        bundle_id: result.bundle_id.clone(),
+        uuid: result.uuid.clone(),
+        slot: result.slot,
+        outcome: 0,
+        compute_units_consumed: result.compute_units_consumed,
+        tip_lamports: result.tip_lamports,
+        error: result.error.clone().unwrap_or_default(),
+        timestamp_ms: result.timestamp_ms,
+    };
+    proto.set_outcome(match result.outcome {
+        BundleOutcome::Landed => bundle_feedback::BundleOutcome::Landed,
+        BundleOutcome::TransactionFailure => bundle_feedback::BundleOutcome::TransactionFailure,
+        BundleOutcome::ExecutionTimeout => bundle_feedback::BundleOutcome::ExecutionTimeout,
+        BundleOutcome::TipFailure => bundle_feedback::BundleOutcome::TipFailure,
+        BundleOutcome::ExceedsCostModel => bundle_feedback::BundleOutcome::ExceedsCostModel,
+        BundleOutcome::LockFailure => bundle_feedback::BundleOutcome::LockFailure,
+        BundleOutcome::SlotExpired => bundle_feedback::BundleOutcome::SlotExpired,
+        BundleOutcome::InternalError => bundle_feedback::BundleOutcome::InternalError,
+    });
+    proto
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::bundle_stage::bundle_result::bundle_result_channel,
+        std::io::{BufRead, BufReader},
+        tempfile::TempDir,
+    };
+
+    fn test_result(bundle_id: &str, outcome: BundleOutcome) -> BundleResult {
+        BundleResult {
+            bundle_id: bundle_id.to_string(),
+            uuid: "uuid".to_string(),
+            source: Some("http://block-engine".to_string()),
+            slot: 100,
+            outcome,
+            compute_units_consumed: 1_000,
+            tip_lamports: 10_000,
+            error: None,
+            timestamp_ms: 1,
+        }
+    }
+
+    #[test]
+    fn test_bundle_result_to_proto() {
+        let proto = bundle_result_to_proto(&test_result("a", BundleOutcome::ExceedsCostModel));
+        assert_eq!(proto.bundle_id, "a");
+        assert_eq!(proto.slot, 100);
+        assert_eq!(
+            proto.outcome(),
+            bundle_feedback::BundleOutcome::ExceedsCostModel
+        );
+        assert_eq!(proto.tip_lamports, 10_000);
+        assert!(proto.error.is_empty());
+    }
+
+    #[test]
+    fn test_drain_to_fallback() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bundle_results.jsonl");
+
+        let (sender, receiver) = bundle_result_channel(10);
+        let mut forwarder = BundleResultForwarder::new(receiver, Some(path.clone()));
+
+        let results = vec![
+            test_result("a", BundleOutcome::Landed),
+            test_result("b", BundleOutcome::TransactionFailure),
+        ];
+        for result in &results {
+            sender.send(result.clone());
+        }
+        forwarder.drain_to_fallback();
+
+        let written: Vec<BundleResult> = BufReader::new(File::open(path).unwrap())
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect();
+        assert_eq!(written, results);
+    }
+
+    #[test]
+    fn test_forward_without_stream_or_fallback_discards() {
+        let (_sender, receiver) = bundle_result_channel(10);
+        let mut forwarder = BundleResultForwarder::new(receiver, None);
+        forwarder.forward(test_result("a", BundleOutcome::Landed));
+        assert_eq!(forwarder.stats.num_discarded, 1);
+        assert_eq!(forwarder.stats.num_streamed, 0);
+    }
+
+    #[test]
+    fn test_forward_to_source_stream() {
+        let (_sender, receiver) = bundle_result_channel(10);
+        let mut forwarder = BundleResultForwarder::new(receiver, None);
+        let (stream_sender, mut stream_receiver) = mpsc::channel(10);
+        forwarder
+            .stream_senders
+            .insert("http://block-engine".to_string(), stream_sender);
+
+        forwarder.forward(test_result("a", BundleOutcome::Landed));
+        assert_eq!(stream_receiver.try_recv().unwrap().bundle_id, "a");
+
+        // results for bundles from other block engines aren't streamed to this one
+        let mut result = test_result("b", BundleOutcome::Landed);
+        result.source = Some("http://other-block-engine".to_string());
+        forwarder.forward(result);
+        assert!(stream_receiver.try_recv().is_err());
+        assert_eq!(forwarder.stats.num_streamed, 1);
+        assert_eq!(forwarder.stats.num_discarded, 1);
+
+        forwarder.stop_stream("http://block-engine");
+        forwarder.forward(test_result("c", BundleOutcome::Landed));
+        assert_eq!(forwarder.stats.num_discarded, 2);
+    }
+}
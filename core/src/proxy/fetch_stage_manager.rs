@@ -1,6 +1,9 @@
 use {
-    crate::proxy::{HeartbeatEvent, ProxyError},
-    crossbeam_channel::{select, tick, Receiver, Sender},
+    crate::proxy::{
+        packet_source_accounting::PacketSourceAccounting, packet_trust::PacketSource,
+        HeartbeatEvent, ProxyError,
+    },
+    crossbeam_channel::{never, select, tick, Receiver, Sender},
     solana_client::connection_cache::Protocol,
     solana_gossip::{cluster_info::ClusterInfo, contact_info},
     solana_perf::packet::PacketBatch,
@@ -31,17 +34,24 @@ impl FetchStageManager {
         cluster_info: Arc<ClusterInfo>,
         // Channel that heartbeats are received from. Entirely responsible for triggering switch-overs.
         heartbeat_rx: Receiver<HeartbeatEvent>,
+        // Heartbeats of a source that heartbeat_rx takes precedence over, received and dropped so
+        // the two don't switch the advertised TPU addresses back and forth.
+        superseded_heartbeat_rx: Option<Receiver<HeartbeatEvent>>,
         // Channel that packets from FetchStage are intercepted from.
         packet_intercept_rx: Receiver<PacketBatch>,
         // Intercepted packets get piped through here.
         packet_tx: Sender<PacketBatch>,
+        // Counts and rate limits the intercepted packets, and reports the counts of every source.
+        packet_source_accounting: Arc<PacketSourceAccounting>,
         exit: Arc<AtomicBool>,
     ) -> Self {
         let t_hdl = Self::start(
             cluster_info,
             heartbeat_rx,
+            superseded_heartbeat_rx.unwrap_or_else(never),
             packet_intercept_rx,
             packet_tx,
+            packet_source_accounting,
             exit,
         );
 
@@ -63,8 +73,10 @@ impl FetchStageManager {
     fn start(
         cluster_info: Arc<ClusterInfo>,
         heartbeat_rx: Receiver<HeartbeatEvent>,
+        superseded_heartbeat_rx: Receiver<HeartbeatEvent>,
         packet_intercept_rx: Receiver<PacketBatch>,
         packet_tx: Sender<PacketBatch>,
+        packet_source_accounting: Arc<PacketSourceAccounting>,
         exit: Arc<AtomicBool>,
     ) -> JoinHandle<()> {
         Builder::new().name("fetch-stage-manager".into()).spawn(move || {
@@ -84,8 +96,12 @@ impl FetchStageManager {
                 select! {
                     recv(packet_intercept_rx) -> pkt => {
                         match pkt {
-                            Ok(pkt) => {
+                            Ok(mut pkt) => {
                                 if fetch_connected {
+                                    packet_source_accounting.record(PacketSource::Tpu, &mut pkt);
+                                    if pkt.is_empty() {
+                                        continue;
+                                    }
                                     if packet_tx.send(pkt).is_err() {
                                         error!("{:?}", ProxyError::PacketForwardError);
                                         return;
@@ -141,12 +157,19 @@ impl FetchStageManager {
                             }
                         }
                     }
+                    recv(superseded_heartbeat_rx) -> tpu_info => {
+                        if tpu_info.is_err() {
+                            warn!("superseded heartbeat receiver disconnected, shutting down");
+                            return;
+                        }
+                    }
                     recv(metrics_tick) -> _ => {
                         datapoint_info!(
                             "relayer-heartbeat",
                             ("fetch_stage_packets_forwarded", packets_forwarded, i64),
                             ("heartbeats_received", heartbeats_received, i64),
                         );
+                        packet_source_accounting.report();
 
                     }
                 }
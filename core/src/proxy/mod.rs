@@ -11,9 +11,15 @@
 //!           to accept bundles while maintaining some level of privacy for in-flight transactions.
 
 mod auth;
+mod block_engine_deduper;
+pub mod block_engine_region_selector;
 pub mod block_engine_stage;
+mod bundle_result_forwarder;
 pub mod fetch_stage_manager;
+pub mod packet_source_accounting;
+pub mod packet_trust;
 pub mod relayer_stage;
+pub mod vortexor_receiver;
 
 use {
     std::{
@@ -0,0 +1,362 @@
+//! Per-source accounting and rate limiting of the packets forwarded to BankingStage.
+//!
+//! Packets arrive from the Relayer, the Block Engines, vortexors and the validator's own TPU fetch
+//! stage.
+//! For every source the packets, unique signatures and duplicate signatures are counted, and a
+//! token bucket caps the packets per second forwarded from it so a misbehaving source can't flood
+//! BankingStage. Packets over the limit are dropped.
+use {
+    crate::proxy::{
+        block_engine_deduper::{check_and_insert, first_signature},
+        packet_trust::PacketSource,
+    },
+    lru::LruCache,
+    solana_perf::packet::PacketBatch,
+    solana_sdk::{saturating_add_assign, signature::Signature},
+    std::{
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+};
+
+const DUPLICATE_WINDOW: Duration = Duration::from_secs(1);
+const MAX_CACHED_SIGNATURES: usize = 100_000;
+
+/// Packets per second forwarded from each source, 0 is unlimited
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PacketRateLimits {
+    pub relayer: u64,
+    pub block_engine: u64,
+    pub tpu: u64,
+    pub vortexor: u64,
+}
+
+impl PacketRateLimits {
+    pub fn get(&self, source: PacketSource) -> u64 {
+        match source {
+            PacketSource::Relayer => self.relayer,
+            PacketSource::BlockEngine => self.block_engine,
+            PacketSource::Tpu => self.tpu,
+            PacketSource::Vortexor => self.vortexor,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PacketSourceStats {
+    pub num_packets: u64,
+    pub num_unique_signatures: u64,
+    pub num_duplicate_signatures: u64,
+    pub num_rate_limited_packets: u64,
+}
+
+impl PacketSourceStats {
+    fn add(&mut self, other: &PacketSourceStats) {
+        saturating_add_assign!(self.num_packets, other.num_packets);
+        saturating_add_assign!(self.num_unique_signatures, other.num_unique_signatures);
+        saturating_add_assign!(
+            self.num_duplicate_signatures,
+            other.num_duplicate_signatures
+        );
+        saturating_add_assign!(
+            self.num_rate_limited_packets,
+            other.num_rate_limited_packets
+        );
+    }
+}
+
+/// Holds up to one second of packets, refilled at `packets_per_second`
+struct TokenBucket {
+    packets_per_second: u64,
+    tokens: u64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(packets_per_second: u64, now: Instant) -> Self {
+        Self {
+            packets_per_second,
+            tokens: packets_per_second,
+            last_refill: now,
+        }
+    }
+
+    /// Takes up to `num_packets` tokens and returns the number taken
+    fn take(&mut self, num_packets: u64, now: Instant) -> u64 {
+        let elapsed_us = now.saturating_duration_since(self.last_refill).as_micros() as u64;
+        let refill = elapsed_us.saturating_mul(self.packets_per_second) / 1_000_000;
+        if refill > 0 || self.tokens == self.packets_per_second {
+            self.tokens = self
+                .tokens
+                .saturating_add(refill)
+                .min(self.packets_per_second);
+            self.last_refill = now;
+        }
+        let taken = num_packets.min(self.tokens);
+        self.tokens -= taken;
+        taken
+    }
+}
+
+struct SourceAccount {
+    bucket: Option<TokenBucket>,
+    signatures: LruCache<Signature, Instant>,
+    // since the last report
+    interval_stats: PacketSourceStats,
+    // since startup
+    total_stats: PacketSourceStats,
+}
+
+impl SourceAccount {
+    fn new(packets_per_second: u64) -> Self {
+        Self {
+            bucket: (packets_per_second > 0)
+                .then(|| TokenBucket::new(packets_per_second, Instant::now())),
+            signatures: LruCache::new(MAX_CACHED_SIGNATURES),
+            interval_stats: PacketSourceStats::default(),
+            total_stats: PacketSourceStats::default(),
+        }
+    }
+}
+
+pub struct PacketSourceAccounting {
+    rate_limits: PacketRateLimits,
+    relayer: Mutex<SourceAccount>,
+    block_engine: Mutex<SourceAccount>,
+    tpu: Mutex<SourceAccount>,
+    vortexor: Mutex<SourceAccount>,
+}
+
+impl Default for PacketSourceAccounting {
+    fn default() -> Self {
+        Self::new(PacketRateLimits::default())
+    }
+}
+
+impl PacketSourceAccounting {
+    pub fn new(rate_limits: PacketRateLimits) -> Self {
+        Self {
+            rate_limits,
+            relayer: Mutex::new(SourceAccount::new(rate_limits.relayer)),
+            block_engine: Mutex::new(SourceAccount::new(rate_limits.block_engine)),
+            tpu: Mutex::new(SourceAccount::new(rate_limits.tpu)),
+            vortexor: Mutex::new(SourceAccount::new(rate_limits.vortexor)),
+        }
+    }
+
+    pub fn rate_limits(&self) -> &PacketRateLimits {
+        &self.rate_limits
+    }
+
+    /// Packet counts of `source` since startup
+    pub fn stats(&self, source: PacketSource) -> PacketSourceStats {
+        self.account(source).lock().unwrap().total_stats
+    }
+
+    /// Packet counts of `source` since the last call
+    pub(crate) fn take_interval_stats(&self, source: PacketSource) -> PacketSourceStats {
+        std::mem::take(&mut self.account(source).lock().unwrap().interval_stats)
+    }
+
+    /// Reports the packet counts of every source since the last report. Called from
+    /// FetchStageManager, which runs whether or not a relayer or block engine is connected.
+    pub(crate) fn report(&self) {
+        let relayer = self.take_interval_stats(PacketSource::Relayer);
+        let block_engine = self.take_interval_stats(PacketSource::BlockEngine);
+        let tpu = self.take_interval_stats(PacketSource::Tpu);
+        let vortexor = self.take_interval_stats(PacketSource::Vortexor);
+        datapoint_info!(
+            "packet_source_accounting-stats",
+            ("relayer_packets", relayer.num_packets, i64),
+            (
+                "relayer_unique_signatures",
+                relayer.num_unique_signatures,
+                i64
+            ),
+            (
+                "relayer_duplicate_signatures",
+                relayer.num_duplicate_signatures,
+                i64
+            ),
+            (
+                "relayer_rate_limited_packets",
+                relayer.num_rate_limited_packets,
+                i64
+            ),
+            ("block_engine_packets", block_engine.num_packets, i64),
+            (
+                "block_engine_unique_signatures",
+                block_engine.num_unique_signatures,
+                i64
+            ),
+            (
+                "block_engine_duplicate_signatures",
+                block_engine.num_duplicate_signatures,
+                i64
+            ),
+            (
+                "block_engine_rate_limited_packets",
+                block_engine.num_rate_limited_packets,
+                i64
+            ),
+            ("tpu_packets", tpu.num_packets, i64),
+            ("tpu_unique_signatures", tpu.num_unique_signatures, i64),
+            (
+                "tpu_duplicate_signatures",
+                tpu.num_duplicate_signatures,
+                i64
+            ),
+            (
+                "tpu_rate_limited_packets",
+                tpu.num_rate_limited_packets,
+                i64
+            ),
+            ("vortexor_packets", vortexor.num_packets, i64),
+            (
+                "vortexor_unique_signatures",
+                vortexor.num_unique_signatures,
+                i64
+            ),
+            (
+                "vortexor_duplicate_signatures",
+                vortexor.num_duplicate_signatures,
+                i64
+            ),
+            (
+                "vortexor_rate_limited_packets",
+                vortexor.num_rate_limited_packets,
+                i64
+            ),
+        );
+    }
+
+    /// Counts the packets received from `source` and drops those over its rate limit
+    pub(crate) fn record(&self, source: PacketSource, packet_batch: &mut PacketBatch) {
+        let now = Instant::now();
+        let mut account = self.account(source).lock().unwrap();
+        let account = &mut *account;
+
+        let mut stats = PacketSourceStats {
+            num_packets: packet_batch.len() as u64,
+            ..PacketSourceStats::default()
+        };
+        for signature in packet_batch.iter().filter_map(first_signature) {
+            if check_and_insert(&mut account.signatures, signature, now, DUPLICATE_WINDOW) {
+                stats.num_unique_signatures += 1;
+            } else {
+                stats.num_duplicate_signatures += 1;
+            }
+        }
+
+        if let Some(bucket) = &mut account.bucket {
+            let num_allowed = bucket.take(stats.num_packets, now);
+            stats.num_rate_limited_packets = stats.num_packets - num_allowed;
+            packet_batch.truncate(num_allowed as usize);
+        }
+
+        account.interval_stats.add(&stats);
+        account.total_stats.add(&stats);
+    }
+
+    fn account(&self, source: PacketSource) -> &Mutex<SourceAccount> {
+        match source {
+            PacketSource::Relayer => &self.relayer,
+            PacketSource::BlockEngine => &self.block_engine,
+            PacketSource::Tpu => &self.tpu,
+            PacketSource::Vortexor => &self.vortexor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_perf::packet::Packet,
+        solana_sdk::{
+            hash::Hash,
+            signature::{Keypair, Signer},
+            system_transaction::transfer,
+        },
+    };
+
+    fn make_packet(keypair: &Keypair, lamports: u64) -> Packet {
+        Packet::from_data(
+            None,
+            transfer(keypair, &keypair.pubkey(), lamports, Hash::default()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(100, now);
+        assert_eq!(bucket.take(60, now), 60);
+        assert_eq!(bucket.take(60, now), 40);
+        assert_eq!(bucket.take(60, now), 0);
+
+        // refilled at the configured rate, up to one second of packets
+        assert_eq!(bucket.take(60, now + Duration::from_millis(100)), 10);
+        assert_eq!(bucket.take(200, now + Duration::from_secs(10)), 100);
+    }
+
+    #[test]
+    fn test_record_counts_duplicates() {
+        let accounting = PacketSourceAccounting::default();
+        let keypair = Keypair::new();
+        let packet = make_packet(&keypair, 1);
+        let mut packet_batch =
+            PacketBatch::new(vec![packet.clone(), make_packet(&keypair, 2), packet]);
+
+        accounting.record(PacketSource::Relayer, &mut packet_batch);
+        assert_eq!(packet_batch.len(), 3);
+        let expected_stats = PacketSourceStats {
+            num_packets: 3,
+            num_unique_signatures: 2,
+            num_duplicate_signatures: 1,
+            num_rate_limited_packets: 0,
+        };
+        assert_eq!(accounting.stats(PacketSource::Relayer), expected_stats);
+        assert_eq!(
+            accounting.stats(PacketSource::BlockEngine),
+            PacketSourceStats::default()
+        );
+
+        assert_eq!(
+            accounting.take_interval_stats(PacketSource::Relayer),
+            expected_stats
+        );
+        assert_eq!(
+            accounting.take_interval_stats(PacketSource::Relayer),
+            PacketSourceStats::default()
+        );
+        assert_eq!(accounting.stats(PacketSource::Relayer), expected_stats);
+    }
+
+    #[test]
+    fn test_record_rate_limits_source() {
+        let accounting = PacketSourceAccounting::new(PacketRateLimits {
+            relayer: 2,
+            ..PacketRateLimits::default()
+        });
+        let keypair = Keypair::new();
+        let make_packet_batch =
+            || PacketBatch::new((0..3).map(|i| make_packet(&keypair, i)).collect());
+
+        let mut packet_batch = make_packet_batch();
+        accounting.record(PacketSource::Relayer, &mut packet_batch);
+        assert_eq!(packet_batch.len(), 2);
+        assert_eq!(
+            accounting
+                .stats(PacketSource::Relayer)
+                .num_rate_limited_packets,
+            1
+        );
+
+        // other sources aren't limited
+        let mut packet_batch = make_packet_batch();
+        accounting.record(PacketSource::Tpu, &mut packet_batch);
+        assert_eq!(packet_batch.len(), 3);
+    }
+}
@@ -0,0 +1,234 @@
+//! Sampled signature verification of packets from a trusted Relayer or Block Engine.
+//!
+//! Packets from a source configured with `trust_packets` skip sigverify. To catch a source that
+//! stops verifying packets, a sample of its packets is still verified. If a sampled packet fails,
+//! the source is no longer trusted: an alert datapoint is emitted and all of its packets go through
+//! sigverify until an operator resets the trust over admin rpc.
+use {
+    rand::{thread_rng, Rng},
+    solana_perf::{packet::PacketBatch, sigverify::verify_packet},
+    std::sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Sample rates are given in basis points of the packets received
+pub const MAX_SAMPLE_BPS: u64 = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PacketSource {
+    Relayer,
+    BlockEngine,
+    /// The validator's own TPU fetch stage, its packets are never trusted
+    Tpu,
+    /// Authenticated vortexor peers, their packets are verified by the vortexor and never sampled
+    Vortexor,
+}
+
+impl PacketSource {
+    pub const ALL: [PacketSource; 4] = [
+        PacketSource::Relayer,
+        PacketSource::BlockEngine,
+        PacketSource::Tpu,
+        PacketSource::Vortexor,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PacketSource::Relayer => "relayer",
+            PacketSource::BlockEngine => "block_engine",
+            PacketSource::Tpu => "tpu",
+            PacketSource::Vortexor => "vortexor",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PacketTrust {
+    /// Basis points of trusted packets whose signatures are verified anyway, 0 disables sampling
+    sample_bps: u64,
+    relayer_revoked: AtomicBool,
+    block_engine_revoked: AtomicBool,
+}
+
+/// Outcome of verifying a sample of a packet batch
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct SampleVerification {
+    pub(crate) num_sampled: u64,
+    pub(crate) num_failed: u64,
+}
+
+impl PacketTrust {
+    pub fn new(sample_bps: u64) -> Self {
+        Self {
+            sample_bps: sample_bps.min(MAX_SAMPLE_BPS),
+            ..Self::default()
+        }
+    }
+
+    pub fn sample_bps(&self) -> u64 {
+        self.sample_bps
+    }
+
+    /// Returns true if packets from `source` can skip sigverify, provided it's configured to
+    /// be trusted
+    pub fn is_trusted(&self, source: PacketSource) -> bool {
+        self.revoked(source)
+            .is_some_and(|revoked| !revoked.load(Ordering::Relaxed))
+    }
+
+    /// Stops trusting packets from `source`. Returns false if it was already revoked or can't be
+    /// trusted.
+    pub fn revoke(&self, source: PacketSource) -> bool {
+        self.revoked(source)
+            .is_some_and(|revoked| !revoked.swap(true, Ordering::Relaxed))
+    }
+
+    /// Trusts `source` again after it was revoked by a failed sample
+    pub fn reset(&self, source: PacketSource) {
+        if self
+            .revoked(source)
+            .is_some_and(|revoked| revoked.swap(false, Ordering::Relaxed))
+        {
+            warn!("trust in {} packets reset", source.as_str());
+        }
+    }
+
+    /// Verifies the signatures of a sample of a batch received from a trusted `source` at `url`,
+    /// marking the packets that fail as discarded. Trust in the source is revoked if any fail.
+    pub(crate) fn verify_sample(
+        &self,
+        source: PacketSource,
+        url: &str,
+        packet_batch: &mut PacketBatch,
+    ) -> SampleVerification {
+        let mut verification = SampleVerification::default();
+        if self.sample_bps == 0 || !self.is_trusted(source) {
+            return verification;
+        }
+
+        let mut rng = thread_rng();
+        for packet in packet_batch
+            .iter_mut()
+            .filter(|_| rng.gen_range(0..MAX_SAMPLE_BPS) < self.sample_bps)
+        {
+            verification.num_sampled += 1;
+            if !verify_packet(packet, false) {
+                packet.meta_mut().set_discard(true);
+                verification.num_failed += 1;
+            }
+        }
+
+        if verification.num_failed > 0 && self.revoke(source) {
+            error!(
+                "{} {url} sent {} packets that failed sigverify, no longer trusting its packets",
+                source.as_str(),
+                verification.num_failed
+            );
+            datapoint_error!(
+                "packet_trust-revoked",
+                ("source", source.as_str(), String),
+                ("url", url, String),
+                ("num_sampled", verification.num_sampled, i64),
+                ("num_failed", verification.num_failed, i64),
+            );
+        }
+        verification
+    }
+
+    fn revoked(&self, source: PacketSource) -> Option<&AtomicBool> {
+        match source {
+            PacketSource::Relayer => Some(&self.relayer_revoked),
+            PacketSource::BlockEngine => Some(&self.block_engine_revoked),
+            PacketSource::Tpu | PacketSource::Vortexor => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_perf::packet::Packet,
+        solana_sdk::{
+            hash::Hash,
+            signature::{Keypair, Signature, Signer},
+            system_transaction::transfer,
+        },
+    };
+
+    fn make_packet_batch(num_packets: usize, valid: bool) -> PacketBatch {
+        PacketBatch::new(
+            (0..num_packets)
+                .map(|_| {
+                    let keypair = Keypair::new();
+                    let mut tx = transfer(&keypair, &keypair.pubkey(), 1, Hash::default());
+                    if !valid {
+                        tx.signatures[0] = Signature::new_unique();
+                    }
+                    Packet::from_data(None, &tx).unwrap()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_sampling_disabled() {
+        let packet_trust = PacketTrust::new(0);
+        let mut packet_batch = make_packet_batch(10, false);
+        assert_eq!(
+            packet_trust.verify_sample(PacketSource::Relayer, "relayer", &mut packet_batch),
+            SampleVerification::default()
+        );
+        assert!(packet_trust.is_trusted(PacketSource::Relayer));
+        assert!(packet_batch.iter().all(|p| !p.meta().discard()));
+        assert!(!packet_trust.is_trusted(PacketSource::Tpu));
+    }
+
+    #[test]
+    fn test_valid_packets_keep_trust() {
+        let packet_trust = PacketTrust::new(MAX_SAMPLE_BPS);
+        let mut packet_batch = make_packet_batch(10, true);
+        assert_eq!(
+            packet_trust.verify_sample(PacketSource::Relayer, "relayer", &mut packet_batch),
+            SampleVerification {
+                num_sampled: 10,
+                num_failed: 0
+            }
+        );
+        assert!(packet_trust.is_trusted(PacketSource::Relayer));
+    }
+
+    #[test]
+    fn test_failed_sample_revokes_trust_until_reset() {
+        let packet_trust = PacketTrust::new(MAX_SAMPLE_BPS);
+        let mut packet_batch = make_packet_batch(3, false);
+        assert_eq!(
+            packet_trust.verify_sample(
+                PacketSource::BlockEngine,
+                "block-engine",
+                &mut packet_batch
+            ),
+            SampleVerification {
+                num_sampled: 3,
+                num_failed: 3
+            }
+        );
+        assert!(packet_batch.iter().all(|p| p.meta().discard()));
+        assert!(!packet_trust.is_trusted(PacketSource::BlockEngine));
+        assert!(packet_trust.is_trusted(PacketSource::Relayer));
+
+        // nothing is sampled from a source that isn't trusted, its packets go through sigverify
+        let mut packet_batch = make_packet_batch(3, true);
+        assert_eq!(
+            packet_trust.verify_sample(
+                PacketSource::BlockEngine,
+                "block-engine",
+                &mut packet_batch
+            ),
+            SampleVerification::default()
+        );
+        assert!(!packet_trust.is_trusted(PacketSource::BlockEngine));
+
+        packet_trust.reset(PacketSource::BlockEngine);
+        assert!(packet_trust.is_trusted(PacketSource::BlockEngine));
+    }
+}
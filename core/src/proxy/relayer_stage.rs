@@ -14,6 +14,8 @@ use {
         proto_packet_to_packet,
         proxy::{
             auth::{generate_auth_tokens, maybe_refresh_auth_tokens, AuthInterceptor},
+            packet_source_accounting::PacketSourceAccounting,
+            packet_trust::{PacketSource, PacketTrust},
             HeartbeatEvent, ProxyError,
         },
     },
@@ -57,6 +59,8 @@ struct RelayerStageStats {
     num_empty_messages: u64,
     num_packets: u64,
     num_heartbeats: u64,
+    num_sampled_packets: u64,
+    num_failed_sampled_packets: u64,
 }
 
 impl RelayerStageStats {
@@ -66,6 +70,12 @@ impl RelayerStageStats {
             ("num_empty_messages", self.num_empty_messages, i64),
             ("num_packets", self.num_packets, i64),
             ("num_heartbeats", self.num_heartbeats, i64),
+            ("num_sampled_packets", self.num_sampled_packets, i64),
+            (
+                "num_failed_sampled_packets",
+                self.num_failed_sampled_packets,
+                i64
+            ),
         );
     }
 }
@@ -100,6 +110,10 @@ impl RelayerStage {
         packet_tx: Sender<PacketBatch>,
         // Channel that trusted streamed packets are piped through.
         banking_packet_sender: BankingPacketSender,
+        // Revoked when a sample of the trusted packets fails sigverify.
+        packet_trust: Arc<PacketTrust>,
+        // Counts and rate limits the packets received from each source.
+        packet_source_accounting: Arc<PacketSourceAccounting>,
         exit: Arc<AtomicBool>,
     ) -> Self {
         let thread = Builder::new()
@@ -116,6 +130,8 @@ impl RelayerStage {
                     heartbeat_tx,
                     packet_tx,
                     banking_packet_sender,
+                    packet_trust,
+                    packet_source_accounting,
                     exit,
                 ));
             })
@@ -140,6 +156,8 @@ impl RelayerStage {
         heartbeat_tx: Sender<HeartbeatEvent>,
         packet_tx: Sender<PacketBatch>,
         banking_packet_sender: BankingPacketSender,
+        packet_trust: Arc<PacketTrust>,
+        packet_source_accounting: Arc<PacketSourceAccounting>,
         exit: Arc<AtomicBool>,
     ) {
         const CONNECTION_TIMEOUT: Duration = Duration::from_secs(CONNECTION_TIMEOUT_S);
@@ -165,6 +183,8 @@ impl RelayerStage {
                 &heartbeat_tx,
                 &packet_tx,
                 &banking_packet_sender,
+                &packet_trust,
+                &packet_source_accounting,
                 &exit,
                 &CONNECTION_TIMEOUT,
             )
@@ -190,6 +210,7 @@ impl RelayerStage {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn connect_auth_and_stream(
         local_relayer_config: &RelayerConfig,
         global_relayer_config: &Arc<Mutex<RelayerConfig>>,
@@ -197,6 +218,8 @@ impl RelayerStage {
         heartbeat_tx: &Sender<HeartbeatEvent>,
         packet_tx: &Sender<PacketBatch>,
         banking_packet_sender: &BankingPacketSender,
+        packet_trust: &PacketTrust,
+        packet_source_accounting: &PacketSourceAccounting,
         exit: &Arc<AtomicBool>,
         connection_timeout: &Duration,
     ) -> crate::proxy::Result<()> {
@@ -263,6 +286,8 @@ impl RelayerStage {
             heartbeat_tx,
             packet_tx,
             banking_packet_sender,
+            packet_trust,
+            packet_source_accounting,
             local_relayer_config,
             global_relayer_config,
             exit,
@@ -282,6 +307,8 @@ impl RelayerStage {
         heartbeat_tx: &Sender<HeartbeatEvent>,
         packet_tx: &Sender<PacketBatch>,
         banking_packet_sender: &BankingPacketSender,
+        packet_trust: &PacketTrust,
+        packet_source_accounting: &PacketSourceAccounting,
         local_config: &RelayerConfig, // local copy of config with current connections
         global_config: &Arc<Mutex<RelayerConfig>>, // guarded reference for detecting run-time updates
         exit: &Arc<AtomicBool>,
@@ -334,6 +361,8 @@ impl RelayerStage {
             local_config,
             global_config,
             banking_packet_sender,
+            packet_trust,
+            packet_source_accounting,
             exit,
             auth_client,
             access_token,
@@ -354,6 +383,8 @@ impl RelayerStage {
         local_config: &RelayerConfig, // local copy of config with current connections
         global_config: &Arc<Mutex<RelayerConfig>>, // guarded reference for detecting run-time updates
         banking_packet_sender: &BankingPacketSender,
+        packet_trust: &PacketTrust,
+        packet_source_accounting: &PacketSourceAccounting,
         exit: &Arc<AtomicBool>,
         mut auth_client: AuthServiceClient<Channel>,
         access_token: Arc<Mutex<Token>>,
@@ -380,7 +411,7 @@ impl RelayerStage {
             tokio::select! {
                 maybe_msg = packet_stream.message() => {
                     let resp = maybe_msg?.ok_or(ProxyError::GrpcStreamDisconnected)?;
-                    Self::handle_relayer_packets(resp, heartbeat_event, heartbeat_tx, &mut last_heartbeat_ts, packet_tx, local_config.trust_packets, banking_packet_sender, &mut relayer_stats)?;
+                    Self::handle_relayer_packets(resp, heartbeat_event, heartbeat_tx, &mut last_heartbeat_ts, packet_tx, local_config, banking_packet_sender, packet_trust, packet_source_accounting, &mut relayer_stats)?;
                 }
                 _ = heartbeat_check_interval.tick() => {
                     if last_heartbeat_ts.elapsed() > local_config.oldest_allowed_heartbeat {
@@ -438,14 +469,17 @@ impl RelayerStage {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn handle_relayer_packets(
         subscribe_packets_resp: relayer::SubscribePacketsResponse,
         heartbeat_event: HeartbeatEvent,
         heartbeat_tx: &Sender<HeartbeatEvent>,
         last_heartbeat_ts: &mut Instant,
         packet_tx: &Sender<PacketBatch>,
-        trust_packets: bool,
+        local_config: &RelayerConfig,
         banking_packet_sender: &BankingPacketSender,
+        packet_trust: &PacketTrust,
+        packet_source_accounting: &PacketSourceAccounting,
         relayer_stats: &mut RelayerStageStats,
     ) -> crate::proxy::Result<()> {
         match subscribe_packets_resp.msg {
@@ -458,7 +492,7 @@ impl RelayerStage {
                     return Ok(());
                 }
 
-                let packet_batch = PacketBatch::new(
+                let mut packet_batch = PacketBatch::new(
                     proto_batch
                         .packets
                         .into_iter()
@@ -468,6 +502,24 @@ impl RelayerStage {
 
                 saturating_add_assign!(relayer_stats.num_packets, packet_batch.len() as u64);
 
+                packet_source_accounting.record(PacketSource::Relayer, &mut packet_batch);
+                if packet_batch.is_empty() {
+                    return Ok(());
+                }
+
+                let trust_packets = local_config.trust_packets && {
+                    let sample = packet_trust.verify_sample(
+                        PacketSource::Relayer,
+                        &local_config.relayer_url,
+                        &mut packet_batch,
+                    );
+                    saturating_add_assign!(relayer_stats.num_sampled_packets, sample.num_sampled);
+                    saturating_add_assign!(
+                        relayer_stats.num_failed_sampled_packets,
+                        sample.num_failed
+                    );
+                    packet_trust.is_trusted(PacketSource::Relayer)
+                };
                 if trust_packets {
                     banking_packet_sender
                         .send(Arc::new(vec![packet_batch]))
@@ -0,0 +1,920 @@
+//! Receives packets verified by a vortexor, an external service taking over the TPU's ingress,
+//! deduplication and signature verification.
+//!
+//! Vortexors connect over QUIC and are authenticated by their identity: only connections from
+//! the configured peers are accepted. Their packets skip sigverify and are sent straight to
+//! BankingStage. Vortexors also send periodic heartbeats, which are passed on to the
+//! [FetchStageManager](crate::proxy::fetch_stage_manager::FetchStageManager) so the vortexor's
+//! TPU addresses are advertised while it's alive and the validator falls back to its own TPU when
+//! heartbeats are missed.
+//!
+//! Peers can be added and removed at runtime. Removing a peer closes its connections, and the
+//! packets it sent before they were closed are dropped.
+//!
+//! The packets read from the connections are queued for the receiver thread, which rate limits
+//! them. The queue holds about a second's worth of packets at the vortexor rate limit, and the
+//! packets a peer sends while it's full are dropped and counted for that peer.
+use {
+    crate::{
+        banking_trace::BankingPacketSender,
+        proxy::{
+            packet_source_accounting::PacketSourceAccounting, packet_trust::PacketSource,
+            HeartbeatEvent, ProxyError,
+        },
+        tpu::MAX_QUIC_CONNECTIONS_PER_PEER,
+    },
+    crossbeam_channel::{Receiver, RecvTimeoutError, Sender, TrySendError},
+    quinn::{
+        crypto::rustls::QuicServerConfig, Connection, Endpoint, EndpointConfig, IdleTimeout,
+        Incoming, ServerConfig, TokioRuntime, VarInt,
+    },
+    rustls::{CertificateError, KeyLogFile},
+    solana_perf::packet::{Packet, PacketBatch, PACKETS_PER_BATCH, PACKET_DATA_SIZE},
+    solana_sdk::{
+        pubkey::Pubkey,
+        quic::{NotifyKeyUpdate, QUIC_MAX_STAKED_CONCURRENT_STREAMS, QUIC_MAX_TIMEOUT},
+        saturating_add_assign,
+        signature::Keypair,
+    },
+    solana_streamer::{
+        nonblocking::quic::{get_remote_pubkey, ALPN_TPU_PROTOCOL_ID},
+        quic::rt,
+    },
+    solana_tls_utils::{new_dummy_x509_certificate, tls_server_config_builder},
+    std::{
+        collections::HashMap,
+        net::{SocketAddr, UdpSocket},
+        str::FromStr,
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc, Mutex, RwLock,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+    tokio::time::timeout,
+};
+
+/// Payload of the heartbeats sent by vortexors, must match
+/// `solana_vortexor::packet_forwarder::HEARTBEAT_PACKET`
+pub const VORTEXOR_HEARTBEAT_PACKET: &[u8] = b"VORTEXOR_HEARTBEAT";
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+const METRICS_CADENCE: Duration = Duration::from_secs(1);
+const MAX_CONCURRENT_UNI_STREAMS: u32 = QUIC_MAX_STAKED_CONCURRENT_STREAMS as u32;
+// Bounds of the packet batches queued for the receiver thread, the upper one also applies when
+// the vortexor packets aren't rate limited
+const MIN_QUEUED_PACKET_BATCHES: usize = 256;
+const MAX_QUEUED_PACKET_BATCHES: usize = 10_000;
+
+const CONNECTION_CLOSE_CODE_SHUTDOWN: VarInt = VarInt::from_u32(1);
+const CONNECTION_CLOSE_CODE_UNKNOWN_PEER: VarInt = VarInt::from_u32(2);
+const CONNECTION_CLOSE_CODE_TOO_MANY_CONNECTIONS: VarInt = VarInt::from_u32(3);
+const CONNECTION_CLOSE_CODE_PEER_REMOVED: VarInt = VarInt::from_u32(4);
+
+const CONNECTION_CLOSE_REASON_SHUTDOWN: &[u8] = b"SHUTDOWN";
+const CONNECTION_CLOSE_REASON_UNKNOWN_PEER: &[u8] = b"UNKNOWN_PEER";
+const CONNECTION_CLOSE_REASON_TOO_MANY_CONNECTIONS: &[u8] = b"TOO_MANY_CONNECTIONS";
+const CONNECTION_CLOSE_REASON_PEER_REMOVED: &[u8] = b"PEER_REMOVED";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VortexorPeer {
+    /// Identity the vortexor authenticates its connections with
+    pub identity: Pubkey,
+    /// TPU address advertised in gossip while the vortexor is alive
+    pub tpu_address: SocketAddr,
+    /// TPU forward address advertised in gossip while the vortexor is alive
+    pub tpu_forwards_address: SocketAddr,
+}
+
+impl FromStr for VortexorPeer {
+    type Err = String;
+
+    /// Parses `IDENTITY,TPU_ADDRESS,TPU_FORWARDS_ADDRESS`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [identity, tpu_address, tpu_forwards_address] = s.split(',').collect::<Vec<_>>()[..]
+        else {
+            return Err(format!(
+                "expected IDENTITY,TPU_ADDRESS,TPU_FORWARDS_ADDRESS, got {s}"
+            ));
+        };
+        let parse_address = |address: &str| {
+            SocketAddr::from_str(address).map_err(|e| format!("invalid address {address}: {e}"))
+        };
+        Ok(Self {
+            identity: Pubkey::from_str(identity)
+                .map_err(|e| format!("invalid identity {identity}: {e}"))?,
+            tpu_address: parse_address(tpu_address)?,
+            tpu_forwards_address: parse_address(tpu_forwards_address)?,
+        })
+    }
+}
+
+/// The vortexors allowed to connect to the receiver.
+///
+/// The first peer's TPU addresses are the ones advertised, vortexors added after it are expected
+/// to share them behind a load balancer.
+#[derive(Debug, Default)]
+pub struct VortexorPeers {
+    peers: RwLock<Vec<VortexorPeer>>,
+    // Open connections by peer identity, closed when the peer is removed
+    connections: Mutex<HashMap<Pubkey, Vec<Connection>>>,
+    // Packets dropped by peer identity since the last report because the receiver thread was
+    // behind
+    dropped_packets: Mutex<HashMap<Pubkey, Arc<AtomicU64>>>,
+}
+
+/// Why a connection wasn't accepted from a vortexor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConnectionRejection {
+    UnknownPeer,
+    TooManyConnections,
+}
+
+impl ConnectionRejection {
+    fn close(&self, connection: &Connection) {
+        let (code, reason) = match self {
+            ConnectionRejection::UnknownPeer => (
+                CONNECTION_CLOSE_CODE_UNKNOWN_PEER,
+                CONNECTION_CLOSE_REASON_UNKNOWN_PEER,
+            ),
+            ConnectionRejection::TooManyConnections => (
+                CONNECTION_CLOSE_CODE_TOO_MANY_CONNECTIONS,
+                CONNECTION_CLOSE_REASON_TOO_MANY_CONNECTIONS,
+            ),
+        };
+        connection.close(code, reason);
+    }
+}
+
+impl VortexorPeers {
+    pub fn new(peers: Vec<VortexorPeer>) -> Self {
+        let vortexor_peers = Self::default();
+        for peer in peers {
+            vortexor_peers.add(peer);
+        }
+        vortexor_peers
+    }
+
+    pub fn get(&self) -> Vec<VortexorPeer> {
+        self.peers.read().unwrap().clone()
+    }
+
+    /// Adds `peer`, replacing the peer with the same identity
+    pub fn add(&self, peer: VortexorPeer) {
+        let mut peers = self.peers.write().unwrap();
+        match peers.iter_mut().find(|p| p.identity == peer.identity) {
+            Some(existing) => *existing = peer,
+            None => peers.push(peer),
+        }
+    }
+
+    /// Removes the peer with `identity` and closes its connections, returns false if there's none
+    pub fn remove(&self, identity: &Pubkey) -> bool {
+        let removed = {
+            let mut peers = self.peers.write().unwrap();
+            let num_peers = peers.len();
+            peers.retain(|peer| peer.identity != *identity);
+            peers.len() != num_peers
+        };
+        self.dropped_packets.lock().unwrap().remove(identity);
+        let connections = self.connections.lock().unwrap().remove(identity);
+        for connection in connections.into_iter().flatten() {
+            connection.close(
+                CONNECTION_CLOSE_CODE_PEER_REMOVED,
+                CONNECTION_CLOSE_REASON_PEER_REMOVED,
+            );
+        }
+        removed
+    }
+
+    fn is_peer(&self, identity: &Pubkey) -> bool {
+        self.peers
+            .read()
+            .unwrap()
+            .iter()
+            .any(|peer| peer.identity == *identity)
+    }
+
+    fn primary(&self) -> Option<VortexorPeer> {
+        self.peers.read().unwrap().first().copied()
+    }
+
+    /// Tracks a connection of `identity` so it's closed when the peer is removed, returns the
+    /// counter of the packets dropped from the peer
+    fn add_connection(
+        &self,
+        identity: Pubkey,
+        connection: Connection,
+    ) -> Result<Arc<AtomicU64>, ConnectionRejection> {
+        let mut connections = self.connections.lock().unwrap();
+        // checked while holding the lock, a concurrent remove closes the connection once added
+        if !self.is_peer(&identity) {
+            return Err(ConnectionRejection::UnknownPeer);
+        }
+        let peer_connections = connections.entry(identity).or_default();
+        if peer_connections.len() >= MAX_QUIC_CONNECTIONS_PER_PEER {
+            return Err(ConnectionRejection::TooManyConnections);
+        }
+        peer_connections.push(connection);
+        Ok(self
+            .dropped_packets
+            .lock()
+            .unwrap()
+            .entry(identity)
+            .or_default()
+            .clone())
+    }
+
+    fn remove_connection(&self, identity: &Pubkey, connection: &Connection) {
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(peer_connections) = connections.get_mut(identity) {
+            peer_connections.retain(|c| c.stable_id() != connection.stable_id());
+            if peer_connections.is_empty() {
+                connections.remove(identity);
+            }
+        }
+    }
+
+    fn num_connections(&self) -> usize {
+        self.connections
+            .lock()
+            .unwrap()
+            .values()
+            .map(Vec::len)
+            .sum()
+    }
+}
+
+#[derive(Default)]
+struct VortexorReceiverStats {
+    num_packets: u64,
+    num_heartbeats: u64,
+    num_dropped_packets: u64,
+}
+
+impl VortexorReceiverStats {
+    fn report(&self, peers: &VortexorPeers) {
+        datapoint_info!(
+            "vortexor_receiver",
+            ("num_peers", peers.peers.read().unwrap().len(), i64),
+            ("num_connections", peers.num_connections(), i64),
+            ("num_packets", self.num_packets, i64),
+            ("num_heartbeats", self.num_heartbeats, i64),
+            ("num_dropped_packets", self.num_dropped_packets, i64),
+        );
+        for (identity, num_dropped_packets) in peers.dropped_packets.lock().unwrap().iter() {
+            datapoint_info!(
+                "vortexor_receiver-peer",
+                "identity" => identity.to_string(),
+                (
+                    "num_dropped_packets",
+                    num_dropped_packets.swap(0, Ordering::Relaxed),
+                    i64
+                ),
+            );
+        }
+    }
+}
+
+/// Packet batches queued for the receiver thread, a second's worth at `packets_per_second`
+fn packet_channel_capacity(packets_per_second: u64) -> usize {
+    if packets_per_second == 0 {
+        return MAX_QUEUED_PACKET_BATCHES;
+    }
+    (packets_per_second as usize)
+        .div_ceil(PACKETS_PER_BATCH)
+        .clamp(MIN_QUEUED_PACKET_BATCHES, MAX_QUEUED_PACKET_BATCHES)
+}
+
+/// Updates the identity the receiver's endpoint authenticates with
+pub struct VortexorReceiverKeyUpdater {
+    endpoint: Endpoint,
+}
+
+impl NotifyKeyUpdate for VortexorReceiverKeyUpdater {
+    fn update_key(&self, key: &Keypair) -> Result<(), Box<dyn std::error::Error>> {
+        self.endpoint
+            .set_server_config(Some(new_server_config(key)?));
+        Ok(())
+    }
+}
+
+pub struct VortexorReceiver {
+    t_hdls: Vec<JoinHandle<()>>,
+}
+
+impl VortexorReceiver {
+    pub fn new(
+        socket: UdpSocket,
+        keypair: &Keypair,
+        peers: Arc<VortexorPeers>,
+        // Channel that vortexor heartbeats are piped through.
+        heartbeat_tx: Sender<HeartbeatEvent>,
+        // Channel that the verified packets are piped through.
+        banking_packet_sender: BankingPacketSender,
+        // Counts and rate limits the verified packets.
+        packet_source_accounting: Arc<PacketSourceAccounting>,
+        coalesce: Duration,
+        exit: Arc<AtomicBool>,
+    ) -> (Self, Arc<VortexorReceiverKeyUpdater>) {
+        let runtime = rt("solVtxRcvRt".to_string());
+        let endpoint = {
+            // Endpoint::new requires entering the runtime context
+            let _guard = runtime.enter();
+            Endpoint::new(
+                EndpointConfig::default(),
+                Some(new_server_config(keypair).unwrap()),
+                socket,
+                Arc::new(TokioRuntime),
+            )
+            .unwrap()
+        };
+
+        let (packet_sender, packet_receiver) = crossbeam_channel::bounded(packet_channel_capacity(
+            packet_source_accounting.rate_limits().vortexor,
+        ));
+        let server_t = {
+            let endpoint = endpoint.clone();
+            let peers = peers.clone();
+            let exit = exit.clone();
+            Builder::new()
+                .name("solVtxRcvQuic".to_string())
+                .spawn(move || {
+                    runtime.block_on(Self::run_server(
+                        endpoint,
+                        peers,
+                        packet_sender,
+                        coalesce,
+                        exit,
+                    ))
+                })
+                .unwrap()
+        };
+
+        let receiver_t = Builder::new()
+            .name("solVtxRcv".to_string())
+            .spawn(move || {
+                Self::run(
+                    packet_receiver,
+                    &peers,
+                    &heartbeat_tx,
+                    &banking_packet_sender,
+                    &packet_source_accounting,
+                    &exit,
+                )
+            })
+            .unwrap();
+
+        (
+            Self {
+                t_hdls: vec![server_t, receiver_t],
+            },
+            Arc::new(VortexorReceiverKeyUpdater { endpoint }),
+        )
+    }
+
+    async fn run_server(
+        endpoint: Endpoint,
+        peers: Arc<VortexorPeers>,
+        packet_sender: Sender<(Pubkey, PacketBatch)>,
+        coalesce: Duration,
+        exit: Arc<AtomicBool>,
+    ) {
+        while !exit.load(Ordering::Relaxed) {
+            let incoming = match timeout(RECV_TIMEOUT, endpoint.accept()).await {
+                Ok(Some(incoming)) => incoming,
+                Ok(None) => break,
+                Err(_) => continue,
+            };
+            tokio::spawn(Self::handle_incoming(
+                incoming,
+                peers.clone(),
+                packet_sender.clone(),
+                coalesce,
+            ));
+        }
+        endpoint.close(
+            CONNECTION_CLOSE_CODE_SHUTDOWN,
+            CONNECTION_CLOSE_REASON_SHUTDOWN,
+        );
+    }
+
+    async fn handle_incoming(
+        incoming: Incoming,
+        peers: Arc<VortexorPeers>,
+        packet_sender: Sender<(Pubkey, PacketBatch)>,
+        coalesce: Duration,
+    ) {
+        let remote_addr = incoming.remote_address();
+        let connection = match incoming.accept() {
+            Ok(connecting) => connecting.await,
+            Err(e) => Err(e),
+        };
+        let connection = match connection {
+            Ok(connection) => connection,
+            Err(e) => {
+                debug!("vortexor connection from {remote_addr} failed: {e:?}");
+                return;
+            }
+        };
+        let Some(identity) = get_remote_pubkey(&connection) else {
+            ConnectionRejection::UnknownPeer.close(&connection);
+            return;
+        };
+        let num_dropped_packets = match peers.add_connection(identity, connection.clone()) {
+            Ok(num_dropped_packets) => num_dropped_packets,
+            Err(rejection) => {
+                debug!(
+                    "rejected connection from vortexor {identity} at {remote_addr}: {rejection:?}"
+                );
+                rejection.close(&connection);
+                return;
+            }
+        };
+
+        Self::handle_connection(
+            &connection,
+            identity,
+            remote_addr,
+            &packet_sender,
+            &num_dropped_packets,
+            coalesce,
+        )
+        .await;
+        peers.remove_connection(&identity, &connection);
+    }
+
+    /// Reads the packets sent over `connection`, one per stream, and sends them on tagged with the
+    /// peer's identity in batches of up to [PACKETS_PER_BATCH] packets, waiting up to `coalesce`
+    /// for a batch to fill. Batches are dropped, and counted in `num_dropped_packets`, when the
+    /// receiver thread is behind.
+    async fn handle_connection(
+        connection: &Connection,
+        identity: Pubkey,
+        remote_addr: SocketAddr,
+        packet_sender: &Sender<(Pubkey, PacketBatch)>,
+        num_dropped_packets: &AtomicU64,
+        coalesce: Duration,
+    ) {
+        // returns false once the receiver thread is gone
+        let send_packets = |packets: &mut Vec<Packet>| {
+            let packets = std::mem::replace(packets, Vec::with_capacity(PACKETS_PER_BATCH));
+            match packet_sender.try_send((identity, PacketBatch::new(packets))) {
+                Ok(()) => true,
+                Err(TrySendError::Full((_, packet_batch))) => {
+                    num_dropped_packets.fetch_add(packet_batch.len() as u64, Ordering::Relaxed);
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        };
+
+        let mut packets = Vec::with_capacity(PACKETS_PER_BATCH);
+        let mut batch_start = Instant::now();
+        loop {
+            let stream = if packets.is_empty() {
+                connection.accept_uni().await
+            } else {
+                let wait = coalesce.saturating_sub(batch_start.elapsed());
+                match timeout(wait, connection.accept_uni()).await {
+                    Ok(stream) => stream,
+                    Err(_) => {
+                        if !send_packets(&mut packets) {
+                            return;
+                        }
+                        continue;
+                    }
+                }
+            };
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    debug!("vortexor {identity} connection from {remote_addr} closed: {e:?}");
+                    break;
+                }
+            };
+            let data = match timeout(QUIC_MAX_TIMEOUT, stream.read_to_end(PACKET_DATA_SIZE)).await {
+                Ok(Ok(data)) => data,
+                Ok(Err(e)) => {
+                    debug!("failed to read a stream from vortexor {identity}: {e:?}");
+                    continue;
+                }
+                Err(_) => {
+                    debug!("timed out reading a stream from vortexor {identity}");
+                    continue;
+                }
+            };
+
+            if packets.is_empty() {
+                batch_start = Instant::now();
+            }
+            let mut packet = Packet::default();
+            packet.buffer_mut()[..data.len()].copy_from_slice(&data);
+            packet.meta_mut().size = data.len();
+            packet.meta_mut().set_socket_addr(&remote_addr);
+            packets.push(packet);
+            if (packets.len() >= PACKETS_PER_BATCH || batch_start.elapsed() >= coalesce)
+                && !send_packets(&mut packets)
+            {
+                return;
+            }
+        }
+        if !packets.is_empty() {
+            send_packets(&mut packets);
+        }
+    }
+
+    fn run(
+        packet_receiver: Receiver<(Pubkey, PacketBatch)>,
+        peers: &VortexorPeers,
+        heartbeat_tx: &Sender<HeartbeatEvent>,
+        banking_packet_sender: &BankingPacketSender,
+        packet_source_accounting: &PacketSourceAccounting,
+        exit: &AtomicBool,
+    ) {
+        let mut stats = VortexorReceiverStats::default();
+        let mut last_report = Instant::now();
+        while !exit.load(Ordering::Relaxed) {
+            match packet_receiver.recv_timeout(RECV_TIMEOUT) {
+                Ok((identity, packet_batch)) => {
+                    if let Err(e) = Self::handle_packet_batch(
+                        &identity,
+                        packet_batch,
+                        peers,
+                        heartbeat_tx,
+                        banking_packet_sender,
+                        packet_source_accounting,
+                        &mut stats,
+                    ) {
+                        error!("vortexor receiver error: {e:?}");
+                        return;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+            if last_report.elapsed() >= METRICS_CADENCE {
+                stats.report(peers);
+                stats = VortexorReceiverStats::default();
+                last_report = Instant::now();
+            }
+        }
+    }
+
+    /// Forwards the verified packets sent by the peer with `identity` to BankingStage and passes
+    /// heartbeats on to the FetchStageManager, advertising the primary peer's TPU addresses
+    fn handle_packet_batch(
+        identity: &Pubkey,
+        packet_batch: PacketBatch,
+        peers: &VortexorPeers,
+        heartbeat_tx: &Sender<HeartbeatEvent>,
+        banking_packet_sender: &BankingPacketSender,
+        packet_source_accounting: &PacketSourceAccounting,
+        stats: &mut VortexorReceiverStats,
+    ) -> crate::proxy::Result<()> {
+        saturating_add_assign!(stats.num_packets, packet_batch.len() as u64);
+        // the peer was removed after it sent the packets
+        let Some(primary) = peers.primary().filter(|_| peers.is_peer(identity)) else {
+            saturating_add_assign!(stats.num_dropped_packets, packet_batch.len() as u64);
+            return Ok(());
+        };
+
+        let mut packets = Vec::<Packet>::from(packet_batch);
+        let num_packets = packets.len();
+        packets.retain(|packet| packet.data(..) != Some(VORTEXOR_HEARTBEAT_PACKET));
+        let num_heartbeats = num_packets - packets.len();
+        if num_heartbeats > 0 {
+            saturating_add_assign!(stats.num_heartbeats, num_heartbeats as u64);
+            heartbeat_tx
+                .send((primary.tpu_address, primary.tpu_forwards_address))
+                .map_err(|_| ProxyError::HeartbeatChannelError)?;
+        }
+        if packets.is_empty() {
+            return Ok(());
+        }
+
+        let mut packet_batch = PacketBatch::new(packets);
+        packet_source_accounting.record(PacketSource::Vortexor, &mut packet_batch);
+        if packet_batch.is_empty() {
+            return Ok(());
+        }
+        banking_packet_sender
+            .send(Arc::new(vec![packet_batch]))
+            .map_err(|_| ProxyError::PacketForwardError)
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        for t in self.t_hdls {
+            t.join()?;
+        }
+        Ok(())
+    }
+}
+
+fn new_server_config(keypair: &Keypair) -> Result<ServerConfig, rustls::Error> {
+    let (cert, key) = new_dummy_x509_certificate(keypair);
+    let mut config = tls_server_config_builder().with_single_cert(vec![cert], key)?;
+    config.alpn_protocols = vec![ALPN_TPU_PROTOCOL_ID.to_vec()];
+    config.key_log = Arc::new(KeyLogFile::new());
+    let Ok(config) = QuicServerConfig::try_from(config) else {
+        return Err(rustls::Error::InvalidCertificate(
+            CertificateError::BadSignature,
+        ));
+    };
+    let mut config = ServerConfig::with_crypto(Arc::new(config));
+    Arc::get_mut(&mut config.transport)
+        .unwrap()
+        .max_concurrent_uni_streams(MAX_CONCURRENT_UNI_STREAMS.into())
+        .max_concurrent_bidi_streams(0u32.into())
+        .stream_receive_window((PACKET_DATA_SIZE as u32).into())
+        .receive_window((PACKET_DATA_SIZE as u32 * MAX_CONCURRENT_UNI_STREAMS).into())
+        .max_idle_timeout(Some(IdleTimeout::try_from(QUIC_MAX_TIMEOUT).unwrap()))
+        .datagram_receive_buffer_size(None);
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_trace::BankingTracer,
+        crossbeam_channel::unbounded,
+        quinn::{crypto::rustls::QuicClientConfig, ClientConfig, ConnectionError},
+        solana_sdk::{
+            hash::Hash, signature::Signer, system_transaction::transfer, transaction::Transaction,
+        },
+        solana_tls_utils::tls_client_config_builder,
+    };
+
+    fn make_peer(port: u16) -> VortexorPeer {
+        VortexorPeer {
+            identity: Pubkey::new_unique(),
+            tpu_address: SocketAddr::from(([127, 0, 0, 1], port)),
+            tpu_forwards_address: SocketAddr::from(([127, 0, 0, 1], port + 1)),
+        }
+    }
+
+    fn make_heartbeat() -> Packet {
+        let mut packet = Packet::default();
+        packet.buffer_mut()[..VORTEXOR_HEARTBEAT_PACKET.len()]
+            .copy_from_slice(VORTEXOR_HEARTBEAT_PACKET);
+        packet.meta_mut().size = VORTEXOR_HEARTBEAT_PACKET.len();
+        packet
+    }
+
+    /// An endpoint connecting the way a vortexor does, authenticated by `keypair`
+    async fn new_client_endpoint(keypair: &Keypair) -> Endpoint {
+        let (cert, key) = new_dummy_x509_certificate(keypair);
+        let mut config = tls_client_config_builder()
+            .with_client_auth_cert(vec![cert], key)
+            .unwrap();
+        config.alpn_protocols = vec![ALPN_TPU_PROTOCOL_ID.to_vec()];
+        let mut endpoint = Endpoint::client(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+        endpoint.set_default_client_config(ClientConfig::new(Arc::new(
+            QuicClientConfig::try_from(config).unwrap(),
+        )));
+        endpoint
+    }
+
+    /// Connects to the receiver at `address` and waits for it to close the connection
+    async fn wait_for_rejection(endpoint: &Endpoint, address: SocketAddr) -> ConnectionError {
+        match endpoint.connect(address, "connect").unwrap().await {
+            Ok(connection) => connection.closed().await,
+            Err(err) => err,
+        }
+    }
+
+    async fn send_transaction(
+        connection: &Connection,
+        transaction: &Transaction,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut stream = connection.open_uni().await?;
+        stream
+            .write_all(&bincode::serialize(transaction).unwrap())
+            .await?;
+        stream.finish()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_vortexor_peers() {
+        let peer = make_peer(8000);
+        let other_peer = make_peer(9000);
+        let vortexor_peers = VortexorPeers::new(vec![peer, other_peer]);
+        assert_eq!(vortexor_peers.get(), vec![peer, other_peer]);
+        assert_eq!(vortexor_peers.primary(), Some(peer));
+        assert!(vortexor_peers.is_peer(&peer.identity));
+
+        // adding a known identity replaces its addresses
+        let moved_peer = VortexorPeer {
+            tpu_address: SocketAddr::from(([127, 0, 0, 1], 10000)),
+            ..peer
+        };
+        vortexor_peers.add(moved_peer);
+        assert_eq!(vortexor_peers.get(), vec![moved_peer, other_peer]);
+
+        assert!(vortexor_peers.remove(&peer.identity));
+        assert!(!vortexor_peers.remove(&peer.identity));
+        assert_eq!(vortexor_peers.primary(), Some(other_peer));
+        assert!(!vortexor_peers.is_peer(&peer.identity));
+    }
+
+    #[test]
+    fn test_packet_channel_capacity() {
+        assert_eq!(packet_channel_capacity(0), MAX_QUEUED_PACKET_BATCHES);
+        assert_eq!(packet_channel_capacity(1), MIN_QUEUED_PACKET_BATCHES);
+        assert_eq!(
+            packet_channel_capacity(1_000 * PACKETS_PER_BATCH as u64 + 1),
+            1_001
+        );
+        assert_eq!(packet_channel_capacity(u64::MAX), MAX_QUEUED_PACKET_BATCHES);
+    }
+
+    #[test]
+    fn test_parse_vortexor_peer() {
+        let peer = make_peer(8000);
+        assert_eq!(
+            format!(
+                "{},{},{}",
+                peer.identity, peer.tpu_address, peer.tpu_forwards_address
+            )
+            .parse::<VortexorPeer>(),
+            Ok(peer)
+        );
+        assert!(format!("{},{}", peer.identity, peer.tpu_address)
+            .parse::<VortexorPeer>()
+            .is_err());
+        assert!("not-a-pubkey,127.0.0.1:8000,127.0.0.1:8001"
+            .parse::<VortexorPeer>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_handle_packet_batch() {
+        let keypair = Keypair::new();
+        let peer = make_peer(8000);
+        let vortexor_peers = VortexorPeers::default();
+        let (heartbeat_tx, heartbeat_rx) = unbounded();
+        let (banking_packet_sender, banking_packet_receiver) = BankingTracer::channel_for_test();
+        let packet_source_accounting = PacketSourceAccounting::default();
+        let mut stats = VortexorReceiverStats::default();
+
+        let transaction = Packet::from_data(
+            None,
+            transfer(&keypair, &keypair.pubkey(), 1, Hash::default()),
+        )
+        .unwrap();
+
+        // packets are dropped without any peers
+        VortexorReceiver::handle_packet_batch(
+            &peer.identity,
+            PacketBatch::new(vec![transaction.clone()]),
+            &vortexor_peers,
+            &heartbeat_tx,
+            &banking_packet_sender,
+            &packet_source_accounting,
+            &mut stats,
+        )
+        .unwrap();
+        assert_eq!(stats.num_dropped_packets, 1);
+        assert!(banking_packet_receiver.try_recv().is_err());
+
+        vortexor_peers.add(peer);
+
+        // a batch of heartbeats only isn't forwarded
+        VortexorReceiver::handle_packet_batch(
+            &peer.identity,
+            PacketBatch::new(vec![make_heartbeat()]),
+            &vortexor_peers,
+            &heartbeat_tx,
+            &banking_packet_sender,
+            &packet_source_accounting,
+            &mut stats,
+        )
+        .unwrap();
+        assert_eq!(
+            heartbeat_rx.try_recv().unwrap(),
+            (peer.tpu_address, peer.tpu_forwards_address)
+        );
+        assert!(banking_packet_receiver.try_recv().is_err());
+
+        // heartbeats are removed from the forwarded packets
+        VortexorReceiver::handle_packet_batch(
+            &peer.identity,
+            PacketBatch::new(vec![make_heartbeat(), transaction.clone()]),
+            &vortexor_peers,
+            &heartbeat_tx,
+            &banking_packet_sender,
+            &packet_source_accounting,
+            &mut stats,
+        )
+        .unwrap();
+        assert!(heartbeat_rx.try_recv().is_ok());
+        let banking_packet_batch = banking_packet_receiver.try_recv().unwrap();
+        assert_eq!(banking_packet_batch[0].len(), 1);
+        assert_eq!(banking_packet_batch[0][0].data(..), transaction.data(..));
+        assert_eq!(stats.num_packets, 4);
+        assert_eq!(stats.num_heartbeats, 2);
+        assert_eq!(
+            packet_source_accounting
+                .stats(PacketSource::Vortexor)
+                .num_packets,
+            1
+        );
+
+        // packets from an identity that isn't a peer are dropped, heartbeats included
+        VortexorReceiver::handle_packet_batch(
+            &Pubkey::new_unique(),
+            PacketBatch::new(vec![make_heartbeat(), transaction.clone()]),
+            &vortexor_peers,
+            &heartbeat_tx,
+            &banking_packet_sender,
+            &packet_source_accounting,
+            &mut stats,
+        )
+        .unwrap();
+        assert_eq!(stats.num_dropped_packets, 3);
+        assert!(heartbeat_rx.try_recv().is_err());
+        assert!(banking_packet_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_remove_connected_peer() {
+        let vortexor_keypair = Keypair::new();
+        let peer = VortexorPeer {
+            identity: vortexor_keypair.pubkey(),
+            ..make_peer(8000)
+        };
+        let vortexor_peers = Arc::new(VortexorPeers::new(vec![peer]));
+        let (heartbeat_tx, _heartbeat_rx) = unbounded();
+        let (banking_packet_sender, banking_packet_receiver) = BankingTracer::channel_for_test();
+        let exit = Arc::new(AtomicBool::new(false));
+
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        let (receiver, _key_updater) = VortexorReceiver::new(
+            socket,
+            &Keypair::new(),
+            vortexor_peers.clone(),
+            heartbeat_tx,
+            banking_packet_sender,
+            Arc::default(),
+            Duration::ZERO,
+            exit.clone(),
+        );
+
+        let transaction = transfer(
+            &vortexor_keypair,
+            &vortexor_keypair.pubkey(),
+            1,
+            Hash::default(),
+        );
+        let runtime = rt("solVtxRcvTest".to_string());
+        let endpoint = runtime.block_on(new_client_endpoint(&vortexor_keypair));
+
+        // connections from identities that aren't peers are closed
+        let unknown_endpoint = runtime.block_on(new_client_endpoint(&Keypair::new()));
+        assert_matches!(
+            runtime.block_on(wait_for_rejection(&unknown_endpoint, address)),
+            ConnectionError::ApplicationClosed(close)
+                if close.error_code == CONNECTION_CLOSE_CODE_UNKNOWN_PEER
+        );
+
+        let connection = runtime
+            .block_on(endpoint.connect(address, "connect").unwrap())
+            .unwrap();
+        runtime
+            .block_on(send_transaction(&connection, &transaction))
+            .unwrap();
+        let banking_packet_batch = banking_packet_receiver
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(
+            banking_packet_batch[0][0].data(..),
+            Some(&bincode::serialize(&transaction).unwrap()[..])
+        );
+
+        // removing the peer closes its connection and its packets aren't forwarded anymore
+        assert!(vortexor_peers.remove(&peer.identity));
+        assert_matches!(
+            runtime.block_on(connection.closed()),
+            ConnectionError::ApplicationClosed(close)
+                if close.error_code == CONNECTION_CLOSE_CODE_PEER_REMOVED
+        );
+        assert!(runtime
+            .block_on(send_transaction(&connection, &transaction))
+            .is_err());
+        assert_matches!(
+            runtime.block_on(wait_for_rejection(&endpoint, address)),
+            ConnectionError::ApplicationClosed(close)
+                if close.error_code == CONNECTION_CLOSE_CODE_UNKNOWN_PEER
+        );
+        assert!(banking_packet_receiver
+            .recv_timeout(Duration::from_secs(1))
+            .is_err());
+        assert_eq!(vortexor_peers.num_connections(), 0);
+
+        exit.store(true, Ordering::Relaxed);
+        receiver.join().unwrap();
+    }
+}
@@ -65,6 +65,7 @@ use {
         prioritization_fee_cache::PrioritizationFeeCache,
         vote_sender_types::ReplayVoteSender,
     },
+    solana_runtime_plugin::runtime_plugin_service::{BankEvent, BankEventSender},
     solana_sdk::{
         clock::{BankId, Slot, MAX_PROCESSING_AGE, NUM_CONSECUTIVE_LEADER_SLOTS},
         hash::Hash,
@@ -279,6 +280,7 @@ pub struct ReplaySenders {
     pub cache_block_meta_sender: Option<CacheBlockMetaSender>,
     pub entry_notification_sender: Option<EntryNotifierSender>,
     pub bank_notification_sender: Option<BankNotificationSenderConfig>,
+    pub runtime_plugin_bank_event_sender: Option<BankEventSender>,
     pub ancestor_hashes_replay_update_sender: AncestorHashesReplayUpdateSender,
     pub retransmit_slots_sender: Sender<u64>,
     pub replay_vote_sender: ReplayVoteSender,
@@ -570,6 +572,7 @@ impl ReplayStage {
             cache_block_meta_sender,
             entry_notification_sender,
             bank_notification_sender,
+            runtime_plugin_bank_event_sender,
             ancestor_hashes_replay_update_sender,
             retransmit_slots_sender,
             replay_vote_sender,
@@ -731,6 +734,7 @@ impl ReplayStage {
                     &mut heaviest_subtree_fork_choice,
                     &replay_vote_sender,
                     &bank_notification_sender,
+                    &runtime_plugin_bank_event_sender,
                     &rpc_subscriptions,
                     &slot_status_notifier,
                     &mut duplicate_slots_tracker,
@@ -1005,6 +1009,7 @@ impl ReplayStage {
                         &block_commitment_cache,
                         &mut heaviest_subtree_fork_choice,
                         &bank_notification_sender,
+                        &runtime_plugin_bank_event_sender,
                         &mut duplicate_slots_tracker,
                         &mut duplicate_confirmed_slots,
                         &mut unfrozen_gossip_verified_vote_hashes,
@@ -2395,6 +2400,7 @@ impl ReplayStage {
         block_commitment_cache: &Arc<RwLock<BlockCommitmentCache>>,
         heaviest_subtree_fork_choice: &mut HeaviestSubtreeForkChoice,
         bank_notification_sender: &Option<BankNotificationSenderConfig>,
+        runtime_plugin_bank_event_sender: &Option<BankEventSender>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
         duplicate_confirmed_slots: &mut DuplicateConfirmedSlots,
         unfrozen_gossip_verified_vote_hashes: &mut UnfrozenGossipVerifiedVoteHashes,
@@ -2466,6 +2472,13 @@ impl ReplayStage {
             blockstore.slots_stats.mark_rooted(new_root);
 
             rpc_subscriptions.notify_roots(rooted_slots);
+            if let Some(sender) = runtime_plugin_bank_event_sender {
+                sender
+                    .try_send(BankEvent::Rooted(root_bank.clone()))
+                    .unwrap_or_else(|err| {
+                        warn!("runtime_plugin_bank_event_sender failed: {:?}", err)
+                    });
+            }
             if let Some(sender) = bank_notification_sender {
                 sender
                     .sender
@@ -3037,6 +3050,7 @@ impl ReplayStage {
         cache_block_meta_sender: Option<&CacheBlockMetaSender>,
         heaviest_subtree_fork_choice: &mut HeaviestSubtreeForkChoice,
         bank_notification_sender: &Option<BankNotificationSenderConfig>,
+        runtime_plugin_bank_event_sender: &Option<BankEventSender>,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
         slot_status_notifier: &Option<SlotStatusNotifier>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
@@ -3266,6 +3280,13 @@ impl ReplayStage {
                         .send(BankNotification::Frozen(bank.clone_without_scheduler()))
                         .unwrap_or_else(|err| warn!("bank_notification_sender failed: {:?}", err));
                 }
+                if let Some(sender) = runtime_plugin_bank_event_sender {
+                    sender
+                        .try_send(BankEvent::Frozen(bank.clone_without_scheduler()))
+                        .unwrap_or_else(|err| {
+                            warn!("runtime_plugin_bank_event_sender failed: {:?}", err)
+                        });
+                }
                 blockstore_processor::cache_block_meta(bank, cache_block_meta_sender);
 
                 let bank_hash = bank.hash();
@@ -3337,6 +3358,7 @@ impl ReplayStage {
         heaviest_subtree_fork_choice: &mut HeaviestSubtreeForkChoice,
         replay_vote_sender: &ReplayVoteSender,
         bank_notification_sender: &Option<BankNotificationSenderConfig>,
+        runtime_plugin_bank_event_sender: &Option<BankEventSender>,
         rpc_subscriptions: &Arc<RpcSubscriptions>,
         slot_status_notifier: &Option<SlotStatusNotifier>,
         duplicate_slots_tracker: &mut DuplicateSlotsTracker,
@@ -3419,6 +3441,7 @@ impl ReplayStage {
             cache_block_meta_sender,
             heaviest_subtree_fork_choice,
             bank_notification_sender,
+            runtime_plugin_bank_event_sender,
             rpc_subscriptions,
             slot_status_notifier,
             duplicate_slots_tracker,
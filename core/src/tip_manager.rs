@@ -547,6 +547,7 @@ impl TipManager {
             Some(SanitizedBundle {
                 transactions,
                 bundle_id,
+                allowed_to_fail_tx_indexes: HashSet::new(),
             })
         }
     }
@@ -602,6 +603,7 @@ impl TipManager {
             Ok(Some(SanitizedBundle {
                 transactions,
                 bundle_id,
+                allowed_to_fail_tx_indexes: HashSet::new(),
             }))
         }
     }
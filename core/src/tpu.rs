@@ -6,16 +6,29 @@ use {
     crate::{
         banking_stage::BankingStage,
         banking_trace::{BankingTracer, Channels, TracerThread},
-        bundle_stage::{bundle_account_locker::BundleAccountLocker, BundleStage},
+        bundle_stage::{
+            bundle_account_locker::BundleAccountLocker,
+            bundle_policy::BundlePolicy,
+            bundle_reservation::{BundleReservation, BundleReservationConfig},
+            bundle_result::{bundle_result_channel, BUNDLE_RESULT_BUFFER_CAPACITY},
+            min_tip_policy::MinTipPolicy,
+            BundleStage,
+        },
         cluster_info_vote_listener::{
             ClusterInfoVoteListener, DuplicateConfirmedSlotsSender, GossipVerifiedVoteHashSender,
             VerifiedVoteSender, VoteTracker,
         },
         fetch_stage::FetchStage,
         proxy::{
-            block_engine_stage::{BlockBuilderFeeInfo, BlockEngineConfig, BlockEngineStage},
+            block_engine_region_selector::BlockEngineRegions,
+            block_engine_stage::{
+                BlockBuilderFeeInfo, BlockEngineConfig, BlockEngineHealth, BlockEngineStage,
+            },
             fetch_stage_manager::FetchStageManager,
+            packet_source_accounting::PacketSourceAccounting,
+            packet_trust::PacketTrust,
             relayer_stage::{RelayerConfig, RelayerStage},
+            vortexor_receiver::{VortexorPeers, VortexorReceiver},
         },
         sigverify::TransactionSigVerifier,
         sigverify_stage::SigVerifyStage,
@@ -26,7 +39,12 @@ use {
     },
     bytes::Bytes,
     crossbeam_channel::{unbounded, Receiver},
+    solana_bundle::bundle_simulation_cache::BundleSimulationCache,
     solana_client::connection_cache::ConnectionCache,
+    solana_geyser_plugin_manager::{
+        banking_transaction_notifier_interface::BankingTransactionNotifierArc,
+        bundle_notifier_interface::BundleNotifierArc,
+    },
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
         blockstore::Blockstore, blockstore_processor::TransactionStatusSender,
@@ -38,7 +56,6 @@ use {
         rpc_subscriptions::RpcSubscriptions,
     },
     solana_runtime::{
-        bank::Bank,
         bank_forks::BankForks,
         prioritization_fee_cache::PrioritizationFeeCache,
         vote_sender_types::{ReplayVoteReceiver, ReplayVoteSender},
@@ -60,6 +77,7 @@ use {
     std::{
         collections::{HashMap, HashSet},
         net::{SocketAddr, UdpSocket},
+        path::PathBuf,
         sync::{atomic::AtomicBool, Arc, Mutex, RwLock},
         thread,
         time::Duration,
@@ -81,20 +99,6 @@ pub struct TpuSockets {
     pub vote_quic: Vec<UdpSocket>,
 }
 
-/// For the first `reserved_ticks` ticks of a bank, the preallocated_bundle_cost is subtracted
-/// from the Bank's block cost limit.
-fn calculate_block_cost_limit_reservation(
-    bank: &Bank,
-    reserved_ticks: u64,
-    preallocated_bundle_cost: u64,
-) -> u64 {
-    if bank.tick_height() % bank.ticks_per_slot() < reserved_ticks {
-        preallocated_bundle_cost
-    } else {
-        0
-    }
-}
-
 pub struct Tpu {
     fetch_stage: FetchStage,
     sigverify_stage: SigVerifyStage,
@@ -111,6 +115,7 @@ pub struct Tpu {
     relayer_stage: RelayerStage,
     block_engine_stage: BlockEngineStage,
     fetch_stage_manager: FetchStageManager,
+    vortexor_receiver: Option<VortexorReceiver>,
     bundle_stage: BundleStage,
 }
 
@@ -156,7 +161,20 @@ impl Tpu {
         relayer_config: Arc<Mutex<RelayerConfig>>,
         tip_manager_config: TipManagerConfig,
         shred_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
-        preallocated_bundle_cost: u64,
+        bundle_reservation_config: BundleReservationConfig,
+        bundle_result_fallback_path: Option<PathBuf>,
+        bundle_stage_num_workers: usize,
+        bundle_simulation_cache: Option<Arc<BundleSimulationCache>>,
+        min_tip_policy: Arc<RwLock<MinTipPolicy>>,
+        bundle_policy: Arc<RwLock<BundlePolicy>>,
+        block_engine_health: Arc<RwLock<Vec<BlockEngineHealth>>>,
+        block_engine_regions: Arc<RwLock<BlockEngineRegions>>,
+        packet_trust: Arc<PacketTrust>,
+        packet_source_accounting: Arc<PacketSourceAccounting>,
+        vortexor_receiver_address: Option<SocketAddr>,
+        vortexor_peers: Arc<VortexorPeers>,
+        bundle_notifier: Option<BundleNotifierArc>,
+        banking_transaction_notifier: Option<BankingTransactionNotifierArc>,
     ) -> (Self, Vec<Arc<dyn NotifyKeyUpdate + Sync + Send>>) {
         let TpuSockets {
             transactions: transactions_sockets,
@@ -300,31 +318,67 @@ impl Tpu {
         }));
 
         let (bundle_sender, bundle_receiver) = unbounded();
+        let (bundle_result_sender, bundle_result_receiver) =
+            bundle_result_channel(BUNDLE_RESULT_BUFFER_CAPACITY);
         let block_engine_stage = BlockEngineStage::new(
             block_engine_config,
             bundle_sender,
             cluster_info.clone(),
             packet_sender.clone(),
             non_vote_sender.clone(),
+            packet_trust.clone(),
+            packet_source_accounting.clone(),
             exit.clone(),
             &block_builder_fee_info,
+            bundle_result_receiver,
+            bundle_result_fallback_path,
+            block_engine_health,
+            block_engine_regions,
         );
 
-        let (heartbeat_tx, heartbeat_rx) = unbounded();
+        // A vortexor takes over advertising the TPU addresses from the relayer when it's configured
+        let (relayer_heartbeat_tx, relayer_heartbeat_rx) = unbounded();
+        let (vortexor_heartbeat_tx, vortexor_heartbeat_rx) = unbounded();
+        let (heartbeat_rx, superseded_heartbeat_rx) = if vortexor_receiver_address.is_some() {
+            (vortexor_heartbeat_rx, Some(relayer_heartbeat_rx))
+        } else {
+            (relayer_heartbeat_rx, None)
+        };
         let fetch_stage_manager = FetchStageManager::new(
             cluster_info.clone(),
             heartbeat_rx,
+            superseded_heartbeat_rx,
             packet_intercept_receiver,
             packet_sender.clone(),
+            packet_source_accounting.clone(),
             exit.clone(),
         );
 
+        // Vortexors stand in for the TPU like the relayer: their heartbeats switch the advertised
+        // TPU addresses over and their packets were already verified
+        let vortexor_receiver = vortexor_receiver_address.map(|address| {
+            let socket = solana_net_utils::bind_to(address.ip(), address.port(), false)
+                .expect("bind vortexor receiver socket");
+            VortexorReceiver::new(
+                socket,
+                keypair,
+                vortexor_peers,
+                vortexor_heartbeat_tx,
+                non_vote_sender.clone(),
+                packet_source_accounting.clone(),
+                tpu_coalesce,
+                exit.clone(),
+            )
+        });
+
         let relayer_stage = RelayerStage::new(
             relayer_config,
             cluster_info.clone(),
-            heartbeat_tx,
+            relayer_heartbeat_tx,
             packet_sender,
             non_vote_sender,
+            packet_trust,
+            packet_source_accounting,
             exit.clone(),
         );
 
@@ -348,14 +402,10 @@ impl Tpu {
         let bundle_account_locker = BundleAccountLocker::default();
 
         // The tip program can't be used in BankingStage to avoid someone from stealing tips mid-slot.
-        // The first 80% of the block, based on poh ticks, has `preallocated_bundle_cost` less compute units.
-        // The last 20% has has full compute so blockspace is maximized if BundleStage is idle.
-        let reserved_ticks = poh_recorder
-            .read()
-            .unwrap()
-            .ticks_per_slot()
-            .saturating_mul(8)
-            .saturating_div(10);
+        // Part of every block is reserved for bundles: BankingStage has the reserved compute units
+        // less, sized by BundleStage based on the bundles it has waiting. After the release point
+        // the preallocated part is given back so blockspace is maximized if BundleStage is idle.
+        let bundle_reservation = Arc::new(BundleReservation::new(bundle_reservation_config));
 
         let mut blacklisted_accounts = HashSet::new();
         blacklisted_accounts.insert(tip_manager.tip_payment_program_id());
@@ -649,6 +699,7 @@ impl Tpu {
             tpu_vote_receiver,
             gossip_vote_receiver,
             transaction_status_sender.clone(),
+            banking_transaction_notifier,
             replay_vote_sender.clone(),
             log_messages_bytes_limit,
             connection_cache.clone(),
@@ -657,12 +708,9 @@ impl Tpu {
             enable_block_production_forwarding,
             blacklisted_accounts,
             bundle_account_locker.clone(),
-            move |bank| {
-                calculate_block_cost_limit_reservation(
-                    bank,
-                    reserved_ticks,
-                    preallocated_bundle_cost,
-                )
+            {
+                let bundle_reservation = bundle_reservation.clone();
+                move |bank| bundle_reservation.block_cost_limit_reservation(bank)
             },
         );
 
@@ -671,6 +719,7 @@ impl Tpu {
             poh_recorder,
             bundle_receiver,
             transaction_status_sender,
+            bundle_notifier,
             replay_vote_sender,
             log_messages_bytes_limit,
             exit.clone(),
@@ -678,6 +727,13 @@ impl Tpu {
             bundle_account_locker,
             &block_builder_fee_info,
             prioritization_fee_cache,
+            Some(bundle_result_sender),
+            bundle_stage_num_workers,
+            bundle_simulation_cache,
+            min_tip_policy,
+            bundle_policy,
+            bundle_reservation,
+            banking_tracer,
         );
 
         let (entry_receiver, tpu_entry_notifier) =
@@ -707,6 +763,13 @@ impl Tpu {
             shred_receiver_address,
         );
 
+        let mut key_updaters: Vec<Arc<dyn NotifyKeyUpdate + Sync + Send>> =
+            vec![key_updater, forwards_key_updater, vote_streamer_key_updater];
+        let vortexor_receiver = vortexor_receiver.map(|(vortexor_receiver, key_updater)| {
+            key_updaters.push(key_updater);
+            vortexor_receiver
+        });
+
         (
             Self {
                 fetch_stage,
@@ -724,14 +787,15 @@ impl Tpu {
                 block_engine_stage,
                 relayer_stage,
                 fetch_stage_manager,
+                vortexor_receiver,
                 bundle_stage,
             },
-            vec![key_updater, forwards_key_updater, vote_streamer_key_updater],
+            key_updaters,
         )
     }
 
     pub fn join(self) -> thread::Result<()> {
-        let results = vec![
+        let mut results = vec![
             self.fetch_stage.join(),
             self.sigverify_stage.join(),
             self.vote_sigverify_stage.join(),
@@ -746,6 +810,9 @@ impl Tpu {
             self.block_engine_stage.join(),
             self.fetch_stage_manager.join(),
         ];
+        if let Some(vortexor_receiver) = self.vortexor_receiver {
+            results.push(vortexor_receiver.join());
+        }
         let broadcast_result = self.broadcast_stage.join();
         for result in results {
             result?;
@@ -765,48 +832,3 @@ impl Tpu {
         Ok(())
     }
 }
-
-#[cfg(test)]
-mod test {
-    use {
-        super::calculate_block_cost_limit_reservation,
-        solana_ledger::genesis_utils::create_genesis_config, solana_pubkey::Pubkey,
-        solana_runtime::bank::Bank, std::sync::Arc,
-    };
-
-    #[test]
-    fn test_calculate_block_cost_limit_reservation() {
-        const BUNDLE_BLOCK_COST_LIMITS_RESERVATION: u64 = 100;
-        const RESERVED_TICKS: u64 = 5;
-        let genesis_config_info = create_genesis_config(100);
-        let bank = Arc::new(Bank::new_for_tests(&genesis_config_info.genesis_config));
-
-        for _ in 0..genesis_config_info.genesis_config.ticks_per_slot {
-            bank.register_default_tick_for_test();
-        }
-        assert!(bank.is_complete());
-        bank.freeze();
-        let bank1 = Arc::new(Bank::new_from_parent(bank.clone(), &Pubkey::default(), 1));
-
-        // wait for reservation to be over
-        (0..RESERVED_TICKS).for_each(|_| {
-            assert_eq!(
-                calculate_block_cost_limit_reservation(
-                    &bank1,
-                    RESERVED_TICKS,
-                    BUNDLE_BLOCK_COST_LIMITS_RESERVATION,
-                ),
-                BUNDLE_BLOCK_COST_LIMITS_RESERVATION
-            );
-            bank1.register_default_tick_for_test();
-        });
-        assert_eq!(
-            calculate_block_cost_limit_reservation(
-                &bank1,
-                RESERVED_TICKS,
-                BUNDLE_BLOCK_COST_LIMITS_RESERVATION,
-            ),
-            0
-        );
-    }
-}
@@ -44,6 +44,7 @@ use {
         commitment::BlockCommitmentCache, prioritization_fee_cache::PrioritizationFeeCache,
         vote_sender_types::ReplayVoteSender,
     },
+    solana_runtime_plugin::runtime_plugin_service::BankEventSender,
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Keypair},
     solana_turbine::retransmit_stage::RetransmitStage,
     std::{
@@ -140,6 +141,7 @@ impl Tvu {
         replay_vote_sender: ReplayVoteSender,
         completed_data_sets_sender: Option<CompletedDataSetsSender>,
         bank_notification_sender: Option<BankNotificationSenderConfig>,
+        runtime_plugin_bank_event_sender: Option<BankEventSender>,
         duplicate_confirmed_slots_receiver: DuplicateConfirmedSlotsReceiver,
         tvu_config: TvuConfig,
         max_slots: &Arc<MaxSlots>,
@@ -282,6 +284,7 @@ impl Tvu {
             cache_block_meta_sender,
             entry_notification_sender,
             bank_notification_sender,
+            runtime_plugin_bank_event_sender,
             ancestor_hashes_replay_update_sender,
             retransmit_slots_sender,
             replay_vote_sender,
@@ -563,6 +566,7 @@ pub mod tests {
             replay_vote_sender,
             /*completed_data_sets_sender:*/ None,
             None,
+            None,
             gossip_confirmed_slots_receiver,
             TvuConfig::default(),
             &Arc::new(MaxSlots::default()),
@@ -6,6 +6,10 @@ use {
         accounts_hash_verifier::AccountsHashVerifier,
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
         banking_trace::{self, BankingTracer, TraceError},
+        bundle_stage::{
+            bundle_policy::BundlePolicy, bundle_reservation::BundleReservationConfig,
+            min_tip_policy::MinTipPolicy, DEFAULT_NUM_BUNDLE_CONSUME_WORKERS,
+        },
         cluster_info_vote_listener::VoteTracker,
         completed_data_sets_service::CompletedDataSetsService,
         consensus::{
@@ -14,7 +18,14 @@ use {
             ExternalRootSource, Tower,
         },
         poh_timing_report_service::PohTimingReportService,
-        proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
+        proxy::{
+            block_engine_region_selector::BlockEngineRegions,
+            block_engine_stage::{BlockEngineConfig, BlockEngineHealth},
+            packet_source_accounting::PacketSourceAccounting,
+            packet_trust::PacketTrust,
+            relayer_stage::RelayerConfig,
+            vortexor_receiver::VortexorPeers,
+        },
         repair::{
             self,
             quic_endpoint::{RepairQuicAsyncSenders, RepairQuicSenders, RepairQuicSockets},
@@ -44,6 +55,7 @@ use {
         },
         utils::{move_and_async_delete_path, move_and_async_delete_path_contents},
     },
+    solana_bundle::bundle_simulation_cache::BundleSimulationCache,
     solana_client::connection_cache::{ConnectionCache, Protocol},
     solana_entry::poh::compute_hash_time,
     solana_geyser_plugin_manager::{
@@ -114,8 +126,9 @@ use {
         snapshot_utils::{self, clean_orphaned_account_snapshot_dirs},
     },
     solana_runtime_plugin::{
+        runtime_plugin::PluginDependencies,
         runtime_plugin_admin_rpc_service::RuntimePluginManagerRpcRequest,
-        runtime_plugin_service::RuntimePluginService,
+        runtime_plugin_service::{bank_event_channel, RuntimePluginService},
     },
     solana_sdk::{
         clock::Slot,
@@ -296,7 +309,16 @@ pub struct ValidatorConfig {
     pub shred_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
     pub shred_retransmit_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
     pub tip_manager_config: TipManagerConfig,
-    pub preallocated_bundle_cost: u64,
+    pub bundle_reservation_config: BundleReservationConfig,
+    pub bundle_result_fallback_path: Option<PathBuf>,
+    pub bundle_stage_num_workers: usize,
+    pub enable_bundle_simulation_cache: bool,
+    pub min_tip_policy: Arc<RwLock<MinTipPolicy>>,
+    pub bundle_policy: Arc<RwLock<BundlePolicy>>,
+    pub packet_trust: Arc<PacketTrust>,
+    pub packet_source_accounting: Arc<PacketSourceAccounting>,
+    pub vortexor_receiver_address: Option<SocketAddr>,
+    pub vortexor_peers: Arc<VortexorPeers>,
 }
 
 impl Default for ValidatorConfig {
@@ -374,7 +396,16 @@ impl Default for ValidatorConfig {
             shred_receiver_address: Arc::new(RwLock::new(None)),
             shred_retransmit_receiver_address: Arc::new(RwLock::new(None)),
             tip_manager_config: TipManagerConfig::default(),
-            preallocated_bundle_cost: u64::default(),
+            bundle_reservation_config: BundleReservationConfig::default(),
+            bundle_result_fallback_path: None,
+            bundle_stage_num_workers: DEFAULT_NUM_BUNDLE_CONSUME_WORKERS,
+            enable_bundle_simulation_cache: false,
+            min_tip_policy: Arc::new(RwLock::new(MinTipPolicy::default())),
+            bundle_policy: Arc::new(RwLock::new(BundlePolicy::default())),
+            packet_trust: Arc::new(PacketTrust::default()),
+            packet_source_accounting: Arc::new(PacketSourceAccounting::default()),
+            vortexor_receiver_address: None,
+            vortexor_peers: Arc::new(VortexorPeers::default()),
         }
     }
 }
@@ -730,6 +761,17 @@ impl Validator {
             .as_ref()
             .and_then(|geyser_plugin_service| geyser_plugin_service.get_slot_status_notifier());
 
+        let bundle_notifier = geyser_plugin_service
+            .as_ref()
+            .and_then(|geyser_plugin_service| geyser_plugin_service.get_bundle_notifier());
+
+        let banking_transaction_notifier =
+            geyser_plugin_service
+                .as_ref()
+                .and_then(|geyser_plugin_service| {
+                    geyser_plugin_service.get_banking_transaction_notifier()
+                });
+
         info!(
             "Geyser plugin: accounts_update_notifier: {}, transaction_notifier: {}, \
              entry_notifier: {}",
@@ -901,6 +943,12 @@ impl Validator {
         // (by both replay stage and banking stage)
         let prioritization_fee_cache = Arc::new(PrioritizationFeeCache::default());
 
+        // bundles that failed simulation over RPC can be rejected by the bundle stage without
+        // executing them again against the same bank state
+        let bundle_simulation_cache = config
+            .enable_bundle_simulation_cache
+            .then(|| Arc::new(BundleSimulationCache::default()));
+
         match &config.block_verification_method {
             BlockVerificationMethod::BlockstoreProcessor => {
                 info!("no scheduler pool is installed for block verification...");
@@ -999,19 +1047,6 @@ impl Validator {
             None,
         ));
 
-        if let Some((runtime_plugin_configs, request_rx)) = runtime_plugin_configs_and_request_rx {
-            RuntimePluginService::start(
-                &runtime_plugin_configs,
-                request_rx,
-                bank_forks.clone(),
-                block_commitment_cache.clone(),
-                exit.clone(),
-            )
-            .map_err(|e| {
-                ValidatorError::Other(format!("Failed to start runtime plugin service: {e:?}"))
-            })?;
-        }
-
         let max_slots = Arc::new(MaxSlots::default());
 
         let startup_verification_complete;
@@ -1035,6 +1070,31 @@ impl Validator {
         };
         let poh_recorder = Arc::new(RwLock::new(poh_recorder));
 
+        let runtime_plugin_bank_event_sender = if let Some((runtime_plugin_configs, request_rx)) =
+            runtime_plugin_configs_and_request_rx
+        {
+            let (bank_event_sender, bank_event_receiver) = bank_event_channel();
+            RuntimePluginService::start(
+                &runtime_plugin_configs,
+                request_rx,
+                bank_event_receiver,
+                PluginDependencies {
+                    bank_forks: bank_forks.clone(),
+                    block_commitment_cache: block_commitment_cache.clone(),
+                    exit: exit.clone(),
+                    cluster_info: cluster_info.clone(),
+                    leader_schedule_cache: leader_schedule_cache.clone(),
+                    poh_recorder: poh_recorder.clone(),
+                },
+            )
+            .map_err(|e| {
+                ValidatorError::Other(format!("Failed to start runtime plugin service: {e:?}"))
+            })?;
+            Some(bank_event_sender)
+        } else {
+            None
+        };
+
         let staked_nodes = Arc::new(RwLock::new(StakedNodes::default()));
 
         let connection_cache = if use_quic {
@@ -1132,6 +1192,7 @@ impl Validator {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache.clone(),
+                bundle_simulation_cache.clone(),
             )
             .map_err(ValidatorError::Other)?;
 
@@ -1464,6 +1525,7 @@ impl Validator {
             replay_vote_sender.clone(),
             completed_data_sets_sender,
             bank_notification_sender.clone(),
+            runtime_plugin_bank_event_sender,
             duplicate_confirmed_slots_receiver,
             TvuConfig {
                 max_ledger_shreds: config.max_ledger_shreds,
@@ -1518,6 +1580,8 @@ impl Validator {
             return Err(ValidatorError::WenRestartFinished.into());
         }
 
+        let block_engine_health = Arc::new(RwLock::new(Vec::new()));
+        let block_engine_regions = Arc::new(RwLock::new(BlockEngineRegions::default()));
         let (tpu, mut key_notifies) = Tpu::new(
             &cluster_info,
             &poh_recorder,
@@ -1566,7 +1630,20 @@ impl Validator {
             config.relayer_config.clone(),
             config.tip_manager_config.clone(),
             config.shred_receiver_address.clone(),
-            config.preallocated_bundle_cost,
+            config.bundle_reservation_config.clone(),
+            config.bundle_result_fallback_path.clone(),
+            config.bundle_stage_num_workers,
+            bundle_simulation_cache,
+            config.min_tip_policy.clone(),
+            config.bundle_policy.clone(),
+            block_engine_health.clone(),
+            block_engine_regions.clone(),
+            config.packet_trust.clone(),
+            config.packet_source_accounting.clone(),
+            config.vortexor_receiver_address,
+            config.vortexor_peers.clone(),
+            bundle_notifier,
+            banking_transaction_notifier,
         );
 
         datapoint_info!(
@@ -1595,6 +1672,13 @@ impl Validator {
             relayer_config: config.relayer_config.clone(),
             shred_receiver_address: config.shred_receiver_address.clone(),
             shred_retransmit_receiver_address: config.shred_retransmit_receiver_address.clone(),
+            min_tip_policy: config.min_tip_policy.clone(),
+            bundle_policy: config.bundle_policy.clone(),
+            block_engine_health,
+            block_engine_regions,
+            packet_trust: config.packet_trust.clone(),
+            packet_source_accounting: config.packet_source_accounting.clone(),
+            vortexor_peers: config.vortexor_peers.clone(),
         });
 
         Ok(Self {
@@ -6,6 +6,7 @@ use {
     solana_clock::{Slot, UnixTimestamp},
     solana_signature::Signature,
     solana_transaction::sanitized::SanitizedTransaction,
+    solana_transaction_error::TransactionError,
     solana_transaction_status::{Reward, RewardsAndNumPartitions, TransactionStatusMeta},
     std::{any::Any, error, io},
     thiserror::Error,
@@ -272,6 +273,117 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_4(&'a ReplicaBlockInfoV4<'a>),
 }
 
+/// The outcome of a bundle execution attempt by the leader's BundleStage
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub enum BundleOutcome {
+    /// The bundle was committed to the block
+    Landed,
+    /// A transaction that wasn't allowed to fail failed
+    TransactionFailure,
+    /// The bundle took too long to execute
+    ExecutionTimeout,
+    /// The bundle didn't pay a valid tip, or paid less than the minimum tip
+    TipFailure,
+    /// The bundle doesn't fit in the block's cost limits
+    ExceedsCostModel,
+    /// The bundle's accounts couldn't be locked
+    LockFailure,
+    /// The leader slot ended before the bundle was committed
+    SlotExpired,
+    /// Any other error
+    InternalError,
+}
+
+/// Lamports paid by a bundle to one of the tip accounts
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ReplicaBundleTip<'a> {
+    /// The Pubkey of the tip account
+    pub tip_account: &'a [u8],
+    pub lamports: u64,
+}
+
+/// Information about a bundle executed by the leader's BundleStage. The transactions of a
+/// landed bundle are committed atomically, in order, in the block of `slot`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ReplicaBundleInfo<'a> {
+    /// Derived from the signatures of the bundle's transactions
+    pub bundle_id: &'a str,
+    /// The slot the bundle was executed in
+    pub slot: Slot,
+    /// The first signature of each of the bundle's transactions, in execution order
+    pub signatures: &'a [Signature],
+    /// The tip accounts that were paid by the bundle, empty unless it landed
+    pub tips: &'a [ReplicaBundleTip<'a>],
+    pub outcome: BundleOutcome,
+    /// Why the bundle didn't land
+    pub error: Option<&'a str>,
+}
+
+/// A wrapper to future-proof ReplicaBundleInfo handling. To make a change to the structure of
+/// ReplicaBundleInfo, add an new enum variant wrapping a newer version, which will force plugin
+/// implementations to handle the change.
+#[repr(u32)]
+pub enum ReplicaBundleInfoVersions<'a> {
+    V0_0_1(&'a ReplicaBundleInfo<'a>),
+}
+
+/// How a transaction buffered by the leader's BankingStage left it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub enum BankingTransactionDisposition {
+    /// The transaction was committed to the block, `error` is set if its execution failed
+    Committed,
+    /// The transaction was executed, or attempted to be, but couldn't be committed
+    NotCommitted,
+    /// The transaction was already processed or failed the fee payer checks
+    Dropped,
+    /// The buffer was full and the transaction was evicted by a higher priority one
+    Evicted,
+    /// The transaction was forwarded to the upcoming leaders
+    Forwarded,
+    /// The transaction was discarded unchecked while the node wasn't leader, or when there
+    /// wasn't enough time left to check it
+    Cleared,
+    /// The transaction couldn't be processed, because its accounts were in use, the block was
+    /// full or the leader slot ended, and was put back in the buffer. It's notified again when
+    /// it leaves BankingStage.
+    Retried,
+    /// The transaction's blockhash expired before it could be processed
+    Expired,
+    /// The transaction would have exceeded the cost model's limits that can't be retried in the
+    /// block, e.g. the total accounts data size
+    DroppedByCostModel,
+}
+
+/// The outcome of a transaction buffered by the leader's BankingStage. Timestamps are in
+/// microseconds since the UNIX epoch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ReplicaBankingTransactionInfo<'a> {
+    /// The first signature of the transaction, used for identifying the transaction.
+    pub signature: &'a Signature,
+    /// The slot of the bank the transaction was executed or checked against, if any
+    pub slot: Option<Slot>,
+    /// When BankingStage buffered the transaction
+    pub received_timestamp_us: u64,
+    /// When the transaction was last scheduled for execution, if ever
+    pub scheduled_timestamp_us: Option<u64>,
+    pub disposition: BankingTransactionDisposition,
+    /// Why the transaction wasn't committed, or why its execution failed
+    pub error: Option<&'a TransactionError>,
+}
+
+/// A wrapper to future-proof ReplicaBankingTransactionInfo handling. To make a change to the
+/// structure of ReplicaBankingTransactionInfo, add an new enum variant wrapping a newer version,
+/// which will force plugin implementations to handle the change.
+#[repr(u32)]
+pub enum ReplicaBankingTransactionInfoVersions<'a> {
+    V0_0_1(&'a ReplicaBankingTransactionInfo<'a>),
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 #[repr(u32)]
@@ -448,6 +560,24 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called when the leader's BundleStage is done executing a bundle, whether it landed or not.
+    /// Notifications are dropped when the plugins can't keep up with BundleStage.
+    #[allow(unused_variables)]
+    fn notify_bundle(&self, bundle: ReplicaBundleInfoVersions) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when a transaction leaves the leader's BankingStage, whether it was committed,
+    /// dropped or forwarded, and every time it's retried. Vote transactions aren't notified.
+    /// Notifications are dropped when the plugins can't keep up with BankingStage.
+    #[allow(unused_variables)]
+    fn notify_banking_transaction(
+        &self,
+        transaction: ReplicaBankingTransactionInfoVersions,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
@@ -476,4 +606,19 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     fn entry_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in bundle data
+    /// Default is false -- if the plugin is interested in
+    /// bundle data, return true.
+    fn bundle_notifications_enabled(&self) -> bool {
+        false
+    }
+
+    /// Check if the plugin is interested in the outcomes of the transactions
+    /// buffered by BankingStage
+    /// Default is false -- if the plugin is interested in
+    /// banking transaction data, return true.
+    fn banking_transaction_notifications_enabled(&self) -> bool {
+        false
+    }
 }
@@ -0,0 +1,95 @@
+/// Module responsible for notifying plugins of the outcomes of the transactions buffered by
+/// BankingStage
+use {
+    crate::{
+        banking_transaction_notifier_interface::BankingTransactionNotifier,
+        geyser_plugin_manager::GeyserPluginManager,
+    },
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        BankingTransactionDisposition, ReplicaBankingTransactionInfo,
+        ReplicaBankingTransactionInfoVersions,
+    },
+    log::*,
+    solana_clock::Slot,
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_signature::Signature,
+    solana_transaction_error::TransactionError,
+    std::sync::{Arc, RwLock},
+};
+
+/// This implementation of BankingTransactionNotifier is passed to BankingStage at the validator
+/// startup. BankingStage's notifier thread invokes the notify_banking_transaction method for every
+/// buffered transaction when it's retried, committed, dropped or forwarded. The implementation in
+/// turn invokes the notify_banking_transaction of each plugin enabled with banking transaction
+/// notification managed by the GeyserPluginManager.
+pub(crate) struct BankingTransactionNotifierImpl {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+}
+
+impl BankingTransactionNotifier for BankingTransactionNotifierImpl {
+    fn notify_banking_transaction(
+        &self,
+        signature: &Signature,
+        slot: Option<Slot>,
+        received_timestamp_us: u64,
+        scheduled_timestamp_us: Option<u64>,
+        disposition: BankingTransactionDisposition,
+        error: Option<&TransactionError>,
+    ) {
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let mut measure = Measure::start("geyser-plugin-notify_plugins_of_banking_transaction");
+        let transaction_info = ReplicaBankingTransactionInfo {
+            signature,
+            slot,
+            received_timestamp_us,
+            scheduled_timestamp_us,
+            disposition,
+            error,
+        };
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.banking_transaction_notifications_enabled() {
+                continue;
+            }
+            match plugin.notify_banking_transaction(ReplicaBankingTransactionInfoVersions::V0_0_1(
+                &transaction_info,
+            )) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify banking transaction {} ({:?}), error: {} to plugin {}",
+                        signature,
+                        disposition,
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified banking transaction {} ({:?}) to plugin {}",
+                        signature,
+                        disposition,
+                        plugin.name()
+                    );
+                }
+            }
+        }
+        measure.stop();
+        inc_new_counter_debug!(
+            "geyser-plugin-notify_plugins_of_banking_transaction-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+    }
+}
+
+impl BankingTransactionNotifierImpl {
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self { plugin_manager }
+    }
+}
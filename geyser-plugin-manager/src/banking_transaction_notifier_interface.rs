@@ -0,0 +1,23 @@
+use {
+    agave_geyser_plugin_interface::geyser_plugin_interface::BankingTransactionDisposition,
+    solana_clock::Slot, solana_signature::Signature, solana_transaction_error::TransactionError,
+    std::sync::Arc,
+};
+
+/// Interface for notifying the outcomes of the transactions buffered by the leader's
+/// BankingStage
+pub trait BankingTransactionNotifier {
+    /// Notify a transaction leaving BankingStage, timestamps are in microseconds since the
+    /// UNIX epoch
+    fn notify_banking_transaction(
+        &self,
+        signature: &Signature,
+        slot: Option<Slot>,
+        received_timestamp_us: u64,
+        scheduled_timestamp_us: Option<u64>,
+        disposition: BankingTransactionDisposition,
+        error: Option<&TransactionError>,
+    );
+}
+
+pub type BankingTransactionNotifierArc = Arc<dyn BankingTransactionNotifier + Sync + Send>;
@@ -0,0 +1,96 @@
+/// Module responsible for notifying plugins of the bundles executed by BundleStage
+use {
+    crate::{
+        bundle_notifier_interface::BundleNotifier, geyser_plugin_manager::GeyserPluginManager,
+    },
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        BundleOutcome, ReplicaBundleInfo, ReplicaBundleInfoVersions, ReplicaBundleTip,
+    },
+    log::*,
+    solana_clock::Slot,
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    std::sync::{Arc, RwLock},
+};
+
+/// This implementation of BundleNotifier is passed to BundleStage at the validator startup.
+/// BundleStage's notifier thread invokes the notify_bundle method for every bundle it executes
+/// while leader. The implementation in turn invokes the notify_bundle of each plugin enabled with
+/// bundle notification managed by the GeyserPluginManager.
+pub(crate) struct BundleNotifierImpl {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+}
+
+impl BundleNotifier for BundleNotifierImpl {
+    fn notify_bundle(
+        &self,
+        bundle_id: &str,
+        slot: Slot,
+        signatures: &[Signature],
+        tips: &[(Pubkey, u64)],
+        outcome: BundleOutcome,
+        error: Option<&str>,
+    ) {
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let mut measure = Measure::start("geyser-plugin-notify_plugins_of_bundle_info");
+        let tips: Vec<_> = tips
+            .iter()
+            .map(|(tip_account, lamports)| ReplicaBundleTip {
+                tip_account: tip_account.as_ref(),
+                lamports: *lamports,
+            })
+            .collect();
+        let bundle_info = ReplicaBundleInfo {
+            bundle_id,
+            slot,
+            signatures,
+            tips: &tips,
+            outcome,
+            error,
+        };
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.bundle_notifications_enabled() {
+                continue;
+            }
+            match plugin.notify_bundle(ReplicaBundleInfoVersions::V0_0_1(&bundle_info)) {
+                Err(err) => {
+                    error!(
+                        "Failed to notify bundle {} at slot {}, error: {} to plugin {}",
+                        bundle_id,
+                        slot,
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified bundle {} at slot {} to plugin {}",
+                        bundle_id,
+                        slot,
+                        plugin.name()
+                    );
+                }
+            }
+        }
+        measure.stop();
+        inc_new_counter_debug!(
+            "geyser-plugin-notify_plugins_of_bundle_info-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+    }
+}
+
+impl BundleNotifierImpl {
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self { plugin_manager }
+    }
+}
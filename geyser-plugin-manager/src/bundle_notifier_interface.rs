@@ -0,0 +1,20 @@
+use {
+    agave_geyser_plugin_interface::geyser_plugin_interface::BundleOutcome, solana_clock::Slot,
+    solana_pubkey::Pubkey, solana_signature::Signature, std::sync::Arc,
+};
+
+/// Interface for notifying the bundles executed by the leader's BundleStage
+pub trait BundleNotifier {
+    /// Notify a bundle execution attempt, `tips` are the lamports paid to each tip account
+    fn notify_bundle(
+        &self,
+        bundle_id: &str,
+        slot: Slot,
+        signatures: &[Signature],
+        tips: &[(Pubkey, u64)],
+        outcome: BundleOutcome,
+        error: Option<&str>,
+    );
+}
+
+pub type BundleNotifierArc = Arc<dyn BundleNotifier + Sync + Send>;
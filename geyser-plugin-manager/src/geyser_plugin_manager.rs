@@ -115,6 +115,26 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin interested in bundle data
+    pub fn bundle_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.bundle_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Check if there is any plugin interested in banking transaction data
+    pub fn banking_transaction_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.banking_transaction_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Admin RPC request handler
     pub(crate) fn list_plugins(&self) -> JsonRpcResult<Vec<String>> {
         Ok(self.plugins.iter().map(|p| p.name().to_owned()).collect())
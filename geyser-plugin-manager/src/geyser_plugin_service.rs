@@ -1,8 +1,12 @@
 use {
     crate::{
         accounts_update_notifier::AccountsUpdateNotifierImpl,
+        banking_transaction_notifier::BankingTransactionNotifierImpl,
+        banking_transaction_notifier_interface::BankingTransactionNotifierArc,
         block_metadata_notifier::BlockMetadataNotifierImpl,
         block_metadata_notifier_interface::BlockMetadataNotifierArc,
+        bundle_notifier::BundleNotifierImpl,
+        bundle_notifier_interface::BundleNotifierArc,
         entry_notifier::EntryNotifierImpl,
         geyser_plugin_manager::{GeyserPluginManager, GeyserPluginManagerRequest},
         slot_status_notifier::SlotStatusNotifierImpl,
@@ -39,6 +43,8 @@ pub struct GeyserPluginService {
     entry_notifier: Option<EntryNotifierArc>,
     block_metadata_notifier: Option<BlockMetadataNotifierArc>,
     slot_status_notifier: Option<SlotStatusNotifier>,
+    bundle_notifier: Option<BundleNotifierArc>,
+    banking_transaction_notifier: Option<BankingTransactionNotifierArc>,
 }
 
 impl GeyserPluginService {
@@ -96,6 +102,11 @@ impl GeyserPluginService {
             plugin_manager.transaction_notifications_enabled() || geyser_plugin_always_enabled;
         let entry_notifications_enabled =
             plugin_manager.entry_notifications_enabled() || geyser_plugin_always_enabled;
+        let bundle_notifications_enabled =
+            plugin_manager.bundle_notifications_enabled() || geyser_plugin_always_enabled;
+        let banking_transaction_notifications_enabled = plugin_manager
+            .banking_transaction_notifications_enabled()
+            || geyser_plugin_always_enabled;
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
         let accounts_update_notifier: Option<AccountsUpdateNotifier> =
@@ -124,6 +135,22 @@ impl GeyserPluginService {
             None
         };
 
+        let bundle_notifier: Option<BundleNotifierArc> = if bundle_notifications_enabled {
+            let bundle_notifier = BundleNotifierImpl::new(plugin_manager.clone());
+            Some(Arc::new(bundle_notifier))
+        } else {
+            None
+        };
+
+        let banking_transaction_notifier: Option<BankingTransactionNotifierArc> =
+            if banking_transaction_notifications_enabled {
+                let banking_transaction_notifier =
+                    BankingTransactionNotifierImpl::new(plugin_manager.clone());
+                Some(Arc::new(banking_transaction_notifier))
+            } else {
+                None
+            };
+
         let (slot_status_observer, block_metadata_notifier, slot_status_notifier): (
             Option<SlotStatusObserver>,
             Option<BlockMetadataNotifierArc>,
@@ -163,6 +190,8 @@ impl GeyserPluginService {
             entry_notifier,
             block_metadata_notifier,
             slot_status_notifier,
+            bundle_notifier,
+            banking_transaction_notifier,
         })
     }
 
@@ -196,6 +225,14 @@ impl GeyserPluginService {
         self.slot_status_notifier.clone()
     }
 
+    pub fn get_bundle_notifier(&self) -> Option<BundleNotifierArc> {
+        self.bundle_notifier.clone()
+    }
+
+    pub fn get_banking_transaction_notifier(&self) -> Option<BankingTransactionNotifierArc> {
+        self.banking_transaction_notifier.clone()
+    }
+
     pub fn join(self) -> thread::Result<()> {
         if let Some(mut slot_status_observer) = self.slot_status_observer {
             slot_status_observer.join()?;
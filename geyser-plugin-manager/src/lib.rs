@@ -1,6 +1,10 @@
 pub mod accounts_update_notifier;
+pub mod banking_transaction_notifier;
+pub mod banking_transaction_notifier_interface;
 pub mod block_metadata_notifier;
 pub mod block_metadata_notifier_interface;
+pub mod bundle_notifier;
+pub mod bundle_notifier_interface;
 pub mod entry_notifier;
 pub mod geyser_plugin_manager;
 pub mod geyser_plugin_service;
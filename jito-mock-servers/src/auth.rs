@@ -0,0 +1,221 @@
+//! Mock auth service. Issues tokens for any keypair that signs its challenge, and checks the access
+//! token sent with every request to the other services of the same server.
+use {
+    jito_protos::proto::auth::{
+        auth_service_server::AuthService, GenerateAuthChallengeRequest,
+        GenerateAuthChallengeResponse, GenerateAuthTokensRequest, GenerateAuthTokensResponse,
+        RefreshAccessTokenRequest, RefreshAccessTokenResponse, Token,
+    },
+    solana_sdk::{pubkey::Pubkey, signature::Signature},
+    std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc, Mutex, RwLock,
+        },
+        time::{Duration, SystemTime},
+    },
+    tonic::{Request, Response, Status},
+};
+
+#[derive(Clone, Debug)]
+pub struct MockAuthConfig {
+    /// How long access tokens are valid for after they're issued or refreshed
+    pub access_token_ttl: Duration,
+    /// How long refresh tokens are valid for, after which the client has to sign a new challenge
+    pub refresh_token_ttl: Duration,
+}
+
+impl Default for MockAuthConfig {
+    fn default() -> Self {
+        Self {
+            access_token_ttl: Duration::from_secs(30 * 60),
+            refresh_token_ttl: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MockAuthStats {
+    pub num_challenges: AtomicU64,
+    pub num_tokens_generated: AtomicU64,
+    pub num_access_tokens_refreshed: AtomicU64,
+    pub num_permission_denied: AtomicU64,
+    /// Requests rejected because of a missing, unknown or expired access token
+    pub num_unauthenticated: AtomicU64,
+}
+
+/// Auth state shared by all services of a mock server
+#[derive(Debug, Default)]
+pub struct MockAuth {
+    config: RwLock<MockAuthConfig>,
+    deny_permission: AtomicBool,
+    // outstanding challenges by client pubkey
+    challenges: Mutex<HashMap<Pubkey, String>>,
+    // token value -> expiry
+    access_tokens: Mutex<HashMap<String, SystemTime>>,
+    refresh_tokens: Mutex<HashMap<String, SystemTime>>,
+    stats: MockAuthStats,
+}
+
+impl MockAuth {
+    pub fn new(config: MockAuthConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            ..Self::default()
+        }
+    }
+
+    /// Applies to tokens issued from now on
+    pub fn set_config(&self, config: MockAuthConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    /// When set, auth challenges are rejected with PermissionDenied, the way the Block Engine
+    /// rejects validators that aren't on the leader schedule
+    pub fn set_deny_permission(&self, deny_permission: bool) {
+        self.deny_permission
+            .store(deny_permission, Ordering::Relaxed);
+    }
+
+    /// Invalidates every token issued so far. Clients have to go through the challenge again.
+    pub fn expire_tokens(&self) {
+        self.access_tokens.lock().unwrap().clear();
+        self.refresh_tokens.lock().unwrap().clear();
+    }
+
+    pub fn stats(&self) -> &MockAuthStats {
+        &self.stats
+    }
+
+    /// Checks the bearer token in the request metadata
+    pub(crate) fn authenticate<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let valid = token.is_some_and(|token| {
+            self.access_tokens
+                .lock()
+                .unwrap()
+                .get(token)
+                .is_some_and(|expiry| *expiry > SystemTime::now())
+        });
+        if valid {
+            Ok(())
+        } else {
+            self.stats
+                .num_unauthenticated
+                .fetch_add(1, Ordering::Relaxed);
+            Err(Status::unauthenticated("missing or expired access token"))
+        }
+    }
+
+    fn issue_token(tokens: &Mutex<HashMap<String, SystemTime>>, ttl: Duration) -> Token {
+        let value = Pubkey::new_unique().to_string();
+        let expiry = SystemTime::now() + ttl;
+        tokens.lock().unwrap().insert(value.clone(), expiry);
+        Token {
+            value,
+            expires_at_utc: Some(prost_types::Timestamp::from(expiry)),
+        }
+    }
+}
+
+pub(crate) struct MockAuthService {
+    pub(crate) auth: Arc<MockAuth>,
+}
+
+#[tonic::async_trait]
+impl AuthService for MockAuthService {
+    async fn generate_auth_challenge(
+        &self,
+        request: Request<GenerateAuthChallengeRequest>,
+    ) -> Result<Response<GenerateAuthChallengeResponse>, Status> {
+        let auth = &self.auth;
+        auth.stats.num_challenges.fetch_add(1, Ordering::Relaxed);
+        if auth.deny_permission.load(Ordering::Relaxed) {
+            auth.stats
+                .num_permission_denied
+                .fetch_add(1, Ordering::Relaxed);
+            return Err(Status::permission_denied("validator not allowed"));
+        }
+
+        let pubkey = Pubkey::try_from(request.into_inner().pubkey.as_slice())
+            .map_err(|_| Status::invalid_argument("invalid pubkey"))?;
+        let challenge = Pubkey::new_unique().to_string();
+        auth.challenges
+            .lock()
+            .unwrap()
+            .insert(pubkey, challenge.clone());
+
+        Ok(Response::new(GenerateAuthChallengeResponse { challenge }))
+    }
+
+    async fn generate_auth_tokens(
+        &self,
+        request: Request<GenerateAuthTokensRequest>,
+    ) -> Result<Response<GenerateAuthTokensResponse>, Status> {
+        let auth = &self.auth;
+        let request = request.into_inner();
+        let pubkey = Pubkey::try_from(request.client_pubkey.as_slice())
+            .map_err(|_| Status::invalid_argument("invalid pubkey"))?;
+        let challenge = auth
+            .challenges
+            .lock()
+            .unwrap()
+            .remove(&pubkey)
+            .ok_or_else(|| Status::failed_precondition("no outstanding challenge"))?;
+        if request.challenge != format!("{pubkey}-{challenge}") {
+            return Err(Status::invalid_argument("wrong challenge"));
+        }
+        let signature = Signature::try_from(request.signed_challenge.as_slice())
+            .map_err(|_| Status::invalid_argument("invalid signature"))?;
+        if !signature.verify(pubkey.as_ref(), request.challenge.as_bytes()) {
+            return Err(Status::unauthenticated("challenge signature mismatch"));
+        }
+
+        let config = auth.config.read().unwrap().clone();
+        let access_token = MockAuth::issue_token(&auth.access_tokens, config.access_token_ttl);
+        let refresh_token = MockAuth::issue_token(&auth.refresh_tokens, config.refresh_token_ttl);
+        auth.stats
+            .num_tokens_generated
+            .fetch_add(1, Ordering::Relaxed);
+
+        Ok(Response::new(GenerateAuthTokensResponse {
+            access_token: Some(access_token),
+            refresh_token: Some(refresh_token),
+        }))
+    }
+
+    async fn refresh_access_token(
+        &self,
+        request: Request<RefreshAccessTokenRequest>,
+    ) -> Result<Response<RefreshAccessTokenResponse>, Status> {
+        let auth = &self.auth;
+        let refresh_token = request.into_inner().refresh_token;
+        let valid = auth
+            .refresh_tokens
+            .lock()
+            .unwrap()
+            .get(&refresh_token)
+            .is_some_and(|expiry| *expiry > SystemTime::now());
+        if !valid {
+            auth.stats
+                .num_unauthenticated
+                .fetch_add(1, Ordering::Relaxed);
+            return Err(Status::unauthenticated("missing or expired refresh token"));
+        }
+
+        let access_token_ttl = auth.config.read().unwrap().access_token_ttl;
+        let access_token = MockAuth::issue_token(&auth.access_tokens, access_token_ttl);
+        auth.stats
+            .num_access_tokens_refreshed
+            .fetch_add(1, Ordering::Relaxed);
+
+        Ok(Response::new(RefreshAccessTokenResponse {
+            access_token: Some(access_token),
+        }))
+    }
+}
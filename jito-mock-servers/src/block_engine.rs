@@ -0,0 +1,259 @@
+//! Mock Block Engine serving the BlockEngineValidator, BundleFeedback and auth services on one port,
+//! like the real one.
+use {
+    crate::{
+        auth::{MockAuth, MockAuthConfig, MockAuthService},
+        header, transaction_to_proto_packet, wait_for, MockServer,
+    },
+    jito_protos::proto::{
+        auth::auth_service_server::AuthServiceServer,
+        block_engine::{
+            block_engine_validator_server::{BlockEngineValidator, BlockEngineValidatorServer},
+            BlockBuilderFeeInfoRequest, BlockBuilderFeeInfoResponse, SubscribeBundlesRequest,
+            SubscribeBundlesResponse, SubscribePacketsRequest, SubscribePacketsResponse,
+        },
+        bundle::{Bundle, BundleUuid},
+        bundle_feedback::{
+            bundle_feedback_server::{BundleFeedback, BundleFeedbackServer},
+            BundleResult, SubmitBundleResultsResponse,
+        },
+        packet::PacketBatch,
+    },
+    solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction},
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    },
+    tokio::{sync::mpsc, time::interval},
+    tokio_stream::wrappers::ReceiverStream,
+    tonic::{transport::Server, Request, Response, Status, Streaming},
+};
+
+/// Empty packet batches are sent at this interval while heartbeats are enabled
+const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(500);
+const SUBSCRIBER_BUFFER_CAPACITY: usize = 1_000;
+
+type BundleSubscriber = mpsc::Sender<Result<SubscribeBundlesResponse, Status>>;
+type PacketSubscriber = mpsc::Sender<Result<SubscribePacketsResponse, Status>>;
+
+#[derive(Default)]
+struct BlockEngineState {
+    bundle_subscribers: Mutex<Vec<BundleSubscriber>>,
+    packet_subscribers: Mutex<Vec<PacketSubscriber>>,
+    heartbeats_enabled: AtomicBool,
+    block_builder_fee_info: Mutex<BlockBuilderFeeInfoResponse>,
+    bundle_results: Mutex<Vec<BundleResult>>,
+}
+
+impl BlockEngineState {
+    fn broadcast_packets(&self, response: SubscribePacketsResponse) {
+        self.packet_subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.try_send(Ok(response.clone())).is_ok());
+    }
+}
+
+pub struct MockBlockEngine {
+    server: MockServer,
+    state: Arc<BlockEngineState>,
+    auth: Arc<MockAuth>,
+}
+
+impl MockBlockEngine {
+    pub fn start(auth_config: MockAuthConfig) -> Self {
+        let auth = Arc::new(MockAuth::new(auth_config));
+        let state = Arc::new(BlockEngineState {
+            heartbeats_enabled: AtomicBool::new(true),
+            block_builder_fee_info: Mutex::new(BlockBuilderFeeInfoResponse {
+                pubkey: Pubkey::new_unique().to_string(),
+                commission: 0,
+            }),
+            ..BlockEngineState::default()
+        });
+
+        let router = Server::builder()
+            .add_service(AuthServiceServer::new(MockAuthService {
+                auth: auth.clone(),
+            }))
+            .add_service(BlockEngineValidatorServer::new(
+                BlockEngineValidatorService {
+                    state: state.clone(),
+                    auth: auth.clone(),
+                },
+            ))
+            .add_service(BundleFeedbackServer::new(BundleFeedbackService {
+                state: state.clone(),
+                auth: auth.clone(),
+            }));
+        let server = MockServer::start("BlockEngine", router);
+
+        let heartbeat_state = state.clone();
+        server.spawn(async move {
+            let mut heartbeat_tick = interval(HEARTBEAT_INTERVAL);
+            loop {
+                heartbeat_tick.tick().await;
+                if heartbeat_state.heartbeats_enabled.load(Ordering::Relaxed) {
+                    heartbeat_state.broadcast_packets(SubscribePacketsResponse {
+                        header: Some(header()),
+                        batch: Some(PacketBatch { packets: vec![] }),
+                    });
+                }
+            }
+        });
+
+        Self {
+            server,
+            state,
+            auth,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    pub fn auth(&self) -> &MockAuth {
+        &self.auth
+    }
+
+    /// Number of validators subscribed to bundles
+    pub fn num_bundle_subscribers(&self) -> usize {
+        let mut subscribers = self.state.bundle_subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| !subscriber.is_closed());
+        subscribers.len()
+    }
+
+    pub fn wait_for_bundle_subscribers(&self, num_subscribers: usize, timeout: Duration) -> bool {
+        wait_for(timeout, || self.num_bundle_subscribers() >= num_subscribers)
+    }
+
+    /// Sends the transactions as a bundle to all subscribers and returns the uuid assigned to it
+    pub fn send_bundle(&self, transactions: &[VersionedTransaction]) -> String {
+        let uuid = Pubkey::new_unique().to_string();
+        let response = SubscribeBundlesResponse {
+            bundles: vec![BundleUuid {
+                bundle: Some(Bundle {
+                    header: Some(header()),
+                    packets: transactions
+                        .iter()
+                        .map(transaction_to_proto_packet)
+                        .collect(),
+                    allowed_to_fail_tx_indexes: vec![],
+                }),
+                uuid: uuid.clone(),
+            }],
+        };
+        self.state
+            .bundle_subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.try_send(Ok(response.clone())).is_ok());
+        uuid
+    }
+
+    /// Sends the transactions as a packet batch to all subscribers
+    pub fn send_packets(&self, transactions: &[VersionedTransaction]) {
+        self.state.broadcast_packets(SubscribePacketsResponse {
+            header: Some(header()),
+            batch: Some(PacketBatch {
+                packets: transactions
+                    .iter()
+                    .map(transaction_to_proto_packet)
+                    .collect(),
+            }),
+        });
+    }
+
+    pub fn set_heartbeats_enabled(&self, enabled: bool) {
+        self.state
+            .heartbeats_enabled
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    /// Ends all bundle and packet streams, the validator sees the Block Engine disconnect
+    pub fn disconnect_all(&self) {
+        self.state.bundle_subscribers.lock().unwrap().clear();
+        self.state.packet_subscribers.lock().unwrap().clear();
+    }
+
+    pub fn set_block_builder_fee_info(&self, block_builder: &Pubkey, commission: u64) {
+        *self.state.block_builder_fee_info.lock().unwrap() = BlockBuilderFeeInfoResponse {
+            pubkey: block_builder.to_string(),
+            commission,
+        };
+    }
+
+    /// Bundle results streamed back by validators, in the order they were received
+    pub fn bundle_results(&self) -> Vec<BundleResult> {
+        self.state.bundle_results.lock().unwrap().clone()
+    }
+}
+
+struct BlockEngineValidatorService {
+    state: Arc<BlockEngineState>,
+    auth: Arc<MockAuth>,
+}
+
+#[tonic::async_trait]
+impl BlockEngineValidator for BlockEngineValidatorService {
+    type SubscribePacketsStream = ReceiverStream<Result<SubscribePacketsResponse, Status>>;
+    type SubscribeBundlesStream = ReceiverStream<Result<SubscribeBundlesResponse, Status>>;
+
+    async fn subscribe_packets(
+        &self,
+        request: Request<SubscribePacketsRequest>,
+    ) -> Result<Response<Self::SubscribePacketsStream>, Status> {
+        self.auth.authenticate(&request)?;
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_BUFFER_CAPACITY);
+        self.state.packet_subscribers.lock().unwrap().push(sender);
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn subscribe_bundles(
+        &self,
+        request: Request<SubscribeBundlesRequest>,
+    ) -> Result<Response<Self::SubscribeBundlesStream>, Status> {
+        self.auth.authenticate(&request)?;
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_BUFFER_CAPACITY);
+        self.state.bundle_subscribers.lock().unwrap().push(sender);
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn get_block_builder_fee_info(
+        &self,
+        request: Request<BlockBuilderFeeInfoRequest>,
+    ) -> Result<Response<BlockBuilderFeeInfoResponse>, Status> {
+        self.auth.authenticate(&request)?;
+        Ok(Response::new(
+            self.state.block_builder_fee_info.lock().unwrap().clone(),
+        ))
+    }
+}
+
+struct BundleFeedbackService {
+    state: Arc<BlockEngineState>,
+    auth: Arc<MockAuth>,
+}
+
+#[tonic::async_trait]
+impl BundleFeedback for BundleFeedbackService {
+    async fn submit_bundle_results(
+        &self,
+        request: Request<Streaming<BundleResult>>,
+    ) -> Result<Response<SubmitBundleResultsResponse>, Status> {
+        self.auth.authenticate(&request)?;
+        let mut results = request.into_inner();
+        let mut num_results_received = 0;
+        while let Some(result) = results.message().await? {
+            self.state.bundle_results.lock().unwrap().push(result);
+            num_results_received += 1;
+        }
+        Ok(Response::new(SubmitBundleResultsResponse {
+            num_results_received,
+        }))
+    }
+}
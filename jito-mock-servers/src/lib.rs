@@ -0,0 +1,126 @@
+//! Mock Block Engine, Relayer and auth servers used to exercise BlockEngineStage, RelayerStage and
+//! FetchStageManager in tests.
+//!
+//! Each server runs on its own tokio runtime and listens on an ephemeral localhost port. Tests
+//! script the server through its handle: push bundles and packets to subscribed validators, pause
+//! heartbeats, drop connections, expire auth tokens or deny authentication.
+pub mod auth;
+pub mod block_engine;
+pub mod relayer;
+
+pub use {
+    auth::{MockAuth, MockAuthConfig},
+    block_engine::MockBlockEngine,
+    relayer::MockRelayer,
+};
+use {
+    jito_protos::proto::{
+        packet::{Meta, Packet},
+        shared::Header,
+    },
+    log::*,
+    solana_sdk::transaction::VersionedTransaction,
+    std::{
+        future::Future,
+        net::SocketAddr,
+        thread::sleep,
+        time::{Duration, Instant, SystemTime},
+    },
+    tokio::{
+        net::TcpListener,
+        runtime::{Builder, Runtime},
+        sync::oneshot,
+    },
+    tokio_stream::wrappers::TcpListenerStream,
+    tonic::transport::server::Router,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A gRPC server running in the background until dropped
+struct MockServer {
+    runtime: Runtime,
+    local_addr: SocketAddr,
+    shutdown_sender: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    fn start(name: &str, router: Router) -> Self {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name(format!("mock{name}"))
+            .enable_all()
+            .build()
+            .unwrap();
+        let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+        let name = name.to_string();
+        runtime.spawn(async move {
+            if let Err(e) = router
+                .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                    let _ = shutdown_receiver.await;
+                })
+                .await
+            {
+                error!("mock {name} server error: {e}");
+            }
+        });
+
+        Self {
+            runtime,
+            local_addr,
+            shutdown_sender: Some(shutdown_sender),
+        }
+    }
+
+    fn url(&self) -> String {
+        format!("http://{}", self.local_addr)
+    }
+
+    fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.runtime.spawn(future);
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown_sender) = self.shutdown_sender.take() {
+            let _ = shutdown_sender.send(());
+        }
+    }
+}
+
+/// Polls `condition` until it returns true, returning false if it's still false after `timeout`
+pub fn wait_for(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let start = Instant::now();
+    while !condition() {
+        if start.elapsed() > timeout {
+            return false;
+        }
+        sleep(POLL_INTERVAL);
+    }
+    true
+}
+
+pub fn transaction_to_proto_packet(transaction: &VersionedTransaction) -> Packet {
+    let data = bincode::serialize(transaction).unwrap();
+    Packet {
+        meta: Some(Meta {
+            size: data.len() as u64,
+            addr: "127.0.0.1".to_string(),
+            ..Meta::default()
+        }),
+        data,
+    }
+}
+
+fn header() -> Header {
+    Header {
+        ts: Some(prost_types::Timestamp::from(SystemTime::now())),
+    }
+}
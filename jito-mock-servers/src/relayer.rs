@@ -0,0 +1,191 @@
+//! Mock Relayer serving the Relayer and auth services on one port
+use {
+    crate::{
+        auth::{MockAuth, MockAuthConfig, MockAuthService},
+        header, transaction_to_proto_packet, wait_for, MockServer,
+    },
+    jito_protos::proto::{
+        auth::auth_service_server::AuthServiceServer,
+        packet::PacketBatch,
+        relayer::{
+            relayer_server::{Relayer, RelayerServer},
+            subscribe_packets_response::Msg,
+            GetTpuConfigsRequest, GetTpuConfigsResponse, SubscribePacketsRequest,
+            SubscribePacketsResponse,
+        },
+        shared::{Heartbeat, Socket},
+    },
+    solana_sdk::transaction::VersionedTransaction,
+    std::{
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    },
+    tokio::{sync::mpsc, time::interval},
+    tokio_stream::wrappers::ReceiverStream,
+    tonic::{transport::Server, Request, Response, Status},
+};
+
+/// Heartbeats are sent well within the validator's default expected heartbeat interval
+const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(100);
+const SUBSCRIBER_BUFFER_CAPACITY: usize = 1_000;
+
+type PacketSubscriber = mpsc::Sender<Result<SubscribePacketsResponse, Status>>;
+
+struct RelayerState {
+    packet_subscribers: Mutex<Vec<PacketSubscriber>>,
+    heartbeats_enabled: AtomicBool,
+    heartbeat_count: AtomicU64,
+    tpu_address: SocketAddr,
+    tpu_forward_address: SocketAddr,
+}
+
+impl RelayerState {
+    fn broadcast(&self, msg: Msg) {
+        let response = SubscribePacketsResponse {
+            header: Some(header()),
+            msg: Some(msg),
+        };
+        self.packet_subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.try_send(Ok(response.clone())).is_ok());
+    }
+}
+
+pub struct MockRelayer {
+    server: MockServer,
+    state: Arc<RelayerState>,
+    auth: Arc<MockAuth>,
+}
+
+impl MockRelayer {
+    /// `tpu_address` and `tpu_forward_address` are returned to validators as the Relayer's TPU
+    /// ports, which they advertise in gossip once the Relayer is sending heartbeats
+    pub fn start(
+        auth_config: MockAuthConfig,
+        tpu_address: SocketAddr,
+        tpu_forward_address: SocketAddr,
+    ) -> Self {
+        let auth = Arc::new(MockAuth::new(auth_config));
+        let state = Arc::new(RelayerState {
+            packet_subscribers: Mutex::default(),
+            heartbeats_enabled: AtomicBool::new(true),
+            heartbeat_count: AtomicU64::default(),
+            tpu_address,
+            tpu_forward_address,
+        });
+
+        let router = Server::builder()
+            .add_service(AuthServiceServer::new(MockAuthService {
+                auth: auth.clone(),
+            }))
+            .add_service(RelayerServer::new(RelayerService {
+                state: state.clone(),
+                auth: auth.clone(),
+            }));
+        let server = MockServer::start("Relayer", router);
+
+        let heartbeat_state = state.clone();
+        server.spawn(async move {
+            let mut heartbeat_tick = interval(HEARTBEAT_INTERVAL);
+            loop {
+                heartbeat_tick.tick().await;
+                if heartbeat_state.heartbeats_enabled.load(Ordering::Relaxed) {
+                    let count = heartbeat_state
+                        .heartbeat_count
+                        .fetch_add(1, Ordering::Relaxed);
+                    heartbeat_state.broadcast(Msg::Heartbeat(Heartbeat { count }));
+                }
+            }
+        });
+
+        Self {
+            server,
+            state,
+            auth,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    pub fn auth(&self) -> &MockAuth {
+        &self.auth
+    }
+
+    /// Number of validators subscribed to packets
+    pub fn num_subscribers(&self) -> usize {
+        let mut subscribers = self.state.packet_subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| !subscriber.is_closed());
+        subscribers.len()
+    }
+
+    pub fn wait_for_subscribers(&self, num_subscribers: usize, timeout: Duration) -> bool {
+        wait_for(timeout, || self.num_subscribers() >= num_subscribers)
+    }
+
+    /// Sends the transactions as a packet batch to all subscribers
+    pub fn send_packets(&self, transactions: &[VersionedTransaction]) {
+        self.state.broadcast(Msg::Batch(PacketBatch {
+            packets: transactions
+                .iter()
+                .map(transaction_to_proto_packet)
+                .collect(),
+        }));
+    }
+
+    /// Stopping heartbeats makes validators fall back to their own TPU ports and reconnect
+    pub fn set_heartbeats_enabled(&self, enabled: bool) {
+        self.state
+            .heartbeats_enabled
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    /// Ends all packet streams, the validator sees the Relayer disconnect
+    pub fn disconnect_all(&self) {
+        self.state.packet_subscribers.lock().unwrap().clear();
+    }
+}
+
+struct RelayerService {
+    state: Arc<RelayerState>,
+    auth: Arc<MockAuth>,
+}
+
+fn to_socket(address: &SocketAddr) -> Socket {
+    Socket {
+        ip: address.ip().to_string(),
+        port: address.port() as i64,
+    }
+}
+
+#[tonic::async_trait]
+impl Relayer for RelayerService {
+    type SubscribePacketsStream = ReceiverStream<Result<SubscribePacketsResponse, Status>>;
+
+    async fn get_tpu_configs(
+        &self,
+        request: Request<GetTpuConfigsRequest>,
+    ) -> Result<Response<GetTpuConfigsResponse>, Status> {
+        self.auth.authenticate(&request)?;
+        Ok(Response::new(GetTpuConfigsResponse {
+            tpu: Some(to_socket(&self.state.tpu_address)),
+            tpu_forward: Some(to_socket(&self.state.tpu_forward_address)),
+        }))
+    }
+
+    async fn subscribe_packets(
+        &self,
+        request: Request<SubscribePacketsRequest>,
+    ) -> Result<Response<Self::SubscribePacketsStream>, Status> {
+        self.auth.authenticate(&request)?;
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_BUFFER_CAPACITY);
+        self.state.packet_subscribers.lock().unwrap().push(sender);
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
@@ -23,9 +23,18 @@ fn main() -> Result<(), std::io::Error> {
         protos.push(proto);
     }
 
+    // Protos that haven't made it into the mev-protos submodule yet.
+    let local_proto_base_path = std::path::PathBuf::from("local_protos");
+    let local_proto_files = ["bundle_feedback.proto"];
+    for proto_file in &local_proto_files {
+        let proto = local_proto_base_path.join(proto_file);
+        println!("cargo:rerun-if-changed={}", proto.display());
+        protos.push(proto);
+    }
+
     configure()
         .build_client(true)
-        .build_server(false)
+        .build_server(true)
         .type_attribute(
             "TransactionErrorType",
             "#[cfg_attr(test, derive(enum_iterator::Sequence))]",
@@ -34,5 +43,5 @@ fn main() -> Result<(), std::io::Error> {
             "InstructionErrorType",
             "#[cfg_attr(test, derive(enum_iterator::Sequence))]",
         )
-        .compile(&protos, &[proto_base_path])
+        .compile(&protos, &[proto_base_path, local_proto_base_path])
 }
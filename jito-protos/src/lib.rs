@@ -11,6 +11,10 @@ pub mod proto {
         tonic::include_proto!("bundle");
     }
 
+    pub mod bundle_feedback {
+        tonic::include_proto!("bundle_feedback");
+    }
+
     pub mod packet {
         tonic::include_proto!("packet");
     }
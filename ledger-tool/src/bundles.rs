@@ -0,0 +1,909 @@
+//! The `bundles` subcommand
+//!
+//! Bundles aren't marked in the ledger, so they're found heuristically. BundleStage records the
+//! transactions of a bundle together, in consecutive entries that only hold bundle transactions,
+//! and bundles pay their tip from one of their transactions. A run of consecutive entries holding
+//! at most [MAX_BUNDLE_TRANSACTIONS] non-vote transactions and ending with an entry that pays a
+//! tip account is reported as a bundle. Tips are taken from the transaction status metadata when
+//! the ledger has it and from the system transfers to the tip accounts otherwise.
+
+use {
+    crate::{
+        args::{accounts_db_args, load_genesis_arg, parse_process_options, snapshot_args},
+        error::{LedgerToolError, Result},
+        ledger_path::canonicalize_ledger_path,
+        ledger_utils::{
+            get_program_ids, load_and_process_ledger_or_exit, open_blockstore,
+            open_genesis_config_by, LoadAndProcessLedgerOutput,
+        },
+    },
+    clap::{value_t, value_t_or_exit, App, AppSettings, Arg, ArgMatches, SubCommand},
+    serde_derive::Serialize,
+    solana_bundle::{bundle_execution::load_and_execute_bundle, SanitizedBundle},
+    solana_clap_utils::input_validators::{is_pubkey, is_slot},
+    solana_cli_output::{OutputFormat, QuietDisplay, VerboseDisplay},
+    solana_core::tip_manager::{TipManager, TipManagerConfig},
+    solana_ledger::{
+        blockstore::Blockstore, blockstore_options::AccessType,
+        leader_schedule_cache::LeaderScheduleCache,
+    },
+    solana_runtime::bank::Bank,
+    solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
+    solana_sdk::{
+        bundle::derive_bundle_id,
+        clock::{Slot, MAX_PROCESSING_AGE},
+        message::AccountKeys,
+        native_token::lamports_to_sol,
+        program_utils::limited_deserialize,
+        pubkey::Pubkey,
+        reward_type::RewardType,
+        signature::Signature,
+        system_instruction::SystemInstruction,
+        system_program,
+        transaction::{MessageHash, VersionedTransaction},
+    },
+    solana_transaction_status::TransactionStatusMeta,
+    std::{
+        collections::{BTreeMap, HashSet},
+        fmt::{self, Display, Formatter},
+        ops::Range,
+        path::Path,
+        sync::Arc,
+        time::Duration,
+    },
+};
+
+/// The maximum number of transactions in a bundle
+const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+const MAX_BUNDLE_REPLAY_TIME: Duration = Duration::from_secs(30);
+const DEFAULT_TIP_PAYMENT_PROGRAM_ID: &str = "T1pyyaTNZsKv2WcRAB8oVnk93mLJw2XzjtVYqCsaHqt";
+
+pub trait BundlesSubCommand {
+    fn bundles_subcommand(self) -> Self;
+}
+
+impl BundlesSubCommand for App<'_, '_> {
+    fn bundles_subcommand(self) -> Self {
+        let starting_slot_arg = Arg::with_name("starting_slot")
+            .long("starting-slot")
+            .value_name("SLOT")
+            .validator(is_slot)
+            .takes_value(true)
+            .default_value("0")
+            .help("Start at this slot");
+        let ending_slot_arg = Arg::with_name("ending_slot")
+            .long("ending-slot")
+            .value_name("SLOT")
+            .validator(is_slot)
+            .takes_value(true)
+            .help("The last slot to iterate to");
+        let allow_dead_slots_arg = Arg::with_name("allow_dead_slots")
+            .long("allow-dead-slots")
+            .takes_value(false)
+            .help("Include dead slots as well");
+        let tip_payment_program_id_arg = Arg::with_name("tip_payment_program_id")
+            .long("tip-payment-program-id")
+            .value_name("PUBKEY")
+            .validator(is_pubkey)
+            .takes_value(true)
+            .default_value(DEFAULT_TIP_PAYMENT_PROGRAM_ID)
+            .help("The tip payment program the tip accounts are derived from");
+
+        self.subcommand(
+            SubCommand::with_name("bundles")
+                .about("Find, summarize and replay the bundles in the ledger")
+                .setting(AppSettings::InferSubcommands)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List the bundles found in a range of slots and the tips they paid")
+                        .arg(&starting_slot_arg)
+                        .arg(&ending_slot_arg)
+                        .arg(&allow_dead_slots_arg)
+                        .arg(&tip_payment_program_id_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("tips")
+                        .about("Summarize the tips paid by bundles per slot and per leader")
+                        .arg(&starting_slot_arg)
+                        .arg(&ending_slot_arg)
+                        .arg(&allow_dead_slots_arg)
+                        .arg(&tip_payment_program_id_arg),
+                )
+                .subcommand(
+                    SubCommand::with_name("replay")
+                        .about(
+                            "Re-execute a recorded bundle against the bank of the parent slot to \
+                             reproduce its result",
+                        )
+                        .arg(
+                            Arg::with_name("slot")
+                                .index(1)
+                                .value_name("SLOT")
+                                .validator(is_slot)
+                                .takes_value(true)
+                                .required(true)
+                                .help("Slot the bundle was recorded in"),
+                        )
+                        .arg(
+                            Arg::with_name("signature")
+                                .long("signature")
+                                .value_name("SIGNATURE")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Signature of any transaction in the bundle"),
+                        )
+                        .arg(
+                            Arg::with_name("replay_preceding_transactions")
+                                .long("replay-preceding-transactions")
+                                .takes_value(false)
+                                .help(
+                                    "Execute the transactions recorded in the slot before the \
+                                     bundle first, so the bundle sees the same state it did on \
+                                     the leader",
+                                ),
+                        )
+                        .arg(&tip_payment_program_id_arg)
+                        .arg(&load_genesis_arg())
+                        .args(&accounts_db_args())
+                        .args(&snapshot_args()),
+                ),
+        )
+    }
+}
+
+pub fn bundles_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
+    do_bundles_process_command(ledger_path, matches).unwrap_or_else(|err| {
+        eprintln!("Failed to complete command: {err:?}");
+        std::process::exit(1);
+    });
+}
+
+fn do_bundles_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -> Result<()> {
+    let ledger_path = canonicalize_ledger_path(ledger_path);
+    let verbose_level = matches.occurrences_of("verbose");
+
+    match matches.subcommand() {
+        ("list", Some(arg_matches)) => {
+            let output_format =
+                OutputFormat::from_matches(arg_matches, "output_format", verbose_level > 0);
+            let blockstore = open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let blocks = load_blocks(&blockstore, arg_matches)?;
+            let cli_bundles = CliBundles {
+                bundles: blocks.iter().flat_map(BlockBundles::cli_bundles).collect(),
+            };
+            println!("{}", output_format.formatted_string(&cli_bundles));
+        }
+        ("tips", Some(arg_matches)) => {
+            let output_format =
+                OutputFormat::from_matches(arg_matches, "output_format", verbose_level > 0);
+            let blockstore = open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+            let blocks = load_blocks(&blockstore, arg_matches)?;
+            println!(
+                "{}",
+                output_format.formatted_string(&CliBundleTips::new(&blocks))
+            );
+        }
+        ("replay", Some(arg_matches)) => {
+            let output_format =
+                OutputFormat::from_matches(arg_matches, "output_format", verbose_level > 0);
+            let replay = replay_bundle(&ledger_path, arg_matches)?;
+            println!("{}", output_format.formatted_string(&replay));
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn tip_accounts(matches: &ArgMatches<'_>) -> HashSet<Pubkey> {
+    let tip_payment_program_id = value_t_or_exit!(matches, "tip_payment_program_id", Pubkey);
+    TipManager::new(TipManagerConfig {
+        tip_payment_program_id,
+        ..TipManagerConfig::default()
+    })
+    .get_tip_accounts()
+}
+
+fn load_blocks(blockstore: &Blockstore, matches: &ArgMatches<'_>) -> Result<Vec<BlockBundles>> {
+    let starting_slot = value_t_or_exit!(matches, "starting_slot", Slot);
+    let ending_slot = value_t!(matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
+    let allow_dead_slots = matches.is_present("allow_dead_slots");
+    let tip_accounts = tip_accounts(matches);
+
+    let mut blocks = vec![];
+    for (slot, _) in blockstore
+        .slot_meta_iterator(starting_slot)?
+        .take_while(|(slot, _)| *slot <= ending_slot)
+    {
+        match BlockBundles::load(blockstore, slot, &tip_accounts, allow_dead_slots) {
+            Ok(block) => blocks.push(block),
+            Err(err) => eprintln!("Slot {slot}: skipping, {err}"),
+        }
+    }
+    Ok(blocks)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct EntrySummary {
+    num_transactions: usize,
+    has_votes: bool,
+    pays_tip: bool,
+}
+
+/// Returns the ranges of entries holding the bundles of a block
+fn find_bundles(entries: &[EntrySummary]) -> Vec<Range<usize>> {
+    let mut bundles = vec![];
+    let mut run_start = 0;
+    let mut run_transactions = 0;
+    for (index, entry) in entries.iter().enumerate() {
+        // ticks and entries from BankingStage split runs
+        if entry.num_transactions == 0
+            || entry.num_transactions > MAX_BUNDLE_TRANSACTIONS
+            || entry.has_votes
+        {
+            run_start = index + 1;
+            run_transactions = 0;
+            continue;
+        }
+
+        // keep the run within the bundle size by dropping its oldest entries
+        run_transactions += entry.num_transactions;
+        while run_transactions > MAX_BUNDLE_TRANSACTIONS {
+            run_transactions -= entries[run_start].num_transactions;
+            run_start += 1;
+        }
+
+        if entry.pays_tip {
+            bundles.push(run_start..index + 1);
+            run_start = index + 1;
+            run_transactions = 0;
+        }
+    }
+    bundles
+}
+
+/// Tips paid by the transaction to any of the tip accounts
+fn transaction_tip_lamports(
+    transaction: &VersionedTransaction,
+    meta: Option<&TransactionStatusMeta>,
+    tip_accounts: &HashSet<Pubkey>,
+) -> u64 {
+    let static_account_keys = transaction.message.static_account_keys();
+    if let Some(meta) = meta {
+        return AccountKeys::new(static_account_keys, Some(&meta.loaded_addresses))
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| tip_accounts.contains(*key))
+            .filter_map(|(index, _)| {
+                Some(
+                    meta.post_balances
+                        .get(index)?
+                        .saturating_sub(*meta.pre_balances.get(index)?),
+                )
+            })
+            .fold(0, u64::saturating_add);
+    }
+
+    transaction
+        .message
+        .instructions()
+        .iter()
+        .filter(|ix| {
+            static_account_keys
+                .get(usize::from(ix.program_id_index))
+                .is_some_and(system_program::check_id)
+        })
+        .filter_map(|ix| {
+            let SystemInstruction::Transfer { lamports } = limited_deserialize(&ix.data).ok()?
+            else {
+                return None;
+            };
+            let to = static_account_keys.get(usize::from(*ix.accounts.get(1)?))?;
+            tip_accounts.contains(to).then_some(lamports)
+        })
+        .fold(0, u64::saturating_add)
+}
+
+/// The leader is credited with the transaction fees of its block
+fn slot_leader(blockstore: &Blockstore, slot: Slot) -> Option<String> {
+    blockstore
+        .read_rewards(slot)
+        .ok()
+        .flatten()?
+        .into_iter()
+        .find(|reward| reward.reward_type == Some(RewardType::Fee))
+        .map(|reward| reward.pubkey)
+}
+
+struct Bundle {
+    entries: Range<usize>,
+    /// Index of the first transaction of the bundle in the block
+    transaction_index: usize,
+    tip_lamports: u64,
+}
+
+/// The transactions of a block grouped by entry and the bundles found in them
+struct BlockBundles {
+    slot: Slot,
+    leader: Option<String>,
+    entries: Vec<Vec<VersionedTransaction>>,
+    bundles: Vec<Bundle>,
+}
+
+impl BlockBundles {
+    fn load(
+        blockstore: &Blockstore,
+        slot: Slot,
+        tip_accounts: &HashSet<Pubkey>,
+        allow_dead_slots: bool,
+    ) -> Result<Self> {
+        let (entries, _num_shreds, _is_full) =
+            blockstore.get_slot_entries_with_shred_info(slot, 0, allow_dead_slots)?;
+        let entries: Vec<Vec<VersionedTransaction>> = entries
+            .into_iter()
+            .map(|entry| entry.transactions)
+            .collect();
+
+        let tips: Vec<Vec<u64>> = entries
+            .iter()
+            .map(|transactions| {
+                transactions
+                    .iter()
+                    .map(|transaction| {
+                        let meta = blockstore
+                            .read_transaction_status((transaction.signatures[0], slot))
+                            .ok()
+                            .flatten();
+                        transaction_tip_lamports(transaction, meta.as_ref(), tip_accounts)
+                    })
+                    .collect()
+            })
+            .collect();
+        let summaries: Vec<EntrySummary> = entries
+            .iter()
+            .zip(&tips)
+            .map(|(transactions, tips)| EntrySummary {
+                num_transactions: transactions.len(),
+                has_votes: transactions.iter().any(|transaction| {
+                    get_program_ids(transaction).any(solana_sdk::vote::program::check_id)
+                }),
+                pays_tip: tips.iter().any(|tip| *tip > 0),
+            })
+            .collect();
+
+        let first_transaction_indexes: Vec<usize> = entries
+            .iter()
+            .scan(0, |index, transactions| {
+                let first = *index;
+                *index += transactions.len();
+                Some(first)
+            })
+            .collect();
+        let bundles = find_bundles(&summaries)
+            .into_iter()
+            .map(|entries| Bundle {
+                transaction_index: first_transaction_indexes[entries.start],
+                tip_lamports: tips[entries.clone()]
+                    .iter()
+                    .flatten()
+                    .fold(0, |total, tip| total.saturating_add(*tip)),
+                entries,
+            })
+            .collect();
+
+        Ok(Self {
+            slot,
+            leader: slot_leader(blockstore, slot),
+            entries,
+            bundles,
+        })
+    }
+
+    fn bundle_transactions(&self, bundle: &Bundle) -> Vec<VersionedTransaction> {
+        self.entries[bundle.entries.clone()]
+            .iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    fn cli_bundles(&self) -> Vec<CliBundle> {
+        self.bundles
+            .iter()
+            .map(|bundle| CliBundle {
+                slot: self.slot,
+                leader: self.leader.clone(),
+                transaction_index: bundle.transaction_index,
+                signatures: self
+                    .bundle_transactions(bundle)
+                    .iter()
+                    .map(|transaction| transaction.signatures[0].to_string())
+                    .collect(),
+                tip_lamports: bundle.tip_lamports,
+            })
+            .collect()
+    }
+
+    fn tip_lamports(&self) -> u64 {
+        self.bundles
+            .iter()
+            .fold(0, |total, bundle| total.saturating_add(bundle.tip_lamports))
+    }
+}
+
+fn replay_bundle(ledger_path: &Path, matches: &ArgMatches<'_>) -> Result<CliBundleReplay> {
+    let slot = value_t_or_exit!(matches, "slot", Slot);
+    let signature = value_t!(matches, "signature", Signature)
+        .map_err(|err| LedgerToolError::BadArgument(format!("invalid signature: {err}")))?;
+    let replay_preceding_transactions = matches.is_present("replay_preceding_transactions");
+    let tip_accounts = tip_accounts(matches);
+
+    let blockstore = open_blockstore(ledger_path, matches, AccessType::Secondary);
+    let parent_slot = blockstore
+        .meta(slot)?
+        .and_then(|meta| meta.parent_slot)
+        .ok_or_else(|| LedgerToolError::Generic(format!("slot {slot} has no parent")))?;
+    let block = BlockBundles::load(&blockstore, slot, &tip_accounts, true)?;
+    let bundle = block
+        .bundles
+        .iter()
+        .find(|bundle| {
+            block
+                .bundle_transactions(bundle)
+                .iter()
+                .any(|transaction| transaction.signatures[0] == signature)
+        })
+        .ok_or_else(|| {
+            LedgerToolError::BadArgument(format!(
+                "no bundle with transaction {signature} found in slot {slot}"
+            ))
+        })?;
+
+    let mut process_options = parse_process_options(ledger_path, matches);
+    process_options.halt_at_slot = Some(parent_slot);
+    let genesis_config = open_genesis_config_by(ledger_path, matches);
+    let LoadAndProcessLedgerOutput { bank_forks, .. } = load_and_process_ledger_or_exit(
+        matches,
+        &genesis_config,
+        Arc::new(blockstore),
+        process_options,
+        None,
+        false,
+    );
+    let parent_bank = bank_forks.read().unwrap().get(parent_slot).ok_or_else(|| {
+        LedgerToolError::Generic(format!("bank for parent slot {parent_slot} not found"))
+    })?;
+    let leader = LeaderScheduleCache::new_from_bank(&parent_bank)
+        .slot_leader_at(slot, Some(&parent_bank))
+        .unwrap_or_default();
+    let bank = Bank::new_from_parent(parent_bank, &leader, slot);
+
+    let mut num_preceding_transactions = 0;
+    if replay_preceding_transactions {
+        for transactions in &block.entries[..bundle.entries.start] {
+            if transactions.is_empty() {
+                continue;
+            }
+            num_preceding_transactions += transactions.len();
+            bank.try_process_entry_transactions(transactions.clone())
+                .map_err(|err| {
+                    LedgerToolError::Generic(format!(
+                        "failed to replay the transactions preceding the bundle: {err}"
+                    ))
+                })?;
+        }
+    }
+
+    let transactions = block.bundle_transactions(bundle);
+    let bundle_id = derive_bundle_id(&transactions);
+    let sanitized_bundle = SanitizedBundle {
+        transactions: transactions
+            .into_iter()
+            .map(|transaction| {
+                RuntimeTransaction::try_create(
+                    transaction,
+                    MessageHash::Compute,
+                    None,
+                    &bank,
+                    bank.get_reserved_account_keys(),
+                )
+                .map_err(|err| {
+                    LedgerToolError::Generic(format!("failed to sanitize transaction: {err}"))
+                })
+            })
+            .collect::<Result<_>>()?,
+        bundle_id: bundle_id.clone(),
+        allowed_to_fail_tx_indexes: HashSet::default(),
+    };
+
+    let num_transactions = sanitized_bundle.transactions.len();
+    let output = load_and_execute_bundle(
+        &bank,
+        &sanitized_bundle,
+        MAX_PROCESSING_AGE,
+        &MAX_BUNDLE_REPLAY_TIME,
+        true,
+        &None,
+        true,
+        None,
+        &vec![None; num_transactions],
+        &vec![None; num_transactions],
+    );
+
+    let mut replayed_transactions = vec![];
+    let mut tip_lamports: u64 = 0;
+    for batch in output.bundle_transaction_results() {
+        let balances = batch
+            .pre_balance_info()
+            .native
+            .iter()
+            .zip(&batch.post_balance_info().0);
+        for ((transaction, result), (pre_balances, post_balances)) in batch
+            .transactions()
+            .iter()
+            .zip(batch.execution_results())
+            .zip(balances)
+        {
+            for (index, key) in transaction.message().account_keys().iter().enumerate() {
+                if tip_accounts.contains(key) {
+                    let (Some(pre), Some(post)) =
+                        (pre_balances.get(index), post_balances.get(index))
+                    else {
+                        continue;
+                    };
+                    tip_lamports = tip_lamports.saturating_add(post.saturating_sub(*pre));
+                }
+            }
+
+            let execution_details = result
+                .as_ref()
+                .ok()
+                .and_then(|processed| processed.execution_details());
+            replayed_transactions.push(CliReplayedTransaction {
+                signature: transaction.signature().to_string(),
+                result: match result {
+                    Ok(processed) => match processed.status() {
+                        Ok(()) => "ok".to_string(),
+                        Err(err) => err.to_string(),
+                    },
+                    Err(err) => err.to_string(),
+                },
+                compute_units_consumed: execution_details.map(|details| details.executed_units),
+                log_messages: execution_details
+                    .and_then(|details| details.log_messages.clone())
+                    .unwrap_or_default(),
+            });
+        }
+    }
+
+    Ok(CliBundleReplay {
+        slot,
+        parent_slot,
+        leader: leader.to_string(),
+        bundle_id,
+        num_preceding_transactions,
+        recorded_tip_lamports: bundle.tip_lamports,
+        result: match output.result() {
+            Ok(()) => "ok".to_string(),
+            Err(err) => err.to_string(),
+        },
+        tip_lamports,
+        transactions: replayed_transactions,
+    })
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CliBundle {
+    slot: Slot,
+    leader: Option<String>,
+    transaction_index: usize,
+    signatures: Vec<String>,
+    tip_lamports: u64,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct CliBundles {
+    bundles: Vec<CliBundle>,
+}
+
+impl Display for CliBundles {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>12} {:>44} {:>8} {:>16}  Signatures",
+            "Slot", "Leader", "Index", "Tip (SOL)"
+        )?;
+        for bundle in &self.bundles {
+            writeln!(
+                f,
+                "{:>12} {:>44} {:>8} {:>16.9}  {}",
+                bundle.slot,
+                bundle.leader.as_deref().unwrap_or("unknown"),
+                bundle.transaction_index,
+                lamports_to_sol(bundle.tip_lamports),
+                bundle.signatures.join(" "),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl QuietDisplay for CliBundles {}
+impl VerboseDisplay for CliBundles {}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CliSlotTips {
+    slot: Slot,
+    leader: Option<String>,
+    num_bundles: usize,
+    tip_lamports: u64,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct CliLeaderTips {
+    leader: String,
+    num_slots: usize,
+    num_bundles: usize,
+    tip_lamports: u64,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct CliBundleTips {
+    slots: Vec<CliSlotTips>,
+    leaders: Vec<CliLeaderTips>,
+    total_tip_lamports: u64,
+}
+
+impl CliBundleTips {
+    fn new(blocks: &[BlockBundles]) -> Self {
+        let slots: Vec<CliSlotTips> = blocks
+            .iter()
+            .map(|block| CliSlotTips {
+                slot: block.slot,
+                leader: block.leader.clone(),
+                num_bundles: block.bundles.len(),
+                tip_lamports: block.tip_lamports(),
+            })
+            .collect();
+
+        let mut leaders: BTreeMap<String, CliLeaderTips> = BTreeMap::new();
+        for slot in &slots {
+            let leader = slot.leader.clone().unwrap_or_else(|| "unknown".to_string());
+            let leader_tips = leaders
+                .entry(leader.clone())
+                .or_insert_with(|| CliLeaderTips {
+                    leader,
+                    ..CliLeaderTips::default()
+                });
+            leader_tips.num_slots += 1;
+            leader_tips.num_bundles += slot.num_bundles;
+            leader_tips.tip_lamports = leader_tips.tip_lamports.saturating_add(slot.tip_lamports);
+        }
+        let mut leaders: Vec<CliLeaderTips> = leaders.into_values().collect();
+        leaders.sort_by(|a, b| b.tip_lamports.cmp(&a.tip_lamports));
+
+        Self {
+            total_tip_lamports: slots
+                .iter()
+                .fold(0, |total, slot| total.saturating_add(slot.tip_lamports)),
+            slots,
+            leaders,
+        }
+    }
+}
+
+impl Display for CliBundleTips {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>12} {:>44} {:>8} {:>16}",
+            "Slot", "Leader", "Bundles", "Tips (SOL)"
+        )?;
+        for slot in &self.slots {
+            writeln!(
+                f,
+                "{:>12} {:>44} {:>8} {:>16.9}",
+                slot.slot,
+                slot.leader.as_deref().unwrap_or("unknown"),
+                slot.num_bundles,
+                lamports_to_sol(slot.tip_lamports),
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>44} {:>8} {:>8} {:>16}",
+            "Leader", "Slots", "Bundles", "Tips (SOL)"
+        )?;
+        for leader in &self.leaders {
+            writeln!(
+                f,
+                "{:>44} {:>8} {:>8} {:>16.9}",
+                leader.leader,
+                leader.num_slots,
+                leader.num_bundles,
+                lamports_to_sol(leader.tip_lamports),
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Total tips: {} SOL",
+            lamports_to_sol(self.total_tip_lamports)
+        )
+    }
+}
+
+impl QuietDisplay for CliBundleTips {}
+impl VerboseDisplay for CliBundleTips {}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CliReplayedTransaction {
+    signature: String,
+    result: String,
+    compute_units_consumed: Option<u64>,
+    log_messages: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CliBundleReplay {
+    slot: Slot,
+    parent_slot: Slot,
+    leader: String,
+    bundle_id: String,
+    num_preceding_transactions: usize,
+    result: String,
+    /// Tips paid according to the ledger
+    recorded_tip_lamports: u64,
+    /// Tips paid by the replay
+    tip_lamports: u64,
+    transactions: Vec<CliReplayedTransaction>,
+}
+
+impl CliBundleReplay {
+    fn write(&self, f: &mut Formatter, verbose: bool) -> fmt::Result {
+        writeln!(f, "Bundle: {}", self.bundle_id)?;
+        writeln!(
+            f,
+            "Slot: {} (parent {}), leader: {}",
+            self.slot, self.parent_slot, self.leader
+        )?;
+        writeln!(
+            f,
+            "Preceding transactions replayed: {}",
+            self.num_preceding_transactions
+        )?;
+        writeln!(f, "Result: {}", self.result)?;
+        writeln!(
+            f,
+            "Tips: {} SOL (recorded {} SOL)",
+            lamports_to_sol(self.tip_lamports),
+            lamports_to_sol(self.recorded_tip_lamports)
+        )?;
+        for transaction in &self.transactions {
+            writeln!(
+                f,
+                "  {} {} compute units: {}",
+                transaction.signature,
+                transaction.result,
+                transaction
+                    .compute_units_consumed
+                    .map(|units| units.to_string())
+                    .unwrap_or_else(|| "n/a".to_string())
+            )?;
+            if verbose {
+                for log in &transaction.log_messages {
+                    writeln!(f, "    {log}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for CliBundleReplay {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+impl QuietDisplay for CliBundleReplay {}
+impl VerboseDisplay for CliBundleReplay {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "{}", VerboseBundleReplay(self))
+    }
+}
+
+struct VerboseBundleReplay<'a>(&'a CliBundleReplay);
+
+impl Display for VerboseBundleReplay<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.write(f, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            hash::Hash, signature::Keypair, signer::Signer, system_transaction::transfer,
+        },
+    };
+
+    fn entry(num_transactions: usize, pays_tip: bool) -> EntrySummary {
+        EntrySummary {
+            num_transactions,
+            has_votes: false,
+            pays_tip,
+        }
+    }
+
+    #[test]
+    fn test_find_bundles() {
+        let entries = [
+            // tick
+            entry(0, false),
+            // bundle split in two entries by account locks
+            entry(2, false),
+            entry(1, true),
+            // a tip paid from a BankingStage entry isn't a bundle
+            entry(64, true),
+            // single transaction bundle right after another one
+            entry(3, false),
+            entry(1, true),
+            entry(1, true),
+            // run longer than a bundle, only the last entries are part of it
+            entry(4, false),
+            entry(2, false),
+            entry(3, true),
+            // entries with votes aren't bundles
+            EntrySummary {
+                num_transactions: 1,
+                has_votes: true,
+                pays_tip: true,
+            },
+            // no tip
+            entry(2, false),
+        ];
+        assert_eq!(find_bundles(&entries), vec![1..3, 4..6, 6..7, 8..10]);
+        assert!(find_bundles(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_transaction_tip_lamports() {
+        let keypair = Keypair::new();
+        let tip_account = Pubkey::new_unique();
+        let tip_accounts = HashSet::from([tip_account]);
+
+        let tip =
+            VersionedTransaction::from(transfer(&keypair, &tip_account, 1_000, Hash::default()));
+        assert_eq!(transaction_tip_lamports(&tip, None, &tip_accounts), 1_000);
+
+        let no_tip = VersionedTransaction::from(transfer(
+            &keypair,
+            &Pubkey::new_unique(),
+            1_000,
+            Hash::default(),
+        ));
+        assert_eq!(transaction_tip_lamports(&no_tip, None, &tip_accounts), 0);
+
+        // balances are used when the status is available, which also covers tips paid by programs
+        let meta = TransactionStatusMeta {
+            pre_balances: vec![10_000, 0, 1],
+            post_balances: vec![7_000, 2_000, 1],
+            ..TransactionStatusMeta::default()
+        };
+        assert_eq!(
+            transaction_tip_lamports(&tip, Some(&meta), &tip_accounts),
+            2_000
+        );
+    }
+}
@@ -4,6 +4,7 @@ use {
         args::*,
         bigtable::*,
         blockstore::*,
+        bundles::*,
         ledger_path::*,
         ledger_utils::*,
         output::{
@@ -23,16 +24,17 @@ use {
     solana_accounts_db::{accounts_db::CalcAccountsHashDataSource, accounts_index::ScanConfig},
     solana_clap_utils::{
         hidden_unless_forced,
-        input_parsers::{cluster_type_of, pubkey_of, pubkeys_of},
+        input_parsers::{cluster_type_of, keypair_of, pubkey_of, pubkeys_of},
         input_validators::{
-            is_parsable, is_pubkey, is_pubkey_or_keypair, is_slot, is_valid_percentage,
+            is_keypair, is_parsable, is_pubkey, is_pubkey_or_keypair, is_slot, is_valid_percentage,
             is_within_range,
         },
     },
     solana_cli_output::OutputFormat,
     solana_core::{
-        banking_simulation::{BankingSimulator, BankingTraceEvents},
+        banking_simulation::{BankingSimulator, BankingTraceEvents, BundleSimulationConfig},
         system_monitor_service::{SystemMonitorService, SystemMonitorStatsReportConfig},
+        tip_manager::{TipDistributionAccountConfig, TipManagerConfig},
         validator::{BlockProductionMethod, BlockVerificationMethod},
     },
     solana_cost_model::{cost_model::CostModel, cost_tracker::CostTracker},
@@ -103,6 +105,7 @@ use {
 mod args;
 mod bigtable;
 mod blockstore;
+mod bundles;
 mod error;
 mod ledger_path;
 mod ledger_utils;
@@ -1000,7 +1003,7 @@ fn main() {
                 .possible_values(&["json", "json-compact"])
                 .help(
                     "Return information in specified output format, currently only available for \
-                     bigtable and program subcommands",
+                     bigtable, bundles and program subcommands",
                 ),
         )
         .arg(
@@ -1014,6 +1017,7 @@ fn main() {
         )
         .bigtable_subcommand()
         .blockstore_subcommand()
+        .bundles_subcommand()
         // All of the blockstore commands are added under the blockstore command.
         // For the sake of legacy support, also directly add the blockstore commands here so that
         // these subcommands can continue to be called from the top level of the binary.
@@ -1483,6 +1487,64 @@ fn main() {
                         .long("no-block-cost-limits")
                         .takes_value(false)
                         .help("Disable block cost limits effectively by setting them to the max"),
+                )
+                .arg(
+                    Arg::with_name("identity")
+                        .long("identity")
+                        .value_name("KEYPAIR")
+                        .validator(is_keypair)
+                        .takes_value(true)
+                        .requires_all(&[
+                            "tip_payment_program_pubkey",
+                            "tip_distribution_program_pubkey",
+                            "vote_account",
+                            "merkle_root_upload_authority",
+                            "commission_bps",
+                        ])
+                        .help(
+                            "Identity keypair of the simulated leader. Traced bundles are only \
+                             simulated with BundleStage when given, as the leader signs the tip \
+                             program cranks",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("tip_payment_program_pubkey")
+                        .long("tip-payment-program-pubkey")
+                        .value_name("PUBKEY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .help("The public key of the tip-payment program"),
+                )
+                .arg(
+                    Arg::with_name("tip_distribution_program_pubkey")
+                        .long("tip-distribution-program-pubkey")
+                        .value_name("PUBKEY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .help("The public key of the tip-distribution program"),
+                )
+                .arg(
+                    Arg::with_name("vote_account")
+                        .long("vote-account")
+                        .value_name("PUBKEY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .help("Vote account of the simulated leader"),
+                )
+                .arg(
+                    Arg::with_name("merkle_root_upload_authority")
+                        .long("merkle-root-upload-authority")
+                        .value_name("PUBKEY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .help("The public key of the authorized merkle-root uploader"),
+                )
+                .arg(
+                    Arg::with_name("commission_bps")
+                        .long("commission-bps")
+                        .value_name("COMMISSION_BPS")
+                        .takes_value(true)
+                        .help("The commission the simulated leader takes from tips in basis points"),
                 ),
         )
         .subcommand(
@@ -1630,6 +1692,7 @@ fn main() {
     match matches.subcommand() {
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         ("blockstore", Some(arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
+        ("bundles", Some(arg_matches)) => bundles_process_command(&ledger_path, arg_matches),
         ("program", Some(arg_matches)) => program(&ledger_path, arg_matches),
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
@@ -2499,11 +2562,48 @@ fn main() {
 
                     info!("Using: block-production-method: {block_production_method}");
 
+                    let bundle_simulation_config =
+                        keypair_of(arg_matches, "identity").map(|identity_keypair| {
+                            BundleSimulationConfig {
+                                identity_keypair: Arc::new(identity_keypair),
+                                tip_manager_config: TipManagerConfig {
+                                    tip_payment_program_id: pubkey_of(
+                                        arg_matches,
+                                        "tip_payment_program_pubkey",
+                                    )
+                                    .unwrap(),
+                                    tip_distribution_program_id: pubkey_of(
+                                        arg_matches,
+                                        "tip_distribution_program_pubkey",
+                                    )
+                                    .unwrap(),
+                                    tip_distribution_account_config: TipDistributionAccountConfig {
+                                        merkle_root_upload_authority: pubkey_of(
+                                            arg_matches,
+                                            "merkle_root_upload_authority",
+                                        )
+                                        .unwrap(),
+                                        vote_account: pubkey_of(arg_matches, "vote_account")
+                                            .unwrap(),
+                                        commission_bps: value_t_or_exit!(
+                                            arg_matches,
+                                            "commission_bps",
+                                            u16
+                                        ),
+                                    },
+                                },
+                            }
+                        });
+                    if bundle_simulation_config.is_none() {
+                        info!("No --identity given, traced bundles won't be simulated");
+                    }
+
                     match simulator.start(
                         genesis_config,
                         bank_forks,
                         blockstore,
                         block_production_method,
+                        bundle_simulation_config,
                     ) {
                         Ok(()) => println!("Ok"),
                         Err(error) => {
@@ -78,7 +78,16 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         shred_receiver_address: config.shred_receiver_address.clone(),
         shred_retransmit_receiver_address: config.shred_retransmit_receiver_address.clone(),
         tip_manager_config: config.tip_manager_config.clone(),
-        preallocated_bundle_cost: config.preallocated_bundle_cost,
+        bundle_reservation_config: config.bundle_reservation_config.clone(),
+        bundle_result_fallback_path: config.bundle_result_fallback_path.clone(),
+        bundle_stage_num_workers: config.bundle_stage_num_workers,
+        enable_bundle_simulation_cache: config.enable_bundle_simulation_cache,
+        min_tip_policy: config.min_tip_policy.clone(),
+        bundle_policy: config.bundle_policy.clone(),
+        packet_trust: config.packet_trust.clone(),
+        packet_source_accounting: config.packet_source_accounting.clone(),
+        vortexor_receiver_address: config.vortexor_receiver_address,
+        vortexor_peers: config.vortexor_peers.clone(),
     }
 }
 
@@ -0,0 +1,269 @@
+//! Runs a validator against the mock Block Engine and Relayer
+#![allow(clippy::arithmetic_side_effects)]
+use {
+    jito_mock_servers::{wait_for, MockAuthConfig, MockBlockEngine, MockRelayer},
+    jito_protos::proto::bundle_feedback::BundleOutcome,
+    serial_test::serial,
+    solana_client::connection_cache::Protocol,
+    solana_core::{
+        proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
+        tip_manager::{TipDistributionAccountConfig, TipManagerConfig},
+        validator::ValidatorConfig,
+    },
+    solana_local_cluster::{
+        integration_tests::{DEFAULT_CLUSTER_LAMPORTS, DEFAULT_NODE_STAKE},
+        local_cluster::{ClusterConfig, LocalCluster},
+    },
+    solana_program_test::programs::spl_programs,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signature, Signer},
+        system_transaction,
+        transaction::VersionedTransaction,
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    std::{
+        net::SocketAddr,
+        str::FromStr,
+        sync::{atomic::Ordering, Arc, Mutex},
+        time::Duration,
+    },
+};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+const LANDED_TIMEOUT: Duration = Duration::from_secs(60);
+
+fn tip_manager_config(vote_account: &Pubkey) -> TipManagerConfig {
+    TipManagerConfig {
+        tip_payment_program_id: Pubkey::from_str("T1pyyaTNZsKv2WcRAB8oVnk93mLJw2XzjtVYqCsaHqt")
+            .unwrap(),
+        tip_distribution_program_id: Pubkey::from_str(
+            "4R3gSG8BpU4t19KYj8CfnbtRpnT8gtk4dvTHxVRwc2r7",
+        )
+        .unwrap(),
+        tip_distribution_account_config: TipDistributionAccountConfig {
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            vote_account: *vote_account,
+            commission_bps: 10,
+        },
+    }
+}
+
+/// Starts a single node cluster connected to the given Block Engine and Relayer
+fn start_cluster(
+    block_engine_config: BlockEngineConfig,
+    relayer_config: RelayerConfig,
+) -> (LocalCluster, RpcClient) {
+    let validator_keypair = Arc::new(Keypair::new());
+    let vote_keypair = Arc::new(Keypair::new());
+
+    let mut validator_config = ValidatorConfig::default_for_test();
+    validator_config.block_engine_config = Arc::new(Mutex::new(block_engine_config));
+    validator_config.relayer_config = Arc::new(Mutex::new(relayer_config));
+    validator_config.tip_manager_config = tip_manager_config(&vote_keypair.pubkey());
+
+    let mut config = ClusterConfig {
+        validator_configs: vec![validator_config],
+        validator_keys: Some(vec![(validator_keypair, true)]),
+        node_vote_keys: Some(vec![vote_keypair]),
+        node_stakes: vec![DEFAULT_NODE_STAKE],
+        cluster_lamports: DEFAULT_CLUSTER_LAMPORTS,
+        // bundles can't land without the tip programs
+        additional_accounts: spl_programs(&Rent::default()),
+        ..ClusterConfig::default()
+    };
+    let cluster = LocalCluster::new(&mut config, SocketAddrSpace::Unspecified);
+    let rpc_client = RpcClient::new_socket_with_commitment(
+        cluster.entry_point_info.rpc().unwrap(),
+        CommitmentConfig::processed(),
+    );
+    (cluster, rpc_client)
+}
+
+fn block_engine_config(block_engine: &MockBlockEngine) -> BlockEngineConfig {
+    BlockEngineConfig {
+        block_engine_url: block_engine.url(),
+        ..BlockEngineConfig::default()
+    }
+}
+
+fn transfers(
+    cluster: &LocalCluster,
+    rpc_client: &RpcClient,
+    num_transfers: usize,
+) -> Vec<VersionedTransaction> {
+    let blockhash = rpc_client.get_latest_blockhash().unwrap();
+    (0..num_transfers)
+        .map(|_| {
+            VersionedTransaction::from(system_transaction::transfer(
+                &cluster.funding_keypair,
+                &Pubkey::new_unique(),
+                1_000_000,
+                blockhash,
+            ))
+        })
+        .collect()
+}
+
+fn wait_for_signatures(rpc_client: &RpcClient, signatures: &[Signature]) -> bool {
+    wait_for(LANDED_TIMEOUT, || {
+        signatures
+            .iter()
+            .all(|signature| matches!(rpc_client.get_signature_status(signature), Ok(Some(Ok(())))))
+    })
+}
+
+/// Sends a bundle and checks it lands and a LANDED result is streamed back for it
+fn assert_bundle_lands(
+    cluster: &LocalCluster,
+    rpc_client: &RpcClient,
+    block_engine: &MockBlockEngine,
+) {
+    let bundle = transfers(cluster, rpc_client, 2);
+    let uuid = block_engine.send_bundle(&bundle);
+
+    let signatures: Vec<_> = bundle.iter().map(|tx| tx.signatures[0]).collect();
+    assert!(wait_for_signatures(rpc_client, &signatures));
+    assert!(wait_for(LANDED_TIMEOUT, || {
+        block_engine
+            .bundle_results()
+            .iter()
+            .any(|result| result.uuid == uuid && result.outcome == BundleOutcome::Landed as i32)
+    }));
+}
+
+#[test]
+#[serial]
+fn test_bundles_land_from_mock_block_engine() {
+    solana_logger::setup_with_default("solana_core::proxy=info,solana_core::bundle_stage=info");
+    let block_engine = MockBlockEngine::start(MockAuthConfig::default());
+    let (cluster, rpc_client) =
+        start_cluster(block_engine_config(&block_engine), RelayerConfig::default());
+
+    assert!(block_engine.wait_for_bundle_subscribers(1, CONNECT_TIMEOUT));
+    assert_bundle_lands(&cluster, &rpc_client, &block_engine);
+
+    // packets from the block engine go through sigverify and banking stage
+    let transactions = transfers(&cluster, &rpc_client, 4);
+    block_engine.send_packets(&transactions);
+    let signatures: Vec<_> = transactions.iter().map(|tx| tx.signatures[0]).collect();
+    assert!(wait_for_signatures(&rpc_client, &signatures));
+
+    // the validator reconnects and re-authenticates after the block engine drops it
+    let num_tokens_generated = block_engine
+        .auth()
+        .stats()
+        .num_tokens_generated
+        .load(Ordering::Relaxed);
+    block_engine.auth().expire_tokens();
+    block_engine.disconnect_all();
+    assert!(wait_for(CONNECT_TIMEOUT, || {
+        block_engine
+            .auth()
+            .stats()
+            .num_tokens_generated
+            .load(Ordering::Relaxed)
+            > num_tokens_generated
+    }));
+    assert!(block_engine.wait_for_bundle_subscribers(1, CONNECT_TIMEOUT));
+    assert_bundle_lands(&cluster, &rpc_client, &block_engine);
+}
+
+#[test]
+#[serial]
+fn test_block_engine_auth_token_refresh() {
+    solana_logger::setup_with_default("solana_core::proxy=info");
+    let block_engine = MockBlockEngine::start(MockAuthConfig {
+        access_token_ttl: Duration::from_secs(3),
+        refresh_token_ttl: Duration::from_secs(8),
+    });
+    let (cluster, rpc_client) =
+        start_cluster(block_engine_config(&block_engine), RelayerConfig::default());
+    assert!(block_engine.wait_for_bundle_subscribers(1, CONNECT_TIMEOUT));
+
+    // the access token is refreshed before it expires and new tokens are generated once the
+    // refresh token is about to expire
+    let stats = block_engine.auth().stats();
+    assert!(wait_for(CONNECT_TIMEOUT, || {
+        stats.num_access_tokens_refreshed.load(Ordering::Relaxed) >= 1
+            && stats.num_tokens_generated.load(Ordering::Relaxed) >= 2
+    }));
+
+    // the connection survives on the refreshed tokens
+    assert_bundle_lands(&cluster, &rpc_client, &block_engine);
+}
+
+#[test]
+#[serial]
+fn test_block_engine_permission_denied() {
+    solana_logger::setup_with_default("solana_core::proxy=info");
+    let block_engine = MockBlockEngine::start(MockAuthConfig::default());
+    block_engine.auth().set_deny_permission(true);
+    let (cluster, rpc_client) =
+        start_cluster(block_engine_config(&block_engine), RelayerConfig::default());
+
+    let stats = block_engine.auth().stats();
+    assert!(wait_for(CONNECT_TIMEOUT, || {
+        stats.num_permission_denied.load(Ordering::Relaxed) >= 2
+    }));
+    assert_eq!(block_engine.num_bundle_subscribers(), 0);
+    assert_eq!(stats.num_tokens_generated.load(Ordering::Relaxed), 0);
+
+    // the validator keeps retrying and connects once it's allowed
+    block_engine.auth().set_deny_permission(false);
+    assert!(block_engine.wait_for_bundle_subscribers(1, CONNECT_TIMEOUT));
+    assert_bundle_lands(&cluster, &rpc_client, &block_engine);
+}
+
+#[test]
+#[serial]
+fn test_relayer_heartbeats_switch_tpu() {
+    solana_logger::setup_with_default("solana_core::proxy=info");
+    let relayer_tpu = SocketAddr::from(([127, 0, 0, 1], 10_001));
+    let relayer_tpu_forward = SocketAddr::from(([127, 0, 0, 1], 10_002));
+    let relayer = MockRelayer::start(MockAuthConfig::default(), relayer_tpu, relayer_tpu_forward);
+    let (cluster, rpc_client) = start_cluster(
+        BlockEngineConfig::default(),
+        RelayerConfig {
+            relayer_url: relayer.url(),
+            expected_heartbeat_interval: Duration::from_millis(500),
+            oldest_allowed_heartbeat: Duration::from_millis(1_500),
+            trust_packets: false,
+        },
+    );
+    let cluster_info = cluster
+        .validators
+        .get(cluster.entry_point_info.pubkey())
+        .unwrap()
+        .validator
+        .as_ref()
+        .unwrap()
+        .cluster_info
+        .clone();
+    let validator_tpu = cluster.entry_point_info.tpu(Protocol::UDP).unwrap();
+    assert!(relayer.wait_for_subscribers(1, CONNECT_TIMEOUT));
+
+    // packets from the relayer are processed
+    let transactions = transfers(&cluster, &rpc_client, 4);
+    relayer.send_packets(&transactions);
+    let signatures: Vec<_> = transactions.iter().map(|tx| tx.signatures[0]).collect();
+    assert!(wait_for_signatures(&rpc_client, &signatures));
+
+    // the relayer's tpu is advertised after heartbeats have been received for a while
+    assert!(wait_for(Duration::from_secs(120), || {
+        cluster_info.my_contact_info().tpu(Protocol::UDP) == Some(relayer_tpu)
+    }));
+    assert_eq!(
+        cluster_info.my_contact_info().tpu_forwards(Protocol::UDP),
+        Some(relayer_tpu_forward)
+    );
+
+    // and the validator's own tpu is restored as soon as heartbeats stop
+    relayer.set_heartbeats_enabled(false);
+    assert!(wait_for(Duration::from_secs(10), || {
+        cluster_info.my_contact_info().tpu(Protocol::UDP) == Some(validator_tpu)
+    }));
+}
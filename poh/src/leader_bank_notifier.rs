@@ -99,6 +99,21 @@ impl LeaderBankNotifier {
         (!wait_timeout_result.timed_out()).then(|| state.slot.expect("some slot when completed"))
     }
 
+    /// Wait for the leader slot `slot` to complete, even if the next leader slot is already in
+    /// progress by the time the waiting thread wakes up.
+    /// Returns `false` if the timeout is reached
+    pub fn wait_for_completed_slot(&self, slot: Slot, timeout: Duration) -> bool {
+        let state = self.state.lock().unwrap();
+        let (_state, wait_timeout_result) = self
+            .condvar
+            .wait_timeout_while(state, timeout, |state| {
+                matches!(state.status, Status::InProgress) && state.slot == Some(slot)
+            })
+            .unwrap();
+
+        !wait_timeout_result.timed_out()
+    }
+
     /// Helper function to get or wait for the `InProgress` status with a given `MutexGuard`.
     /// If `InProgress` status is reached, the state `MutexGuard` is returned, otherwise None.
     fn get_or_wait_for_in_progress_state<'a>(
@@ -118,7 +133,7 @@ impl LeaderBankNotifier {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {super::*, solana_pubkey::Pubkey};
 
     #[test]
     fn test_leader_bank_notifier_default() {
@@ -263,6 +278,28 @@ mod tests {
         jh.join().unwrap();
     }
 
+    #[test]
+    fn test_leader_bank_notifier_wait_for_completed_slot() {
+        let leader_bank_notifier = LeaderBankNotifier::default();
+        let bank = Arc::new(Bank::default_for_tests());
+        leader_bank_notifier.set_in_progress(&bank);
+        assert!(
+            !leader_bank_notifier.wait_for_completed_slot(bank.slot(), Duration::from_millis(1))
+        );
+
+        // the next leader slot already started
+        let next_bank = Arc::new(Bank::new_from_parent(
+            bank.clone(),
+            &Pubkey::default(),
+            bank.slot() + 1,
+        ));
+        leader_bank_notifier.set_completed(bank.slot());
+        leader_bank_notifier.set_in_progress(&next_bank);
+        assert!(leader_bank_notifier.wait_for_completed_slot(bank.slot(), Duration::from_millis(1)));
+        assert!(!leader_bank_notifier
+            .wait_for_completed_slot(next_bank.slot(), Duration::from_millis(1)));
+    }
+
     #[test]
     fn test_leader_bank_notifier_wait_for_completed_timeout() {
         let leader_bank_notifier = LeaderBankNotifier::default();
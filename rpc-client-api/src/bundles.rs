@@ -138,6 +138,10 @@ pub struct RpcSimulateBundleConfig {
     /// Replace recent blockhash to simulate old transactions without resigning.
     #[serde(default)]
     pub replace_recent_blockhash: bool,
+
+    /// Indexes of the transactions that may fail without failing the bundle.
+    #[serde(default)]
+    pub allowed_to_fail_tx_indexes: Vec<usize>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
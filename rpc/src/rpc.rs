@@ -24,6 +24,7 @@ use {
         accounts::AccountAddressFilter,
         accounts_index::{AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig, ScanResult},
     },
+    solana_bundle::bundle_simulation_cache::BundleSimulationCache,
     solana_client::connection_cache::Protocol,
     solana_entry::entry::Entry,
     solana_faucet::faucet::request_airdrop_transaction,
@@ -253,6 +254,7 @@ pub struct JsonRpcRequestProcessor {
     max_complete_rewards_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
     runtime: Arc<Runtime>,
+    bundle_simulation_cache: Option<Arc<BundleSimulationCache>>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -412,6 +414,7 @@ impl JsonRpcRequestProcessor {
         max_complete_rewards_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
         runtime: Arc<Runtime>,
+        bundle_simulation_cache: Option<Arc<BundleSimulationCache>>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (transaction_sender, transaction_receiver) = unbounded();
         (
@@ -435,6 +438,7 @@ impl JsonRpcRequestProcessor {
                 max_complete_rewards_slot,
                 prioritization_fee_cache,
                 runtime,
+                bundle_simulation_cache,
             },
             transaction_receiver,
         )
@@ -520,6 +524,7 @@ impl JsonRpcRequestProcessor {
             max_complete_rewards_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
             runtime: service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
+            bundle_simulation_cache: None,
         }
     }
 
@@ -3653,6 +3658,7 @@ pub mod rpc_full {
         jsonrpc_core::ErrorCode,
         solana_bundle::{
             bundle_execution::{load_and_execute_bundle, LoadAndExecuteBundleError},
+            bundle_simulation_cache::BundleAccountsState,
             SanitizedBundle,
         },
         solana_rpc_client_api::bundles::{
@@ -4305,6 +4311,23 @@ pub mod rpc_full {
                     "pre/post_execution_accounts_configs must be equal in length to the number of transactions",
                 ));
             }
+            let allowed_to_fail_tx_indexes =
+                HashSet::from_iter(config.allowed_to_fail_tx_indexes.iter().copied());
+            if allowed_to_fail_tx_indexes
+                .iter()
+                .any(|index| *index >= rpc_bundle_request.encoded_transactions.len())
+            {
+                return Err(Error::invalid_params(
+                    "allowed_to_fail_tx_indexes must be less than the number of transactions",
+                ));
+            }
+            if !allowed_to_fail_tx_indexes.is_empty()
+                && allowed_to_fail_tx_indexes.len() == rpc_bundle_request.encoded_transactions.len()
+            {
+                return Err(Error::invalid_params(
+                    "at least one transaction must not be allowed to fail",
+                ));
+            }
 
             let bank = match config.simulation_bank.unwrap_or_default() {
                 SimulationSlotConfig::Commitment(commitment) => Ok(meta.bank(Some(commitment))),
@@ -4351,6 +4374,7 @@ pub mod rpc_full {
             let sanitized_bundle = SanitizedBundle {
                 transactions: runtime_txs,
                 bundle_id,
+                allowed_to_fail_tx_indexes,
             };
 
             if !config.skip_sig_verify {
@@ -4363,6 +4387,17 @@ pub mod rpc_full {
                 account_configs_to_accounts(&config.pre_execution_accounts_configs)?;
             let post_execution_accounts =
                 account_configs_to_accounts(&config.post_execution_accounts_configs)?;
+
+            // Failures are shared with BundleStage, so only bundles that would be accepted by it
+            // are cached. The state is loaded up front because the bank may change during the
+            // simulation, in which case the cached failure is discarded on its first lookup.
+            let bundle_simulation_cache = meta
+                .bundle_simulation_cache
+                .as_ref()
+                .filter(|_| !config.skip_sig_verify);
+            let accounts_state = bundle_simulation_cache
+                .map(|_| BundleAccountsState::load(&bank, &sanitized_bundle));
+
             let bundle_execution_result = load_and_execute_bundle(
                 &bank,
                 &sanitized_bundle,
@@ -4376,6 +4411,20 @@ pub mod rpc_full {
                 &post_execution_accounts,
             );
 
+            if let (Some(bundle_simulation_cache), Some(accounts_state), Err(e)) = (
+                bundle_simulation_cache,
+                accounts_state,
+                bundle_execution_result.result(),
+            ) {
+                bundle_simulation_cache.insert(
+                    &bank,
+                    &sanitized_bundle.bundle_id,
+                    &sanitized_bundle.allowed_to_fail_tx_indexes,
+                    accounts_state,
+                    e,
+                );
+            }
+
             // only return error if irrecoverable (timeout or tx malformed)
             // bundle execution failures w/ context are returned to client
             match bundle_execution_result.result() {
@@ -5033,6 +5082,7 @@ pub mod tests {
                 max_complete_rewards_slot,
                 Arc::new(PrioritizationFeeCache::default()),
                 service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
+                None,
             )
             .0;
 
@@ -7144,6 +7194,7 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
             service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
+            None,
         );
         let client = ConnectionCacheClient::<NullTpuInfo>::new(
             connection_cache.clone(),
@@ -7422,6 +7473,7 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
             service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
+            None,
         );
         let client = ConnectionCacheClient::<NullTpuInfo>::new(
             connection_cache.clone(),
@@ -9079,6 +9131,7 @@ pub mod tests {
             max_complete_rewards_slot,
             Arc::new(PrioritizationFeeCache::default()),
             service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
+            None,
         );
 
         let mut io = MetaIoHandler::default();
@@ -16,6 +16,7 @@ use {
         RequestMiddlewareAction, ServerBuilder,
     },
     regex::Regex,
+    solana_bundle::bundle_simulation_cache::BundleSimulationCache,
     solana_client::connection_cache::ConnectionCache,
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
@@ -360,6 +361,7 @@ impl JsonRpcService {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        bundle_simulation_cache: Option<Arc<BundleSimulationCache>>,
     ) -> Result<Self, String> {
         info!("rpc bound to {:?}", rpc_addr);
         info!("rpc configuration: {:?}", config);
@@ -458,6 +460,7 @@ impl JsonRpcService {
             max_complete_rewards_slot,
             prioritization_fee_cache,
             Arc::clone(&runtime),
+            bundle_simulation_cache,
         );
 
         let leader_info =
@@ -677,6 +680,7 @@ mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            None,
         )
         .expect("assume successful JsonRpcService start");
         let thread = rpc_service.thread_hdl.thread();
@@ -1,5 +1,9 @@
 use {
-    solana_runtime::{bank_forks::BankForks, commitment::BlockCommitmentCache},
+    solana_gossip::cluster_info::ClusterInfo,
+    solana_ledger::leader_schedule_cache::LeaderScheduleCache,
+    solana_poh::poh_recorder::PohRecorder,
+    solana_runtime::{bank::Bank, bank_forks::BankForks, commitment::BlockCommitmentCache},
+    solana_sdk::clock::{Epoch, Slot},
     std::{
         any::Any,
         error,
@@ -28,14 +32,39 @@ pub enum RuntimePluginError {
     FailedToLoadPlugin(#[from] Box<dyn std::error::Error>),
 }
 
+#[derive(Clone)]
 pub struct PluginDependencies {
     pub bank_forks: Arc<RwLock<BankForks>>,
     pub block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     pub exit: Arc<AtomicBool>,
+    pub cluster_info: Arc<ClusterInfo>,
+    pub leader_schedule_cache: Arc<LeaderScheduleCache>,
+    /// For reading the leader state, e.g. `would_be_leader` or `bank`. Locks must only be held
+    /// briefly as PohService and BankingStage contend on them.
+    pub poh_recorder: Arc<RwLock<PohRecorder>>,
 }
 
+/// The `on_*` event callbacks are invoked by the RuntimePluginService threads, one event at a
+/// time, so a slow callback delays the following events for every plugin. Plugins doing heavy
+/// work should hand it off to their own threads.
 pub trait RuntimePlugin: Any + Debug + Send + Sync {
     fn name(&self) -> &'static str;
     fn on_load(&mut self, config_file: &str, dependencies: PluginDependencies) -> Result<()>;
     fn on_unload(&mut self);
+
+    /// Called when ReplayStage freezes a bank, which may be on a fork that's later abandoned
+    fn on_bank_frozen(&self, _bank: &Arc<Bank>) {}
+
+    /// Called when ReplayStage sets a new root. Slots rooted along with it, between the previous
+    /// root and `bank`, aren't notified individually.
+    fn on_slot_rooted(&self, _bank: &Arc<Bank>) {}
+
+    /// Called when this validator starts building a block for `bank`'s slot
+    fn on_leader_slot_started(&self, _bank: &Arc<Bank>) {}
+
+    /// Called when this validator is done building the block for `slot`
+    fn on_leader_slot_ended(&self, _slot: Slot) {}
+
+    /// Called when the first root of a new epoch is set, with the epoch of the previous root
+    fn on_epoch_boundary(&self, _bank: &Arc<Bank>, _previous_epoch: Epoch) {}
 }
@@ -3,12 +3,10 @@ use {
     jsonrpc_core::{serde_json, ErrorCode, Result as JsonRpcResult},
     libloading::Library,
     log::*,
-    solana_runtime::{bank_forks::BankForks, commitment::BlockCommitmentCache},
     std::{
         fs::File,
         io::Read,
         path::{Path, PathBuf},
-        sync::{atomic::AtomicBool, Arc, RwLock},
     },
 };
 
@@ -42,23 +40,27 @@ pub enum RuntimePluginManagerError {
 pub struct RuntimePluginManager {
     plugins: Vec<Box<dyn RuntimePlugin>>,
     libs: Vec<Library>,
-    bank_forks: Arc<RwLock<BankForks>>,
-    block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
-    exit: Arc<AtomicBool>,
+    dependencies: PluginDependencies,
 }
 
 impl RuntimePluginManager {
-    pub fn new(
-        bank_forks: Arc<RwLock<BankForks>>,
-        block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
-        exit: Arc<AtomicBool>,
-    ) -> Self {
+    pub fn new(dependencies: PluginDependencies) -> Self {
         Self {
             plugins: vec![],
             libs: vec![],
-            bank_forks,
-            block_commitment_cache,
-            exit,
+            dependencies,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn add_plugin_for_tests(&mut self, plugin: Box<dyn RuntimePlugin>) {
+        self.plugins.push(plugin);
+    }
+
+    /// Invokes `notify` on every loaded plugin, in load order
+    pub(crate) fn notify_plugins(&self, notify: impl Fn(&dyn RuntimePlugin)) {
+        for plugin in &self.plugins {
+            notify(plugin.as_ref());
         }
     }
 
@@ -92,14 +94,7 @@ impl RuntimePluginManager {
         }
 
         new_plugin
-            .on_load(
-                config_file,
-                PluginDependencies {
-                    bank_forks: self.bank_forks.clone(),
-                    block_commitment_cache: self.block_commitment_cache.clone(),
-                    exit: self.exit.clone(),
-                },
-            )
+            .on_load(config_file, self.dependencies.clone())
             .map_err(|on_load_err| jsonrpc_core::Error {
                 code: ErrorCode::InvalidRequest,
                 message: format!(
@@ -172,14 +167,7 @@ impl RuntimePluginManager {
             })?;
 
         // Attempt to on_load with new plugin
-        match new_plugin.on_load(
-            new_parsed_config_file,
-            PluginDependencies {
-                bank_forks: self.bank_forks.clone(),
-                block_commitment_cache: self.block_commitment_cache.clone(),
-                exit: self.exit.clone(),
-            },
-        ) {
+        match new_plugin.on_load(new_parsed_config_file, self.dependencies.clone()) {
             // On success, push plugin and library
             Ok(()) => {
                 self.plugins.push(new_plugin);
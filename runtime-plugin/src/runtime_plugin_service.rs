@@ -1,12 +1,14 @@
 use {
     crate::{
-        runtime_plugin::RuntimePluginError,
+        runtime_plugin::{PluginDependencies, RuntimePluginError},
         runtime_plugin_admin_rpc_service::RuntimePluginManagerRpcRequest,
         runtime_plugin_manager::RuntimePluginManager,
     },
-    crossbeam_channel::Receiver,
+    crossbeam_channel::{Receiver, RecvTimeoutError, Sender},
     log::{error, info},
-    solana_runtime::{bank_forks::BankForks, commitment::BlockCommitmentCache},
+    solana_poh::leader_bank_notifier::LeaderBankNotifier,
+    solana_runtime::bank::Bank,
+    solana_sdk::clock::{Epoch, Slot},
     std::{
         path::PathBuf,
         sync::{
@@ -18,21 +20,47 @@ use {
     },
 };
 
+/// Bank events sent by ReplayStage to be dispatched to the runtime plugins
+pub enum BankEvent {
+    Frozen(Arc<Bank>),
+    Rooted(Arc<Bank>),
+}
+
+pub type BankEventSender = Sender<BankEvent>;
+pub type BankEventReceiver = Receiver<BankEvent>;
+
+/// Bank events waiting to be dispatched, each holding on to its bank. ReplayStage drops events
+/// rather than wait on the plugins once they fall this far behind.
+pub const BANK_EVENT_CHANNEL_CAPACITY: usize = 256;
+
+const EVENT_TIMEOUT: Duration = Duration::from_millis(100);
+
+pub fn bank_event_channel() -> (BankEventSender, BankEventReceiver) {
+    crossbeam_channel::bounded(BANK_EVENT_CHANNEL_CAPACITY)
+}
+
 pub struct RuntimePluginService {
     plugin_manager: Arc<RwLock<RuntimePluginManager>>,
     rpc_thread: JoinHandle<()>,
+    bank_event_thread: JoinHandle<()>,
+    leader_slot_thread: JoinHandle<()>,
 }
 
 impl RuntimePluginService {
     pub fn start(
         plugin_config_files: &[PathBuf],
         rpc_receiver: Receiver<RuntimePluginManagerRpcRequest>,
-        bank_forks: Arc<RwLock<BankForks>>,
-        block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
-        exit: Arc<AtomicBool>,
+        bank_event_receiver: BankEventReceiver,
+        dependencies: PluginDependencies,
     ) -> Result<Self, RuntimePluginError> {
-        let mut plugin_manager =
-            RuntimePluginManager::new(bank_forks, block_commitment_cache, exit.clone());
+        let exit = dependencies.exit.clone();
+        let root_epoch = dependencies.bank_forks.read().unwrap().root_bank().epoch();
+        let leader_bank_notifier = dependencies
+            .poh_recorder
+            .read()
+            .unwrap()
+            .new_leader_bank_notifier();
+        let mut plugin_manager = RuntimePluginManager::new(dependencies);
 
         for config in plugin_config_files {
             let name = plugin_manager
@@ -43,11 +71,21 @@ impl RuntimePluginService {
 
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
         let rpc_thread =
-            Self::start_rpc_request_handler(rpc_receiver, plugin_manager.clone(), exit);
+            Self::start_rpc_request_handler(rpc_receiver, plugin_manager.clone(), exit.clone());
+        let bank_event_thread = Self::start_bank_event_handler(
+            bank_event_receiver,
+            plugin_manager.clone(),
+            root_epoch,
+            exit.clone(),
+        );
+        let leader_slot_thread =
+            Self::start_leader_slot_handler(leader_bank_notifier, plugin_manager.clone(), exit);
 
         Ok(Self {
             plugin_manager,
             rpc_thread,
+            bank_event_thread,
+            leader_slot_thread,
         })
     }
 
@@ -55,9 +93,89 @@ impl RuntimePluginService {
         if let Err(e) = self.rpc_thread.join() {
             error!("error joining rpc thread: {e:?}");
         }
+        if let Err(e) = self.bank_event_thread.join() {
+            error!("error joining bank event thread: {e:?}");
+        }
+        if let Err(e) = self.leader_slot_thread.join() {
+            error!("error joining leader slot thread: {e:?}");
+        }
         self.plugin_manager.write().unwrap().unload_all_plugins();
     }
 
+    /// Dispatches the frozen and rooted banks sent by ReplayStage, and an epoch boundary on the
+    /// first root of every new epoch
+    fn start_bank_event_handler(
+        bank_event_receiver: BankEventReceiver,
+        plugin_manager: Arc<RwLock<RuntimePluginManager>>,
+        mut root_epoch: Epoch,
+        exit: Arc<AtomicBool>,
+    ) -> JoinHandle<()> {
+        thread::Builder::new()
+            .name("solRtPluginBank".to_string())
+            .spawn(move || {
+                while !exit.load(Ordering::Relaxed) {
+                    match bank_event_receiver.recv_timeout(EVENT_TIMEOUT) {
+                        Ok(BankEvent::Frozen(bank)) => plugin_manager
+                            .read()
+                            .unwrap()
+                            .notify_plugins(|plugin| plugin.on_bank_frozen(&bank)),
+                        Ok(BankEvent::Rooted(bank)) => {
+                            let plugin_manager = plugin_manager.read().unwrap();
+                            plugin_manager.notify_plugins(|plugin| plugin.on_slot_rooted(&bank));
+                            if bank.epoch() > root_epoch {
+                                plugin_manager.notify_plugins(|plugin| {
+                                    plugin.on_epoch_boundary(&bank, root_epoch)
+                                });
+                                root_epoch = bank.epoch();
+                            }
+                        }
+                        Err(RecvTimeoutError::Timeout) => (),
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            })
+            .unwrap()
+    }
+
+    /// Dispatches the start and end of this validator's leader slots. The end of the slot in
+    /// progress is waited on by slot so it's still seen when the next leader slot starts right
+    /// after it.
+    fn start_leader_slot_handler(
+        leader_bank_notifier: Arc<LeaderBankNotifier>,
+        plugin_manager: Arc<RwLock<RuntimePluginManager>>,
+        exit: Arc<AtomicBool>,
+    ) -> JoinHandle<()> {
+        thread::Builder::new()
+            .name("solRtPluginLdr".to_string())
+            .spawn(move || {
+                let mut in_progress_slot: Option<Slot> = None;
+                while !exit.load(Ordering::Relaxed) {
+                    if let Some(slot) = in_progress_slot {
+                        if !leader_bank_notifier.wait_for_completed_slot(slot, EVENT_TIMEOUT) {
+                            continue;
+                        }
+                        plugin_manager
+                            .read()
+                            .unwrap()
+                            .notify_plugins(|plugin| plugin.on_leader_slot_ended(slot));
+                        in_progress_slot = None;
+                    }
+
+                    if let Some(bank) = leader_bank_notifier
+                        .get_or_wait_for_in_progress(EVENT_TIMEOUT)
+                        .upgrade()
+                    {
+                        plugin_manager
+                            .read()
+                            .unwrap()
+                            .notify_plugins(|plugin| plugin.on_leader_slot_started(&bank));
+                        in_progress_slot = Some(bank.slot());
+                    }
+                }
+            })
+            .unwrap()
+    }
+
     fn start_rpc_request_handler(
         rpc_receiver: Receiver<RuntimePluginManagerRpcRequest>,
         plugin_manager: Arc<RwLock<RuntimePluginManager>>,
@@ -121,3 +239,268 @@ impl RuntimePluginService {
             .unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::runtime_plugin::{self, RuntimePlugin},
+        crossbeam_channel::unbounded,
+        solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
+        solana_ledger::{
+            blockstore::Blockstore, get_tmp_ledger_path_auto_delete,
+            leader_schedule_cache::LeaderScheduleCache,
+        },
+        solana_poh::poh_recorder::PohRecorder,
+        solana_runtime::{
+            bank_forks::BankForks,
+            commitment::BlockCommitmentCache,
+            genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        },
+        solana_sdk::{
+            poh_config::PohConfig,
+            pubkey::Pubkey,
+            signature::{Keypair, Signer},
+            timing::timestamp,
+        },
+        solana_streamer::socket::SocketAddrSpace,
+    };
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum PluginEvent {
+        BankFrozen(Slot),
+        SlotRooted(Slot),
+        LeaderSlotStarted(Slot),
+        LeaderSlotEnded(Slot),
+        EpochBoundary { epoch: Epoch, previous_epoch: Epoch },
+    }
+
+    #[derive(Debug)]
+    struct RecordingPlugin {
+        events: Sender<PluginEvent>,
+    }
+
+    impl RuntimePlugin for RecordingPlugin {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn on_load(
+            &mut self,
+            _config_file: &str,
+            _dependencies: PluginDependencies,
+        ) -> runtime_plugin::Result<()> {
+            Ok(())
+        }
+
+        fn on_unload(&mut self) {}
+
+        fn on_bank_frozen(&self, bank: &Arc<Bank>) {
+            self.events
+                .send(PluginEvent::BankFrozen(bank.slot()))
+                .unwrap();
+        }
+
+        fn on_slot_rooted(&self, bank: &Arc<Bank>) {
+            self.events
+                .send(PluginEvent::SlotRooted(bank.slot()))
+                .unwrap();
+        }
+
+        fn on_leader_slot_started(&self, bank: &Arc<Bank>) {
+            self.events
+                .send(PluginEvent::LeaderSlotStarted(bank.slot()))
+                .unwrap();
+        }
+
+        fn on_leader_slot_ended(&self, slot: Slot) {
+            self.events
+                .send(PluginEvent::LeaderSlotEnded(slot))
+                .unwrap();
+        }
+
+        fn on_epoch_boundary(&self, bank: &Arc<Bank>, previous_epoch: Epoch) {
+            self.events
+                .send(PluginEvent::EpochBoundary {
+                    epoch: bank.epoch(),
+                    previous_epoch,
+                })
+                .unwrap();
+        }
+    }
+
+    struct TestSetup {
+        root_bank: Arc<Bank>,
+        poh_recorder: Arc<RwLock<PohRecorder>>,
+        plugin_manager: Arc<RwLock<RuntimePluginManager>>,
+        events: Receiver<PluginEvent>,
+    }
+
+    fn setup(blockstore: Blockstore) -> TestSetup {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank_forks = BankForks::new_rw_arc(Bank::new_for_tests(&genesis_config));
+        let root_bank = bank_forks.read().unwrap().root_bank();
+        let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(&root_bank));
+        let exit = Arc::new(AtomicBool::new(false));
+        let (poh_recorder, _entry_receiver, _record_receiver) = PohRecorder::new(
+            root_bank.tick_height(),
+            root_bank.last_blockhash(),
+            root_bank.clone(),
+            None,
+            root_bank.ticks_per_slot(),
+            Arc::new(blockstore),
+            &leader_schedule_cache,
+            &PohConfig::default(),
+            exit.clone(),
+        );
+        let keypair = Arc::new(Keypair::new());
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new_localhost(&keypair.pubkey(), timestamp()),
+            keypair,
+            SocketAddrSpace::Unspecified,
+        ));
+        let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+
+        let mut plugin_manager = RuntimePluginManager::new(PluginDependencies {
+            bank_forks,
+            block_commitment_cache: Arc::new(RwLock::new(BlockCommitmentCache::default())),
+            exit,
+            cluster_info,
+            leader_schedule_cache,
+            poh_recorder: poh_recorder.clone(),
+        });
+        let (events_sender, events) = unbounded();
+        plugin_manager.add_plugin_for_tests(Box::new(RecordingPlugin {
+            events: events_sender,
+        }));
+
+        TestSetup {
+            root_bank,
+            poh_recorder,
+            plugin_manager: Arc::new(RwLock::new(plugin_manager)),
+            events,
+        }
+    }
+
+    #[test]
+    fn test_bank_event_handler() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let TestSetup {
+            root_bank,
+            plugin_manager,
+            events,
+            ..
+        } = setup(Blockstore::open(ledger_path.path()).unwrap());
+        let epoch_schedule = root_bank.epoch_schedule().clone();
+        let new_bank = |slot| {
+            Arc::new(Bank::new_from_parent(
+                root_bank.clone(),
+                &Pubkey::new_unique(),
+                slot,
+            ))
+        };
+        let bank_1 = new_bank(1);
+        let first_bank_in_epoch_1 = new_bank(epoch_schedule.get_first_slot_in_epoch(1));
+        let second_bank_in_epoch_1 = new_bank(epoch_schedule.get_first_slot_in_epoch(1) + 1);
+        let first_bank_in_epoch_3 = new_bank(epoch_schedule.get_first_slot_in_epoch(3));
+
+        let (bank_event_sender, bank_event_receiver) = bank_event_channel();
+        let bank_event_thread = RuntimePluginService::start_bank_event_handler(
+            bank_event_receiver,
+            plugin_manager,
+            root_bank.epoch(),
+            Arc::new(AtomicBool::new(false)),
+        );
+        for event in [
+            BankEvent::Frozen(bank_1.clone()),
+            BankEvent::Rooted(bank_1.clone()),
+            BankEvent::Rooted(first_bank_in_epoch_1.clone()),
+            BankEvent::Rooted(second_bank_in_epoch_1.clone()),
+            // skipping an epoch is reported as a single boundary
+            BankEvent::Rooted(first_bank_in_epoch_3.clone()),
+        ] {
+            bank_event_sender.send(event).unwrap();
+        }
+        // the handler exits once the sender is dropped and the events are drained
+        drop(bank_event_sender);
+        bank_event_thread.join().unwrap();
+
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                PluginEvent::BankFrozen(1),
+                PluginEvent::SlotRooted(1),
+                PluginEvent::SlotRooted(first_bank_in_epoch_1.slot()),
+                PluginEvent::EpochBoundary {
+                    epoch: 1,
+                    previous_epoch: 0,
+                },
+                PluginEvent::SlotRooted(second_bank_in_epoch_1.slot()),
+                PluginEvent::SlotRooted(first_bank_in_epoch_3.slot()),
+                PluginEvent::EpochBoundary {
+                    epoch: 3,
+                    previous_epoch: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_leader_slot_handler() {
+        const TIMEOUT: Duration = Duration::from_secs(5);
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let TestSetup {
+            root_bank,
+            poh_recorder,
+            plugin_manager,
+            events,
+        } = setup(Blockstore::open(ledger_path.path()).unwrap());
+        let new_bank = |slot| {
+            Arc::new(Bank::new_from_parent(
+                root_bank.clone(),
+                &Pubkey::new_unique(),
+                slot,
+            ))
+        };
+
+        let exit = Arc::new(AtomicBool::new(false));
+        let leader_bank_notifier = poh_recorder.read().unwrap().new_leader_bank_notifier();
+        let leader_slot_thread = RuntimePluginService::start_leader_slot_handler(
+            leader_bank_notifier,
+            plugin_manager,
+            exit.clone(),
+        );
+
+        poh_recorder.write().unwrap().set_bank_for_test(new_bank(1));
+        assert_eq!(
+            events.recv_timeout(TIMEOUT),
+            Ok(PluginEvent::LeaderSlotStarted(1))
+        );
+
+        // the end of a slot isn't missed when the next leader slot starts right after it
+        {
+            let mut poh_recorder = poh_recorder.write().unwrap();
+            poh_recorder.clear_bank_for_test();
+            poh_recorder.set_bank_for_test(new_bank(2));
+        }
+        assert_eq!(
+            events.recv_timeout(TIMEOUT),
+            Ok(PluginEvent::LeaderSlotEnded(1))
+        );
+        assert_eq!(
+            events.recv_timeout(TIMEOUT),
+            Ok(PluginEvent::LeaderSlotStarted(2))
+        );
+
+        poh_recorder.write().unwrap().clear_bank_for_test();
+        assert_eq!(
+            events.recv_timeout(TIMEOUT),
+            Ok(PluginEvent::LeaderSlotEnded(2))
+        );
+
+        exit.store(true, Ordering::Relaxed);
+        leader_slot_thread.join().unwrap();
+        assert!(events.try_recv().is_err());
+    }
+}
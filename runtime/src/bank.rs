@@ -482,6 +482,25 @@ pub struct BankFieldsToDeserialize {
     pub(crate) bank_hash_stats: BankHashStats,
 }
 
+impl BankFieldsToDeserialize {
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub fn epoch_vote_accounts(&self, epoch: Epoch) -> Option<&VoteAccountsHashMap> {
+        let epoch_stakes = self.epoch_stakes.get(&epoch)?.stakes();
+        Some(epoch_stakes.vote_accounts().as_ref())
+    }
+}
+
 /// Bank's common fields shared by all supported snapshot versions for serialization.
 /// This was separated from BankFieldsToDeserialize to avoid cloning by using refs.
 /// So, sync fields with BankFieldsToDeserialize!
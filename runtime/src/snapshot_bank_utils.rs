@@ -19,13 +19,15 @@ use {
             serialize_snapshot_data_file, verify_and_unarchive_snapshots,
             verify_unpacked_snapshots_dir_and_version, ArchiveFormat, BankSnapshotInfo,
             SnapshotError, SnapshotRootPaths, SnapshotVersion, StorageAndNextAccountsFileId,
-            UnpackedSnapshotsDirAndVersion, VerifyEpochStakesError, VerifySlotDeltasError,
+            UnarchivedSnapshot, UnpackedSnapshotsDirAndVersion, VerifyEpochStakesError,
+            VerifySlotDeltasError,
         },
         status_cache,
     },
     bincode::{config::Options, serialize_into},
     log::*,
     solana_accounts_db::{
+        account_storage::AccountStorageMap,
         accounts_db::{
             AccountStorageEntry, AccountsDbConfig, AtomicAccountsFileId,
             CalcAccountsHashDataSource, DuplicatesLtHash,
@@ -81,6 +83,69 @@ pub struct BankFromDirTimings {
     pub rebuild_bank_us: u64,
 }
 
+/// Bank fields and account storages unpacked from a full and an optional incremental snapshot
+/// archive, for tools that only read a few accounts and don't need to rebuild the bank. The
+/// unpacked files are removed when this is dropped.
+pub struct UnarchivedSnapshotArchives {
+    pub bank_fields: BankFieldsToDeserialize,
+    full_snapshot: UnarchivedSnapshot,
+    incremental_snapshot: Option<UnarchivedSnapshot>,
+    _unpack_dir: TempDir,
+    _accounts_dir: TempDir,
+}
+
+impl UnarchivedSnapshotArchives {
+    /// Storages of the full snapshot, followed by the incremental snapshot's if any. Accounts in
+    /// later storages replace the ones in earlier storages of lower slots.
+    pub fn storages(&self) -> impl Iterator<Item = &AccountStorageMap> {
+        std::iter::once(&self.full_snapshot.storage).chain(
+            self.incremental_snapshot
+                .as_ref()
+                .map(|incremental_snapshot| &incremental_snapshot.storage),
+        )
+    }
+}
+
+/// Unpacks the snapshot archives into temporary directories under `unpack_dir` and reads the bank
+/// fields, without rebuilding the accounts index or the bank.
+pub fn unarchive_snapshot_archives(
+    unpack_dir: impl AsRef<Path>,
+    full_snapshot_archive_info: &FullSnapshotArchiveInfo,
+    incremental_snapshot_archive_info: Option<&IncrementalSnapshotArchiveInfo>,
+    storage_access: StorageAccess,
+) -> snapshot_utils::Result<UnarchivedSnapshotArchives> {
+    let temp_unpack_dir = TempDir::new_in(&unpack_dir)?;
+    let temp_accounts_dir = TempDir::new_in(&unpack_dir)?;
+
+    let account_paths = vec![temp_accounts_dir.path().to_path_buf()];
+
+    let (unarchived_full_snapshot, unarchived_incremental_snapshot, _next_append_vec_id) =
+        verify_and_unarchive_snapshots(
+            &temp_unpack_dir,
+            full_snapshot_archive_info,
+            incremental_snapshot_archive_info,
+            &account_paths,
+            storage_access,
+        )?;
+
+    let bank_fields = bank_fields_from_snapshots(
+        &unarchived_full_snapshot.unpacked_snapshots_dir_and_version,
+        unarchived_incremental_snapshot
+            .as_ref()
+            .map(|unarchive_preparation_result| {
+                &unarchive_preparation_result.unpacked_snapshots_dir_and_version
+            }),
+    )?;
+
+    Ok(UnarchivedSnapshotArchives {
+        bank_fields,
+        full_snapshot: unarchived_full_snapshot,
+        incremental_snapshot: unarchived_incremental_snapshot,
+        _unpack_dir: temp_unpack_dir,
+        _accounts_dir: temp_accounts_dir,
+    })
+}
+
 /// Utility for parsing out bank specific information from a snapshot archive. This utility can be used
 /// to parse out bank specific information like the leader schedule, epoch schedule, etc.
 pub fn bank_fields_from_snapshot_archives(
@@ -1114,6 +1179,7 @@ mod tests {
             sorted_storages::SortedStorages,
         },
         solana_sdk::{
+            account::ReadableAccount,
             feature_set,
             genesis_config::create_genesis_config,
             native_token::{sol_to_lamports, LAMPORTS_PER_SOL},
@@ -1232,6 +1298,62 @@ mod tests {
         assert_eq!(original_bank, roundtrip_bank);
     }
 
+    /// Test that unarchiving a full snapshot archive yields the bank fields and the accounts of the
+    /// bank it was taken from, and that the unpacked files are removed on drop
+    #[test]
+    fn test_unarchive_snapshot_archives() {
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let bank = Bank::new_for_tests(&genesis_config);
+        let recipient = Pubkey::new_unique();
+        bank.transfer(LAMPORTS_PER_SOL, &mint_keypair, &recipient)
+            .unwrap();
+        bank.fill_bank_with_ticks_for_tests();
+
+        let bank_snapshots_dir = TempDir::new().unwrap();
+        let snapshot_archives_dir = TempDir::new().unwrap();
+        let unpack_dir = TempDir::new().unwrap();
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &bank,
+            None,
+            &snapshot_archives_dir,
+            &snapshot_archives_dir,
+            ArchiveFormat::Tar,
+        )
+        .unwrap();
+
+        let unarchived_snapshot_archives = unarchive_snapshot_archives(
+            &unpack_dir,
+            &full_snapshot_archive_info,
+            None,
+            StorageAccess::default(),
+        )
+        .unwrap();
+        let bank_fields = &unarchived_snapshot_archives.bank_fields;
+        assert_eq!(bank_fields.slot(), bank.slot());
+        assert_eq!(bank_fields.epoch(), bank.epoch());
+        assert_eq!(bank_fields.hash(), bank.hash());
+        assert_eq!(
+            bank_fields.epoch_vote_accounts(bank.epoch()),
+            bank.epoch_vote_accounts(bank.epoch())
+        );
+
+        let mut recipient_lamports = None;
+        for storage_map in unarchived_snapshot_archives.storages() {
+            for entry in storage_map.iter() {
+                entry.value().storage.accounts.scan_accounts(|account| {
+                    if account.pubkey() == &recipient {
+                        recipient_lamports = Some(account.lamports());
+                    }
+                });
+            }
+        }
+        assert_eq!(recipient_lamports, Some(LAMPORTS_PER_SOL));
+
+        drop(unarchived_snapshot_archives);
+        assert_eq!(fs::read_dir(&unpack_dir).unwrap().count(), 0);
+    }
+
     /// Test roundtrip of bank to a full snapshot, then back again.  This test is more involved
     /// than the simple version above; creating multiple banks over multiple slots and doing
     /// multiple transfers.  So this full snapshot should contain more data.
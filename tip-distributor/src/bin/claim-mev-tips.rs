@@ -10,7 +10,7 @@ use {
         signature::{read_keypair_file, Keypair},
     },
     solana_tip_distributor::{
-        claim_mev_workflow::{claim_mev_tips, ClaimMevError},
+        claim_mev_workflow::{claim_mev_tips, ClaimMevError, ClaimThrottle},
         read_json_from_file,
         reclaim_rent_workflow::reclaim_rent,
         GeneratedMerkleTreeCollection,
@@ -56,15 +56,39 @@ struct Args {
     /// The price to pay for priority fee
     #[arg(long, env, default_value_t = 1)]
     micro_lamports: u64,
+
+    /// Added to the claims' priority fee every round claims are still left
+    #[arg(long, env, default_value_t = 0)]
+    micro_lamports_increment: u64,
+
+    /// Upper bound on the claims' priority fee
+    #[arg(long, env, default_value_t = 1_000_000)]
+    max_micro_lamports: u64,
+
+    /// Maximum number of claim transactions sent per second
+    #[arg(long, env)]
+    max_tps: Option<u64>,
+
+    /// Path to the claim journal. Sent and landed claims are recorded there so a restarted run
+    /// picks up where the previous one left off.
+    #[arg(long, env)]
+    journal_path: Option<PathBuf>,
+
+    /// Print the claim and reclaim rent transactions instead of sending them.
+    #[arg(long, env)]
+    dry_run: bool,
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn start_mev_claim_process(
     merkle_trees: GeneratedMerkleTreeCollection,
     rpc_url: String,
     tip_distribution_program_id: Pubkey,
     signer: Arc<Keypair>,
     max_loop_duration: Duration,
-    micro_lamports: u64,
+    throttle: ClaimThrottle,
+    journal_path: Option<PathBuf>,
+    dry_run: bool,
 ) -> Result<(), ClaimMevError> {
     let start = Instant::now();
 
@@ -74,7 +98,9 @@ async fn start_mev_claim_process(
         tip_distribution_program_id,
         signer,
         max_loop_duration,
-        micro_lamports,
+        throttle,
+        journal_path.as_deref(),
+        dry_run,
     )
     .await
     {
@@ -99,6 +125,7 @@ async fn start_mev_claim_process(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn start_rent_claim(
     rpc_url: String,
     tip_distribution_program_id: Pubkey,
@@ -107,6 +134,7 @@ async fn start_rent_claim(
     should_reclaim_tdas: bool,
     micro_lamports: u64,
     epoch: u64,
+    dry_run: bool,
 ) -> Result<(), ClaimMevError> {
     let start = Instant::now();
     match reclaim_rent(
@@ -116,6 +144,7 @@ async fn start_rent_claim(
         max_loop_duration,
         should_reclaim_tdas,
         micro_lamports,
+        dry_run,
     )
     .await
     {
@@ -168,7 +197,14 @@ async fn main() -> Result<(), ClaimMevError> {
         args.tip_distribution_program_id,
         keypair.clone(),
         max_loop_duration,
-        args.micro_lamports,
+        ClaimThrottle {
+            max_tps: args.max_tps,
+            micro_lamports: args.micro_lamports,
+            micro_lamports_increment: args.micro_lamports_increment,
+            max_micro_lamports: args.max_micro_lamports,
+        },
+        args.journal_path.clone(),
+        args.dry_run,
     )));
     if args.should_reclaim_rent {
         futs.push(tokio::spawn(start_rent_claim(
@@ -179,6 +215,7 @@ async fn main() -> Result<(), ClaimMevError> {
             args.should_reclaim_tdas,
             args.micro_lamports,
             epoch,
+            args.dry_run,
         )));
     }
     let results = join_all(futs).await;
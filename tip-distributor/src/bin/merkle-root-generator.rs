@@ -21,6 +21,11 @@ struct Args {
     /// Path to JSON file to get populated with tree node data.
     #[arg(long, env)]
     out_path: PathBuf,
+
+    /// Path to JSON file with the [DistributionPolicyConfig] used to split the tips between
+    /// claimants. Defaults to the validator commission with the rest pro-rata to delegations.
+    #[arg(long, env)]
+    distribution_policy_path: Option<PathBuf>,
 }
 
 fn main() {
@@ -28,7 +33,12 @@ fn main() {
     info!("Starting merkle-root-generator workflow...");
 
     let args: Args = Args::parse();
-    generate_merkle_root(&args.stake_meta_coll_path, &args.out_path, &args.rpc_url)
-        .expect("merkle tree produced");
+    generate_merkle_root(
+        &args.stake_meta_coll_path,
+        &args.out_path,
+        &args.rpc_url,
+        args.distribution_policy_path.as_ref(),
+    )
+    .expect("merkle tree produced");
     info!("saved merkle roots to {:?}", args.stake_meta_coll_path);
 }
@@ -29,6 +29,10 @@ struct Args {
     /// Number of transactions to send to RPC at a time.
     #[arg(long, env, default_value_t = 64)]
     txn_send_batch_size: usize,
+
+    /// Print the upload transactions instead of sending them.
+    #[arg(long, env)]
+    dry_run: bool,
 }
 
 fn main() {
@@ -44,6 +48,7 @@ fn main() {
         &args.tip_distribution_program_id,
         args.max_concurrent_rpc_get_reqs,
         args.txn_send_batch_size,
+        args.dry_run,
     ) {
         panic!("failed to upload merkle roots: {:?}", e);
     }
@@ -0,0 +1,58 @@
+//! This binary audits a [GeneratedMerkleTreeCollection] against the chain: whether the uploaded
+//! merkle roots match the local ones, and how much was claimed, is left unclaimed and can be
+//! reclaimed as rent, per validator and per staker.
+use {
+    clap::Parser,
+    log::*,
+    solana_tip_distributor::reconcile_workflow::{
+        reconcile_merkle_trees, MerkleRootStatus, ReconcileError,
+    },
+    std::path::PathBuf,
+};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to JSON file containing the [GeneratedMerkleTreeCollection] object.
+    #[arg(long, env)]
+    merkle_trees_path: PathBuf,
+
+    /// RPC to read the tip distribution and claim status accounts from.
+    #[arg(long, env, default_value = "http://localhost:8899")]
+    rpc_url: String,
+
+    /// Path to JSON file to get populated with the reconciliation report.
+    #[arg(long, env)]
+    out_path: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), ReconcileError> {
+    env_logger::init();
+
+    let args: Args = Args::parse();
+    let report =
+        reconcile_merkle_trees(&args.merkle_trees_path, args.rpc_url, &args.out_path).await?;
+
+    for validator in &report.validators {
+        if validator.merkle_root_status != MerkleRootStatus::Matches {
+            warn!(
+                "tip distribution account {} merkle root status: {:?}",
+                validator.tip_distribution_account, validator.merkle_root_status
+            );
+        }
+    }
+    info!(
+        "epoch {}: {} validators, {} merkle root mismatches, claimed {} of {} lamports, {} \
+         unclaimed, {} rent reclaimable. Report saved to {:?}",
+        report.epoch,
+        report.validators.len(),
+        report.num_merkle_root_mismatches,
+        report.total_claimed,
+        report.total_expected_claim,
+        report.total_unclaimed,
+        report.total_rent_reclaimable,
+        args.out_path
+    );
+    Ok(())
+}
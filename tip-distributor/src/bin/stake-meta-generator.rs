@@ -1,12 +1,18 @@
 //! This binary is responsible for generating a JSON file that contains meta-data about stake
-//! & delegations given a ledger snapshot directory. The JSON file is structured as an array
-//! of [StakeMeta] objects.
+//! & delegations given a ledger snapshot directory, or a full (and optional incremental) snapshot
+//! archive when there's no ledger around. The JSON file is structured as an array of [StakeMeta]
+//! objects.
 
 use {
     clap::Parser,
     log::*,
     solana_sdk::{clock::Slot, pubkey::Pubkey},
-    solana_tip_distributor::{self, stake_meta_generator_workflow::generate_stake_meta},
+    solana_tip_distributor::{
+        self,
+        stake_meta_generator_workflow::{
+            generate_stake_meta, generate_stake_meta_from_snapshot_archives,
+        },
+    },
     std::{
         fs::{self},
         path::PathBuf,
@@ -18,8 +24,25 @@ use {
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Ledger path, where you created the snapshot.
-    #[arg(long, env, value_parser = Args::ledger_path_parser)]
-    ledger_path: PathBuf,
+    #[arg(
+        long,
+        env,
+        value_parser = Args::ledger_path_parser,
+        required_unless_present = "full_snapshot_archive"
+    )]
+    ledger_path: Option<PathBuf>,
+
+    /// Full snapshot archive to read the accounts from instead of loading a bank from the ledger.
+    #[arg(long, env, conflicts_with = "ledger_path")]
+    full_snapshot_archive: Option<PathBuf>,
+
+    /// Incremental snapshot archive on top of --full-snapshot-archive.
+    #[arg(long, env, requires = "full_snapshot_archive")]
+    incremental_snapshot_archive: Option<PathBuf>,
+
+    /// Directory the snapshot archives are unpacked in, defaults to the system temp directory.
+    #[arg(long, env, requires = "full_snapshot_archive")]
+    snapshot_unpack_dir: Option<PathBuf>,
 
     /// The tip-distribution program id.
     #[arg(long, env)]
@@ -53,13 +76,30 @@ fn main() {
 
     let args: Args = Args::parse();
 
-    if let Err(e) = generate_stake_meta(
-        &args.ledger_path,
-        &args.snapshot_slot,
-        &args.tip_distribution_program_id,
-        &args.out_path,
-        &args.tip_payment_program_id,
-    ) {
+    let result = if let Some(full_snapshot_archive) = &args.full_snapshot_archive {
+        generate_stake_meta_from_snapshot_archives(
+            full_snapshot_archive,
+            args.incremental_snapshot_archive.as_deref(),
+            &args
+                .snapshot_unpack_dir
+                .clone()
+                .unwrap_or_else(std::env::temp_dir),
+            &args.snapshot_slot,
+            &args.tip_distribution_program_id,
+            &args.out_path,
+            &args.tip_payment_program_id,
+        )
+    } else {
+        generate_stake_meta(
+            args.ledger_path.as_ref().unwrap(),
+            &args.snapshot_slot,
+            &args.tip_distribution_program_id,
+            &args.out_path,
+            &args.tip_payment_program_id,
+        )
+    };
+
+    if let Err(e) = result {
         error!("error producing stake-meta: {:?}", e);
     } else {
         info!("produced stake meta");
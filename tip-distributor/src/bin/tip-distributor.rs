@@ -0,0 +1,148 @@
+//! This binary runs the whole tip distribution as a daemon: once an epoch ends and its snapshot
+//! shows up, it generates the stake meta and merkle trees, uploads the merkle roots, claims the
+//! tips and reclaims rent, persisting its progress in between.
+use {
+    clap::Parser,
+    gethostname::gethostname,
+    log::*,
+    solana_metrics::set_host_id,
+    solana_sdk::{clock::Slot, pubkey::Pubkey},
+    solana_tip_distributor::{
+        claim_mev_workflow::ClaimThrottle,
+        scheduler_workflow::{run_scheduler, SchedulerConfig},
+    },
+    std::{
+        path::PathBuf,
+        sync::{atomic::AtomicBool, Arc},
+        time::Duration,
+    },
+};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// RPC to read the epoch from and send transactions through.
+    #[arg(long, env, default_value = "http://localhost:8899")]
+    rpc_url: String,
+
+    /// The tip-distribution program id.
+    #[arg(long, env)]
+    tip_distribution_program_id: Pubkey,
+
+    /// The tip-payment program id.
+    #[arg(long, env)]
+    tip_payment_program_id: Pubkey,
+
+    /// Path to the keypair that uploads the merkle roots and pays for the claims.
+    #[arg(long, env)]
+    keypair_path: PathBuf,
+
+    /// Directory the snapshot archive of the last slot of every epoch is written to.
+    #[arg(long, env)]
+    snapshot_archives_dir: PathBuf,
+
+    /// Directory the snapshot archives are unpacked in, defaults to the system temp directory.
+    #[arg(long, env)]
+    snapshot_unpack_dir: Option<PathBuf>,
+
+    /// Directory the scheduler state and the stake meta, merkle tree and claim journal files of
+    /// every epoch are kept in.
+    #[arg(long, env)]
+    work_dir: PathBuf,
+
+    /// Use the highest snapshot up to this many slots before the end of the epoch. Meant for test
+    /// clusters, where no snapshot is taken at the last slot of an epoch.
+    #[arg(long, env, default_value_t = 0)]
+    max_snapshot_slot_lag: Slot,
+
+    /// Path to JSON file with the [DistributionPolicyConfig] used to split the tips between
+    /// claimants. Defaults to the validator commission with the rest pro-rata to delegations.
+    #[arg(long, env)]
+    distribution_policy_path: Option<PathBuf>,
+
+    /// Rate-limits the maximum number of requests per RPC connection
+    #[arg(long, env, default_value_t = 100)]
+    max_concurrent_rpc_get_reqs: usize,
+
+    /// Number of merkle root upload transactions to send to RPC at a time.
+    #[arg(long, env, default_value_t = 64)]
+    txn_send_batch_size: usize,
+
+    /// Limits how long the claim and reclaim rent send loops run before stopping
+    #[arg(long, env, default_value_t = 60 * 60)]
+    max_retry_duration_secs: u64,
+
+    /// The price to pay for priority fee
+    #[arg(long, env, default_value_t = 1)]
+    micro_lamports: u64,
+
+    /// Added to the claims' priority fee every round claims are still left
+    #[arg(long, env, default_value_t = 0)]
+    micro_lamports_increment: u64,
+
+    /// Upper bound on the claims' priority fee
+    #[arg(long, env, default_value_t = 1_000_000)]
+    max_micro_lamports: u64,
+
+    /// Maximum number of claim transactions sent per second
+    #[arg(long, env)]
+    max_tps: Option<u64>,
+
+    /// Specifies whether to reclaim any rent.
+    #[arg(long, env, default_value_t = true)]
+    should_reclaim_rent: bool,
+
+    /// Specifies whether to reclaim rent on behalf of validators from respective TDAs.
+    #[arg(long, env)]
+    should_reclaim_tdas: bool,
+
+    /// How often to check for an epoch rollover, a snapshot, or retry a failed stage.
+    #[arg(long, env, default_value_t = 30)]
+    poll_interval_secs: u64,
+
+    /// How many times a stage is tried before the epoch is given up on and the next one is
+    /// distributed.
+    #[arg(long, env, default_value_t = 20)]
+    max_stage_attempts: u32,
+}
+
+fn main() {
+    env_logger::init();
+
+    gethostname()
+        .into_string()
+        .map(set_host_id)
+        .expect("set hostname");
+
+    let args: Args = Args::parse();
+    info!("starting tip-distributor...");
+
+    let config = SchedulerConfig {
+        rpc_url: args.rpc_url,
+        tip_distribution_program_id: args.tip_distribution_program_id,
+        tip_payment_program_id: args.tip_payment_program_id,
+        keypair_path: args.keypair_path,
+        snapshot_archives_dir: args.snapshot_archives_dir,
+        snapshot_unpack_dir: args.snapshot_unpack_dir.unwrap_or_else(std::env::temp_dir),
+        work_dir: args.work_dir,
+        max_snapshot_slot_lag: args.max_snapshot_slot_lag,
+        distribution_policy_path: args.distribution_policy_path,
+        max_concurrent_rpc_get_reqs: args.max_concurrent_rpc_get_reqs,
+        txn_send_batch_size: args.txn_send_batch_size,
+        claim_throttle: ClaimThrottle {
+            max_tps: args.max_tps,
+            micro_lamports: args.micro_lamports,
+            micro_lamports_increment: args.micro_lamports_increment,
+            max_micro_lamports: args.max_micro_lamports,
+        },
+        max_claim_duration: Duration::from_secs(args.max_retry_duration_secs),
+        should_reclaim_rent: args.should_reclaim_rent,
+        should_reclaim_tdas: args.should_reclaim_tdas,
+        poll_interval: Duration::from_secs(args.poll_interval_secs),
+        max_stage_attempts: args.max_stage_attempts,
+    };
+
+    if let Err(e) = run_scheduler(config, Arc::new(AtomicBool::new(false))) {
+        panic!("tip-distributor exited: {:?}", e);
+    }
+}
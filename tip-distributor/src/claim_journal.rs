@@ -0,0 +1,227 @@
+//! Append-only journal of the claim workflow's progress, so a restarted `claim-mev-tips` picks up
+//! where it left off instead of re-reading every ClaimStatus PDA of the epoch.
+//!
+//! Every line is a JSON [JournalEntry]. Entries are flushed and synced as they're recorded; a
+//! partially written last line left by a crash is ignored when the journal is reopened.
+use {
+    crate::{claim_mev_workflow::ClaimMevError, pubkey_string_conversion},
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_sdk::{clock::Epoch, pubkey::Pubkey, signature::Signature},
+    std::{
+        collections::{HashMap, HashSet},
+        fs::{File, OpenOptions},
+        io::{Read, Write},
+        path::Path,
+        str::FromStr,
+    },
+};
+
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum JournalEntry {
+    /// First entry of a journal, a journal only covers the claims of a single epoch.
+    Started { epoch: Epoch },
+    /// A claim transaction was signed and is about to be sent.
+    Submitted {
+        #[serde(with = "pubkey_string_conversion")]
+        claim_status: Pubkey,
+        signature: String,
+    },
+    /// The claim landed or the ClaimStatus account was found on-chain, it never needs to be
+    /// looked at again.
+    Confirmed {
+        #[serde(with = "pubkey_string_conversion")]
+        claim_status: Pubkey,
+    },
+}
+
+pub struct ClaimJournal {
+    file: File,
+    confirmed: HashSet<Pubkey>,
+    /// Signatures sent for claims that aren't confirmed yet, by claim status
+    pending: HashMap<Pubkey, Vec<Signature>>,
+}
+
+impl ClaimJournal {
+    /// Opens the journal at `path`, creating it if it doesn't exist. Fails if the journal was
+    /// started for a different epoch.
+    pub fn open(path: &Path, epoch: Epoch) -> Result<Self, ClaimMevError> {
+        let mut journal = Self {
+            file: OpenOptions::new()
+                .create(true)
+                .read(true)
+                .append(true)
+                .open(path)?,
+            confirmed: HashSet::new(),
+            pending: HashMap::new(),
+        };
+
+        let mut contents = String::new();
+        journal.file.read_to_string(&mut contents)?;
+        // don't let new entries get appended to a partially written one
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            journal.file.write_all(b"\n")?;
+        }
+
+        let mut journal_epoch = None;
+        let mut num_entries = 0;
+        for line in contents.lines() {
+            let Ok(entry) = serde_json::from_str::<JournalEntry>(line) else {
+                warn!("ignoring corrupt claim journal entry: {line}");
+                continue;
+            };
+            num_entries += 1;
+            match entry {
+                JournalEntry::Started { epoch } => journal_epoch = Some(epoch),
+                JournalEntry::Submitted {
+                    claim_status,
+                    signature,
+                } => {
+                    if let Ok(signature) = Signature::from_str(&signature) {
+                        journal
+                            .pending
+                            .entry(claim_status)
+                            .or_default()
+                            .push(signature);
+                    }
+                }
+                JournalEntry::Confirmed { claim_status } => {
+                    journal.pending.remove(&claim_status);
+                    journal.confirmed.insert(claim_status);
+                }
+            }
+        }
+
+        match journal_epoch {
+            Some(journal_epoch) if journal_epoch != epoch => {
+                return Err(ClaimMevError::JournalEpochMismatch {
+                    journal_epoch,
+                    epoch,
+                });
+            }
+            Some(_) => {}
+            None => journal.append(&[JournalEntry::Started { epoch }])?,
+        }
+        info!(
+            "opened claim journal with {num_entries} entries, {} confirmed and {} pending claims",
+            journal.confirmed.len(),
+            journal.pending.len()
+        );
+
+        Ok(journal)
+    }
+
+    pub fn confirmed(&self) -> &HashSet<Pubkey> {
+        &self.confirmed
+    }
+
+    /// Signatures of claim transactions sent by a previous run that aren't known to have landed.
+    pub fn pending_signatures(&self) -> impl Iterator<Item = (&Pubkey, &Signature)> {
+        self.pending.iter().flat_map(|(claim_status, signatures)| {
+            signatures
+                .iter()
+                .map(move |signature| (claim_status, signature))
+        })
+    }
+
+    pub fn record_submitted(
+        &mut self,
+        submitted: impl IntoIterator<Item = (Pubkey, Signature)>,
+    ) -> std::io::Result<()> {
+        let entries: Vec<_> = submitted
+            .into_iter()
+            .map(|(claim_status, signature)| {
+                self.pending
+                    .entry(claim_status)
+                    .or_default()
+                    .push(signature);
+                JournalEntry::Submitted {
+                    claim_status,
+                    signature: signature.to_string(),
+                }
+            })
+            .collect();
+        self.append(&entries)
+    }
+
+    pub fn record_confirmed(
+        &mut self,
+        claim_statuses: impl IntoIterator<Item = Pubkey>,
+    ) -> std::io::Result<()> {
+        let entries: Vec<_> = claim_statuses
+            .into_iter()
+            .filter(|claim_status| self.confirmed.insert(*claim_status))
+            .map(|claim_status| {
+                self.pending.remove(&claim_status);
+                JournalEntry::Confirmed { claim_status }
+            })
+            .collect();
+        self.append(&entries)
+    }
+
+    fn append(&mut self, entries: &[JournalEntry]) -> std::io::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut buf = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut buf, entry)?;
+            buf.push(b'\n');
+        }
+        self.file.write_all(&buf)?;
+        self.file.sync_data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    #[test]
+    fn test_claim_journal_resume() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("claim-journal.jsonl");
+        let (claim_status_0, claim_status_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (signature_0, signature_1) = (Signature::new_unique(), Signature::new_unique());
+
+        {
+            let mut journal = ClaimJournal::open(&path, 10).unwrap();
+            journal
+                .record_submitted([(claim_status_0, signature_0), (claim_status_1, signature_1)])
+                .unwrap();
+            journal.record_confirmed([claim_status_0]).unwrap();
+        }
+        // a crash in the middle of writing an entry
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"confirmed\":{\"claim_st")
+            .unwrap();
+
+        let mut journal = ClaimJournal::open(&path, 10).unwrap();
+        assert_eq!(journal.confirmed(), &HashSet::from([claim_status_0]));
+        assert_eq!(
+            journal.pending_signatures().collect::<Vec<_>>(),
+            vec![(&claim_status_1, &signature_1)]
+        );
+        journal.record_confirmed([claim_status_1]).unwrap();
+        drop(journal);
+
+        let journal = ClaimJournal::open(&path, 10).unwrap();
+        assert_eq!(
+            journal.confirmed(),
+            &HashSet::from([claim_status_0, claim_status_1])
+        );
+        assert_eq!(journal.pending_signatures().count(), 0);
+
+        assert!(matches!(
+            ClaimJournal::open(&path, 11),
+            Err(ClaimMevError::JournalEpochMismatch {
+                journal_epoch: 10,
+                epoch: 11
+            })
+        ));
+    }
+}
@@ -1,5 +1,8 @@
 use {
-    crate::{send_until_blockhash_expires, GeneratedMerkleTreeCollection},
+    crate::{
+        claim_journal::ClaimJournal, get_batched_signatures_statuses, print_dry_run_transactions,
+        send_signed_until_blockhash_expires, GeneratedMerkleTreeCollection,
+    },
     anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas},
     itertools::Itertools,
     jito_tip_distribution::state::{ClaimStatus, Config, TipDistributionAccount},
@@ -14,15 +17,17 @@ use {
     solana_rpc_client_api::config::RpcSimulateTransactionConfig,
     solana_sdk::{
         account::Account,
+        clock::Epoch,
         commitment_config::CommitmentConfig,
         compute_budget::ComputeBudgetInstruction,
         instruction::Instruction,
         pubkey::Pubkey,
-        signature::{Keypair, Signer},
+        signature::{Keypair, Signature, Signer},
         transaction::Transaction,
     },
     std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet},
+        path::Path,
         sync::Arc,
         time::{Duration, Instant},
     },
@@ -56,19 +61,50 @@ pub enum ClaimMevError {
 
     #[error("UncaughtError {e:?}")]
     UncaughtError { e: String },
+
+    #[error("Claim journal is for epoch {journal_epoch}, not epoch {epoch}")]
+    JournalEpochMismatch { journal_epoch: Epoch, epoch: Epoch },
+}
+
+/// Controls how fast claims are sent and what they pay in priority fees.
+#[derive(Clone, Copy, Debug)]
+pub struct ClaimThrottle {
+    /// Maximum number of claim transactions sent per second, unlimited if None
+    pub max_tps: Option<u64>,
+    /// Priority fee of the first round of claims
+    pub micro_lamports: u64,
+    /// Added to the priority fee every round claims are still left
+    pub micro_lamports_increment: u64,
+    /// Upper bound on the priority fee
+    pub max_micro_lamports: u64,
 }
 
+impl ClaimThrottle {
+    /// Priority fee for the given send round, starting at 0.
+    pub fn micro_lamports(&self, round: u64) -> u64 {
+        self.micro_lamports_increment
+            .saturating_mul(round)
+            .saturating_add(self.micro_lamports)
+            .min(self.max_micro_lamports.max(self.micro_lamports))
+    }
+}
+
+/// Returns the claim transactions of tree nodes not in `confirmed`, adding the claims found
+/// on-chain to it.
 pub async fn get_claim_transactions_for_valid_unclaimed(
     rpc_client: &RpcClient,
     merkle_trees: &GeneratedMerkleTreeCollection,
     tip_distribution_program_id: Pubkey,
     micro_lamports: u64,
     payer_pubkey: Pubkey,
-) -> Result<Vec<Transaction>, ClaimMevError> {
+    confirmed: &mut HashSet<Pubkey>,
+) -> Result<Vec<(Pubkey, Transaction)>, ClaimMevError> {
+    // confirmed claims don't need their claimant and claim status read again
     let tree_nodes = merkle_trees
         .generated_merkle_trees
         .iter()
         .flat_map(|tree| &tree.tree_nodes)
+        .filter(|tree_node| !confirmed.contains(&tree_node.claim_status_pubkey))
         .collect_vec();
 
     info!(
@@ -124,6 +160,8 @@ pub async fn get_claim_transactions_for_valid_unclaimed(
         ("claim_statuses", claim_status_pubkeys.len(), i64),
         ("claim_statuses_onchain", claim_statuses.len(), i64),
     );
+    // a claim status account only exists once the claim landed
+    confirmed.extend(claim_statuses.keys());
 
     let transactions = build_mev_claim_transactions(
         tip_distribution_program_id,
@@ -133,18 +171,27 @@ pub async fn get_claim_transactions_for_valid_unclaimed(
         claim_statuses,
         micro_lamports,
         payer_pubkey,
+        confirmed,
     );
 
     Ok(transactions)
 }
 
+/// Claims the tips of all valid, unclaimed tree nodes. With `dry_run` the claim transactions are
+/// printed instead of sent.
+///
+/// With a `journal_path`, sent and confirmed claims are recorded to a [ClaimJournal] so a
+/// restarted run skips the claims that already landed.
+#[allow(clippy::too_many_arguments)]
 pub async fn claim_mev_tips(
     merkle_trees: &GeneratedMerkleTreeCollection,
     rpc_url: String,
     tip_distribution_program_id: Pubkey,
     keypair: Arc<Keypair>,
     max_loop_duration: Duration,
-    micro_lamports: u64,
+    throttle: ClaimThrottle,
+    journal_path: Option<&Path>,
+    dry_run: bool,
 ) -> Result<(), ClaimMevError> {
     let rpc_client = RpcClient::new_with_timeout_and_commitment(
         rpc_url,
@@ -152,20 +199,57 @@ pub async fn claim_mev_tips(
         CommitmentConfig::confirmed(),
     );
 
+    if dry_run {
+        let transactions = get_claim_transactions_for_valid_unclaimed(
+            &rpc_client,
+            merkle_trees,
+            tip_distribution_program_id,
+            throttle.micro_lamports(0),
+            keypair.pubkey(),
+            &mut HashSet::new(),
+        )
+        .await?;
+        info!(
+            "dry run, not sending {} claim transactions",
+            transactions.len()
+        );
+        let transactions: Vec<_> = transactions.into_iter().map(|(_, tx)| tx).collect();
+        print_dry_run_transactions(&transactions);
+        return Ok(());
+    }
+
+    let mut journal = journal_path
+        .map(|path| ClaimJournal::open(path, merkle_trees.epoch))
+        .transpose()?;
+    if let Some(journal) = &mut journal {
+        confirm_pending_claims(&rpc_client, journal).await?;
+    }
+    let mut confirmed = journal
+        .as_ref()
+        .map(|journal| journal.confirmed().clone())
+        .unwrap_or_default();
+
     let start = Instant::now();
+    let mut round = 0;
     while start.elapsed() <= max_loop_duration {
         let mut all_claim_transactions = get_claim_transactions_for_valid_unclaimed(
             &rpc_client,
             merkle_trees,
             tip_distribution_program_id,
-            micro_lamports,
+            throttle.micro_lamports(round),
             keypair.pubkey(),
+            &mut confirmed,
         )
         .await?;
+        if let Some(journal) = &mut journal {
+            journal.record_confirmed(confirmed.iter().copied())?;
+        }
 
         datapoint_info!(
             "claim_mev_tips-send_summary",
             ("claim_transactions_left", all_claim_transactions.len(), i64),
+            ("round", round, i64),
+            ("micro_lamports", throttle.micro_lamports(round), i64),
         );
 
         if all_claim_transactions.is_empty() {
@@ -188,17 +272,54 @@ pub async fn claim_mev_tips(
         }
 
         let blockhash = rpc_client.get_latest_blockhash().await?;
-        let _ = send_until_blockhash_expires(&rpc_client, transactions, blockhash, &keypair).await;
+        let mut claim_statuses = HashMap::with_capacity(transactions.len());
+        let transactions: Vec<_> = transactions
+            .into_iter()
+            .map(|(claim_status, mut tx)| {
+                tx.sign(&[&keypair], blockhash);
+                claim_statuses.insert(*tx.get_signature(), claim_status);
+                tx
+            })
+            .collect();
+        if let Some(journal) = &mut journal {
+            journal.record_submitted(
+                claim_statuses
+                    .iter()
+                    .map(|(signature, claim_status)| (*claim_status, *signature)),
+            )?;
+        }
+
+        let landed = send_signed_until_blockhash_expires(
+            &rpc_client,
+            transactions,
+            blockhash,
+            throttle.max_tps,
+        )
+        .await;
+        match (landed, &mut journal) {
+            (Ok(landed), Some(journal)) => journal.record_confirmed(
+                landed
+                    .iter()
+                    .filter_map(|signature| claim_statuses.get(signature).copied()),
+            )?,
+            (Ok(_), None) => {}
+            (Err(e), _) => warn!("error sending claim transactions: {e:?}"),
+        }
+        round += 1;
     }
 
     let transactions = get_claim_transactions_for_valid_unclaimed(
         &rpc_client,
         merkle_trees,
         tip_distribution_program_id,
-        micro_lamports,
+        throttle.micro_lamports(round),
         keypair.pubkey(),
+        &mut confirmed,
     )
     .await?;
+    if let Some(journal) = &mut journal {
+        journal.record_confirmed(confirmed.iter().copied())?;
+    }
     if transactions.is_empty() {
         return Ok(());
     }
@@ -206,7 +327,7 @@ pub async fn claim_mev_tips(
     // if more transactions left, we'll simulate them all to make sure its not an uncaught error
     let mut is_error = false;
     let mut error_str = String::new();
-    for tx in &transactions {
+    for (_, tx) in &transactions {
         match rpc_client
             .simulate_transaction_with_config(
                 tx,
@@ -246,6 +367,36 @@ pub async fn claim_mev_tips(
     }
 }
 
+/// Records the claims sent by a previous run that have since been confirmed. Claims that were only
+/// processed stay pending until a later run sees them confirmed.
+async fn confirm_pending_claims(
+    rpc_client: &RpcClient,
+    journal: &mut ClaimJournal,
+) -> Result<(), ClaimMevError> {
+    let (claim_statuses, signatures): (Vec<Pubkey>, Vec<Signature>) = journal
+        .pending_signatures()
+        .map(|(claim_status, signature)| (*claim_status, *signature))
+        .unzip();
+    if signatures.is_empty() {
+        return Ok(());
+    }
+
+    let statuses = get_batched_signatures_statuses(rpc_client, &signatures).await?;
+    let landed =
+        claim_statuses
+            .into_iter()
+            .zip(statuses)
+            .filter_map(|(claim_status, (_, status))| {
+                status.filter(|status| {
+                    status.err.is_none()
+                        && status.satisfies_commitment(CommitmentConfig::confirmed())
+                })?;
+                Some(claim_status)
+            });
+    journal.record_confirmed(landed)?;
+    Ok(())
+}
+
 /// Returns a list of claim transactions for valid, unclaimed MEV tips
 /// A valid, unclaimed transaction consists of the following:
 /// - there must be lamports to claim for the tip distribution account.
@@ -255,6 +406,9 @@ pub async fn claim_mev_tips(
 /// - the claimant must have enough lamports post-claim to be rent-exempt.
 ///   - note: there aren't any rent exempt accounts on solana mainnet anymore.
 /// - it must not have already been claimed.
+///
+/// Tree nodes whose claim status is in `confirmed` are skipped. Each transaction is returned with
+/// the claim status it creates.
 fn build_mev_claim_transactions(
     tip_distribution_program_id: Pubkey,
     merkle_trees: &GeneratedMerkleTreeCollection,
@@ -263,7 +417,8 @@ fn build_mev_claim_transactions(
     claim_status: HashMap<Pubkey, Account>,
     micro_lamports: u64,
     payer_pubkey: Pubkey,
-) -> Vec<Transaction> {
+    confirmed: &HashSet<Pubkey>,
+) -> Vec<(Pubkey, Transaction)> {
     let tip_distribution_accounts: HashMap<Pubkey, TipDistributionAccount> = tdas
         .iter()
         .filter_map(|(pubkey, account)| {
@@ -318,40 +473,47 @@ fn build_mev_claim_transactions(
             // doesn't make sense to claim for claimants that don't exist anymore
             // can't claim for something already claimed
             // don't need to claim for claimants that get 0 MEV
-            if !claimants.contains_key(&node.claimant)
+            if confirmed.contains(&node.claim_status_pubkey)
+                || !claimants.contains_key(&node.claimant)
                 || claim_statuses.contains_key(&node.claim_status_pubkey)
                 || node.amount == 0
             {
                 continue;
             }
 
-            instructions.push(Instruction {
-                program_id: tip_distribution_program_id,
-                data: jito_tip_distribution::instruction::Claim {
-                    proof: node.proof.clone().unwrap(),
-                    amount: node.amount,
-                    bump: node.claim_status_bump,
-                }
-                .data(),
-                accounts: jito_tip_distribution::accounts::Claim {
-                    config: tip_distribution_config,
-                    tip_distribution_account: tree.tip_distribution_account,
-                    claimant: node.claimant,
-                    claim_status: node.claim_status_pubkey,
-                    payer: payer_pubkey,
-                    system_program: system_program::id(),
-                }
-                .to_account_metas(None),
-            });
+            instructions.push((
+                node.claim_status_pubkey,
+                Instruction {
+                    program_id: tip_distribution_program_id,
+                    data: jito_tip_distribution::instruction::Claim {
+                        proof: node.proof.clone().unwrap(),
+                        amount: node.amount,
+                        bump: node.claim_status_bump,
+                    }
+                    .data(),
+                    accounts: jito_tip_distribution::accounts::Claim {
+                        config: tip_distribution_config,
+                        tip_distribution_account: tree.tip_distribution_account,
+                        claimant: node.claimant,
+                        claim_status: node.claim_status_pubkey,
+                        payer: payer_pubkey,
+                        system_program: system_program::id(),
+                    }
+                    .to_account_metas(None),
+                },
+            ));
         }
     }
 
     // TODO (LB): see if we can do >1 claim here
-    let transactions: Vec<Transaction> = instructions
+    let transactions: Vec<(Pubkey, Transaction)> = instructions
         .into_iter()
-        .map(|claim_ix| {
+        .map(|(claim_status, claim_ix)| {
             let priority_fee_ix = ComputeBudgetInstruction::set_compute_unit_price(micro_lamports);
-            Transaction::new_with_payer(&[priority_fee_ix, claim_ix], Some(&payer_pubkey))
+            (
+                claim_status,
+                Transaction::new_with_payer(&[priority_fee_ix, claim_ix], Some(&payer_pubkey)),
+            )
         })
         .collect();
 
@@ -396,3 +558,60 @@ async fn is_sufficient_balance(
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde_json::json,
+        solana_client::{
+            nonblocking::rpc_client::Mocks,
+            rpc_request::RpcRequest,
+            rpc_response::{Response, RpcResponseContext},
+        },
+        solana_transaction_status::{TransactionConfirmationStatus, TransactionStatus},
+        tempfile::TempDir,
+    };
+
+    fn rpc_client_with_status(confirmation_status: TransactionConfirmationStatus) -> RpcClient {
+        let statuses = Response {
+            context: RpcResponseContext::new(1),
+            value: vec![Some(TransactionStatus {
+                slot: 1,
+                confirmations: Some(0),
+                status: Ok(()),
+                err: None,
+                confirmation_status: Some(confirmation_status),
+            })],
+        };
+        RpcClient::new_mock_with_mocks(
+            "succeeds".to_string(),
+            Mocks::from([(RpcRequest::GetSignatureStatuses, json!(statuses))]),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_confirm_pending_claims_requires_confirmed() {
+        let dir = TempDir::new().unwrap();
+        let mut journal = ClaimJournal::open(&dir.path().join("claim-journal.jsonl"), 10).unwrap();
+        let claim_status = Pubkey::new_unique();
+        journal
+            .record_submitted([(claim_status, Signature::new_unique())])
+            .unwrap();
+
+        // a processed claim could still be rolled back, so it stays pending
+        let rpc_client = rpc_client_with_status(TransactionConfirmationStatus::Processed);
+        confirm_pending_claims(&rpc_client, &mut journal)
+            .await
+            .unwrap();
+        assert!(journal.confirmed().is_empty());
+        assert_eq!(journal.pending_signatures().count(), 1);
+
+        let rpc_client = rpc_client_with_status(TransactionConfirmationStatus::Confirmed);
+        confirm_pending_claims(&rpc_client, &mut journal)
+            .await
+            .unwrap();
+        assert_eq!(journal.confirmed(), &HashSet::from([claim_status]));
+        assert_eq!(journal.pending_signatures().count(), 0);
+    }
+}
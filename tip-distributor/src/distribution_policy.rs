@@ -0,0 +1,556 @@
+//! Policies deciding how a validator's tips are split between the validator, its delegators and
+//! anyone else when building the merkle trees. [DefaultPolicy] is the split the tip distribution
+//! program was designed around; operators can layer protocol fees, minimum claims and per-stake
+//! authority commissions on top of it through a [DistributionPolicyConfig].
+use {
+    crate::{pubkey_string_conversion, Delegation, StakeMeta, TipDistributionMeta},
+    serde::{Deserialize, Serialize},
+    solana_sdk::pubkey::Pubkey,
+    std::{collections::HashMap, iter},
+    thiserror::Error,
+};
+
+const MAX_BPS: u128 = 10_000;
+
+#[derive(Debug, Error)]
+pub enum DistributionPolicyConfigError {
+    #[error("protocol fee_bps {0} is more than {MAX_BPS}")]
+    InvalidProtocolFeeBps(u16),
+
+    #[error("validator_fee_bps {validator_fee_bps} of {stake_authority} is more than {MAX_BPS}")]
+    InvalidValidatorFeeBps {
+        stake_authority: Pubkey,
+        validator_fee_bps: u16,
+    },
+}
+
+/// An amount of tips a claimant is entitled to, turned into a [crate::TreeNode] once the claim
+/// status PDA is derived.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TipClaim {
+    pub claimant: Pubkey,
+    pub staker_pubkey: Pubkey,
+    pub withdrawer_pubkey: Pubkey,
+    pub amount: u64,
+}
+
+impl TipClaim {
+    fn validator(stake_meta: &StakeMeta, amount: u64) -> Self {
+        Self {
+            claimant: stake_meta.validator_vote_account,
+            staker_pubkey: Pubkey::default(),
+            withdrawer_pubkey: Pubkey::default(),
+            amount,
+        }
+    }
+
+    fn delegation(delegation: &Delegation, amount: u64) -> Self {
+        Self {
+            claimant: delegation.stake_account_pubkey,
+            staker_pubkey: delegation.staker_pubkey,
+            withdrawer_pubkey: delegation.withdrawer_pubkey,
+            amount,
+        }
+    }
+}
+
+pub trait DistributionPolicy {
+    /// Splits the validator's `total_tips` into claims, the validator's first. The claims must not
+    /// add up to more than `total_tips`.
+    fn claims(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipClaim>;
+}
+
+fn bps_of(amount: u64, bps: u16) -> u64 {
+    (amount as u128)
+        .checked_mul(bps as u128)
+        .unwrap()
+        .checked_div(MAX_BPS)
+        .unwrap() as u64
+}
+
+/// The validator takes its on-chain commission off the top and the remainder goes to the
+/// delegations pro-rata to their stake. Rounding dust is left in the tip distribution account.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultPolicy;
+
+impl DistributionPolicy for DefaultPolicy {
+    fn claims(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipClaim> {
+        let validator_amount = bps_of(
+            tip_distribution_meta.total_tips,
+            tip_distribution_meta.validator_fee_bps,
+        );
+        let remaining_total_rewards = tip_distribution_meta
+            .total_tips
+            .checked_sub(validator_amount)
+            .unwrap() as u128;
+        let total_delegated = stake_meta.total_delegated as u128;
+
+        iter::once(TipClaim::validator(stake_meta, validator_amount))
+            .chain(stake_meta.delegations.iter().map(|delegation| {
+                let amount_delegated = delegation.lamports_delegated as u128;
+                let reward_amount = amount_delegated
+                    .checked_mul(remaining_total_rewards)
+                    .unwrap()
+                    .checked_div(total_delegated)
+                    .unwrap();
+                TipClaim::delegation(delegation, reward_amount as u64)
+            }))
+            .collect()
+    }
+}
+
+/// Delegations whose staker or withdrawer authority has an override pay that commission on their
+/// pro-rata share of the tips instead of the validator's on-chain one. The validator gets whatever
+/// the delegations don't, including rounding dust.
+pub struct StakeAuthorityOverridePolicy {
+    validator_fee_bps_overrides: HashMap<Pubkey, u16>,
+}
+
+impl StakeAuthorityOverridePolicy {
+    pub fn new(validator_fee_bps_overrides: HashMap<Pubkey, u16>) -> Self {
+        Self {
+            validator_fee_bps_overrides,
+        }
+    }
+
+    fn validator_fee_bps(&self, delegation: &Delegation, default_fee_bps: u16) -> u16 {
+        self.validator_fee_bps_overrides
+            .get(&delegation.staker_pubkey)
+            .or_else(|| {
+                self.validator_fee_bps_overrides
+                    .get(&delegation.withdrawer_pubkey)
+            })
+            .copied()
+            .unwrap_or(default_fee_bps)
+    }
+}
+
+impl DistributionPolicy for StakeAuthorityOverridePolicy {
+    fn claims(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipClaim> {
+        let total_tips = tip_distribution_meta.total_tips as u128;
+        let total_delegated = stake_meta.total_delegated as u128;
+
+        let delegation_claims: Vec<_> = stake_meta
+            .delegations
+            .iter()
+            .map(|delegation| {
+                let share = (delegation.lamports_delegated as u128)
+                    .checked_mul(total_tips)
+                    .unwrap()
+                    .checked_div(total_delegated)
+                    .unwrap() as u64;
+                let validator_fee_bps =
+                    self.validator_fee_bps(delegation, tip_distribution_meta.validator_fee_bps);
+                let amount = share.checked_sub(bps_of(share, validator_fee_bps)).unwrap();
+                TipClaim::delegation(delegation, amount)
+            })
+            .collect();
+        let delegations_total = delegation_claims
+            .iter()
+            .map(|claim| claim.amount)
+            .fold(0u64, |sum, amount| sum.checked_add(amount).unwrap());
+        let validator_amount = tip_distribution_meta
+            .total_tips
+            .checked_sub(delegations_total)
+            .expect("delegations claim more than total tips");
+
+        iter::once(TipClaim::validator(stake_meta, validator_amount))
+            .chain(delegation_claims)
+            .collect()
+    }
+}
+
+/// Takes `fee_bps` of the tips off the top for `fee_account` and splits the rest with `inner`. If
+/// `fee_account` is also one of the claimants of `inner`, the fee is added to that claim since each
+/// claimant can only have one claim status.
+pub struct ProtocolFeePolicy {
+    fee_account: Pubkey,
+    fee_bps: u16,
+    inner: Box<dyn DistributionPolicy>,
+}
+
+impl ProtocolFeePolicy {
+    pub fn new(fee_account: Pubkey, fee_bps: u16, inner: Box<dyn DistributionPolicy>) -> Self {
+        Self {
+            fee_account,
+            fee_bps,
+            inner,
+        }
+    }
+}
+
+impl DistributionPolicy for ProtocolFeePolicy {
+    fn claims(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipClaim> {
+        let fee = bps_of(tip_distribution_meta.total_tips, self.fee_bps);
+        let mut claims = self.inner.claims(
+            stake_meta,
+            &TipDistributionMeta {
+                total_tips: tip_distribution_meta.total_tips.checked_sub(fee).unwrap(),
+                ..tip_distribution_meta.clone()
+            },
+        );
+        if fee > 0 {
+            match claims
+                .iter_mut()
+                .find(|claim| claim.claimant == self.fee_account)
+            {
+                Some(claim) => claim.amount = claim.amount.checked_add(fee).unwrap(),
+                None => claims.push(TipClaim {
+                    claimant: self.fee_account,
+                    staker_pubkey: Pubkey::default(),
+                    withdrawer_pubkey: Pubkey::default(),
+                    amount: fee,
+                }),
+            }
+        }
+        claims
+    }
+}
+
+/// Drops the claims of `inner` below `minimum_claim_lamports`, which cost more to claim than
+/// they're worth, and rolls them and any rounding dust into the validator's claim so the claims
+/// add up to exactly `total_tips`.
+pub struct MinimumClaimPolicy {
+    minimum_claim_lamports: u64,
+    inner: Box<dyn DistributionPolicy>,
+}
+
+impl MinimumClaimPolicy {
+    pub fn new(minimum_claim_lamports: u64, inner: Box<dyn DistributionPolicy>) -> Self {
+        Self {
+            minimum_claim_lamports,
+            inner,
+        }
+    }
+}
+
+impl DistributionPolicy for MinimumClaimPolicy {
+    fn claims(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipClaim> {
+        let mut claims = self.inner.claims(stake_meta, tip_distribution_meta);
+        claims.retain(|claim| {
+            claim.claimant == stake_meta.validator_vote_account
+                || claim.amount >= self.minimum_claim_lamports
+        });
+
+        let claimed = claims
+            .iter()
+            .map(|claim| claim.amount)
+            .fold(0u64, |sum, amount| sum.checked_add(amount).unwrap());
+        let dust = tip_distribution_meta
+            .total_tips
+            .checked_sub(claimed)
+            .expect("claims exceed total tips");
+        match claims
+            .iter_mut()
+            .find(|claim| claim.claimant == stake_meta.validator_vote_account)
+        {
+            Some(validator_claim) => {
+                validator_claim.amount = validator_claim.amount.checked_add(dust).unwrap()
+            }
+            None => claims.insert(0, TipClaim::validator(stake_meta, dust)),
+        }
+        claims
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ValidatorFeeOverride {
+    /// Staker or withdrawer authority of the delegations the override applies to.
+    #[serde(with = "pubkey_string_conversion")]
+    pub stake_authority: Pubkey,
+    pub validator_fee_bps: u16,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProtocolFee {
+    #[serde(with = "pubkey_string_conversion")]
+    pub fee_account: Pubkey,
+    pub fee_bps: u16,
+}
+
+/// JSON configuration of the distribution policy. The default configuration is the
+/// [DefaultPolicy].
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct DistributionPolicyConfig {
+    pub validator_fee_overrides: Vec<ValidatorFeeOverride>,
+    pub protocol_fee: Option<ProtocolFee>,
+    pub minimum_claim_lamports: Option<u64>,
+}
+
+impl DistributionPolicyConfig {
+    /// Fails if any of the basis points are more than 100%, which the policies can't split.
+    pub fn build(&self) -> Result<Box<dyn DistributionPolicy>, DistributionPolicyConfigError> {
+        if let Some(fee_override) = self
+            .validator_fee_overrides
+            .iter()
+            .find(|fee_override| fee_override.validator_fee_bps as u128 > MAX_BPS)
+        {
+            return Err(DistributionPolicyConfigError::InvalidValidatorFeeBps {
+                stake_authority: fee_override.stake_authority,
+                validator_fee_bps: fee_override.validator_fee_bps,
+            });
+        }
+        if let Some(protocol_fee) = self
+            .protocol_fee
+            .as_ref()
+            .filter(|protocol_fee| protocol_fee.fee_bps as u128 > MAX_BPS)
+        {
+            return Err(DistributionPolicyConfigError::InvalidProtocolFeeBps(
+                protocol_fee.fee_bps,
+            ));
+        }
+
+        let mut policy: Box<dyn DistributionPolicy> = if self.validator_fee_overrides.is_empty() {
+            Box::new(DefaultPolicy)
+        } else {
+            Box::new(StakeAuthorityOverridePolicy::new(
+                self.validator_fee_overrides
+                    .iter()
+                    .map(|fee_override| {
+                        (fee_override.stake_authority, fee_override.validator_fee_bps)
+                    })
+                    .collect(),
+            ))
+        };
+        if let Some(protocol_fee) = &self.protocol_fee {
+            policy = Box::new(ProtocolFeePolicy::new(
+                protocol_fee.fee_account,
+                protocol_fee.fee_bps,
+                policy,
+            ));
+        }
+        if let Some(minimum_claim_lamports) = self.minimum_claim_lamports {
+            policy = Box::new(MinimumClaimPolicy::new(minimum_claim_lamports, policy));
+        }
+        Ok(policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, proptest::prelude::*};
+
+    fn stake_meta(total_tips: u64, validator_fee_bps: u16, delegated: &[u64]) -> StakeMeta {
+        let delegations: Vec<_> = delegated
+            .iter()
+            .map(|lamports_delegated| Delegation {
+                stake_account_pubkey: Pubkey::new_unique(),
+                staker_pubkey: Pubkey::new_unique(),
+                withdrawer_pubkey: Pubkey::new_unique(),
+                lamports_delegated: *lamports_delegated,
+            })
+            .collect();
+        StakeMeta {
+            validator_vote_account: Pubkey::new_unique(),
+            validator_node_pubkey: Pubkey::new_unique(),
+            maybe_tip_distribution_meta: Some(TipDistributionMeta {
+                merkle_root_upload_authority: Pubkey::new_unique(),
+                tip_distribution_pubkey: Pubkey::new_unique(),
+                total_tips,
+                validator_fee_bps,
+            }),
+            total_delegated: delegated.iter().sum(),
+            delegations,
+            commission: 0,
+        }
+    }
+
+    fn claims_total(claims: &[TipClaim]) -> u64 {
+        claims.iter().map(|claim| claim.amount).sum()
+    }
+
+    fn claims(policy: &dyn DistributionPolicy, stake_meta: &StakeMeta) -> Vec<TipClaim> {
+        policy.claims(
+            stake_meta,
+            stake_meta.maybe_tip_distribution_meta.as_ref().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_default_config_is_default_policy() {
+        let stake_meta = stake_meta(1_000_003, 500, &[1, 2, 3]);
+        assert_eq!(
+            claims(
+                DistributionPolicyConfig::default()
+                    .build()
+                    .unwrap()
+                    .as_ref(),
+                &stake_meta
+            ),
+            claims(&DefaultPolicy, &stake_meta)
+        );
+        assert_eq!(
+            serde_json::from_str::<DistributionPolicyConfig>("{}").unwrap(),
+            DistributionPolicyConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_protocol_fee_policy() {
+        let stake_meta = stake_meta(1_000_000, 1_000, &[1, 1]);
+        let fee_account = Pubkey::new_unique();
+        let policy = ProtocolFeePolicy::new(fee_account, 500, Box::new(DefaultPolicy));
+
+        let claims = claims(&policy, &stake_meta);
+        let amounts: Vec<_> = claims.iter().map(|claim| claim.amount).collect();
+        // 5% protocol fee, then 10% of the remaining 950_000 to the validator
+        assert_eq!(amounts, vec![95_000, 427_500, 427_500, 50_000]);
+        assert_eq!(claims.last().unwrap().claimant, fee_account);
+    }
+
+    #[test]
+    fn test_protocol_fee_to_existing_claimant() {
+        let stake_meta = stake_meta(1_000_000, 1_000, &[1, 1]);
+        let fee_account = stake_meta.delegations[0].stake_account_pubkey;
+        let policy = ProtocolFeePolicy::new(fee_account, 500, Box::new(DefaultPolicy));
+
+        let claims = claims(&policy, &stake_meta);
+        let amounts: Vec<_> = claims.iter().map(|claim| claim.amount).collect();
+        // the fee is merged into the delegation's claim instead of a second claim for the account
+        assert_eq!(amounts, vec![95_000, 427_500 + 50_000, 427_500]);
+        assert_eq!(claims_total(&claims), 1_000_000);
+    }
+
+    #[test]
+    fn test_invalid_config_bps() {
+        let config = DistributionPolicyConfig {
+            protocol_fee: Some(ProtocolFee {
+                fee_account: Pubkey::new_unique(),
+                fee_bps: 10_001,
+            }),
+            ..DistributionPolicyConfig::default()
+        };
+        assert!(matches!(
+            config.build(),
+            Err(DistributionPolicyConfigError::InvalidProtocolFeeBps(10_001))
+        ));
+
+        let stake_authority = Pubkey::new_unique();
+        let config = DistributionPolicyConfig {
+            validator_fee_overrides: vec![ValidatorFeeOverride {
+                stake_authority,
+                validator_fee_bps: u16::MAX,
+            }],
+            ..DistributionPolicyConfig::default()
+        };
+        assert!(matches!(
+            config.build(),
+            Err(DistributionPolicyConfigError::InvalidValidatorFeeBps {
+                stake_authority: pubkey,
+                validator_fee_bps: u16::MAX,
+            }) if pubkey == stake_authority
+        ));
+
+        let config = DistributionPolicyConfig {
+            validator_fee_overrides: vec![ValidatorFeeOverride {
+                stake_authority,
+                validator_fee_bps: 10_000,
+            }],
+            protocol_fee: Some(ProtocolFee {
+                fee_account: Pubkey::new_unique(),
+                fee_bps: 10_000,
+            }),
+            minimum_claim_lamports: None,
+        };
+        assert!(config.build().is_ok());
+    }
+
+    #[test]
+    fn test_minimum_claim_policy() {
+        let stake_meta = stake_meta(1_000_000, 1_000, &[1, 1_000, 1_000]);
+        let policy = MinimumClaimPolicy::new(1_000, Box::new(DefaultPolicy));
+
+        let claims = claims(&policy, &stake_meta);
+        // the smallest delegation's 449 lamports and 1 lamport of rounding go to the validator
+        assert_eq!(claims.len(), 3);
+        assert_eq!(claims[0].claimant, stake_meta.validator_vote_account);
+        assert_eq!(claims[0].amount, 100_000 + 449 + 1);
+        assert_eq!(claims[1].amount, 449_775);
+        assert_eq!(claims_total(&claims), 1_000_000);
+    }
+
+    #[test]
+    fn test_stake_authority_override_policy() {
+        let stake_meta = stake_meta(1_000_000, 1_000, &[1, 1]);
+        let policy = StakeAuthorityOverridePolicy::new(HashMap::from([(
+            stake_meta.delegations[1].withdrawer_pubkey,
+            0,
+        )]));
+
+        let amounts: Vec<_> = claims(&policy, &stake_meta)
+            .iter()
+            .map(|claim| claim.amount)
+            .collect();
+        assert_eq!(amounts, vec![50_000, 450_000, 500_000]);
+    }
+
+    proptest! {
+        #[test]
+        fn test_default_policy_claims_at_most_total_tips(
+            total_tips in 0..u64::MAX / 2,
+            validator_fee_bps in 0..=10_000u16,
+            delegated in prop::collection::vec(1..u64::MAX >> 8, 1..32),
+        ) {
+            let stake_meta = stake_meta(total_tips, validator_fee_bps, &delegated);
+            let total = claims_total(&claims(&DefaultPolicy, &stake_meta));
+
+            // each claim loses less than a lamport to rounding
+            prop_assert!(total <= total_tips);
+            prop_assert!(total_tips - total <= delegated.len() as u64);
+        }
+
+        #[test]
+        fn test_policies_claim_total_tips(
+            total_tips in 0..u64::MAX / 2,
+            validator_fee_bps in 0..=10_000u16,
+            delegated in prop::collection::vec(1..u64::MAX >> 8, 1..32),
+            protocol_fee_bps in 0..=10_000u16,
+            minimum_claim_lamports in 0..1_000_000u64,
+            override_fee_bps in 0..=10_000u16,
+        ) {
+            let stake_meta = stake_meta(total_tips, validator_fee_bps, &delegated);
+
+            let config = DistributionPolicyConfig {
+                validator_fee_overrides: vec![ValidatorFeeOverride {
+                    stake_authority: stake_meta.delegations[0].staker_pubkey,
+                    validator_fee_bps: override_fee_bps,
+                }],
+                protocol_fee: Some(ProtocolFee {
+                    fee_account: Pubkey::new_unique(),
+                    fee_bps: protocol_fee_bps,
+                }),
+                minimum_claim_lamports: Some(minimum_claim_lamports),
+            };
+            let config_claims = claims(config.build().unwrap().as_ref(), &stake_meta);
+            prop_assert_eq!(claims_total(&config_claims), total_tips);
+            prop_assert_eq!(config_claims[0].claimant, stake_meta.validator_vote_account);
+
+            let override_policy = StakeAuthorityOverridePolicy::new(HashMap::from([(
+                stake_meta.delegations[0].staker_pubkey,
+                override_fee_bps,
+            )]));
+            prop_assert_eq!(claims_total(&claims(&override_policy, &stake_meta)), total_tips);
+        }
+    }
+}
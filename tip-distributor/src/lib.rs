@@ -1,11 +1,16 @@
+pub mod claim_journal;
 pub mod claim_mev_workflow;
+pub mod distribution_policy;
 pub mod merkle_root_generator_workflow;
 pub mod merkle_root_upload_workflow;
 pub mod reclaim_rent_workflow;
+pub mod reconcile_workflow;
+pub mod scheduler_workflow;
 pub mod stake_meta_generator_workflow;
 
 use {
     crate::{
+        distribution_policy::{DefaultPolicy, DistributionPolicy},
         merkle_root_generator_workflow::MerkleRootGeneratorError,
         stake_meta_generator_workflow::StakeMetaGeneratorError::CheckedMathError,
     },
@@ -52,7 +57,7 @@ use {
             TransactionError::{self},
         },
     },
-    solana_transaction_status::TransactionStatus,
+    solana_transaction_status::{Encodable, TransactionStatus, UiTransactionEncoding},
     std::{
         collections::{HashMap, HashSet},
         fs::File,
@@ -61,7 +66,10 @@ use {
         sync::Arc,
         time::{Duration, Instant},
     },
-    tokio::{sync::Semaphore, time::sleep},
+    tokio::{
+        sync::Semaphore,
+        time::{interval, sleep, MissedTickBehavior},
+    },
 };
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -130,16 +138,31 @@ impl GeneratedMerkleTreeCollection {
     pub fn new_from_stake_meta_collection(
         stake_meta_coll: StakeMetaCollection,
         maybe_rpc_client: Option<SyncRpcClient>,
+    ) -> Result<GeneratedMerkleTreeCollection, MerkleRootGeneratorError> {
+        Self::new_from_stake_meta_collection_with_policy(
+            stake_meta_coll,
+            maybe_rpc_client,
+            &DefaultPolicy,
+        )
+    }
+
+    /// Same as [Self::new_from_stake_meta_collection], with the tips split between claimants by
+    /// `distribution_policy`.
+    pub fn new_from_stake_meta_collection_with_policy(
+        stake_meta_coll: StakeMetaCollection,
+        maybe_rpc_client: Option<SyncRpcClient>,
+        distribution_policy: &dyn DistributionPolicy,
     ) -> Result<GeneratedMerkleTreeCollection, MerkleRootGeneratorError> {
         let generated_merkle_trees = stake_meta_coll
             .stake_metas
             .into_iter()
             .filter(|stake_meta| stake_meta.maybe_tip_distribution_meta.is_some())
             .filter_map(|stake_meta| {
-                let mut tree_nodes = match TreeNode::vec_from_stake_meta(&stake_meta) {
-                    Err(e) => return Some(Err(e)),
-                    Ok(maybe_tree_nodes) => maybe_tree_nodes,
-                }?;
+                let mut tree_nodes =
+                    match TreeNode::vec_from_stake_meta(&stake_meta, distribution_policy) {
+                        Err(e) => return Some(Err(e)),
+                        Ok(maybe_tree_nodes) => maybe_tree_nodes,
+                    }?;
 
                 if let Some(rpc_client) = &maybe_rpc_client {
                     if let Some(tda) = stake_meta.maybe_tip_distribution_meta.as_ref() {
@@ -247,67 +270,32 @@ pub struct TreeNode {
 impl TreeNode {
     fn vec_from_stake_meta(
         stake_meta: &StakeMeta,
+        distribution_policy: &dyn DistributionPolicy,
     ) -> Result<Option<Vec<TreeNode>>, MerkleRootGeneratorError> {
         if let Some(tip_distribution_meta) = stake_meta.maybe_tip_distribution_meta.as_ref() {
-            let validator_amount = (tip_distribution_meta.total_tips as u128)
-                .checked_mul(tip_distribution_meta.validator_fee_bps as u128)
-                .unwrap()
-                .checked_div(10_000)
-                .unwrap() as u64;
-            let (claim_status_pubkey, claim_status_bump) = Pubkey::find_program_address(
-                &[
-                    ClaimStatus::SEED,
-                    &stake_meta.validator_vote_account.to_bytes(),
-                    &tip_distribution_meta.tip_distribution_pubkey.to_bytes(),
-                ],
-                &JitoTipDistribution::id(),
-            );
-            let mut tree_nodes = vec![TreeNode {
-                claimant: stake_meta.validator_vote_account,
-                claim_status_pubkey,
-                claim_status_bump,
-                staker_pubkey: Pubkey::default(),
-                withdrawer_pubkey: Pubkey::default(),
-                amount: validator_amount,
-                proof: None,
-            }];
-
-            let remaining_total_rewards = tip_distribution_meta
-                .total_tips
-                .checked_sub(validator_amount)
-                .unwrap() as u128;
-
-            let total_delegated = stake_meta.total_delegated as u128;
-            tree_nodes.extend(
-                stake_meta
-                    .delegations
-                    .iter()
-                    .map(|delegation| {
-                        let amount_delegated = delegation.lamports_delegated as u128;
-                        let reward_amount = (amount_delegated.checked_mul(remaining_total_rewards))
-                            .unwrap()
-                            .checked_div(total_delegated)
-                            .unwrap();
-                        let (claim_status_pubkey, claim_status_bump) = Pubkey::find_program_address(
-                            &[
-                                ClaimStatus::SEED,
-                                &delegation.stake_account_pubkey.to_bytes(),
-                                &tip_distribution_meta.tip_distribution_pubkey.to_bytes(),
-                            ],
-                            &JitoTipDistribution::id(),
-                        );
-                        Ok(TreeNode {
-                            claimant: delegation.stake_account_pubkey,
-                            claim_status_pubkey,
-                            claim_status_bump,
-                            staker_pubkey: delegation.staker_pubkey,
-                            withdrawer_pubkey: delegation.withdrawer_pubkey,
-                            amount: reward_amount as u64,
-                            proof: None,
-                        })
-                    })
-                    .collect::<Result<Vec<TreeNode>, MerkleRootGeneratorError>>()?,
-            );
+            let tree_nodes = distribution_policy
+                .claims(stake_meta, tip_distribution_meta)
+                .into_iter()
+                .map(|claim| {
+                    let (claim_status_pubkey, claim_status_bump) = Pubkey::find_program_address(
+                        &[
+                            ClaimStatus::SEED,
+                            &claim.claimant.to_bytes(),
+                            &tip_distribution_meta.tip_distribution_pubkey.to_bytes(),
+                        ],
+                        &JitoTipDistribution::id(),
+                    );
+                    TreeNode {
+                        claimant: claim.claimant,
+                        claim_status_pubkey,
+                        claim_status_bump,
+                        staker_pubkey: claim.staker_pubkey,
+                        withdrawer_pubkey: claim.withdrawer_pubkey,
+                        amount: claim.amount,
+                        proof: None,
+                    }
+                })
+                .collect();
 
             Ok(Some(tree_nodes))
         } else {
@@ -547,21 +535,57 @@ pub async fn sign_and_send_transactions_with_retries(
     (transactions_to_process.values().cloned().collect(), errors)
 }
 
+/// Prints the transactions a `--dry-run` would have sent as JSON, one per line, instead of sending
+/// them.
+pub fn print_dry_run_transactions(transactions: &[Transaction]) {
+    for transaction in transactions {
+        println!(
+            "{}",
+            serde_json::to_string(&transaction.encode(UiTransactionEncoding::Json))
+                .expect("serialize transaction")
+        );
+    }
+}
+
 pub async fn send_until_blockhash_expires(
     rpc_client: &RpcClient,
     transactions: Vec<Transaction>,
     blockhash: Hash,
     keypair: &Arc<Keypair>,
-) -> solana_rpc_client_api::client_error::Result<()> {
-    let mut claim_transactions: HashMap<Signature, Transaction> = transactions
+) -> solana_rpc_client_api::client_error::Result<HashSet<Signature>> {
+    let transactions = transactions
         .into_iter()
         .map(|mut tx| {
             tx.sign(&[&keypair], blockhash);
-            (*tx.get_signature(), tx)
+            tx
         })
         .collect();
+    send_signed_until_blockhash_expires(rpc_client, transactions, blockhash, None).await
+}
+
+/// Sends transactions signed with `blockhash` until they land or the blockhash expires, at most
+/// `max_tps` transactions per second. Returns the signatures of the transactions that were
+/// confirmed without error or had already been processed. Transactions that were only processed
+/// are resent until they're confirmed, since they could still be dropped on a minority fork.
+pub async fn send_signed_until_blockhash_expires(
+    rpc_client: &RpcClient,
+    transactions: Vec<Transaction>,
+    blockhash: Hash,
+    max_tps: Option<u64>,
+) -> solana_rpc_client_api::client_error::Result<HashSet<Signature>> {
+    let mut claim_transactions: HashMap<Signature, Transaction> = transactions
+        .into_iter()
+        .map(|tx| (*tx.get_signature(), tx))
+        .collect();
 
     let txs_requesting_send = claim_transactions.len();
+    let mut landed = HashSet::with_capacity(claim_transactions.len());
+    let mut send_interval = max_tps.map(|max_tps| {
+        let mut send_interval =
+            interval(Duration::from_secs(1) / u32::try_from(max_tps).unwrap_or(u32::MAX).max(1));
+        send_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        send_interval
+    });
 
     while rpc_client
         .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
@@ -572,6 +596,9 @@ pub async fn send_until_blockhash_expires(
         let mut is_blockhash_not_found = false;
 
         for (signature, tx) in &claim_transactions {
+            if let Some(send_interval) = &mut send_interval {
+                send_interval.tick().await;
+            }
             match rpc_client
                 .send_transaction_with_config(
                     tx,
@@ -620,14 +647,20 @@ pub async fn send_until_blockhash_expires(
         let statuses = get_batched_signatures_statuses(rpc_client, &signatures).await?;
 
         for (signature, maybe_status) in &statuses {
-            if let Some(_status) = maybe_status {
-                claim_transactions.remove(signature);
+            if let Some(status) = maybe_status {
+                if status.err.is_some() {
+                    claim_transactions.remove(signature);
+                } else if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                    landed.insert(*signature);
+                    claim_transactions.remove(signature);
+                }
                 check_signatures.remove(signature);
             }
         }
 
         for signature in already_processed {
             claim_transactions.remove(&signature);
+            landed.insert(signature);
         }
 
         if claim_transactions.is_empty() || is_blockhash_not_found {
@@ -640,7 +673,7 @@ pub async fn send_until_blockhash_expires(
         .unwrap();
     info!("num_landed: {:?}", num_landed);
 
-    Ok(())
+    Ok(landed)
 }
 
 pub async fn get_batched_signatures_statuses(
@@ -1,5 +1,8 @@
 use {
-    crate::{read_json_from_file, GeneratedMerkleTreeCollection, StakeMetaCollection},
+    crate::{
+        distribution_policy::{DistributionPolicyConfig, DistributionPolicyConfigError},
+        read_json_from_file, GeneratedMerkleTreeCollection, StakeMetaCollection,
+    },
     log::*,
     solana_client::rpc_client::RpcClient,
     std::{
@@ -21,20 +24,34 @@ pub enum MerkleRootGeneratorError {
 
     #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    DistributionPolicyConfigError(#[from] DistributionPolicyConfigError),
 }
 
+/// Generates the merkle trees, with the tips split by the [DistributionPolicyConfig] JSON file at
+/// `distribution_policy_path` or the default policy if there's none.
 pub fn generate_merkle_root(
     stake_meta_coll_path: &PathBuf,
     out_path: &PathBuf,
     rpc_url: &str,
+    distribution_policy_path: Option<&PathBuf>,
 ) -> Result<(), MerkleRootGeneratorError> {
     let stake_meta_coll: StakeMetaCollection = read_json_from_file(stake_meta_coll_path)?;
+    let distribution_policy_config: DistributionPolicyConfig = distribution_policy_path
+        .map(read_json_from_file)
+        .transpose()?
+        .unwrap_or_default();
+    info!("distribution policy: {:?}", distribution_policy_config);
+    let distribution_policy = distribution_policy_config.build()?;
 
     let rpc_client = RpcClient::new(rpc_url);
-    let merkle_tree_coll = GeneratedMerkleTreeCollection::new_from_stake_meta_collection(
-        stake_meta_coll,
-        Some(rpc_client),
-    )?;
+    let merkle_tree_coll =
+        GeneratedMerkleTreeCollection::new_from_stake_meta_collection_with_policy(
+            stake_meta_coll,
+            Some(rpc_client),
+            distribution_policy.as_ref(),
+        )?;
 
     write_to_json_file(&merkle_tree_coll, out_path)?;
     Ok(())
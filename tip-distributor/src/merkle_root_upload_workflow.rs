@@ -1,7 +1,7 @@
 use {
     crate::{
-        read_json_from_file, sign_and_send_transactions_with_retries, GeneratedMerkleTree,
-        GeneratedMerkleTreeCollection,
+        print_dry_run_transactions, read_json_from_file, sign_and_send_transactions_with_retries,
+        GeneratedMerkleTree, GeneratedMerkleTreeCollection,
     },
     anchor_lang::AccountDeserialize,
     jito_tip_distribution::{
@@ -33,6 +33,8 @@ pub enum MerkleRootUploadError {
     JsonError(#[from] serde_json::Error),
 }
 
+/// Uploads the merkle roots of the trees the keypair is the upload authority for. With `dry_run`
+/// the upload transactions are printed instead of sent.
 pub fn upload_merkle_root(
     merkle_root_path: &PathBuf,
     keypair_path: &PathBuf,
@@ -40,6 +42,7 @@ pub fn upload_merkle_root(
     tip_distribution_program_id: &Pubkey,
     max_concurrent_rpc_get_reqs: usize,
     txn_send_batch_size: usize,
+    dry_run: bool,
 ) -> Result<(), MerkleRootUploadError> {
     const MAX_RETRY_DURATION: Duration = Duration::from_secs(600);
 
@@ -68,7 +71,7 @@ pub fn upload_merkle_root(
         info!("num trees to upload: {:?}", trees.len());
 
         // heuristic to make sure we have enough funds to cover execution, assumes all trees need updating 
-        {
+        if !dry_run {
             let initial_balance = rpc_client.get_balance(&keypair.pubkey()).await.expect("failed to get balance");
             let desired_balance = (trees.len() as u64).checked_mul(DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE).unwrap();
             if initial_balance < desired_balance {
@@ -127,6 +130,12 @@ pub fn upload_merkle_root(
             })
             .collect();
 
+        if dry_run {
+            info!("dry run, not sending {} upload transactions", transactions.len());
+            print_dry_run_transactions(&transactions);
+            return;
+        }
+
         let (to_process, failed_transactions) = sign_and_send_transactions_with_retries(
             &keypair, &rpc_client, max_concurrent_rpc_get_reqs, transactions, txn_send_batch_size, MAX_RETRY_DURATION).await;
         if !to_process.is_empty() {
@@ -1,6 +1,6 @@
 use {
     crate::{
-        claim_mev_workflow::ClaimMevError, get_batched_accounts,
+        claim_mev_workflow::ClaimMevError, get_batched_accounts, print_dry_run_transactions,
         reclaim_rent_workflow::ClaimMevError::AnchorError, send_until_blockhash_expires,
     },
     anchor_lang::AccountDeserialize,
@@ -34,7 +34,8 @@ use {
     },
 };
 
-/// Clear old ClaimStatus accounts
+/// Clear old ClaimStatus accounts. With `dry_run` the close transactions are printed instead of
+/// sent.
 pub async fn reclaim_rent(
     rpc_url: String,
     tip_distribution_program_id: Pubkey,
@@ -43,6 +44,7 @@ pub async fn reclaim_rent(
     // Optionally reclaim TipDistributionAccount rents on behalf of validators.
     should_reclaim_tdas: bool,
     micro_lamports: u64,
+    dry_run: bool,
 ) -> Result<(), ClaimMevError> {
     let rpc_client = RpcClient::new_with_timeout_and_commitment(
         rpc_url.clone(),
@@ -94,6 +96,15 @@ pub async fn reclaim_rent(
             return Ok(());
         }
 
+        if dry_run {
+            info!(
+                "dry run, not sending {} reclaim rent transactions",
+                transactions.len()
+            );
+            print_dry_run_transactions(&transactions);
+            return Ok(());
+        }
+
         transactions.shuffle(&mut thread_rng());
         let transactions: Vec<_> = transactions.into_iter().take(10_000).collect();
         let blockhash = rpc_client.get_latest_blockhash().await?;
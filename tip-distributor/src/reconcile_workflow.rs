@@ -0,0 +1,454 @@
+use {
+    crate::{
+        get_batched_accounts, pubkey_string_conversion, read_json_from_file,
+        GeneratedMerkleTreeCollection,
+    },
+    anchor_lang::AccountDeserialize,
+    itertools::Itertools,
+    jito_tip_distribution::state::{ClaimStatus, TipDistributionAccount},
+    log::info,
+    serde::{Deserialize, Serialize},
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_sdk::{
+        account::Account,
+        clock::{Epoch, Slot},
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+    },
+    std::{
+        collections::HashMap,
+        fs::File,
+        io::{BufWriter, Write},
+        path::PathBuf,
+        time::Duration,
+    },
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub enum ReconcileError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    RpcError(#[from] solana_rpc_client_api::client_error::Error),
+}
+
+/// How the merkle root uploaded to a [TipDistributionAccount] compares to the locally generated one.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MerkleRootStatus {
+    Matches,
+    Mismatch,
+    NotUploaded,
+    MissingTipDistributionAccount,
+}
+
+/// The on-chain state of a single tree node.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClaimantReconciliation {
+    #[serde(with = "pubkey_string_conversion")]
+    pub claimant: Pubkey,
+    #[serde(with = "pubkey_string_conversion")]
+    pub staker_pubkey: Pubkey,
+    #[serde(with = "pubkey_string_conversion")]
+    pub withdrawer_pubkey: Pubkey,
+    /// The amount this claimant is entitled to according to the local tree.
+    pub amount: u64,
+    /// Whether the ClaimStatus account exists, i.e. the amount was claimed.
+    pub claimed: bool,
+}
+
+/// The on-chain state of a single [TipDistributionAccount] compared to its local tree.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ValidatorReconciliation {
+    #[serde(with = "pubkey_string_conversion")]
+    pub tip_distribution_account: Pubkey,
+    /// Default if the tip distribution account doesn't exist anymore.
+    #[serde(with = "pubkey_string_conversion")]
+    pub validator_vote_account: Pubkey,
+    pub merkle_root_status: MerkleRootStatus,
+    /// Total the tree nodes are entitled to.
+    pub expected_total_claim: u64,
+    /// Sum of the amounts of the claimed tree nodes.
+    pub total_claimed: u64,
+    /// `total_funds_claimed` of the uploaded merkle root, if any.
+    pub onchain_total_claimed: Option<u64>,
+    pub unclaimed: u64,
+    pub num_nodes: usize,
+    pub num_nodes_claimed: usize,
+    /// Lamports held by the [TipDistributionAccount] and ClaimStatus accounts that expired and can
+    /// be closed.
+    pub rent_reclaimable: u64,
+    pub claimants: Vec<ClaimantReconciliation>,
+}
+
+/// Per-validator and per-staker audit of a [GeneratedMerkleTreeCollection] against the chain.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReconciliationReport {
+    pub epoch: Epoch,
+    pub slot: Slot,
+    /// The epoch the on-chain accounts were read at.
+    pub current_epoch: Epoch,
+    pub validators: Vec<ValidatorReconciliation>,
+    pub num_merkle_root_mismatches: usize,
+    pub total_expected_claim: u64,
+    pub total_claimed: u64,
+    pub total_unclaimed: u64,
+    pub total_rent_reclaimable: u64,
+}
+
+/// Reconciles the [GeneratedMerkleTreeCollection] JSON file against the chain and writes the
+/// [ReconciliationReport] to `out_path`.
+pub async fn reconcile_merkle_trees(
+    merkle_trees_path: &PathBuf,
+    rpc_url: String,
+    out_path: &PathBuf,
+) -> Result<ReconciliationReport, ReconcileError> {
+    let merkle_trees: GeneratedMerkleTreeCollection = read_json_from_file(merkle_trees_path)?;
+    let report = reconcile(&merkle_trees, rpc_url).await?;
+
+    let mut writer = BufWriter::new(File::create(out_path)?);
+    serde_json::to_writer_pretty(&mut writer, &report)?;
+    writer.flush()?;
+
+    Ok(report)
+}
+
+/// Fetches the [TipDistributionAccount]s and ClaimStatus accounts of the merkle trees and
+/// reconciles them against the trees.
+pub async fn reconcile(
+    merkle_trees: &GeneratedMerkleTreeCollection,
+    rpc_url: String,
+) -> Result<ReconciliationReport, ReconcileError> {
+    let rpc_client = RpcClient::new_with_timeout_and_commitment(
+        rpc_url,
+        Duration::from_secs(300),
+        CommitmentConfig::confirmed(),
+    );
+    let current_epoch = rpc_client.get_epoch_info().await?.epoch;
+
+    info!(
+        "reading tip distribution related accounts for epoch {}",
+        merkle_trees.epoch
+    );
+    let tda_pubkeys = merkle_trees
+        .generated_merkle_trees
+        .iter()
+        .map(|tree| tree.tip_distribution_account)
+        .collect_vec();
+    let tdas: HashMap<Pubkey, Account> = get_batched_accounts(&rpc_client, &tda_pubkeys)
+        .await?
+        .into_iter()
+        .filter_map(|(pubkey, a)| Some((pubkey, a?)))
+        .collect();
+
+    let claim_status_pubkeys = merkle_trees
+        .generated_merkle_trees
+        .iter()
+        .flat_map(|tree| &tree.tree_nodes)
+        .map(|tree_node| tree_node.claim_status_pubkey)
+        .collect_vec();
+    let claim_statuses: HashMap<Pubkey, Account> =
+        get_batched_accounts(&rpc_client, &claim_status_pubkeys)
+            .await?
+            .into_iter()
+            .filter_map(|(pubkey, a)| Some((pubkey, a?)))
+            .collect();
+
+    Ok(build_reconciliation_report(
+        merkle_trees,
+        &tdas,
+        &claim_statuses,
+        current_epoch,
+    ))
+}
+
+fn build_reconciliation_report(
+    merkle_trees: &GeneratedMerkleTreeCollection,
+    tdas: &HashMap<Pubkey, Account>,
+    claim_statuses: &HashMap<Pubkey, Account>,
+    current_epoch: Epoch,
+) -> ReconciliationReport {
+    let validators = merkle_trees
+        .generated_merkle_trees
+        .iter()
+        .map(|tree| {
+            let tda = tdas
+                .get(&tree.tip_distribution_account)
+                .and_then(|account| {
+                    TipDistributionAccount::try_deserialize(&mut account.data.as_slice()).ok()
+                });
+            let merkle_root_status = match &tda {
+                None => MerkleRootStatus::MissingTipDistributionAccount,
+                Some(tda) => match &tda.merkle_root {
+                    None => MerkleRootStatus::NotUploaded,
+                    Some(merkle_root) if merkle_root.root == tree.merkle_root.to_bytes() => {
+                        MerkleRootStatus::Matches
+                    }
+                    Some(_) => MerkleRootStatus::Mismatch,
+                },
+            };
+
+            let claimants = tree
+                .tree_nodes
+                .iter()
+                .map(|node| ClaimantReconciliation {
+                    claimant: node.claimant,
+                    staker_pubkey: node.staker_pubkey,
+                    withdrawer_pubkey: node.withdrawer_pubkey,
+                    amount: node.amount,
+                    claimed: claim_statuses.contains_key(&node.claim_status_pubkey),
+                })
+                .collect_vec();
+            let total_claimed = claimants
+                .iter()
+                .filter(|claimant| claimant.claimed)
+                .map(|claimant| claimant.amount)
+                .sum();
+            let claim_status_rent_reclaimable: u64 = tree
+                .tree_nodes
+                .iter()
+                .filter_map(|node| claim_statuses.get(&node.claim_status_pubkey))
+                .filter(|account| {
+                    ClaimStatus::try_deserialize(&mut account.data.as_slice())
+                        .map(|claim_status| current_epoch > claim_status.expires_at)
+                        .unwrap_or_default()
+                })
+                .map(|account| account.lamports)
+                .sum();
+            // closing an expired tip distribution account reclaims its rent and unclaimed tips
+            let tda_rent_reclaimable = tda
+                .as_ref()
+                .filter(|tda| current_epoch > tda.expires_at)
+                .and_then(|_| tdas.get(&tree.tip_distribution_account))
+                .map(|account| account.lamports)
+                .unwrap_or_default();
+            let rent_reclaimable =
+                claim_status_rent_reclaimable.saturating_add(tda_rent_reclaimable);
+
+            ValidatorReconciliation {
+                tip_distribution_account: tree.tip_distribution_account,
+                validator_vote_account: tda
+                    .as_ref()
+                    .map(|tda| tda.validator_vote_account)
+                    .unwrap_or_default(),
+                merkle_root_status,
+                expected_total_claim: tree.max_total_claim,
+                total_claimed,
+                onchain_total_claimed: tda
+                    .as_ref()
+                    .and_then(|tda| tda.merkle_root.as_ref())
+                    .map(|merkle_root| merkle_root.total_funds_claimed),
+                unclaimed: tree.max_total_claim.saturating_sub(total_claimed),
+                num_nodes: tree.tree_nodes.len(),
+                num_nodes_claimed: claimants.iter().filter(|claimant| claimant.claimed).count(),
+                rent_reclaimable,
+                claimants,
+            }
+        })
+        .collect_vec();
+
+    ReconciliationReport {
+        epoch: merkle_trees.epoch,
+        slot: merkle_trees.slot,
+        current_epoch,
+        num_merkle_root_mismatches: validators
+            .iter()
+            .filter(|validator| validator.merkle_root_status == MerkleRootStatus::Mismatch)
+            .count(),
+        total_expected_claim: validators
+            .iter()
+            .map(|validator| validator.expected_total_claim)
+            .sum(),
+        total_claimed: validators
+            .iter()
+            .map(|validator| validator.total_claimed)
+            .sum(),
+        total_unclaimed: validators.iter().map(|validator| validator.unclaimed).sum(),
+        total_rent_reclaimable: validators
+            .iter()
+            .map(|validator| validator.rent_reclaimable)
+            .sum(),
+        validators,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{GeneratedMerkleTree, TreeNode},
+        anchor_lang::AccountSerialize,
+        jito_tip_distribution::state::MerkleRoot,
+        solana_sdk::hash::Hash,
+    };
+
+    fn tree_node(amount: u64) -> TreeNode {
+        TreeNode {
+            claimant: Pubkey::new_unique(),
+            claim_status_pubkey: Pubkey::new_unique(),
+            claim_status_bump: 0,
+            staker_pubkey: Pubkey::new_unique(),
+            withdrawer_pubkey: Pubkey::new_unique(),
+            amount,
+            proof: None,
+        }
+    }
+
+    fn claim_status_account(lamports: u64, expires_at: Epoch) -> Account {
+        let claim_status = ClaimStatus {
+            expires_at,
+            ..ClaimStatus::default()
+        };
+        let mut data = vec![];
+        claim_status.try_serialize(&mut data).unwrap();
+        Account {
+            lamports,
+            data,
+            ..Account::default()
+        }
+    }
+
+    fn tda_account(validator_vote_account: Pubkey, merkle_root: Option<MerkleRoot>) -> Account {
+        let tda = TipDistributionAccount {
+            validator_vote_account,
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            merkle_root,
+            epoch_created_at: 10,
+            validator_commission_bps: 100,
+            expires_at: 13,
+            bump: 0,
+        };
+        let mut data = vec![];
+        tda.try_serialize(&mut data).unwrap();
+        Account {
+            lamports: 1_000_000,
+            data,
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_build_reconciliation_report() {
+        let matching_tree = GeneratedMerkleTree {
+            tip_distribution_account: Pubkey::new_unique(),
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            merkle_root: Hash::new_unique(),
+            tree_nodes: vec![tree_node(100), tree_node(200), tree_node(300)],
+            max_total_claim: 600,
+            max_num_nodes: 3,
+        };
+        let mismatched_tree = GeneratedMerkleTree {
+            tip_distribution_account: Pubkey::new_unique(),
+            tree_nodes: vec![tree_node(50)],
+            max_total_claim: 50,
+            max_num_nodes: 1,
+            ..matching_tree.clone()
+        };
+        let missing_tree = GeneratedMerkleTree {
+            tip_distribution_account: Pubkey::new_unique(),
+            tree_nodes: vec![tree_node(10)],
+            max_total_claim: 10,
+            max_num_nodes: 1,
+            ..matching_tree.clone()
+        };
+        let merkle_trees = GeneratedMerkleTreeCollection {
+            generated_merkle_trees: vec![
+                matching_tree.clone(),
+                mismatched_tree.clone(),
+                missing_tree.clone(),
+            ],
+            bank_hash: Hash::default().to_string(),
+            epoch: 10,
+            slot: 4_320_000,
+        };
+
+        let validator_vote_account = Pubkey::new_unique();
+        let merkle_root = |root: Hash, total_funds_claimed| MerkleRoot {
+            root: root.to_bytes(),
+            max_total_claim: 600,
+            max_num_nodes: 3,
+            total_funds_claimed,
+            num_nodes_claimed: 2,
+        };
+        let tdas = HashMap::from([
+            (
+                matching_tree.tip_distribution_account,
+                tda_account(
+                    validator_vote_account,
+                    Some(merkle_root(matching_tree.merkle_root, 400)),
+                ),
+            ),
+            (
+                mismatched_tree.tip_distribution_account,
+                tda_account(
+                    Pubkey::new_unique(),
+                    Some(merkle_root(Hash::new_unique(), 0)),
+                ),
+            ),
+        ]);
+        // the first and last node of the matching tree were claimed, the first claim status expired
+        let claim_statuses = HashMap::from([
+            (
+                matching_tree.tree_nodes[0].claim_status_pubkey,
+                claim_status_account(2_000, 10),
+            ),
+            (
+                matching_tree.tree_nodes[2].claim_status_pubkey,
+                claim_status_account(3_000, 13),
+            ),
+        ]);
+
+        let report = build_reconciliation_report(&merkle_trees, &tdas, &claim_statuses, 11);
+        assert_eq!(report.epoch, 10);
+        assert_eq!(report.current_epoch, 11);
+        assert_eq!(report.num_merkle_root_mismatches, 1);
+        assert_eq!(report.total_expected_claim, 660);
+        assert_eq!(report.total_claimed, 400);
+        assert_eq!(report.total_unclaimed, 260);
+        assert_eq!(report.total_rent_reclaimable, 2_000);
+
+        let matching = &report.validators[0];
+        assert_eq!(matching.merkle_root_status, MerkleRootStatus::Matches);
+        assert_eq!(matching.validator_vote_account, validator_vote_account);
+        assert_eq!(matching.total_claimed, 400);
+        assert_eq!(matching.onchain_total_claimed, Some(400));
+        assert_eq!(matching.unclaimed, 200);
+        assert_eq!(matching.num_nodes, 3);
+        assert_eq!(matching.num_nodes_claimed, 2);
+        assert_eq!(matching.rent_reclaimable, 2_000);
+        assert_eq!(
+            matching
+                .claimants
+                .iter()
+                .map(|claimant| claimant.claimed)
+                .collect_vec(),
+            vec![true, false, true]
+        );
+
+        let mismatched = &report.validators[1];
+        assert_eq!(mismatched.merkle_root_status, MerkleRootStatus::Mismatch);
+        assert_eq!(mismatched.unclaimed, 50);
+        assert_eq!(mismatched.rent_reclaimable, 0);
+
+        let missing = &report.validators[2];
+        assert_eq!(
+            missing.merkle_root_status,
+            MerkleRootStatus::MissingTipDistributionAccount
+        );
+        assert_eq!(missing.validator_vote_account, Pubkey::default());
+        assert_eq!(missing.onchain_total_claimed, None);
+        assert_eq!(missing.rent_reclaimable, 0);
+
+        // once the tip distribution accounts expire they can be closed too
+        let report = build_reconciliation_report(&merkle_trees, &tdas, &claim_statuses, 14);
+        assert_eq!(report.validators[0].rent_reclaimable, 1_000_000 + 5_000);
+        assert_eq!(report.validators[1].rent_reclaimable, 1_000_000);
+        assert_eq!(report.validators[2].rent_reclaimable, 0);
+        assert_eq!(report.total_rent_reclaimable, 2_000_000 + 5_000);
+    }
+}
@@ -0,0 +1,623 @@
+//! Long-running tip distribution. Waits for every epoch to end and then runs the stake meta
+//! generator, merkle root generator, merkle root uploader, claimer and rent reclaimer for it, one
+//! after the other.
+//!
+//! The stage an epoch is at is persisted to `state.json` in the work directory after every stage,
+//! along with the stake meta and merkle trees JSON files, so a restarted daemon resumes where it
+//! stopped.
+use {
+    crate::{
+        claim_mev_workflow::{claim_mev_tips, ClaimMevError, ClaimThrottle},
+        merkle_root_generator_workflow::{generate_merkle_root, MerkleRootGeneratorError},
+        merkle_root_upload_workflow::{upload_merkle_root, MerkleRootUploadError},
+        read_json_from_file,
+        reclaim_rent_workflow::reclaim_rent,
+        stake_meta_generator_workflow::{
+            generate_stake_meta_from_snapshot_archives, StakeMetaGeneratorError,
+        },
+        GeneratedMerkleTreeCollection,
+    },
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_client::{client_error::ClientError, rpc_client::RpcClient},
+    solana_metrics::{datapoint_error, datapoint_info},
+    solana_runtime::{
+        snapshot_archive_info::{
+            FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
+        },
+        snapshot_utils::{get_full_snapshot_archives, get_incremental_snapshot_archives},
+    },
+    solana_sdk::{
+        clock::{Epoch, Slot},
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+        signature::{read_keypair_file, Keypair},
+    },
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::sleep,
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+    tokio::runtime::{Builder, Runtime},
+};
+
+const STATE_FILE: &str = "state.json";
+
+#[derive(Error, Debug)]
+pub enum SchedulerError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    RpcError(#[from] Box<ClientError>),
+
+    #[error("failed to read keypair: {0}")]
+    KeypairError(String),
+
+    #[error(transparent)]
+    StakeMetaGeneratorError(#[from] StakeMetaGeneratorError),
+
+    #[error(transparent)]
+    MerkleRootGeneratorError(#[from] MerkleRootGeneratorError),
+
+    #[error(transparent)]
+    MerkleRootUploadError(#[from] MerkleRootUploadError),
+
+    #[error(transparent)]
+    ClaimMevError(#[from] ClaimMevError),
+}
+
+/// The steps of distributing the tips of an epoch, in the order they run.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    StakeMeta,
+    MerkleTrees,
+    UploadMerkleRoots,
+    ClaimTips,
+    ReclaimRent,
+    Done,
+}
+
+impl Stage {
+    fn next(self) -> Self {
+        match self {
+            Stage::StakeMeta => Stage::MerkleTrees,
+            Stage::MerkleTrees => Stage::UploadMerkleRoots,
+            Stage::UploadMerkleRoots => Stage::ClaimTips,
+            Stage::ClaimTips => Stage::ReclaimRent,
+            Stage::ReclaimRent | Stage::Done => Stage::Done,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Stage::StakeMeta => "stake_meta",
+            Stage::MerkleTrees => "merkle_trees",
+            Stage::UploadMerkleRoots => "upload_merkle_roots",
+            Stage::ClaimTips => "claim_tips",
+            Stage::ReclaimRent => "reclaim_rent",
+            Stage::Done => "done",
+        }
+    }
+}
+
+/// Persisted progress of the scheduler.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SchedulerState {
+    /// The epoch whose tips are being distributed.
+    pub epoch: Epoch,
+    /// The next stage to run.
+    pub stage: Stage,
+    /// Slot of the snapshot the stake meta was generated from.
+    pub snapshot_slot: Option<Slot>,
+    /// How many times in a row the stage failed.
+    #[serde(default)]
+    pub failed_attempts: u32,
+}
+
+impl SchedulerState {
+    /// Returns the state to run next given the current epoch, or None if there's nothing to do
+    /// until the epoch rolls over. Only the last completed epoch is distributed, the epochs that
+    /// went by while the scheduler was down are skipped.
+    fn next(state: Option<&SchedulerState>, current_epoch: Epoch) -> Option<SchedulerState> {
+        let completed_epoch = current_epoch.checked_sub(1)?;
+        match state {
+            Some(state) if state.stage != Stage::Done => Some(state.clone()),
+            Some(state) if state.epoch >= completed_epoch => None,
+            _ => {
+                if let Some(state) = state {
+                    if state.epoch + 1 < completed_epoch {
+                        warn!(
+                            "skipping tip distribution of epochs {} to {}",
+                            state.epoch + 1,
+                            completed_epoch - 1
+                        );
+                    }
+                }
+                Some(SchedulerState {
+                    epoch: completed_epoch,
+                    stage: Stage::StakeMeta,
+                    snapshot_slot: None,
+                    failed_attempts: 0,
+                })
+            }
+        }
+    }
+
+    /// Counts a failure of the stage. After `max_stage_attempts` failures the epoch is given up on
+    /// and marked done, so the scheduler moves on to the last completed epoch instead of retrying
+    /// a stage that may never succeed, e.g. because the tip distribution accounts expired. Returns
+    /// true if the epoch was given up on.
+    fn record_failure(&mut self, max_stage_attempts: u32) -> bool {
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+        if self.failed_attempts < max_stage_attempts {
+            return false;
+        }
+        self.stage = Stage::Done;
+        self.failed_attempts = 0;
+        true
+    }
+
+    /// Once the epoch after this one has ended too, the snapshot of this epoch is no longer
+    /// going to show up and the epoch has to be given up on.
+    fn is_snapshot_overdue(&self, current_epoch: Epoch) -> bool {
+        current_epoch > self.epoch.saturating_add(1)
+    }
+}
+
+pub struct SchedulerConfig {
+    pub rpc_url: String,
+    pub tip_distribution_program_id: Pubkey,
+    pub tip_payment_program_id: Pubkey,
+    /// Signs and pays for the merkle root uploads, claims and rent reclaims.
+    pub keypair_path: PathBuf,
+    /// Where the snapshot archives of the last slot of every epoch show up.
+    pub snapshot_archives_dir: PathBuf,
+    /// Directory the snapshot archives are unpacked in.
+    pub snapshot_unpack_dir: PathBuf,
+    /// Holds the scheduler state and the JSON files of every epoch.
+    pub work_dir: PathBuf,
+    /// How many slots before the end of the epoch a snapshot can be and still be used. Only
+    /// useful for test clusters, which don't snapshot the last slot of an epoch.
+    pub max_snapshot_slot_lag: Slot,
+    pub distribution_policy_path: Option<PathBuf>,
+    pub max_concurrent_rpc_get_reqs: usize,
+    pub txn_send_batch_size: usize,
+    pub claim_throttle: ClaimThrottle,
+    pub max_claim_duration: Duration,
+    pub should_reclaim_rent: bool,
+    pub should_reclaim_tdas: bool,
+    /// How often to check for an epoch rollover or a snapshot to show up.
+    pub poll_interval: Duration,
+    /// How many times a stage is tried before the tip distribution of its epoch is given up on.
+    pub max_stage_attempts: u32,
+}
+
+impl SchedulerConfig {
+    fn epoch_dir(&self, epoch: Epoch) -> PathBuf {
+        self.work_dir.join(format!("epoch-{epoch}"))
+    }
+
+    fn stake_meta_path(&self, epoch: Epoch) -> PathBuf {
+        self.epoch_dir(epoch).join("stake-meta.json")
+    }
+
+    fn merkle_trees_path(&self, epoch: Epoch) -> PathBuf {
+        self.epoch_dir(epoch).join("merkle-trees.json")
+    }
+
+    fn claim_journal_path(&self, epoch: Epoch) -> PathBuf {
+        self.epoch_dir(epoch).join("claim-journal.jsonl")
+    }
+}
+
+/// Distributes the tips of every epoch that ends until `exit` is set. Failed stages are retried
+/// every poll interval, up to [SchedulerConfig::max_stage_attempts] times.
+pub fn run_scheduler(config: SchedulerConfig, exit: Arc<AtomicBool>) -> Result<(), SchedulerError> {
+    fs::create_dir_all(&config.work_dir)?;
+    let keypair = Arc::new(
+        read_keypair_file(&config.keypair_path)
+            .map_err(|e| SchedulerError::KeypairError(e.to_string()))?,
+    );
+    let rpc_client =
+        RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+    let runtime = Builder::new_multi_thread().enable_all().build()?;
+
+    let state_path = config.work_dir.join(STATE_FILE);
+    let mut state: Option<SchedulerState> = if state_path.exists() {
+        Some(read_json_from_file(&state_path)?)
+    } else {
+        None
+    };
+    info!("starting tip distribution scheduler at {:?}", state);
+
+    while !exit.load(Ordering::Relaxed) {
+        match run_next_stage(&config, &rpc_client, &runtime, &keypair, &mut state) {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(e) => {
+                error!("tip distribution failed at {:?}: {:?}", state, e);
+                datapoint_error!(
+                    "tip_distributor-stage_error",
+                    (
+                        "epoch",
+                        state.as_ref().map(|s| s.epoch).unwrap_or_default(),
+                        i64
+                    ),
+                    (
+                        "stage",
+                        state.as_ref().map(|s| s.stage.name()).unwrap_or_default(),
+                        String
+                    ),
+                    ("err_str", e.to_string(), String),
+                );
+                if let Some(state) = &mut state {
+                    let (stage, failed_attempts) = (state.stage, state.failed_attempts + 1);
+                    if state.record_failure(config.max_stage_attempts) {
+                        error!(
+                            "giving up on tip distribution of epoch {} after stage {} failed {} times",
+                            state.epoch,
+                            stage.name(),
+                            failed_attempts
+                        );
+                        datapoint_error!(
+                            "tip_distributor-epoch_abandoned",
+                            ("epoch", state.epoch, i64),
+                            ("stage", stage.name(), String),
+                        );
+                    }
+                    if let Err(e) = write_state(&config.work_dir, state) {
+                        error!("failed to write scheduler state: {:?}", e);
+                    }
+                }
+            }
+        }
+        sleep(config.poll_interval);
+    }
+    Ok(())
+}
+
+/// Runs the next stage, if any, and persists the state. Returns false when waiting on an epoch
+/// rollover or a snapshot.
+fn run_next_stage(
+    config: &SchedulerConfig,
+    rpc_client: &RpcClient,
+    runtime: &Runtime,
+    keypair: &Arc<Keypair>,
+    state: &mut Option<SchedulerState>,
+) -> Result<bool, SchedulerError> {
+    let current_epoch = rpc_client.get_epoch_info().map_err(Box::new)?.epoch;
+    let Some(mut next_state) = SchedulerState::next(state.as_ref(), current_epoch) else {
+        return Ok(false);
+    };
+    // failures are counted against the epoch and stage that's being run
+    *state = Some(next_state.clone());
+    let epoch = next_state.epoch;
+    fs::create_dir_all(config.epoch_dir(epoch))?;
+
+    let start = Instant::now();
+    let stage = next_state.stage;
+    match stage {
+        Stage::StakeMeta => {
+            let epoch_schedule = rpc_client.get_epoch_schedule().map_err(Box::new)?;
+            let last_slot = epoch_schedule.get_last_slot_in_epoch(epoch);
+            let first_slot = last_slot
+                .saturating_sub(config.max_snapshot_slot_lag)
+                .max(epoch_schedule.get_first_slot_in_epoch(epoch));
+            let Some((full_snapshot_archive, incremental_snapshot_archive)) =
+                find_snapshot_archives(&config.snapshot_archives_dir, first_slot, last_slot)
+            else {
+                if next_state.is_snapshot_overdue(current_epoch) {
+                    error!(
+                        "giving up on tip distribution of epoch {}, no snapshot between slots {} and {} showed up before epoch {} ended",
+                        epoch,
+                        first_slot,
+                        last_slot,
+                        epoch + 1
+                    );
+                    datapoint_error!(
+                        "tip_distributor-epoch_abandoned",
+                        ("epoch", epoch, i64),
+                        ("stage", stage.name(), String),
+                    );
+                    next_state.stage = Stage::Done;
+                    next_state.failed_attempts = 0;
+                    write_state(&config.work_dir, &next_state)?;
+                    *state = Some(next_state);
+                    return Ok(true);
+                }
+                info!(
+                    "waiting for a snapshot of epoch {} between slots {} and {}",
+                    epoch, first_slot, last_slot
+                );
+                return Ok(false);
+            };
+            let snapshot_slot = incremental_snapshot_archive
+                .as_ref()
+                .map(|archive| archive.slot())
+                .unwrap_or_else(|| full_snapshot_archive.slot());
+            generate_stake_meta_from_snapshot_archives(
+                full_snapshot_archive.path(),
+                incremental_snapshot_archive
+                    .as_ref()
+                    .map(|archive| archive.path().as_path()),
+                &config.snapshot_unpack_dir,
+                &snapshot_slot,
+                &config.tip_distribution_program_id,
+                &config.stake_meta_path(epoch).to_string_lossy(),
+                &config.tip_payment_program_id,
+            )?;
+            next_state.snapshot_slot = Some(snapshot_slot);
+        }
+        Stage::MerkleTrees => generate_merkle_root(
+            &config.stake_meta_path(epoch),
+            &config.merkle_trees_path(epoch),
+            &config.rpc_url,
+            config.distribution_policy_path.as_ref(),
+        )?,
+        Stage::UploadMerkleRoots => upload_merkle_root(
+            &config.merkle_trees_path(epoch),
+            &config.keypair_path,
+            &config.rpc_url,
+            &config.tip_distribution_program_id,
+            config.max_concurrent_rpc_get_reqs,
+            config.txn_send_batch_size,
+            false,
+        )?,
+        Stage::ClaimTips => {
+            let merkle_trees: GeneratedMerkleTreeCollection =
+                read_json_from_file(&config.merkle_trees_path(epoch))?;
+            runtime.block_on(claim_mev_tips(
+                &merkle_trees,
+                config.rpc_url.clone(),
+                config.tip_distribution_program_id,
+                keypair.clone(),
+                config.max_claim_duration,
+                config.claim_throttle,
+                Some(config.claim_journal_path(epoch).as_path()),
+                false,
+            ))?
+        }
+        Stage::ReclaimRent => {
+            if config.should_reclaim_rent {
+                runtime.block_on(reclaim_rent(
+                    config.rpc_url.clone(),
+                    config.tip_distribution_program_id,
+                    keypair.clone(),
+                    config.max_claim_duration,
+                    config.should_reclaim_tdas,
+                    config.claim_throttle.micro_lamports(0),
+                    false,
+                ))?
+            }
+        }
+        Stage::Done => {}
+    }
+
+    next_state.stage = stage.next();
+    next_state.failed_attempts = 0;
+    write_state(&config.work_dir, &next_state)?;
+    *state = Some(next_state);
+
+    datapoint_info!(
+        "tip_distributor-stage_completion",
+        ("epoch", epoch, i64),
+        ("stage", stage.name(), String),
+        ("elapsed_us", start.elapsed().as_micros(), i64),
+    );
+    info!(
+        "finished stage {} of epoch {} in {:?}",
+        stage.name(),
+        epoch,
+        start.elapsed()
+    );
+    Ok(true)
+}
+
+/// Writes the state to a temporary file first so a crash never leaves a torn state behind.
+fn write_state(work_dir: &Path, state: &SchedulerState) -> Result<(), SchedulerError> {
+    let tmp_path = work_dir.join(format!("{STATE_FILE}.tmp"));
+    fs::write(&tmp_path, serde_json::to_vec_pretty(state)?)?;
+    fs::rename(tmp_path, work_dir.join(STATE_FILE))?;
+    Ok(())
+}
+
+/// Returns the snapshot archives with the highest slot between `first_slot` and `last_slot`. An
+/// incremental snapshot archive is only used if its full snapshot archive is around.
+fn find_snapshot_archives(
+    snapshot_archives_dir: &Path,
+    first_slot: Slot,
+    last_slot: Slot,
+) -> Option<(
+    FullSnapshotArchiveInfo,
+    Option<IncrementalSnapshotArchiveInfo>,
+)> {
+    let slots = first_slot..=last_slot;
+    let full_snapshot_archives = get_full_snapshot_archives(snapshot_archives_dir);
+    let highest_full_snapshot_archive = full_snapshot_archives
+        .iter()
+        .filter(|archive| slots.contains(&archive.slot()))
+        .max()
+        .cloned();
+    let highest_incremental_snapshot_archive =
+        get_incremental_snapshot_archives(snapshot_archives_dir)
+            .into_iter()
+            .filter(|archive| slots.contains(&archive.slot()))
+            .filter_map(|archive| {
+                let full_snapshot_archive =
+                    full_snapshot_archives
+                        .iter()
+                        .find(|full_snapshot_archive| {
+                            full_snapshot_archive.slot() == archive.base_slot()
+                        })?;
+                Some((full_snapshot_archive.clone(), archive))
+            })
+            .max_by_key(|(_, archive)| archive.slot());
+
+    match (
+        highest_full_snapshot_archive,
+        highest_incremental_snapshot_archive,
+    ) {
+        (Some(full), Some((_, incremental))) if full.slot() >= incremental.slot() => {
+            Some((full, None))
+        }
+        (_, Some((full, incremental))) => Some((full, Some(incremental))),
+        (full, None) => full.map(|full| (full, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_runtime::{
+            snapshot_hash::SnapshotHash,
+            snapshot_utils::{
+                build_full_snapshot_archive_path, build_incremental_snapshot_archive_path,
+                ArchiveFormat,
+            },
+        },
+        solana_sdk::hash::Hash,
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_next_state() {
+        let state = |epoch, stage| SchedulerState {
+            epoch,
+            stage,
+            snapshot_slot: None,
+            failed_attempts: 0,
+        };
+
+        // nothing completed yet
+        assert_eq!(SchedulerState::next(None, 0), None);
+        assert_eq!(
+            SchedulerState::next(None, 5),
+            Some(state(4, Stage::StakeMeta))
+        );
+        // an unfinished epoch is picked back up, even after a rollover
+        assert_eq!(
+            SchedulerState::next(Some(&state(4, Stage::ClaimTips)), 6),
+            Some(state(4, Stage::ClaimTips))
+        );
+        // wait for the next rollover once done
+        assert_eq!(SchedulerState::next(Some(&state(4, Stage::Done)), 5), None);
+        assert_eq!(
+            SchedulerState::next(Some(&state(4, Stage::Done)), 6),
+            Some(state(5, Stage::StakeMeta))
+        );
+        // missed epochs are skipped
+        assert_eq!(
+            SchedulerState::next(Some(&state(4, Stage::Done)), 9),
+            Some(state(8, Stage::StakeMeta))
+        );
+    }
+
+    #[test]
+    fn test_record_failure() {
+        let mut state = SchedulerState {
+            epoch: 4,
+            stage: Stage::UploadMerkleRoots,
+            snapshot_slot: Some(100),
+            failed_attempts: 0,
+        };
+        assert!(!state.record_failure(3));
+        assert!(!state.record_failure(3));
+        assert_eq!(state.stage, Stage::UploadMerkleRoots);
+        assert_eq!(state.failed_attempts, 2);
+
+        // the epoch is given up on and the next completed epoch is picked up
+        assert!(state.record_failure(3));
+        assert_eq!(state.stage, Stage::Done);
+        assert_eq!(state.failed_attempts, 0);
+        assert_eq!(SchedulerState::next(Some(&state), 5), None);
+        assert_eq!(
+            SchedulerState::next(Some(&state), 7),
+            Some(SchedulerState {
+                epoch: 6,
+                stage: Stage::StakeMeta,
+                snapshot_slot: None,
+                failed_attempts: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_snapshot_overdue() {
+        let state = SchedulerState {
+            epoch: 4,
+            stage: Stage::StakeMeta,
+            snapshot_slot: None,
+            failed_attempts: 0,
+        };
+        // the snapshot may still show up while the next epoch is running
+        assert!(!state.is_snapshot_overdue(5));
+        assert!(state.is_snapshot_overdue(6));
+        assert!(state.is_snapshot_overdue(9));
+    }
+
+    #[test]
+    fn test_find_snapshot_archives() {
+        let dir = TempDir::new().unwrap();
+        let full = |slot| {
+            let path = build_full_snapshot_archive_path(
+                dir.path(),
+                slot,
+                &SnapshotHash(Hash::new_unique()),
+                ArchiveFormat::TarZstd,
+            );
+            fs::write(&path, b"").unwrap();
+            path
+        };
+        let incremental = |base_slot, slot| {
+            let path = build_incremental_snapshot_archive_path(
+                dir.path(),
+                base_slot,
+                slot,
+                &SnapshotHash(Hash::new_unique()),
+                ArchiveFormat::TarZstd,
+            );
+            fs::write(&path, b"").unwrap();
+            path
+        };
+        let found = |first_slot, last_slot| {
+            find_snapshot_archives(dir.path(), first_slot, last_slot).map(|(full, incremental)| {
+                (
+                    full.path().clone(),
+                    incremental.map(|incremental| incremental.path().clone()),
+                )
+            })
+        };
+
+        assert_eq!(found(0, 100), None);
+
+        let full_90 = full(90);
+        assert_eq!(found(0, 100), Some((full_90.clone(), None)));
+        assert_eq!(found(95, 100), None);
+
+        // the incremental needs its full snapshot archive
+        incremental(50, 99);
+        assert_eq!(found(0, 100), Some((full_90.clone(), None)));
+        let incremental_99 = incremental(90, 99);
+        assert_eq!(
+            found(0, 100),
+            Some((full_90.clone(), Some(incremental_99.clone())))
+        );
+
+        // a full snapshot archive at a higher slot wins
+        let full_100 = full(100);
+        assert_eq!(found(0, 100), Some((full_100, None)));
+        assert_eq!(found(0, 99), Some((full_90, Some(incremental_99))));
+    }
+}
@@ -7,8 +7,11 @@ use {
     anchor_lang::AccountDeserialize,
     itertools::Itertools,
     log::*,
-    solana_accounts_db::hardened_unpack::{
-        open_genesis_config, OpenGenesisConfigError, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+    solana_accounts_db::{
+        accounts_file::StorageAccess,
+        hardened_unpack::{
+            open_genesis_config, OpenGenesisConfigError, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+        },
     },
     solana_client::client_error::ClientError,
     solana_ledger::{
@@ -20,13 +23,26 @@ use {
         blockstore_processor::{BlockstoreProcessorError, ProcessOptions},
     },
     solana_program::{stake_history::StakeHistory, sysvar},
-    solana_runtime::{bank::Bank, snapshot_config::SnapshotConfig, stakes::StakeAccount},
+    solana_runtime::{
+        bank::Bank,
+        snapshot_archive_info::{FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo},
+        snapshot_bank_utils::{unarchive_snapshot_archives, UnarchivedSnapshotArchives},
+        snapshot_config::SnapshotConfig,
+        snapshot_utils::SnapshotError,
+        stakes::StakeAccount,
+    },
     solana_sdk::{
-        account::{from_account, ReadableAccount, WritableAccount},
-        clock::Slot,
+        account::{from_account, AccountSharedData, ReadableAccount, WritableAccount},
+        clock::{Epoch, Slot},
+        epoch_schedule::EpochSchedule,
+        feature,
+        feature_set::reduce_stake_warmup_cooldown,
+        hash::Hash,
         pubkey::Pubkey,
+        rent::Rent,
+        stake,
     },
-    solana_vote::vote_account::VoteAccount,
+    solana_vote::vote_account::{VoteAccount, VoteAccountsHashMap},
     std::{
         collections::HashMap,
         fmt::{Debug, Display, Formatter},
@@ -63,6 +79,11 @@ pub enum StakeMetaGeneratorError {
 
     SnapshotSlotNotFound,
 
+    SnapshotAccountNotFound(Pubkey),
+
+    #[error(transparent)]
+    SnapshotError(#[from] SnapshotError),
+
     BankForksUtilsError(#[from] BankForksUtilsError),
 
     GenesisConfigError(#[from] OpenGenesisConfigError),
@@ -96,6 +117,72 @@ pub fn generate_stake_meta(
     Ok(())
 }
 
+/// Same as [generate_stake_meta], but reads the stake, vote and tip accounts straight out of a full
+/// and optional incremental snapshot archive instead of loading a bank from a ledger. The archives
+/// are unpacked into a temporary directory under `unpack_dir`, which is removed once done.
+pub fn generate_stake_meta_from_snapshot_archives(
+    full_snapshot_archive_path: &Path,
+    incremental_snapshot_archive_path: Option<&Path>,
+    unpack_dir: &Path,
+    snapshot_slot: &Slot,
+    tip_distribution_program_id: &Pubkey,
+    out_path: &str,
+    tip_payment_program_id: &Pubkey,
+) -> Result<(), StakeMetaGeneratorError> {
+    let full_snapshot_archive_info =
+        FullSnapshotArchiveInfo::new_from_path(full_snapshot_archive_path.to_path_buf())?;
+    let incremental_snapshot_archive_info = incremental_snapshot_archive_path
+        .map(|path| IncrementalSnapshotArchiveInfo::new_from_path(path.to_path_buf()))
+        .transpose()?;
+
+    info!("Unpacking snapshot archives...");
+    let unarchived_snapshot_archives = unarchive_snapshot_archives(
+        unpack_dir,
+        &full_snapshot_archive_info,
+        incremental_snapshot_archive_info.as_ref(),
+        StorageAccess::default(),
+    )?;
+    let bank_fields = &unarchived_snapshot_archives.bank_fields;
+    if bank_fields.slot() != *snapshot_slot {
+        error!(
+            "expected snapshot slot {}, found {}",
+            snapshot_slot,
+            bank_fields.slot()
+        );
+        return Err(StakeMetaGeneratorError::SnapshotSlotNotFound);
+    }
+
+    info!("Reading accounts from snapshot storages...");
+    let snapshot_accounts = SnapshotAccounts::new(
+        &unarchived_snapshot_archives,
+        tip_distribution_program_id,
+        tip_payment_program_id,
+    )?;
+    let epoch_vote_accounts = bank_fields
+        .epoch_vote_accounts(bank_fields.epoch())
+        .unwrap_or_else(|| {
+            panic!(
+                "No epoch_vote_accounts found for slot {} at epoch {}",
+                bank_fields.slot(),
+                bank_fields.epoch()
+            )
+        });
+
+    info!("Generating stake_meta_collection object...");
+    let stake_meta_coll = stake_meta_collection_from_source(
+        &snapshot_accounts,
+        epoch_vote_accounts,
+        &snapshot_accounts.stake_delegations(),
+        tip_distribution_program_id,
+        tip_payment_program_id,
+    )?;
+
+    info!("Writing stake_meta_collection to JSON {}...", out_path);
+    write_to_json_file(&stake_meta_coll, out_path)?;
+
+    Ok(())
+}
+
 fn create_bank_from_snapshot(
     ledger_path: &Path,
     snapshot_slot: &Slot,
@@ -158,6 +245,173 @@ fn write_to_json_file(
     Ok(())
 }
 
+/// The bank state stake meta is generated from, either a [Bank] or the accounts read out of
+/// snapshot archives.
+trait StakeMetaSource {
+    fn slot(&self) -> Slot;
+
+    fn epoch(&self) -> Epoch;
+
+    fn bank_hash(&self) -> Hash;
+
+    fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData>;
+
+    fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> u64;
+
+    fn new_warmup_cooldown_rate_epoch(&self) -> Option<Epoch>;
+}
+
+impl StakeMetaSource for Bank {
+    fn slot(&self) -> Slot {
+        Bank::slot(self)
+    }
+
+    fn epoch(&self) -> Epoch {
+        Bank::epoch(self)
+    }
+
+    fn bank_hash(&self) -> Hash {
+        Bank::hash(self)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        Bank::get_account(self, pubkey)
+    }
+
+    fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> u64 {
+        Bank::get_minimum_balance_for_rent_exemption(self, data_len)
+    }
+
+    fn new_warmup_cooldown_rate_epoch(&self) -> Option<Epoch> {
+        Bank::new_warmup_cooldown_rate_epoch(self)
+    }
+}
+
+/// The accounts needed to generate stake meta, read straight out of the account storages of
+/// unpacked snapshot archives: stake accounts, tip program accounts and the sysvars and feature
+/// the stake math depends on. Vote accounts come from the epoch stakes in the bank fields.
+struct SnapshotAccounts {
+    slot: Slot,
+    epoch: Epoch,
+    bank_hash: Hash,
+    accounts: HashMap<Pubkey, AccountSharedData>,
+    rent: Rent,
+    new_warmup_cooldown_rate_epoch: Option<Epoch>,
+}
+
+impl SnapshotAccounts {
+    fn new(
+        unarchived_snapshot_archives: &UnarchivedSnapshotArchives,
+        tip_distribution_program_id: &Pubkey,
+        tip_payment_program_id: &Pubkey,
+    ) -> Result<Self, StakeMetaGeneratorError> {
+        let is_needed = |pubkey: &Pubkey, owner: &Pubkey| {
+            owner == &stake::program::id()
+                || owner == tip_distribution_program_id
+                || owner == tip_payment_program_id
+                || pubkey == &sysvar::stake_history::id()
+                || pubkey == &sysvar::rent::id()
+                || pubkey == &sysvar::epoch_schedule::id()
+                || pubkey == &reduce_stake_warmup_cooldown::id()
+        };
+
+        // an account may be stored in several slots, the version in the highest slot is the
+        // latest one
+        let mut storages: Vec<_> = unarchived_snapshot_archives
+            .storages()
+            .flat_map(|storage_map| {
+                storage_map
+                    .iter()
+                    .map(|entry| entry.value().storage.clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        storages.sort_by_key(|storage| storage.slot());
+
+        let mut accounts = HashMap::new();
+        for storage in storages {
+            storage.accounts.scan_accounts(|stored_account| {
+                if is_needed(stored_account.pubkey(), stored_account.owner()) {
+                    if stored_account.lamports() == 0 {
+                        accounts.remove(stored_account.pubkey());
+                    } else {
+                        accounts.insert(
+                            *stored_account.pubkey(),
+                            stored_account.to_account_shared_data(),
+                        );
+                    }
+                }
+            });
+        }
+
+        let rent = accounts
+            .get(&sysvar::rent::id())
+            .and_then(from_account::<Rent, _>)
+            .ok_or(StakeMetaGeneratorError::SnapshotAccountNotFound(
+                sysvar::rent::id(),
+            ))?;
+        let epoch_schedule = accounts
+            .get(&sysvar::epoch_schedule::id())
+            .and_then(from_account::<EpochSchedule, _>)
+            .ok_or(StakeMetaGeneratorError::SnapshotAccountNotFound(
+                sysvar::epoch_schedule::id(),
+            ))?;
+        // matches FeatureSet::new_warmup_cooldown_rate_epoch
+        let new_warmup_cooldown_rate_epoch = accounts
+            .get(&reduce_stake_warmup_cooldown::id())
+            .and_then(feature::from_account)
+            .and_then(|feature| feature.activated_at)
+            .map(|slot| epoch_schedule.get_epoch(slot));
+
+        let bank_fields = &unarchived_snapshot_archives.bank_fields;
+        Ok(Self {
+            slot: bank_fields.slot(),
+            epoch: bank_fields.epoch(),
+            bank_hash: bank_fields.hash(),
+            accounts,
+            rent,
+            new_warmup_cooldown_rate_epoch,
+        })
+    }
+
+    fn stake_delegations(&self) -> im::HashMap<Pubkey, StakeAccount> {
+        self.accounts
+            .iter()
+            .filter_map(|(pubkey, account)| {
+                let stake_account = StakeAccount::try_from(account.clone()).ok()?;
+                Some((*pubkey, stake_account))
+            })
+            .collect()
+    }
+}
+
+impl StakeMetaSource for SnapshotAccounts {
+    fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    fn bank_hash(&self) -> Hash {
+        self.bank_hash
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.get(pubkey).cloned()
+    }
+
+    fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> u64 {
+        // matches Bank::get_minimum_balance_for_rent_exemption
+        self.rent.minimum_balance(data_len).max(1)
+    }
+
+    fn new_warmup_cooldown_rate_epoch(&self) -> Option<Epoch> {
+        self.new_warmup_cooldown_rate_epoch
+    }
+}
+
 /// Creates a collection of [StakeMeta]'s from the given bank.
 pub fn generate_stake_meta_collection(
     bank: &Arc<Bank>,
@@ -177,7 +431,23 @@ pub fn generate_stake_meta_collection(
     let l_stakes = bank.stakes_cache.stakes();
     let delegations = l_stakes.stake_delegations();
 
-    let voter_pubkey_to_delegations = group_delegations_by_voter_pubkey(delegations, bank);
+    stake_meta_collection_from_source(
+        bank.as_ref(),
+        epoch_vote_accounts,
+        delegations,
+        tip_distribution_program_id,
+        tip_payment_program_id,
+    )
+}
+
+fn stake_meta_collection_from_source(
+    source: &impl StakeMetaSource,
+    epoch_vote_accounts: &VoteAccountsHashMap,
+    delegations: &im::HashMap<Pubkey, StakeAccount>,
+    tip_distribution_program_id: &Pubkey,
+    tip_payment_program_id: &Pubkey,
+) -> Result<StakeMetaCollection, StakeMetaGeneratorError> {
+    let voter_pubkey_to_delegations = group_delegations_by_voter_pubkey(delegations, source);
 
     // the last leader in an epoch may not crank the tip program before the epoch is over, which
     // would result in MEV rewards for epoch N not being cranked until epoch N + 1. This means that
@@ -185,7 +455,7 @@ pub fn generate_stake_meta_collection(
     // We assume that the rewards sitting in the tip program PDAs are cranked out by the time all of
     // the rewards are claimed.
     let tip_accounts = derive_tip_payment_pubkeys(tip_payment_program_id);
-    let account = bank
+    let account = source
         .get_account(&tip_accounts.config_pda)
         .expect("config pda exists");
 
@@ -199,10 +469,12 @@ pub fn generate_stake_meta_collection(
         .tip_pdas
         .iter()
         .map(|pubkey| {
-            let tip_account = bank.get_account(pubkey).expect("tip account exists");
+            let tip_account = source.get_account(pubkey).expect("tip account exists");
             tip_account
                 .lamports()
-                .checked_sub(bank.get_minimum_balance_for_rent_exemption(tip_account.data().len()))
+                .checked_sub(
+                    source.get_minimum_balance_for_rent_exemption(tip_account.data().len()),
+                )
                 .expect("tip balance underflow")
         })
         .sum();
@@ -225,10 +497,10 @@ pub fn generate_stake_meta_collection(
             let tip_distribution_pubkey = derive_tip_distribution_account_address(
                 tip_distribution_program_id,
                 vote_pubkey,
-                bank.epoch(),
+                source.epoch(),
             )
             .0;
-            let tda = if let Some(mut account_data) = bank.get_account(&tip_distribution_pubkey) {
+            let tda = if let Some(mut account_data) = source.get_account(&tip_distribution_pubkey) {
                 // TDAs may be funded with lamports and therefore exist in the bank, but would fail the deserialization step
                 // if the buffer is yet to be allocated thru the init call to the program.
                 if let Ok(tip_distribution_account) =
@@ -273,7 +545,7 @@ pub fn generate_stake_meta_collection(
                     warn!("len mismatch actual={actual_len}, expected={expected_len}");
                 }
                 let rent_exempt_amount =
-                    bank.get_minimum_balance_for_rent_exemption(tda.account_data.data().len());
+                    source.get_minimum_balance_for_rent_exemption(tda.account_data.data().len());
 
                 Some(TipDistributionMeta::from_tda_wrapper(
                     tda,
@@ -305,27 +577,27 @@ pub fn generate_stake_meta_collection(
     Ok(StakeMetaCollection {
         stake_metas,
         tip_distribution_program_id: *tip_distribution_program_id,
-        bank_hash: bank.hash().to_string(),
-        epoch: bank.epoch(),
-        slot: bank.slot(),
+        bank_hash: source.bank_hash().to_string(),
+        epoch: source.epoch(),
+        slot: source.slot(),
     })
 }
 
 /// Given an [EpochStakes] object, return delegations grouped by voter_pubkey (validator delegated to).
 fn group_delegations_by_voter_pubkey(
     delegations: &im::HashMap<Pubkey, StakeAccount>,
-    bank: &Bank,
+    source: &impl StakeMetaSource,
 ) -> HashMap<Pubkey, Vec<crate::Delegation>> {
     delegations
         .into_iter()
         .filter(|(_stake_pubkey, stake_account)| {
             stake_account.delegation().stake(
-                bank.epoch(),
+                source.epoch(),
                 &from_account::<StakeHistory, _>(
-                    &bank.get_account(&sysvar::stake_history::id()).unwrap(),
+                    &source.get_account(&sysvar::stake_history::id()).unwrap(),
                 )
                 .unwrap(),
-                bank.new_warmup_cooldown_rate_epoch(),
+                source.new_warmup_cooldown_rate_epoch(),
             ) > 0
         })
         .into_group_map_by(|(_stake_pubkey, stake_account)| stake_account.delegation().voter_pubkey)
@@ -367,8 +639,13 @@ mod tests {
             TIP_ACCOUNT_SEED_1, TIP_ACCOUNT_SEED_2, TIP_ACCOUNT_SEED_3, TIP_ACCOUNT_SEED_4,
             TIP_ACCOUNT_SEED_5, TIP_ACCOUNT_SEED_6, TIP_ACCOUNT_SEED_7,
         },
-        solana_runtime::genesis_utils::{
-            create_genesis_config_with_vote_accounts, GenesisConfigInfo, ValidatorVoteKeypairs,
+        solana_runtime::{
+            genesis_utils::{
+                create_genesis_config_with_vote_accounts, GenesisConfigInfo, ValidatorVoteKeypairs,
+            },
+            snapshot_archive_info::SnapshotArchiveInfoGetter,
+            snapshot_bank_utils::bank_to_full_snapshot_archive,
+            snapshot_utils::ArchiveFormat,
         },
         solana_sdk::{
             self,
@@ -835,6 +1112,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_stake_meta_from_snapshot_archives_matches_bank() {
+        let validator_keypairs_0 = ValidatorVoteKeypairs::new_rand();
+        let validator_keypairs_1 = ValidatorVoteKeypairs::new_rand();
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config_with_vote_accounts(
+            1_000_000_000,
+            &[&validator_keypairs_0, &validator_keypairs_1],
+            vec![10_000, 20_000],
+        );
+        let (bank, _bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+
+        let tip_distribution_program_id = Pubkey::new_unique();
+        let tip_payment_program_id = Pubkey::new_unique();
+        for (pubkey, data) in create_config_account_data(&tip_payment_program_id, &bank) {
+            bank.store_account(&pubkey, &data);
+        }
+        // only the first validator has a tip distribution account
+        let (tip_distribution_pubkey, bump) = derive_tip_distribution_account_address(
+            &tip_distribution_program_id,
+            &validator_keypairs_0.vote_keypair.pubkey(),
+            bank.epoch(),
+        );
+        let tda = TipDistributionAccount {
+            validator_vote_account: validator_keypairs_0.vote_keypair.pubkey(),
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            merkle_root: None,
+            epoch_created_at: bank.epoch(),
+            validator_commission_bps: 800,
+            expires_at: bank.epoch() + 3,
+            bump,
+        };
+        bank.store_account(
+            &tip_distribution_pubkey,
+            &tda_to_account_shared_data(&tip_distribution_program_id, 123_456_789, tda),
+        );
+        bank.fill_bank_with_ticks_for_tests();
+        bank.freeze();
+
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let unpack_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &bank,
+            None,
+            &snapshot_archives_dir,
+            &snapshot_archives_dir,
+            ArchiveFormat::Tar,
+        )
+        .unwrap();
+
+        let out_path = unpack_dir.path().join("stake-meta.json");
+        generate_stake_meta_from_snapshot_archives(
+            full_snapshot_archive_info.path(),
+            None,
+            unpack_dir.path(),
+            &bank.slot(),
+            &tip_distribution_program_id,
+            out_path.to_str().unwrap(),
+            &tip_payment_program_id,
+        )
+        .unwrap();
+        let from_snapshot: StakeMetaCollection = crate::read_json_from_file(&out_path).unwrap();
+        let from_bank = generate_stake_meta_collection(
+            &bank,
+            &tip_distribution_program_id,
+            &tip_payment_program_id,
+        )
+        .unwrap();
+
+        assert_eq!(from_snapshot.stake_metas.len(), 2);
+        assert!(from_snapshot
+            .stake_metas
+            .iter()
+            .any(|stake_meta| stake_meta.maybe_tip_distribution_meta.is_some()));
+        assert_eq!(from_snapshot.stake_metas, from_bank.stake_metas);
+        assert_eq!(
+            from_snapshot.tip_distribution_program_id,
+            from_bank.tip_distribution_program_id
+        );
+        assert_eq!(from_snapshot.bank_hash, from_bank.bank_hash);
+        assert_eq!(from_snapshot.epoch, from_bank.epoch);
+        assert_eq!(from_snapshot.slot, from_bank.slot);
+    }
+
     /// Helper function that sends a delegate stake instruction to the bank.
     /// Returns the created stake account pubkey.
     fn delegate_stake_helper(
@@ -0,0 +1,171 @@
+//! Runs the tip distribution scheduler against a local test validator through a whole epoch.
+use {
+    anchor_lang::AccountSerialize,
+    jito_tip_payment::{
+        Config, InitBumps, TipPaymentAccount, CONFIG_ACCOUNT_SEED, TIP_ACCOUNT_SEED_0,
+        TIP_ACCOUNT_SEED_1, TIP_ACCOUNT_SEED_2, TIP_ACCOUNT_SEED_3, TIP_ACCOUNT_SEED_4,
+        TIP_ACCOUNT_SEED_5, TIP_ACCOUNT_SEED_6, TIP_ACCOUNT_SEED_7,
+    },
+    solana_sdk::{
+        account::AccountSharedData, epoch_schedule::EpochSchedule, pubkey::Pubkey, rent::Rent,
+        signature::write_keypair_file,
+    },
+    solana_test_validator::TestValidatorGenesis,
+    solana_tip_distributor::{
+        claim_mev_workflow::ClaimThrottle,
+        read_json_from_file,
+        scheduler_workflow::{run_scheduler, SchedulerConfig, SchedulerState, Stage},
+    },
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, sleep},
+        time::{Duration, Instant},
+    },
+    tempfile::TempDir,
+};
+
+// the test validator takes a full snapshot every 100 slots, so the first epoch has one
+const SLOTS_PER_EPOCH: u64 = 128;
+
+/// The tip payment config and tip accounts the stake meta generator reads the tips from
+fn tip_payment_accounts(tip_payment_program_id: &Pubkey) -> Vec<(Pubkey, AccountSharedData)> {
+    let rent = Rent::default();
+    let config_pda = Pubkey::find_program_address(&[CONFIG_ACCOUNT_SEED], tip_payment_program_id);
+    let tip_accounts = [
+        TIP_ACCOUNT_SEED_0,
+        TIP_ACCOUNT_SEED_1,
+        TIP_ACCOUNT_SEED_2,
+        TIP_ACCOUNT_SEED_3,
+        TIP_ACCOUNT_SEED_4,
+        TIP_ACCOUNT_SEED_5,
+        TIP_ACCOUNT_SEED_6,
+        TIP_ACCOUNT_SEED_7,
+    ]
+    .map(|seed| Pubkey::find_program_address(&[seed], tip_payment_program_id));
+
+    let config = Config {
+        tip_receiver: Pubkey::new_unique(),
+        block_builder: Pubkey::new_unique(),
+        block_builder_commission_pct: 10,
+        bumps: InitBumps {
+            config: config_pda.1,
+            tip_payment_account_0: tip_accounts[0].1,
+            tip_payment_account_1: tip_accounts[1].1,
+            tip_payment_account_2: tip_accounts[2].1,
+            tip_payment_account_3: tip_accounts[3].1,
+            tip_payment_account_4: tip_accounts[4].1,
+            tip_payment_account_5: tip_accounts[5].1,
+            tip_payment_account_6: tip_accounts[6].1,
+            tip_payment_account_7: tip_accounts[7].1,
+        },
+    };
+    let mut data = vec![];
+    config.try_serialize(&mut data).unwrap();
+    data.resize(Config::SIZE, 0);
+    let mut config_account = AccountSharedData::new(
+        rent.minimum_balance(Config::SIZE),
+        Config::SIZE,
+        tip_payment_program_id,
+    );
+    config_account.set_data_from_slice(&data);
+
+    let mut accounts = vec![(config_pda.0, config_account)];
+    accounts.extend(tip_accounts.into_iter().map(|(pubkey, _)| {
+        let mut data = vec![];
+        TipPaymentAccount::default()
+            .try_serialize(&mut data)
+            .unwrap();
+        data.resize(TipPaymentAccount::SIZE, 0);
+        let mut tip_account = AccountSharedData::new(
+            rent.minimum_balance(TipPaymentAccount::SIZE),
+            TipPaymentAccount::SIZE,
+            tip_payment_program_id,
+        );
+        tip_account.set_data_from_slice(&data);
+        (pubkey, tip_account)
+    }));
+    accounts
+}
+
+#[test]
+fn test_scheduler_distributes_epoch() {
+    let tip_distribution_program_id = Pubkey::new_unique();
+    let tip_payment_program_id = Pubkey::new_unique();
+
+    let ledger_dir = TempDir::new().unwrap();
+    let (test_validator, payer) = TestValidatorGenesis::default()
+        .ledger_path(ledger_dir.path())
+        .epoch_schedule(EpochSchedule::custom(
+            SLOTS_PER_EPOCH,
+            SLOTS_PER_EPOCH,
+            /*warmup:*/ false,
+        ))
+        .add_accounts(tip_payment_accounts(&tip_payment_program_id))
+        .start();
+
+    let work_dir = TempDir::new().unwrap();
+    let unpack_dir = TempDir::new().unwrap();
+    let keypair_path = work_dir.path().join("payer.json");
+    write_keypair_file(&payer, &keypair_path).unwrap();
+
+    let config = SchedulerConfig {
+        rpc_url: test_validator.rpc_url(),
+        tip_distribution_program_id,
+        tip_payment_program_id,
+        keypair_path,
+        snapshot_archives_dir: ledger_dir.path().to_path_buf(),
+        snapshot_unpack_dir: unpack_dir.path().to_path_buf(),
+        work_dir: work_dir.path().to_path_buf(),
+        max_snapshot_slot_lag: SLOTS_PER_EPOCH,
+        distribution_policy_path: None,
+        max_concurrent_rpc_get_reqs: 10,
+        txn_send_batch_size: 10,
+        claim_throttle: ClaimThrottle {
+            max_tps: None,
+            micro_lamports: 1,
+            micro_lamports_increment: 0,
+            max_micro_lamports: 1,
+        },
+        max_claim_duration: Duration::from_secs(10),
+        should_reclaim_rent: false,
+        should_reclaim_tdas: false,
+        poll_interval: Duration::from_millis(500),
+        // failing stages are retried until the test times out instead of giving up on the epoch
+        max_stage_attempts: u32::MAX,
+    };
+    let exit = Arc::new(AtomicBool::new(false));
+    let scheduler = {
+        let exit = exit.clone();
+        thread::spawn(move || run_scheduler(config, exit))
+    };
+
+    // the first epoch ends after ~50 seconds
+    let state_path = work_dir.path().join("state.json");
+    let start = Instant::now();
+    let state = loop {
+        assert!(
+            start.elapsed() < Duration::from_secs(180),
+            "epoch 0 wasn't distributed in time"
+        );
+        // the state is renamed into place, so it's never read half written
+        if state_path.exists() {
+            let state: SchedulerState = read_json_from_file(&state_path).unwrap();
+            if state.stage == Stage::Done {
+                break state;
+            }
+        }
+        sleep(Duration::from_secs(1));
+    };
+    exit.store(true, Ordering::Relaxed);
+    scheduler.join().unwrap().unwrap();
+
+    assert_eq!(state.epoch, 0);
+    assert!(state.snapshot_slot.is_some());
+    assert_eq!(state.failed_attempts, 0);
+    let epoch_dir = work_dir.path().join("epoch-0");
+    assert!(epoch_dir.join("stake-meta.json").exists());
+    assert!(epoch_dir.join("merkle-trees.json").exists());
+}
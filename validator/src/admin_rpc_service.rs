@@ -11,10 +11,15 @@ use {
     solana_accounts_db::accounts_index::AccountIndex,
     solana_core::{
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
+        bundle_stage::{bundle_policy::BundlePolicy, min_tip_policy::MinTipPolicy},
         consensus::{tower_storage::TowerStorage, Tower},
         proxy::{
-            block_engine_stage::{BlockEngineConfig, BlockEngineStage},
+            block_engine_region_selector::{BlockEngineRegionLatency, BlockEngineRegions},
+            block_engine_stage::{BlockEngineConfig, BlockEngineHealth, BlockEngineStage},
+            packet_source_accounting::PacketSourceStats,
+            packet_trust::PacketSource,
             relayer_stage::{RelayerConfig, RelayerStage},
+            vortexor_receiver::VortexorPeer,
         },
         repair::repair_service,
         validator::ValidatorStartProgress,
@@ -145,6 +150,168 @@ impl Display for AdminRpcRepairWhitelist {
     }
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcBlockEngineHealth {
+    pub block_engines: Vec<BlockEngineHealth>,
+}
+
+impl Display for AdminRpcBlockEngineHealth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.block_engines.is_empty() {
+            return writeln!(f, "Not connected to any block engines");
+        }
+        for block_engine in &self.block_engines {
+            writeln!(f, "Block Engine: {}", block_engine.block_engine_url)?;
+            writeln!(f, "  Connected: {}", block_engine.connected)?;
+            writeln!(f, "  Active: {}", block_engine.active)?;
+            match block_engine.latency_us {
+                Some(latency_us) => writeln!(f, "  Latency: {latency_us}us")?,
+                None => writeln!(f, "  Latency: unknown")?,
+            }
+            match block_engine.last_message_timestamp_ms {
+                Some(timestamp_ms) => writeln!(f, "  Last Message Timestamp: {timestamp_ms}")?,
+                None => writeln!(f, "  Last Message Timestamp: none")?,
+            }
+            writeln!(
+                f,
+                "  Bundles: {} ({} duplicate)",
+                block_engine.num_bundles, block_engine.num_duplicate_bundles
+            )?;
+            writeln!(
+                f,
+                "  Packets: {} ({} duplicate)",
+                block_engine.num_packets, block_engine.num_duplicate_packets
+            )?;
+            writeln!(
+                f,
+                "  Connection Errors: {}",
+                block_engine.num_connection_errors
+            )?;
+            if let Some(last_error) = &block_engine.last_error {
+                writeln!(f, "  Last Error: {last_error}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcPacketSourceStats {
+    pub sources: Vec<AdminRpcPacketSource>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcPacketSource {
+    pub source: String,
+    /// Packets per second, 0 is unlimited
+    pub rate_limit: u64,
+    pub stats: PacketSourceStats,
+}
+
+impl Display for AdminRpcPacketSourceStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for source in &self.sources {
+            writeln!(f, "Source: {}", source.source)?;
+            match source.rate_limit {
+                0 => writeln!(f, "  Rate Limit: unlimited")?,
+                rate_limit => writeln!(f, "  Rate Limit: {rate_limit} packets/s")?,
+            }
+            writeln!(
+                f,
+                "  Packets: {} ({} rate limited)",
+                source.stats.num_packets, source.stats.num_rate_limited_packets
+            )?;
+            writeln!(
+                f,
+                "  Signatures: {} unique, {} duplicate",
+                source.stats.num_unique_signatures, source.stats.num_duplicate_signatures
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcVortexorPeers {
+    pub peers: Vec<AdminRpcVortexorPeer>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcVortexorPeer {
+    pub identity: String,
+    pub tpu_address: SocketAddr,
+    pub tpu_forwards_address: SocketAddr,
+}
+
+impl From<VortexorPeer> for AdminRpcVortexorPeer {
+    fn from(peer: VortexorPeer) -> Self {
+        Self {
+            identity: peer.identity.to_string(),
+            tpu_address: peer.tpu_address,
+            tpu_forwards_address: peer.tpu_forwards_address,
+        }
+    }
+}
+
+impl Display for AdminRpcVortexorPeers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.peers.is_empty() {
+            return writeln!(f, "No vortexor peers");
+        }
+        for peer in &self.peers {
+            writeln!(f, "Vortexor: {}", peer.identity)?;
+            writeln!(f, "  TPU: {}", peer.tpu_address)?;
+            writeln!(f, "  TPU Forwards: {}", peer.tpu_forwards_address)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcBlockEngineRegions {
+    pub selected_block_engine_url: Option<String>,
+    pub regions: Vec<BlockEngineRegionLatency>,
+    pub last_evaluated_timestamp_ms: Option<u64>,
+}
+
+impl From<BlockEngineRegions> for AdminRpcBlockEngineRegions {
+    fn from(block_engine_regions: BlockEngineRegions) -> Self {
+        Self {
+            selected_block_engine_url: block_engine_regions.selected_block_engine_url,
+            regions: block_engine_regions.latencies,
+            last_evaluated_timestamp_ms: block_engine_regions.last_evaluated_timestamp_ms,
+        }
+    }
+}
+
+impl Display for AdminRpcBlockEngineRegions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Some(last_evaluated_timestamp_ms) = self.last_evaluated_timestamp_ms else {
+            return writeln!(f, "Block engine region selection is disabled");
+        };
+        writeln!(
+            f,
+            "Selected Block Engine: {}",
+            self.selected_block_engine_url.as_deref().unwrap_or("none")
+        )?;
+        writeln!(f, "Last Evaluated Timestamp: {last_evaluated_timestamp_ms}")?;
+        for region in &self.regions {
+            write!(f, "{}: ", region.block_engine_url)?;
+            match (region.connect_us, region.auth_us) {
+                (Some(connect_us), Some(auth_us)) => {
+                    writeln!(f, "connect {connect_us}us, auth {auth_us}us")?
+                }
+                _ => writeln!(
+                    f,
+                    "unreachable ({})",
+                    region.error.as_deref().unwrap_or("unknown error")
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
 #[rpc]
 pub trait AdminRpc {
     type Metadata;
@@ -255,8 +422,49 @@ pub trait AdminRpc {
         meta: Self::Metadata,
         block_engine_url: String,
         trust_packets: bool,
+        failover_block_engine_urls: Option<Vec<String>>,
+        candidate_block_engine_urls: Option<Vec<String>>,
+    ) -> Result<()>;
+
+    #[rpc(meta, name = "blockEngineHealth")]
+    fn block_engine_health(&self, meta: Self::Metadata) -> Result<AdminRpcBlockEngineHealth>;
+
+    #[rpc(meta, name = "blockEngineRegions")]
+    fn block_engine_regions(&self, meta: Self::Metadata) -> Result<AdminRpcBlockEngineRegions>;
+
+    #[rpc(meta, name = "setMinTipPolicy")]
+    fn set_min_tip_policy(
+        &self,
+        meta: Self::Metadata,
+        min_tip_lamports: u64,
+        min_tip_micro_lamports_per_cu: u64,
+        exempt_sources: Vec<String>,
     ) -> Result<()>;
 
+    #[rpc(meta, name = "setBundlePolicy")]
+    fn set_bundle_policy(&self, meta: Self::Metadata, path: String) -> Result<()>;
+
+    #[rpc(meta, name = "resetPacketTrust")]
+    fn reset_packet_trust(&self, meta: Self::Metadata) -> Result<()>;
+
+    #[rpc(meta, name = "packetSourceStats")]
+    fn packet_source_stats(&self, meta: Self::Metadata) -> Result<AdminRpcPacketSourceStats>;
+
+    #[rpc(meta, name = "vortexorPeers")]
+    fn vortexor_peers(&self, meta: Self::Metadata) -> Result<AdminRpcVortexorPeers>;
+
+    #[rpc(meta, name = "addVortexorPeer")]
+    fn add_vortexor_peer(
+        &self,
+        meta: Self::Metadata,
+        identity: String,
+        tpu_address: String,
+        tpu_forwards_address: String,
+    ) -> Result<()>;
+
+    #[rpc(meta, name = "removeVortexorPeer")]
+    fn remove_vortexor_peer(&self, meta: Self::Metadata, identity: String) -> Result<()>;
+
     #[rpc(meta, name = "setRelayerConfig")]
     fn set_relayer_config(
         &self,
@@ -484,10 +692,14 @@ impl AdminRpc for AdminRpcImpl {
         meta: Self::Metadata,
         block_engine_url: String,
         trust_packets: bool,
+        failover_block_engine_urls: Option<Vec<String>>,
+        candidate_block_engine_urls: Option<Vec<String>>,
     ) -> Result<()> {
         debug!("set_block_engine_config request received");
         let config = BlockEngineConfig {
             block_engine_url,
+            failover_block_engine_urls: failover_block_engine_urls.unwrap_or_default(),
+            candidate_block_engine_urls: candidate_block_engine_urls.unwrap_or_default(),
             trust_packets,
         };
         // Detailed log messages are printed inside validate function
@@ -503,6 +715,138 @@ impl AdminRpc for AdminRpcImpl {
         }
     }
 
+    fn block_engine_health(&self, meta: Self::Metadata) -> Result<AdminRpcBlockEngineHealth> {
+        debug!("block_engine_health request received");
+        meta.with_post_init(|post_init| {
+            Ok(AdminRpcBlockEngineHealth {
+                block_engines: post_init.block_engine_health.read().unwrap().clone(),
+            })
+        })
+    }
+
+    fn block_engine_regions(&self, meta: Self::Metadata) -> Result<AdminRpcBlockEngineRegions> {
+        debug!("block_engine_regions request received");
+        meta.with_post_init(|post_init| {
+            Ok(AdminRpcBlockEngineRegions::from(
+                post_init.block_engine_regions.read().unwrap().clone(),
+            ))
+        })
+    }
+
+    fn set_min_tip_policy(
+        &self,
+        meta: Self::Metadata,
+        min_tip_lamports: u64,
+        min_tip_micro_lamports_per_cu: u64,
+        exempt_sources: Vec<String>,
+    ) -> Result<()> {
+        debug!("set_min_tip_policy request received");
+        let policy = MinTipPolicy {
+            min_tip_lamports,
+            min_tip_micro_lamports_per_cu,
+            exempt_sources: HashSet::from_iter(exempt_sources),
+        };
+        meta.with_post_init(|post_init| {
+            *post_init.min_tip_policy.write().unwrap() = policy;
+            Ok(())
+        })
+    }
+
+    fn set_bundle_policy(&self, meta: Self::Metadata, path: String) -> Result<()> {
+        debug!("set_bundle_policy request received");
+        let policy = load_bundle_policy(&path).map_err(|err| {
+            error!("Failed to load bundle policy from {}: {}", &path, err);
+            jsonrpc_core::error::Error::invalid_params(format!(
+                "failed to load bundle policy from {path}: {err}"
+            ))
+        })?;
+        meta.with_post_init(|post_init| {
+            *post_init.bundle_policy.write().unwrap() = policy;
+            info!("Bundle policy loaded from {}", path);
+            Ok(())
+        })
+    }
+
+    fn reset_packet_trust(&self, meta: Self::Metadata) -> Result<()> {
+        debug!("reset_packet_trust request received");
+        meta.with_post_init(|post_init| {
+            post_init.packet_trust.reset(PacketSource::Relayer);
+            post_init.packet_trust.reset(PacketSource::BlockEngine);
+            Ok(())
+        })
+    }
+
+    fn packet_source_stats(&self, meta: Self::Metadata) -> Result<AdminRpcPacketSourceStats> {
+        debug!("packet_source_stats request received");
+        meta.with_post_init(|post_init| {
+            let accounting = &post_init.packet_source_accounting;
+            Ok(AdminRpcPacketSourceStats {
+                sources: PacketSource::ALL
+                    .into_iter()
+                    .map(|source| AdminRpcPacketSource {
+                        source: source.as_str().to_string(),
+                        rate_limit: accounting.rate_limits().get(source),
+                        stats: accounting.stats(source),
+                    })
+                    .collect(),
+            })
+        })
+    }
+
+    fn vortexor_peers(&self, meta: Self::Metadata) -> Result<AdminRpcVortexorPeers> {
+        debug!("vortexor_peers request received");
+        meta.with_post_init(|post_init| {
+            Ok(AdminRpcVortexorPeers {
+                peers: post_init
+                    .vortexor_peers
+                    .get()
+                    .into_iter()
+                    .map(AdminRpcVortexorPeer::from)
+                    .collect(),
+            })
+        })
+    }
+
+    fn add_vortexor_peer(
+        &self,
+        meta: Self::Metadata,
+        identity: String,
+        tpu_address: String,
+        tpu_forwards_address: String,
+    ) -> Result<()> {
+        debug!("add_vortexor_peer request received");
+        let parse_address = |addr: &str| {
+            SocketAddr::from_str(addr).map_err(|_| {
+                jsonrpc_core::error::Error::invalid_params(format!("invalid address: {addr}"))
+            })
+        };
+        let peer = VortexorPeer {
+            identity: verify_pubkey(&identity)?,
+            tpu_address: parse_address(&tpu_address)?,
+            tpu_forwards_address: parse_address(&tpu_forwards_address)?,
+        };
+        meta.with_post_init(|post_init| {
+            post_init.vortexor_peers.add(peer);
+            info!("Vortexor peer {} added", peer.identity);
+            Ok(())
+        })
+    }
+
+    fn remove_vortexor_peer(&self, meta: Self::Metadata, identity: String) -> Result<()> {
+        debug!("remove_vortexor_peer request received");
+        let identity = verify_pubkey(&identity)?;
+        meta.with_post_init(|post_init| {
+            if post_init.vortexor_peers.remove(&identity) {
+                info!("Vortexor peer {identity} removed");
+                Ok(())
+            } else {
+                Err(jsonrpc_core::error::Error::invalid_params(format!(
+                    "unknown vortexor peer: {identity}"
+                )))
+            }
+        })
+    }
+
     fn set_identity(
         &self,
         meta: Self::Metadata,
@@ -988,6 +1332,12 @@ pub fn load_staked_nodes_overrides(
     }
 }
 
+pub fn load_bundle_policy(path: &str) -> std::result::Result<BundlePolicy, Box<dyn error::Error>> {
+    debug!("Loading bundle policy from {}", path);
+    let file = std::fs::File::open(path)?;
+    Ok(serde_yaml::from_reader(file)?)
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -1102,6 +1452,13 @@ mod tests {
                     relayer_config,
                     shred_receiver_address,
                     shred_retransmit_receiver_address,
+                    min_tip_policy: Arc::default(),
+                    bundle_policy: Arc::default(),
+                    block_engine_health: Arc::default(),
+                    block_engine_regions: Arc::default(),
+                    packet_trust: Arc::default(),
+                    packet_source_accounting: Arc::default(),
+                    vortexor_peers: Arc::default(),
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
@@ -1440,6 +1797,357 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_set_min_tip_policy() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start_with_config(TestConfig::default());
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"setMinTipPolicy","params":[1000, 10, ["http://block-engine"]]}"#;
+        let response = io.handle_request_sync(request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        let expected_parsed_response: Value =
+            serde_json::from_str(r#"{"id": 1, "jsonrpc": "2.0", "result": null}"#)
+                .expect("Failed to parse expected response");
+        assert_eq!(actual_parsed_response, expected_parsed_response);
+
+        let min_tip_policy = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .min_tip_policy
+            .read()
+            .unwrap()
+            .clone();
+        assert_eq!(
+            min_tip_policy,
+            MinTipPolicy {
+                min_tip_lamports: 1000,
+                min_tip_micro_lamports_per_cu: 10,
+                exempt_sources: HashSet::from_iter(["http://block-engine".to_string()]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_set_bundle_policy() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start_with_config(TestConfig::default());
+
+        let denied_program = Pubkey::new_unique();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle-policy.yaml");
+        std::fs::write(
+            &path,
+            format!(
+                "denied_programs:\n  - {denied_program}\nmax_transactions: 3\nreject_sandwiches: true\n"
+            ),
+        )
+        .unwrap();
+
+        let request = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"setBundlePolicy","params":["{}"]}}"#,
+            path.display()
+        );
+        let response = io.handle_request_sync(&request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        let expected_parsed_response: Value =
+            serde_json::from_str(r#"{"id": 1, "jsonrpc": "2.0", "result": null}"#)
+                .expect("Failed to parse expected response");
+        assert_eq!(actual_parsed_response, expected_parsed_response);
+
+        let bundle_policy = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .bundle_policy
+            .read()
+            .unwrap()
+            .clone();
+        assert_eq!(
+            bundle_policy,
+            BundlePolicy {
+                denied_programs: HashSet::from([denied_program]),
+                max_transactions: Some(3),
+                reject_sandwiches: true,
+                ..BundlePolicy::default()
+            }
+        );
+
+        // a bad file leaves the running policy untouched
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"setBundlePolicy","params":["/does/not/exist.yaml"]}"#;
+        let response = io.handle_request_sync(request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        assert!(actual_parsed_response.get("error").is_some());
+        assert_eq!(
+            *meta
+                .post_init
+                .read()
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .bundle_policy
+                .read()
+                .unwrap(),
+            bundle_policy
+        );
+    }
+
+    #[test]
+    fn test_reset_packet_trust() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start_with_config(TestConfig::default());
+        let packet_trust = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .packet_trust
+            .clone();
+
+        // a failed sample revokes trust
+        assert!(packet_trust.revoke(PacketSource::Relayer));
+        assert!(packet_trust.revoke(PacketSource::BlockEngine));
+        assert!(!packet_trust.is_trusted(PacketSource::Relayer));
+        assert!(!packet_trust.is_trusted(PacketSource::BlockEngine));
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"resetPacketTrust"}"#;
+        let response = io.handle_request_sync(request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        let expected_parsed_response: Value =
+            serde_json::from_str(r#"{"id": 1, "jsonrpc": "2.0", "result": null}"#)
+                .expect("Failed to parse expected response");
+        assert_eq!(actual_parsed_response, expected_parsed_response);
+
+        assert!(packet_trust.is_trusted(PacketSource::Relayer));
+        assert!(packet_trust.is_trusted(PacketSource::BlockEngine));
+    }
+
+    #[test]
+    fn test_packet_source_stats() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start_with_config(TestConfig::default());
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"packetSourceStats"}"#;
+        let response = io.handle_request_sync(request, meta);
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        let result: AdminRpcPacketSourceStats =
+            serde_json::from_value(actual_parsed_response["result"].clone())
+                .expect("result deserialization");
+        assert_eq!(
+            result
+                .sources
+                .iter()
+                .map(|source| source.source.as_str())
+                .collect::<Vec<_>>(),
+            vec!["relayer", "block_engine", "tpu"]
+        );
+        assert!(result
+            .sources
+            .iter()
+            .all(|source| source.rate_limit == 0 && source.stats == PacketSourceStats::default()));
+    }
+
+    #[test]
+    fn test_vortexor_peers() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start_with_config(TestConfig::default());
+        let identity = Pubkey::new_unique();
+
+        let request = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"addVortexorPeer","params":["{identity}","127.0.0.1:9000","127.0.0.1:9001"]}}"#
+        );
+        let response = io.handle_request_sync(&request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        assert_eq!(actual_parsed_response["result"], Value::Null);
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"vortexorPeers"}"#;
+        let response = io.handle_request_sync(request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        let result: AdminRpcVortexorPeers =
+            serde_json::from_value(actual_parsed_response["result"].clone())
+                .expect("result deserialization");
+        assert_eq!(result.peers.len(), 1);
+        assert_eq!(result.peers[0].identity, identity.to_string());
+        assert_eq!(
+            result.peers[0].tpu_address,
+            SocketAddr::from_str("127.0.0.1:9000").unwrap()
+        );
+
+        // removing an unknown peer fails
+        let request = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"removeVortexorPeer","params":["{}"]}}"#,
+            Pubkey::new_unique()
+        );
+        let response = io.handle_request_sync(&request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        assert!(actual_parsed_response.get("error").is_some());
+
+        let request = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"removeVortexorPeer","params":["{identity}"]}}"#
+        );
+        let response = io.handle_request_sync(&request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        assert_eq!(actual_parsed_response["result"], Value::Null);
+
+        let post_init = meta.post_init.read().unwrap();
+        assert!(post_init.as_ref().unwrap().vortexor_peers.get().is_empty());
+    }
+
+    #[test]
+    fn test_set_block_engine_config_with_failover() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start_with_config(TestConfig::default());
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"setBlockEngineConfig","params":["http://block-engine-a", false, ["http://block-engine-b"]]}"#;
+        let response = io.handle_request_sync(request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        let expected_parsed_response: Value =
+            serde_json::from_str(r#"{"id": 1, "jsonrpc": "2.0", "result": null}"#)
+                .expect("Failed to parse expected response");
+        assert_eq!(actual_parsed_response, expected_parsed_response);
+
+        let block_engine_config = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .block_engine_config
+            .lock()
+            .unwrap()
+            .clone();
+        assert_eq!(
+            block_engine_config.block_engine_urls(),
+            vec!["http://block-engine-a", "http://block-engine-b"]
+        );
+
+        // failover urls are optional
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"setBlockEngineConfig","params":["http://block-engine-a", true]}"#;
+        let response = io.handle_request_sync(request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        assert_eq!(actual_parsed_response, expected_parsed_response);
+        let post_init = meta.post_init.read().unwrap();
+        let block_engine_config = post_init
+            .as_ref()
+            .unwrap()
+            .block_engine_config
+            .lock()
+            .unwrap();
+        assert!(block_engine_config.failover_block_engine_urls.is_empty());
+        assert!(block_engine_config.trust_packets);
+    }
+
+    #[test]
+    fn test_block_engine_health() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start_with_config(TestConfig::default());
+
+        let block_engine_health = BlockEngineHealth {
+            block_engine_url: "http://block-engine".to_string(),
+            connected: true,
+            active: true,
+            latency_us: Some(1_000),
+            num_bundles: 10,
+            num_duplicate_bundles: 2,
+            ..BlockEngineHealth::default()
+        };
+        *meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .block_engine_health
+            .write()
+            .unwrap() = vec![block_engine_health.clone()];
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"blockEngineHealth"}"#;
+        let response = io.handle_request_sync(request, meta);
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        let result: AdminRpcBlockEngineHealth =
+            serde_json::from_value(actual_parsed_response["result"].clone())
+                .expect("result deserialization");
+        assert_eq!(result.block_engines, vec![block_engine_health]);
+    }
+
+    #[test]
+    fn test_block_engine_regions() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start_with_config(TestConfig::default());
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"blockEngineRegions"}"#;
+        let response = io.handle_request_sync(request, meta.clone());
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        let result: AdminRpcBlockEngineRegions =
+            serde_json::from_value(actual_parsed_response["result"].clone())
+                .expect("result deserialization");
+        assert_eq!(result.selected_block_engine_url, None);
+        assert!(result.regions.is_empty());
+
+        let latencies = vec![
+            BlockEngineRegionLatency {
+                block_engine_url: "http://ny".to_string(),
+                connect_us: Some(1_000),
+                auth_us: Some(2_000),
+                error: None,
+            },
+            BlockEngineRegionLatency {
+                block_engine_url: "http://tokyo".to_string(),
+                error: Some("timed out".to_string()),
+                ..BlockEngineRegionLatency::default()
+            },
+        ];
+        *meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .block_engine_regions
+            .write()
+            .unwrap() = BlockEngineRegions {
+            selected_block_engine_url: Some("http://ny".to_string()),
+            latencies: latencies.clone(),
+            last_evaluated_timestamp_ms: Some(1),
+        };
+
+        let response = io.handle_request_sync(request, meta);
+        let actual_parsed_response: Value =
+            serde_json::from_str(&response.expect("actual response"))
+                .expect("actual response deserialization");
+        let result: AdminRpcBlockEngineRegions =
+            serde_json::from_value(actual_parsed_response["result"].clone())
+                .expect("result deserialization");
+        assert_eq!(
+            result.selected_block_engine_url.as_deref(),
+            Some("http://ny")
+        );
+        assert_eq!(result.regions, latencies);
+    }
+
     // This test checks that the rpc call to `set_identity` works a expected with
     // Bank but without validator.
     #[test]
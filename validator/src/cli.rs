@@ -21,6 +21,7 @@ use {
     },
     solana_core::{
         banking_trace::{DirByteLimit, BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT},
+        proxy::vortexor_receiver::VortexorPeer,
         validator::{BlockProductionMethod, BlockVerificationMethod},
     },
     solana_faucet::faucet::{self, FAUCET_PORT},
@@ -50,7 +51,7 @@ use {
     solana_streamer::quic::DEFAULT_QUIC_ENDPOINTS,
     solana_tpu_client::tpu_client::{DEFAULT_TPU_CONNECTION_POOL_SIZE, DEFAULT_VOTE_USE_QUIC},
     solana_unified_scheduler_pool::DefaultSchedulerPool,
-    std::{path::PathBuf, str::FromStr},
+    std::{num::NonZeroUsize, path::PathBuf, str::FromStr},
 };
 
 pub mod thread_args;
@@ -70,8 +71,10 @@ const MAX_SNAPSHOT_DOWNLOAD_ABORT: u32 = 5;
 const MINIMUM_TICKS_PER_SLOT: u64 = 2;
 
 const DEFAULT_PREALLOCATED_BUNDLE_COST: &str = "3000000";
+const DEFAULT_PREALLOCATED_BUNDLE_COST_RELEASE_PERCENT: &str = "80";
 const DEFAULT_RELAYER_EXPECTED_HEARTBEAT_INTERVAL_MS: &str = "500";
 const DEFAULT_RELAYER_MAX_FAILED_HEARTBEATS: &str = "3";
+const DEFAULT_BUNDLE_STAGE_NUM_WORKERS: &str = "4";
 
 pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
     return App::new(crate_name!())
@@ -92,6 +95,25 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Block engine url.  Set to empty string to disable block engine connection.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("failover_block_engine_url")
+                .long("failover-block-engine-url")
+                .value_name("BLOCK_ENGINE_URL")
+                .takes_value(true)
+                .multiple(true)
+                .help("Block engine to stay connected to alongside --block-engine-url, in order of preference. \
+                       Packets from all connected block engines are merged, bundles are only taken from the active one. \
+                       May be specified multiple times.")
+        )
+        .arg(
+            Arg::with_name("candidate_block_engine_url")
+                .long("candidate-block-engine-url")
+                .value_name("BLOCK_ENGINE_URL")
+                .takes_value(true)
+                .multiple(true)
+                .help("Block engine in another region to consider along with --block-engine-url. The one with the \
+                       lowest latency is used and periodically re-evaluated. May be specified multiple times.")
+        )
         .arg(
             Arg::with_name("relayer_url")
                 .long("relayer-url")
@@ -124,6 +146,129 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .takes_value(false)
                 .help("Skip signature verification on block engine packets. Not recommended unless the block engine is trusted.")
         )
+        .arg(
+            Arg::with_name("trusted_packet_sample_bps")
+                .long("trusted-packet-sample-bps")
+                .value_name("BASIS_POINTS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .default_value("0")
+                .help("Basis points, up to 10000, of relayer and block engine packets skipping signature verification that are verified anyway. \
+                       If a verified packet fails, packets from that source are verified until trust is restored with the reset-packet-trust command.")
+        )
+        .arg(
+            Arg::with_name("relayer_packet_rate_limit")
+                .long("relayer-packet-rate-limit")
+                .value_name("PACKETS_PER_SECOND")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .default_value("0")
+                .help("Maximum packets per second forwarded from the relayer, packets over the limit are dropped. 0 is unlimited.")
+        )
+        .arg(
+            Arg::with_name("block_engine_packet_rate_limit")
+                .long("block-engine-packet-rate-limit")
+                .value_name("PACKETS_PER_SECOND")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .default_value("0")
+                .help("Maximum packets per second forwarded from the block engines, packets over the limit are dropped. 0 is unlimited.")
+        )
+        .arg(
+            Arg::with_name("tpu_packet_rate_limit")
+                .long("tpu-packet-rate-limit")
+                .value_name("PACKETS_PER_SECOND")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .default_value("0")
+                .help("Maximum packets per second forwarded from the TPU fetch stage while it's intercepted for the relayer, packets over the limit are dropped. 0 is unlimited.")
+        )
+        .arg(
+            Arg::with_name("vortexor_packet_rate_limit")
+                .long("vortexor-packet-rate-limit")
+                .value_name("PACKETS_PER_SECOND")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .default_value("0")
+                .help("Maximum packets per second forwarded from the vortexor peers, packets over the limit are dropped. 0 is unlimited.")
+        )
+        .arg(
+            Arg::with_name("vortexor_receiver_address")
+                .long("vortexor-receiver-address")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .validator(solana_net_utils::is_host_port)
+                .help("Address to receive packets verified by vortexors on. Only the vortexors set with --vortexor-peer \
+                       or the add-vortexor-peer command can connect. While it's set, vortexor heartbeats take over advertising \
+                       the TPU addresses from the relayer's.")
+        )
+        .arg(
+            Arg::with_name("vortexor_peer")
+                .long("vortexor-peer")
+                .value_name("IDENTITY,TPU_ADDRESS,TPU_FORWARDS_ADDRESS")
+                .takes_value(true)
+                .multiple(true)
+                .requires("vortexor_receiver_address")
+                .validator(is_parsable::<VortexorPeer>)
+                .help("Vortexor allowed to connect to --vortexor-receiver-address, authenticated by its identity. \
+                       Its TPU addresses are advertised while it sends heartbeats, falling back to the validator's own TPU when they're missed. \
+                       May be specified multiple times.")
+        )
+        .arg(
+            Arg::with_name("bundle_result_fallback_path")
+                .long("bundle-result-fallback-path")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Append bundle execution results to this file as JSON lines when they can't be streamed to the block engine.")
+        )
+        .arg(
+            Arg::with_name("bundle_stage_num_workers")
+                .long("bundle-stage-num-workers")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<NonZeroUsize>)
+                .default_value(DEFAULT_BUNDLE_STAGE_NUM_WORKERS)
+                .help("Number of workers the bundle stage executes non-conflicting bundles on in parallel.")
+        )
+        .arg(
+            Arg::with_name("enable_bundle_simulation_cache")
+                .long("enable-bundle-simulation-cache")
+                .takes_value(false)
+                .help("Cache bundles that fail simulateBundle or bundle execution so the bundle stage can reject them without executing them again against the same bank state.")
+        )
+        .arg(
+            Arg::with_name("min_bundle_tip_lamports")
+                .long("min-bundle-tip-lamports")
+                .value_name("LAMPORTS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .default_value("0")
+                .help("Minimum tip a bundle must pay to be committed.")
+        )
+        .arg(
+            Arg::with_name("min_bundle_tip_micro_lamports_per_cu")
+                .long("min-bundle-tip-micro-lamports-per-cu")
+                .value_name("MICRO_LAMPORTS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .default_value("0")
+                .help("Minimum tip a bundle must pay per compute unit consumed to be committed.")
+        )
+        .arg(
+            Arg::with_name("min_bundle_tip_exempt_source")
+                .long("min-bundle-tip-exempt-source")
+                .value_name("BLOCK_ENGINE_URL")
+                .takes_value(true)
+                .multiple(true)
+                .help("Block engine whose bundles are exempt from the minimum bundle tip. May be specified multiple times.")
+        )
+        .arg(
+            Arg::with_name("bundle_policy")
+                .long("bundle-policy")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("YAML file with the rules bundles must pass to be executed: denied_programs, allowed_programs, protected_accounts, max_transactions, max_compute_units, reject_sandwiches and sandwich_programs. The program rules only check the programs called by top-level instructions, not through CPI. Can be reloaded with the set-bundle-policy command.")
+        )
         .arg(
             Arg::with_name("tip_payment_program_pubkey")
                 .long("tip-payment-program-pubkey")
@@ -160,6 +305,32 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(DEFAULT_PREALLOCATED_BUNDLE_COST)
                 .help("Number of CUs to allocate for bundles at beginning of slot.")
         )
+        .arg(
+            Arg::with_name("max_preallocated_bundle_cost")
+                .long("max-preallocated-bundle-cost")
+                .value_name("MAX_PREALLOCATED_BUNDLE_COST")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .help("Number of CUs the bundle allocation can grow to while bundles paying at least --high-tip-bundle-micro-lamports-per-cu are waiting to be executed. Defaults to --preallocated-bundle-cost, so the allocation never grows.")
+        )
+        .arg(
+            Arg::with_name("preallocated_bundle_cost_release_percent")
+                .long("preallocated-bundle-cost-release-percent")
+                .value_name("PERCENT")
+                .takes_value(true)
+                .validator(is_valid_percentage)
+                .default_value(DEFAULT_PREALLOCATED_BUNDLE_COST_RELEASE_PERCENT)
+                .help("Percent of the slot, in ticks, after which the CUs allocated for bundles at the beginning of the slot are released to regular transactions.")
+        )
+        .arg(
+            Arg::with_name("high_tip_bundle_micro_lamports_per_cu")
+                .long("high-tip-bundle-micro-lamports-per-cu")
+                .value_name("MICRO_LAMPORTS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .default_value("0")
+                .help("Minimum estimated tip per compute unit of a waiting bundle for it to grow the bundle allocation.")
+        )
         .arg(
             Arg::with_name("shred_receiver_address")
                 .long("shred-receiver-address")
@@ -1762,7 +1933,25 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         .long("block-engine-url")
                         .help("Block engine url.  Set to empty string to disable block engine connection.")
                         .takes_value(true)
-                        .required(true)
+                        .required_unless("candidate_block_engine_url")
+                )
+                .arg(
+                    Arg::with_name("failover_block_engine_url")
+                        .long("failover-block-engine-url")
+                        .value_name("BLOCK_ENGINE_URL")
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Block engine to stay connected to alongside --block-engine-url, in order of preference. \
+                               May be specified multiple times.")
+                )
+                .arg(
+                    Arg::with_name("candidate_block_engine_url")
+                        .long("candidate-block-engine-url")
+                        .value_name("BLOCK_ENGINE_URL")
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Block engine in another region to consider along with --block-engine-url. \
+                               The one with the lowest latency is used. May be specified multiple times.")
                 )
                 .arg(
                     Arg::with_name("trust_block_engine_packets")
@@ -1771,6 +1960,141 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         .help("Skip signature verification on block engine packets. Not recommended unless the block engine is trusted.")
                 )
         )
+        .subcommand(
+            SubCommand::with_name("block-engine-health")
+                .about("Display the health of the validator's block engine connections")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("block-engine-regions")
+                .about("Display the latency of the candidate block engines and the selected one")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("set-min-tip-policy")
+                .about("Set the minimum tip bundles must pay to be committed")
+                .arg(
+                    Arg::with_name("min_bundle_tip_lamports")
+                        .long("min-bundle-tip-lamports")
+                        .value_name("LAMPORTS")
+                        .takes_value(true)
+                        .validator(is_parsable::<u64>)
+                        .default_value("0")
+                        .help("Minimum tip a bundle must pay to be committed.")
+                )
+                .arg(
+                    Arg::with_name("min_bundle_tip_micro_lamports_per_cu")
+                        .long("min-bundle-tip-micro-lamports-per-cu")
+                        .value_name("MICRO_LAMPORTS")
+                        .takes_value(true)
+                        .validator(is_parsable::<u64>)
+                        .default_value("0")
+                        .help("Minimum tip a bundle must pay per compute unit consumed to be committed.")
+                )
+                .arg(
+                    Arg::with_name("min_bundle_tip_exempt_source")
+                        .long("min-bundle-tip-exempt-source")
+                        .value_name("BLOCK_ENGINE_URL")
+                        .takes_value(true)
+                        .multiple(true)
+                        .help("Block engine whose bundles are exempt from the minimum bundle tip. May be specified multiple times.")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("set-bundle-policy")
+                .about("Reload the rules bundles must pass to be executed")
+                .arg(
+                    Arg::with_name("path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("YAML file with the bundle policy, see --bundle-policy")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("packet-source-stats")
+                .about("Display the packets received from the relayer, block engines and TPU fetch stage")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("reset-packet-trust")
+                .about("Skip signature verification again on relayer and block engine packets after a sampled packet failed it")
+        )
+        .subcommand(
+            SubCommand::with_name("vortexor-peers")
+                .about("Display the vortexors allowed to send verified packets")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("add-vortexor-peer")
+                .about("Allow a vortexor to send verified packets, replacing the addresses of a known vortexor")
+                .arg(
+                    Arg::with_name("identity")
+                        .value_name("IDENTITY")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Identity the vortexor authenticates with")
+                )
+                .arg(
+                    Arg::with_name("tpu_address")
+                        .long("tpu-address")
+                        .value_name("HOST:PORT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(solana_net_utils::is_host_port)
+                        .help("Vortexor TPU address to advertise while it sends heartbeats")
+                )
+                .arg(
+                    Arg::with_name("tpu_forwards_address")
+                        .long("tpu-forwards-address")
+                        .value_name("HOST:PORT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(solana_net_utils::is_host_port)
+                        .help("Vortexor TPU forwards address to advertise while it sends heartbeats")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("remove-vortexor-peer")
+                .about("Disconnect a vortexor and stop accepting its packets")
+                .arg(
+                    Arg::with_name("identity")
+                        .value_name("IDENTITY")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Identity of the vortexor")
+                )
+        )
         .subcommand(
             SubCommand::with_name("set-relayer-config")
                 .about("Set configuration for connection to a relayer")
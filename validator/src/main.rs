@@ -4,7 +4,9 @@ use jemallocator::Jemalloc;
 use {
     agave_validator::{
         admin_rpc_service,
-        admin_rpc_service::{load_staked_nodes_overrides, StakedNodesOverrides},
+        admin_rpc_service::{
+            load_bundle_policy, load_staked_nodes_overrides, StakedNodesOverrides,
+        },
         bootstrap,
         cli::{self, app, warn_for_deprecated_arguments, DefaultArgs},
         dashboard::Dashboard,
@@ -31,8 +33,15 @@ use {
     solana_clap_utils::input_parsers::{keypair_of, keypairs_of, pubkey_of, value_of, values_of},
     solana_core::{
         banking_trace::DISABLED_BAKING_TRACE_DIR,
+        bundle_stage::{bundle_reservation::BundleReservationConfig, min_tip_policy::MinTipPolicy},
         consensus::tower_storage,
-        proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
+        proxy::{
+            block_engine_stage::BlockEngineConfig,
+            packet_source_accounting::{PacketRateLimits, PacketSourceAccounting},
+            packet_trust::PacketTrust,
+            relayer_stage::RelayerConfig,
+            vortexor_receiver::{VortexorPeer, VortexorPeers},
+        },
         system_monitor_service::SystemMonitorService,
         tip_manager::{TipDistributionAccountConfig, TipManagerConfig},
         tpu::DEFAULT_TPU_COALESCE,
@@ -470,14 +479,24 @@ pub fn main() {
     let operation = match matches.subcommand() {
         ("", _) | ("run", _) => Operation::Run,
         ("set-block-engine-config", Some(subcommand_matches)) => {
-            let block_engine_url = value_t_or_exit!(subcommand_matches, "block_engine_url", String);
+            let block_engine_url =
+                value_t!(subcommand_matches, "block_engine_url", String).unwrap_or_default();
             let trust_packets = subcommand_matches.is_present("trust_block_engine_packets");
+            let failover_block_engine_urls =
+                values_t!(subcommand_matches, "failover_block_engine_url", String).ok();
+            let candidate_block_engine_urls =
+                values_t!(subcommand_matches, "candidate_block_engine_url", String).ok();
             let admin_client = admin_rpc_service::connect(&ledger_path);
             admin_rpc_service::runtime()
                 .block_on(async move {
                     admin_client
                         .await?
-                        .set_block_engine_config(block_engine_url, trust_packets)
+                        .set_block_engine_config(
+                            block_engine_url,
+                            trust_packets,
+                            failover_block_engine_urls,
+                            candidate_block_engine_urls,
+                        )
                         .await
                 })
                 .unwrap_or_else(|err| {
@@ -486,6 +505,186 @@ pub fn main() {
                 });
             return;
         }
+        ("block-engine-health", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let block_engine_health = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.block_engine_health().await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Block engine health query failed: {err}");
+                    exit(1);
+                });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&block_engine_health).unwrap()
+                    ),
+                    "json-compact" => {
+                        print!("{}", serde_json::to_string(&block_engine_health).unwrap())
+                    }
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{block_engine_health}");
+            }
+            return;
+        }
+        ("block-engine-regions", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let block_engine_regions = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.block_engine_regions().await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Block engine regions query failed: {err}");
+                    exit(1);
+                });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&block_engine_regions).unwrap()
+                    ),
+                    "json-compact" => {
+                        print!("{}", serde_json::to_string(&block_engine_regions).unwrap())
+                    }
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{block_engine_regions}");
+            }
+            return;
+        }
+        ("set-min-tip-policy", Some(subcommand_matches)) => {
+            let MinTipPolicy {
+                min_tip_lamports,
+                min_tip_micro_lamports_per_cu,
+                exempt_sources,
+            } = min_tip_policy_from_matches(subcommand_matches);
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move {
+                    admin_client
+                        .await?
+                        .set_min_tip_policy(
+                            min_tip_lamports,
+                            min_tip_micro_lamports_per_cu,
+                            exempt_sources.into_iter().collect(),
+                        )
+                        .await
+                })
+                .unwrap_or_else(|err| {
+                    println!("set min tip policy failed: {}", err);
+                    exit(1);
+                });
+            return;
+        }
+        ("set-bundle-policy", Some(subcommand_matches)) => {
+            let path = value_t_or_exit!(subcommand_matches, "path", String);
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.set_bundle_policy(path).await })
+                .unwrap_or_else(|err| {
+                    println!("set bundle policy failed: {}", err);
+                    exit(1);
+                });
+            return;
+        }
+        ("packet-source-stats", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let packet_source_stats = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.packet_source_stats().await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Packet source stats query failed: {err}");
+                    exit(1);
+                });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&packet_source_stats).unwrap()
+                    ),
+                    "json-compact" => {
+                        print!("{}", serde_json::to_string(&packet_source_stats).unwrap())
+                    }
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{packet_source_stats}");
+            }
+            return;
+        }
+        ("vortexor-peers", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let vortexor_peers = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.vortexor_peers().await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Vortexor peers query failed: {err}");
+                    exit(1);
+                });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => {
+                        println!("{}", serde_json::to_string_pretty(&vortexor_peers).unwrap())
+                    }
+                    "json-compact" => print!("{}", serde_json::to_string(&vortexor_peers).unwrap()),
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{vortexor_peers}");
+            }
+            return;
+        }
+        ("add-vortexor-peer", Some(subcommand_matches)) => {
+            let identity = value_t_or_exit!(subcommand_matches, "identity", String);
+            let parse_address = |name| {
+                let address = subcommand_matches.value_of(name).unwrap();
+                solana_net_utils::parse_host_port(address)
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to parse {name}: {err}");
+                        exit(1);
+                    })
+                    .to_string()
+            };
+            let tpu_address = parse_address("tpu_address");
+            let tpu_forwards_address = parse_address("tpu_forwards_address");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move {
+                    admin_client
+                        .await?
+                        .add_vortexor_peer(identity, tpu_address, tpu_forwards_address)
+                        .await
+                })
+                .unwrap_or_else(|err| {
+                    println!("add vortexor peer failed: {}", err);
+                    exit(1);
+                });
+            return;
+        }
+        ("remove-vortexor-peer", Some(subcommand_matches)) => {
+            let identity = value_t_or_exit!(subcommand_matches, "identity", String);
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.remove_vortexor_peer(identity).await })
+                .unwrap_or_else(|err| {
+                    println!("remove vortexor peer failed: {}", err);
+                    exit(1);
+                });
+            return;
+        }
+        ("reset-packet-trust", _) => {
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.reset_packet_trust().await })
+                .unwrap_or_else(|err| {
+                    println!("reset packet trust failed: {}", err);
+                    exit(1);
+                });
+            return;
+        }
         ("set-relayer-config", Some(subcommand_matches)) => {
             let relayer_url = value_t_or_exit!(subcommand_matches, "relayer_url", String);
             let trust_packets = subcommand_matches.is_present("trust_relayer_packets");
@@ -1626,6 +1825,10 @@ pub fn main() {
         } else {
             "".to_string()
         },
+        failover_block_engine_urls: values_t!(matches, "failover_block_engine_url", String)
+            .unwrap_or_default(),
+        candidate_block_engine_urls: values_t!(matches, "candidate_block_engine_url", String)
+            .unwrap_or_default(),
         trust_packets: matches.is_present("trust_block_engine_packets"),
     };
 
@@ -1818,8 +2021,53 @@ pub fn main() {
             .is_present("delay_leader_block_for_pending_fork"),
         wen_restart_proto_path: value_t!(matches, "wen_restart", PathBuf).ok(),
         wen_restart_coordinator: value_t!(matches, "wen_restart_coordinator", Pubkey).ok(),
-        preallocated_bundle_cost: value_of(&matches, "preallocated_bundle_cost")
-            .expect("preallocated_bundle_cost set as default"),
+        bundle_reservation_config: bundle_reservation_config_from_matches(&matches),
+        bundle_result_fallback_path: value_t!(matches, "bundle_result_fallback_path", PathBuf).ok(),
+        bundle_stage_num_workers: value_t_or_exit!(
+            matches,
+            "bundle_stage_num_workers",
+            NonZeroUsize
+        )
+        .get(),
+        enable_bundle_simulation_cache: matches.is_present("enable_bundle_simulation_cache"),
+        min_tip_policy: Arc::new(RwLock::new(min_tip_policy_from_matches(&matches))),
+        bundle_policy: Arc::new(RwLock::new(
+            matches
+                .value_of("bundle_policy")
+                .map(|path| {
+                    load_bundle_policy(path).unwrap_or_else(|err| {
+                        error!("Failed to load bundle policy from {}: {}", path, err);
+                        clap::Error::with_description(
+                            "Failed to load configuration of bundle-policy argument",
+                            clap::ErrorKind::InvalidValue,
+                        )
+                        .exit()
+                    })
+                })
+                .unwrap_or_default(),
+        )),
+        packet_trust: Arc::new(PacketTrust::new(value_t_or_exit!(
+            matches,
+            "trusted_packet_sample_bps",
+            u64
+        ))),
+        packet_source_accounting: Arc::new(PacketSourceAccounting::new(PacketRateLimits {
+            relayer: value_t_or_exit!(matches, "relayer_packet_rate_limit", u64),
+            block_engine: value_t_or_exit!(matches, "block_engine_packet_rate_limit", u64),
+            tpu: value_t_or_exit!(matches, "tpu_packet_rate_limit", u64),
+            vortexor: value_t_or_exit!(matches, "vortexor_packet_rate_limit", u64),
+        })),
+        vortexor_receiver_address: matches
+            .value_of("vortexor_receiver_address")
+            .map(|address| {
+                solana_net_utils::parse_host_port(address).unwrap_or_else(|err| {
+                    eprintln!("Failed to parse --vortexor-receiver-address: {err}");
+                    exit(1);
+                })
+            }),
+        vortexor_peers: Arc::new(VortexorPeers::new(
+            values_t!(matches, "vortexor_peer", VortexorPeer).unwrap_or_default(),
+        )),
         ..ValidatorConfig::default()
     };
 
@@ -2448,3 +2696,31 @@ fn tip_manager_config_from_matches(
         },
     }
 }
+
+fn bundle_reservation_config_from_matches(matches: &ArgMatches) -> BundleReservationConfig {
+    let preallocated_bundle_cost = value_of(matches, "preallocated_bundle_cost")
+        .expect("preallocated_bundle_cost set as default");
+    BundleReservationConfig {
+        preallocated_bundle_cost,
+        max_preallocated_bundle_cost: value_of(matches, "max_preallocated_bundle_cost")
+            .unwrap_or(preallocated_bundle_cost),
+        release_slot_percent: value_of(matches, "preallocated_bundle_cost_release_percent")
+            .expect("preallocated_bundle_cost_release_percent set as default"),
+        high_tip_micro_lamports_per_cu: value_of(matches, "high_tip_bundle_micro_lamports_per_cu")
+            .expect("high_tip_bundle_micro_lamports_per_cu set as default"),
+    }
+}
+
+fn min_tip_policy_from_matches(matches: &ArgMatches) -> MinTipPolicy {
+    MinTipPolicy {
+        min_tip_lamports: value_t_or_exit!(matches, "min_bundle_tip_lamports", u64),
+        min_tip_micro_lamports_per_cu: value_t_or_exit!(
+            matches,
+            "min_bundle_tip_micro_lamports_per_cu",
+            u64
+        ),
+        exempt_sources: values_t!(matches, "min_bundle_tip_exempt_source", String)
+            .map(HashSet::from_iter)
+            .unwrap_or_default(),
+    }
+}
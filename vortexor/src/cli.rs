@@ -1,6 +1,7 @@
 use {
+    crate::stake_updater::DEFAULT_STAKE_REFRESH_INTERVAL,
     clap::{crate_description, crate_name, App, AppSettings, Arg},
-    solana_clap_utils::input_validators::{is_keypair_or_ask_keyword, is_parsable},
+    solana_clap_utils::input_validators::{is_keypair_or_ask_keyword, is_parsable, is_url},
     solana_net_utils::{MINIMUM_VALIDATOR_PORT_RANGE_WIDTH, VALIDATOR_PORT_RANGE},
     solana_sdk::quic::QUIC_PORT_OFFSET,
     solana_streamer::{
@@ -25,6 +26,7 @@ pub struct DefaultArgs {
     pub max_streams_per_ms: String,
     pub max_connections_per_ipaddr_per_min: String,
     pub num_quic_endpoints: String,
+    pub stake_refresh_interval_secs: String,
 }
 
 impl Default for DefaultArgs {
@@ -43,6 +45,7 @@ impl Default for DefaultArgs {
             max_connections_per_ipaddr_per_min: DEFAULT_MAX_CONNECTIONS_PER_IPADDR_PER_MINUTE
                 .to_string(),
             num_quic_endpoints: DEFAULT_NUM_QUIC_ENDPOINTS.to_string(),
+            stake_refresh_interval_secs: DEFAULT_STAKE_REFRESH_INTERVAL.as_secs().to_string(),
         }
     }
 }
@@ -173,5 +176,34 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .takes_value(true)
                 .validator(is_parsable::<u64>)
                 .help("Milliseconds to wait in the TPU receiver for packet coalescing."),
+        )
+        .arg(
+            Arg::with_name("destination")
+                .long("destination")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .multiple(true)
+                .required(true)
+                .validator(solana_net_utils::is_host_port)
+                .help("Address of a validator's vortexor receiver to forward verified packets to. \
+                       May be specified multiple times."),
+        )
+        .arg(
+            Arg::with_name("rpc_url")
+                .long("rpc-url")
+                .value_name("URL")
+                .takes_value(true)
+                .validator(is_url)
+                .help("RPC endpoint the stakes of the cluster's nodes are fetched from for \
+                       stake-weighted QoS. Without it all connections are treated as unstaked."),
+        )
+        .arg(
+            Arg::with_name("stake_refresh_interval_secs")
+                .long("stake-refresh-interval-secs")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .default_value(&default_args.stake_refresh_interval_secs)
+                .validator(is_parsable::<u64>)
+                .help("Seconds between refreshes of the stakes fetched from --rpc-url."),
         );
 }
@@ -1,2 +1,5 @@
 pub mod cli;
+pub mod packet_forwarder;
+pub mod sigverifier;
+pub mod stake_updater;
 pub mod vortexor;
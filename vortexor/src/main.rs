@@ -1,19 +1,27 @@
 use {
     clap::{value_t, value_t_or_exit},
     crossbeam_channel::unbounded,
+    log::*,
     solana_clap_utils::input_parsers::keypair_of,
+    solana_client::connection_cache::ConnectionCache,
     solana_sdk::net::DEFAULT_TPU_COALESCE,
     solana_streamer::streamer::StakedNodes,
     solana_vortexor::{
         cli::{app, DefaultArgs},
+        packet_forwarder::PacketForwarder,
+        sigverifier::SigVerifier,
+        stake_updater::StakeUpdater,
         vortexor::Vortexor,
     },
     std::{
+        collections::HashMap,
         sync::{atomic::AtomicBool, Arc, RwLock},
         time::Duration,
     },
 };
 
+const FORWARDER_CONNECTION_POOL_SIZE: usize = 4;
+
 pub fn main() {
     let default_args = DefaultArgs::default();
     let solana_version = solana_version::version!();
@@ -51,16 +59,44 @@ pub fn main() {
             .expect("invalid dynamic_port_range");
 
     let max_streams_per_ms = value_t_or_exit!(matches, "max_streams_per_ms", u64);
+    let destinations: Vec<_> = matches
+        .values_of("destination")
+        .unwrap()
+        .map(|destination| {
+            solana_net_utils::parse_host_port(destination).expect("invalid destination")
+        })
+        .collect();
+    let stake_refresh_interval = Duration::from_secs(value_t_or_exit!(
+        matches,
+        "stake_refresh_interval_secs",
+        u64
+    ));
+
+    solana_logger::setup_with_default_filter();
+    solana_metrics::set_panic_hook("vortexor", Some(String::from(solana_version)));
+
     let exit = Arc::new(AtomicBool::new(false));
-    // To be linked with the Tpu sigverify and forwarder service
-    let (tpu_sender, _tpu_receiver) = unbounded();
-    let (tpu_fwd_sender, _tpu_fwd_receiver) = unbounded();
+    let (tpu_sender, tpu_receiver) = unbounded();
+    let (tpu_fwd_sender, tpu_fwd_receiver) = unbounded();
+    let (verified_sender, verified_receiver) = unbounded();
 
     let tpu_sockets =
         Vortexor::create_tpu_sockets(bind_address, dynamic_port_range, num_quic_endpoints);
 
-    // To be linked with StakedNodes service.
     let staked_nodes = Arc::new(RwLock::new(StakedNodes::default()));
+    let stake_updater = match matches.value_of("rpc_url") {
+        Some(rpc_url) => Some(StakeUpdater::new(
+            rpc_url.to_string(),
+            staked_nodes.clone(),
+            HashMap::default(),
+            stake_refresh_interval,
+            exit.clone(),
+        )),
+        None => {
+            warn!("--rpc-url not specified, all connections are treated as unstaked");
+            None
+        }
+    };
 
     let vortexor = Vortexor::create_vortexor(
         tpu_sockets,
@@ -78,5 +114,38 @@ pub fn main() {
         &identity_keypair,
         exit,
     );
+
+    let sigverifiers = [
+        SigVerifier::new(
+            tpu_receiver,
+            verified_sender.clone(),
+            "solVtxSigVer",
+            "vortexor_sigverifier_tpu",
+        ),
+        SigVerifier::new(
+            tpu_fwd_receiver,
+            verified_sender,
+            "solVtxSigVerFwd",
+            "vortexor_sigverifier_tpu_forwards",
+        ),
+    ];
+
+    let connection_cache = Arc::new(ConnectionCache::new_with_client_options(
+        "vortexor_packet_forwarder",
+        FORWARDER_CONNECTION_POOL_SIZE,
+        None,
+        Some((&identity_keypair, bind_address)),
+        None,
+    ));
+    info!("forwarding verified packets to {destinations:?}");
+    let packet_forwarder = PacketForwarder::new(verified_receiver, connection_cache, destinations);
+
     vortexor.join().unwrap();
+    for sigverifier in sigverifiers {
+        sigverifier.join().unwrap();
+    }
+    packet_forwarder.join().unwrap();
+    if let Some(stake_updater) = stake_updater {
+        stake_updater.join().unwrap();
+    }
 }
@@ -0,0 +1,122 @@
+//! Forwards verified packets to the validators linked with the vortexor.
+//!
+//! Every packet is sent as its own QUIC stream, the same as a TPU client, over connections
+//! authenticated by the vortexor's identity keypair. Validators accept the packets as already
+//! verified when they come from a vortexor identity they're configured with. A
+//! [HEARTBEAT_PACKET] is sent every [HEARTBEAT_INTERVAL] so a validator can tell the vortexor is
+//! alive when there's no traffic.
+use {
+    crossbeam_channel::{Receiver, RecvTimeoutError},
+    log::*,
+    solana_client::connection_cache::ConnectionCache,
+    solana_connection_cache::client_connection::ClientConnection,
+    solana_metrics::datapoint_info,
+    solana_perf::packet::PacketBatch,
+    std::{
+        net::SocketAddr,
+        sync::Arc,
+        thread::{self, Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+/// Payload of the heartbeats sent to the validators, never a valid transaction
+pub const HEARTBEAT_PACKET: &[u8] = b"VORTEXOR_HEARTBEAT";
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(500);
+const METRICS_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct PacketForwarderStats {
+    num_batches: u64,
+    num_packets: u64,
+    num_heartbeats: u64,
+    num_send_errors: u64,
+}
+
+impl PacketForwarderStats {
+    fn report(&self) {
+        datapoint_info!(
+            "vortexor_packet_forwarder",
+            ("num_batches", self.num_batches, i64),
+            ("num_packets", self.num_packets, i64),
+            ("num_heartbeats", self.num_heartbeats, i64),
+            ("num_send_errors", self.num_send_errors, i64),
+        );
+    }
+}
+
+pub struct PacketForwarder {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl PacketForwarder {
+    pub fn new(
+        verified_receiver: Receiver<Vec<PacketBatch>>,
+        connection_cache: Arc<ConnectionCache>,
+        destinations: Vec<SocketAddr>,
+    ) -> Self {
+        let thread_hdl = Builder::new()
+            .name("solVtxPktFwd".to_string())
+            .spawn(move || {
+                let mut stats = PacketForwarderStats::default();
+                let mut last_heartbeat = Instant::now();
+                let mut last_report = Instant::now();
+                loop {
+                    match verified_receiver.recv_timeout(HEARTBEAT_INTERVAL) {
+                        Ok(batches) => {
+                            let packets: Vec<Vec<u8>> = batches
+                                .iter()
+                                .flat_map(|batch| batch.iter())
+                                .filter(|packet| !packet.meta().discard())
+                                .filter_map(|packet| packet.data(..).map(<[u8]>::to_vec))
+                                .collect();
+                            stats.num_batches += batches.len() as u64;
+                            stats.num_packets += packets.len() as u64;
+                            Self::send(&connection_cache, &destinations, packets, &mut stats);
+                        }
+                        Err(RecvTimeoutError::Timeout) => (),
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                    if last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
+                        stats.num_heartbeats += 1;
+                        Self::send(
+                            &connection_cache,
+                            &destinations,
+                            vec![HEARTBEAT_PACKET.to_vec()],
+                            &mut stats,
+                        );
+                        last_heartbeat = Instant::now();
+                    }
+                    if last_report.elapsed() >= METRICS_INTERVAL {
+                        stats.report();
+                        stats = PacketForwarderStats::default();
+                        last_report = Instant::now();
+                    }
+                }
+            })
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    fn send(
+        connection_cache: &ConnectionCache,
+        destinations: &[SocketAddr],
+        packets: Vec<Vec<u8>>,
+        stats: &mut PacketForwarderStats,
+    ) {
+        if packets.is_empty() {
+            return;
+        }
+        for destination in destinations {
+            let connection = connection_cache.get_connection(destination);
+            if let Err(err) = connection.send_data_batch_async(packets.clone()) {
+                stats.num_send_errors += 1;
+                debug!("failed to forward packets to {destination}: {err}");
+            }
+        }
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
@@ -0,0 +1,191 @@
+//! Deduplicates the packets received by the TPU streamer and verifies their signatures. Batches
+//! with at least one valid packet are sent on to the packet forwarder, with the packets that
+//! failed marked as discarded.
+use {
+    crossbeam_channel::{RecvTimeoutError, Sender},
+    log::*,
+    solana_metrics::datapoint_info,
+    solana_perf::{
+        deduper::{self, Deduper},
+        packet::PacketBatch,
+        sigverify::{count_valid_packets, ed25519_verify_cpu, shrink_batches},
+    },
+    solana_streamer::streamer::{self, PacketBatchReceiver, StreamerError},
+    std::{
+        thread::{self, Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+const MAX_DEDUPER_AGE: Duration = Duration::from_secs(2);
+const DEDUPER_FALSE_POSITIVE_RATE: f64 = 0.001;
+const DEDUPER_NUM_BITS: u64 = 63_999_979;
+const METRICS_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+struct SigVerifierStats {
+    num_batches: u64,
+    num_packets: u64,
+    num_duplicates: u64,
+    num_valid_packets: u64,
+    num_deduper_saturations: u64,
+    verify_us: u64,
+}
+
+impl SigVerifierStats {
+    fn report(&self, metrics_name: &'static str) {
+        datapoint_info!(
+            metrics_name,
+            ("num_batches", self.num_batches, i64),
+            ("num_packets", self.num_packets, i64),
+            ("num_duplicates", self.num_duplicates, i64),
+            ("num_valid_packets", self.num_valid_packets, i64),
+            (
+                "num_failed_packets",
+                self.num_packets
+                    .saturating_sub(self.num_duplicates)
+                    .saturating_sub(self.num_valid_packets),
+                i64
+            ),
+            ("num_deduper_saturations", self.num_deduper_saturations, i64),
+            ("verify_us", self.verify_us, i64),
+        );
+    }
+}
+
+pub struct SigVerifier {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl SigVerifier {
+    pub fn new(
+        packet_receiver: PacketBatchReceiver,
+        verified_sender: Sender<Vec<PacketBatch>>,
+        thread_name: &'static str,
+        metrics_name: &'static str,
+    ) -> Self {
+        let thread_hdl = Builder::new()
+            .name(thread_name.to_string())
+            .spawn(move || {
+                let mut rng = rand::thread_rng();
+                let mut deduper = Deduper::<2, [u8]>::new(&mut rng, DEDUPER_NUM_BITS);
+                let mut stats = SigVerifierStats::default();
+                let mut last_report = Instant::now();
+                loop {
+                    if deduper.maybe_reset(&mut rng, DEDUPER_FALSE_POSITIVE_RATE, MAX_DEDUPER_AGE) {
+                        stats.num_deduper_saturations += 1;
+                    }
+                    match streamer::recv_packet_batches(&packet_receiver) {
+                        Ok((batches, num_packets, _)) => {
+                            let batches = Self::verify(&deduper, batches, num_packets, &mut stats);
+                            if !batches.is_empty() && verified_sender.send(batches).is_err() {
+                                break;
+                            }
+                        }
+                        Err(StreamerError::RecvTimeout(RecvTimeoutError::Timeout)) => (),
+                        Err(StreamerError::RecvTimeout(RecvTimeoutError::Disconnected)) => break,
+                        Err(e) => error!("{thread_name}: {e:?}"),
+                    }
+                    if last_report.elapsed() > METRICS_INTERVAL {
+                        stats.report(metrics_name);
+                        stats = SigVerifierStats::default();
+                        last_report = Instant::now();
+                    }
+                }
+            })
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    /// Discards the duplicate packets and those with invalid signatures, dropping batches left
+    /// without any valid packet
+    fn verify<const K: usize>(
+        deduper: &Deduper<K, [u8]>,
+        mut batches: Vec<PacketBatch>,
+        num_packets: usize,
+        stats: &mut SigVerifierStats,
+    ) -> Vec<PacketBatch> {
+        let num_duplicates = deduper::dedup_packets_and_count_discards(deduper, &mut batches);
+        let num_unique = num_packets.saturating_sub(num_duplicates as usize);
+
+        let verify_start = Instant::now();
+        ed25519_verify_cpu(&mut batches, false, num_unique);
+        let verify_us = verify_start.elapsed().as_micros() as u64;
+        let num_valid_packets = count_valid_packets(&batches);
+        // compacts the valid packets into the leading batches and drops the rest
+        shrink_batches(&mut batches);
+
+        stats.num_batches += batches.len() as u64;
+        stats.num_packets += num_packets as u64;
+        stats.num_duplicates += num_duplicates;
+        stats.num_valid_packets += num_valid_packets as u64;
+        stats.verify_us += verify_us;
+        batches
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crossbeam_channel::unbounded,
+        solana_perf::packet::Packet,
+        solana_sdk::{
+            hash::Hash,
+            signature::{Keypair, Signature, Signer},
+            system_transaction::transfer,
+        },
+    };
+
+    fn make_packet(keypair: &Keypair, lamports: u64, valid: bool) -> Packet {
+        let mut tx = transfer(keypair, &keypair.pubkey(), lamports, Hash::default());
+        if !valid {
+            tx.signatures[0] = Signature::new_unique();
+        }
+        Packet::from_data(None, tx).unwrap()
+    }
+
+    #[test]
+    fn test_sigverifier_discards_duplicate_and_invalid_packets() {
+        let keypair = Keypair::new();
+        let valid_packet = make_packet(&keypair, 1, true);
+        let (packet_sender, packet_receiver) = unbounded();
+        let (verified_sender, verified_receiver) = unbounded();
+        let sigverifier = SigVerifier::new(
+            packet_receiver,
+            verified_sender,
+            "solVtxSigVerTest",
+            "vortexor_sigverifier_test",
+        );
+
+        packet_sender
+            .send(PacketBatch::new(vec![
+                valid_packet.clone(),
+                make_packet(&keypair, 2, false),
+                valid_packet,
+            ]))
+            .unwrap();
+        // a batch without valid packets isn't forwarded
+        packet_sender
+            .send(PacketBatch::new(vec![make_packet(&keypair, 3, false)]))
+            .unwrap();
+
+        let batches = verified_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        let verified: Vec<_> = batches
+            .iter()
+            .flat_map(|batch| batch.iter())
+            .filter(|packet| !packet.meta().discard())
+            .collect();
+        assert_eq!(verified.len(), 1);
+
+        drop(packet_sender);
+        sigverifier.join().unwrap();
+        assert!(verified_receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! Keeps the stake table used by the TPU streamer for stake-weighted QoS up to date with the
+//! activated stake of the cluster's vote accounts, fetched from an RPC endpoint.
+use {
+    log::*,
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_rpc_client_api::response::RpcVoteAccountStatus,
+    solana_sdk::pubkey::Pubkey,
+    solana_streamer::streamer::StakedNodes,
+    std::{
+        collections::HashMap,
+        str::FromStr,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, sleep, Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+pub const DEFAULT_STAKE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+const EXIT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct StakeUpdater {
+    thread_hdl: JoinHandle<()>,
+}
+
+impl StakeUpdater {
+    pub fn new(
+        rpc_url: String,
+        staked_nodes: Arc<RwLock<StakedNodes>>,
+        staked_nodes_overrides: HashMap<Pubkey, u64>,
+        refresh_interval: Duration,
+        exit: Arc<AtomicBool>,
+    ) -> Self {
+        let thread_hdl = Builder::new()
+            .name("solVtxStakeUpd".to_string())
+            .spawn(move || {
+                let rpc_client = RpcClient::new(rpc_url);
+                let mut last_refresh: Option<Instant> = None;
+                while !exit.load(Ordering::Relaxed) {
+                    if last_refresh.map_or(true, |last| last.elapsed() >= refresh_interval) {
+                        last_refresh = Some(Instant::now());
+                        match rpc_client.get_vote_accounts() {
+                            Ok(vote_accounts) => {
+                                let stakes = stakes_from_vote_accounts(&vote_accounts);
+                                info!("refreshed stakes of {} nodes", stakes.len());
+                                *staked_nodes.write().unwrap() = StakedNodes::new(
+                                    Arc::new(stakes),
+                                    staked_nodes_overrides.clone(),
+                                );
+                            }
+                            Err(err) => warn!("failed to fetch vote accounts: {err}"),
+                        }
+                    }
+                    sleep(EXIT_CHECK_INTERVAL);
+                }
+            })
+            .unwrap();
+        Self { thread_hdl }
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.thread_hdl.join()
+    }
+}
+
+/// Sums the activated stake of the current and delinquent vote accounts per node identity
+fn stakes_from_vote_accounts(vote_accounts: &RpcVoteAccountStatus) -> HashMap<Pubkey, u64> {
+    let mut stakes = HashMap::new();
+    for vote_account in vote_accounts
+        .current
+        .iter()
+        .chain(vote_accounts.delinquent.iter())
+    {
+        let Ok(node_pubkey) = Pubkey::from_str(&vote_account.node_pubkey) else {
+            warn!("invalid node pubkey {}", vote_account.node_pubkey);
+            continue;
+        };
+        let stake: &mut u64 = stakes.entry(node_pubkey).or_default();
+        *stake = stake.saturating_add(vote_account.activated_stake);
+    }
+    stakes
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_rpc_client_api::response::RpcVoteAccountInfo};
+
+    fn vote_account_info(node_pubkey: &str, activated_stake: u64) -> RpcVoteAccountInfo {
+        RpcVoteAccountInfo {
+            vote_pubkey: Pubkey::new_unique().to_string(),
+            node_pubkey: node_pubkey.to_string(),
+            activated_stake,
+            commission: 0,
+            epoch_vote_account: true,
+            epoch_credits: vec![],
+            last_vote: 0,
+            root_slot: 0,
+        }
+    }
+
+    #[test]
+    fn test_stakes_from_vote_accounts() {
+        let node_a = Pubkey::new_unique();
+        let node_b = Pubkey::new_unique();
+        let vote_accounts = RpcVoteAccountStatus {
+            current: vec![
+                vote_account_info(&node_a.to_string(), 100),
+                vote_account_info(&node_a.to_string(), 50),
+                vote_account_info("not-a-pubkey", 10),
+            ],
+            delinquent: vec![vote_account_info(&node_b.to_string(), 20)],
+        };
+        assert_eq!(
+            stakes_from_vote_accounts(&vote_accounts),
+            HashMap::from([(node_a, 150), (node_b, 20)])
+        );
+    }
+}
@@ -1,17 +1,28 @@
 use {
     crossbeam_channel::unbounded,
+    solana_client::connection_cache::ConnectionCache,
+    solana_connection_cache::client_connection::ClientConnection,
     solana_net_utils::VALIDATOR_PORT_RANGE,
-    solana_sdk::{net::DEFAULT_TPU_COALESCE, pubkey::Pubkey, signature::Keypair, signer::Signer},
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_sdk::{
+        native_token::LAMPORTS_PER_SOL, net::DEFAULT_TPU_COALESCE, pubkey::Pubkey,
+        signature::Keypair, signer::Signer, system_transaction,
+    },
     solana_streamer::{
         nonblocking::{
             quic::{DEFAULT_MAX_CONNECTIONS_PER_IPADDR_PER_MINUTE, DEFAULT_MAX_STREAMS_PER_MS},
             testing_utilities::check_multiple_streams,
         },
         quic::{MAX_STAKED_CONNECTIONS, MAX_UNSTAKED_CONNECTIONS},
+        socket::SocketAddrSpace,
         streamer::StakedNodes,
     },
+    solana_test_validator::TestValidator,
     solana_vortexor::{
         cli::{DEFAULT_MAX_QUIC_CONNECTIONS_PER_PEER, DEFAULT_NUM_QUIC_ENDPOINTS},
+        packet_forwarder::PacketForwarder,
+        sigverifier::SigVerifier,
+        stake_updater::StakeUpdater,
         vortexor::Vortexor,
     },
     std::{
@@ -20,6 +31,8 @@ use {
             atomic::{AtomicBool, Ordering},
             Arc, RwLock,
         },
+        thread::sleep,
+        time::{Duration, Instant},
     },
 };
 
@@ -74,3 +87,126 @@ async fn test_vortexor() {
     exit.store(true, Ordering::Relaxed);
     vortexor.join().unwrap();
 }
+
+#[test]
+fn test_vortexor_forwards_verified_transactions() {
+    solana_logger::setup();
+
+    let mint_keypair = Keypair::new();
+    let test_validator =
+        TestValidator::with_no_fees(mint_keypair.pubkey(), None, SocketAddrSpace::Unspecified);
+    let rpc_client = RpcClient::new(test_validator.rpc_url());
+    let validator_tpu_address = rpc_client.get_cluster_nodes().unwrap()[0].tpu_quic.unwrap();
+
+    let bind_address = solana_net_utils::parse_host("127.0.0.1").expect("invalid bind_address");
+    let keypair = Keypair::new();
+    let exit = Arc::new(AtomicBool::new(false));
+
+    let staked_nodes = Arc::new(RwLock::new(StakedNodes::default()));
+    let stake_updater = StakeUpdater::new(
+        test_validator.rpc_url(),
+        staked_nodes.clone(),
+        HashMap::default(),
+        Duration::from_secs(1),
+        exit.clone(),
+    );
+    let validator_id = test_validator.cluster_info().id();
+    let start = Instant::now();
+    while staked_nodes
+        .read()
+        .unwrap()
+        .get_node_stake(&validator_id)
+        .is_none()
+    {
+        assert!(
+            start.elapsed() < Duration::from_secs(30),
+            "stakes not refreshed"
+        );
+        sleep(Duration::from_millis(100));
+    }
+
+    let (tpu_sender, tpu_receiver) = unbounded();
+    let (tpu_fwd_sender, tpu_fwd_receiver) = unbounded();
+    let (verified_sender, verified_receiver) = unbounded();
+    let tpu_sockets = Vortexor::create_tpu_sockets(
+        bind_address,
+        VALIDATOR_PORT_RANGE,
+        DEFAULT_NUM_QUIC_ENDPOINTS.try_into().unwrap(),
+    );
+    let tpu_address = tpu_sockets.tpu_quic[0].local_addr().unwrap();
+
+    let vortexor = Vortexor::create_vortexor(
+        tpu_sockets,
+        staked_nodes,
+        tpu_sender,
+        tpu_fwd_sender,
+        DEFAULT_MAX_QUIC_CONNECTIONS_PER_PEER.try_into().unwrap(),
+        MAX_STAKED_CONNECTIONS.try_into().unwrap(),
+        MAX_UNSTAKED_CONNECTIONS.try_into().unwrap(),
+        MAX_STAKED_CONNECTIONS
+            .saturating_add(MAX_UNSTAKED_CONNECTIONS)
+            .try_into()
+            .unwrap(), // max_fwd_staked_connections
+        0, // max_fwd_unstaked_connections
+        DEFAULT_MAX_STREAMS_PER_MS,
+        DEFAULT_MAX_CONNECTIONS_PER_IPADDR_PER_MINUTE,
+        DEFAULT_TPU_COALESCE,
+        &keypair,
+        exit.clone(),
+    );
+    let sigverifiers = [
+        SigVerifier::new(
+            tpu_receiver,
+            verified_sender.clone(),
+            "solVtxSigVer",
+            "vortexor_sigverifier_tpu",
+        ),
+        SigVerifier::new(
+            tpu_fwd_receiver,
+            verified_sender,
+            "solVtxSigVerFwd",
+            "vortexor_sigverifier_tpu_forwards",
+        ),
+    ];
+    let forwarder_connection_cache = Arc::new(ConnectionCache::new_with_client_options(
+        "vortexor_packet_forwarder",
+        1,
+        None,
+        Some((&keypair, bind_address)),
+        None,
+    ));
+    let packet_forwarder = PacketForwarder::new(
+        verified_receiver,
+        forwarder_connection_cache,
+        vec![validator_tpu_address],
+    );
+
+    // the transfer is sent to the vortexor and lands once forwarded to the validator
+    let recipient = Pubkey::new_unique();
+    let blockhash = rpc_client.get_latest_blockhash().unwrap();
+    let transaction =
+        system_transaction::transfer(&mint_keypair, &recipient, LAMPORTS_PER_SOL, blockhash);
+    let wire_transaction = bincode::serialize(&transaction).unwrap();
+    let client_connection_cache = ConnectionCache::new_quic("vortexor_test_client", 1);
+    let start = Instant::now();
+    while rpc_client.get_balance(&recipient).unwrap() != LAMPORTS_PER_SOL {
+        assert!(
+            start.elapsed() < Duration::from_secs(30),
+            "transaction not forwarded"
+        );
+        client_connection_cache
+            .get_connection(&tpu_address)
+            .send_data(&wire_transaction)
+            .unwrap();
+        sleep(Duration::from_millis(500));
+    }
+
+    exit.store(true, Ordering::Relaxed);
+    vortexor.join().unwrap();
+    // the sigverifiers and the forwarder stop once the streamers drop their senders
+    for sigverifier in sigverifiers {
+        sigverifier.join().unwrap();
+    }
+    packet_forwarder.join().unwrap();
+    stake_updater.join().unwrap();
+}